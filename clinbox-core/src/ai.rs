@@ -0,0 +1,997 @@
+//! Email analysis, reply drafts, article summaries, spam checks and expense
+//! extraction via OpenRouter
+
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::config::Config;
+use crate::context;
+use crate::diagnostics;
+use crate::email::{Category, Email, EmailAnalysis, Priority};
+use crate::redact::Redactions;
+use crate::timings;
+
+const OPENROUTER_API_URL: &str = "https://openrouter.ai/api/v1/chat/completions";
+
+/// Tokens of email body sent per request, unless `ai.context_tokens` sets one for the model
+const ANALYSIS_CONTEXT_TOKENS: usize = 400;
+const REPLY_CONTEXT_TOKENS: usize = 500;
+const SUMMARY_CONTEXT_TOKENS: usize = 1000;
+const SPAM_CHECK_CONTEXT_TOKENS: usize = 250;
+const QUESTION_CONTEXT_TOKENS: usize = 1500;
+const EXPENSE_CONTEXT_TOKENS: usize = 600;
+/// Tokens of body sent for each sent message when learning the writing style
+const STYLE_CONTEXT_TOKENS: usize = 150;
+/// Tokens of body sent for each earlier message with the sender
+const HISTORY_CONTEXT_TOKENS: usize = 150;
+
+const ANALYSIS_PROMPT: &str = r#"You are an email assistant for a software developer.
+
+Analyze this email and provide a JSON response with:
+- priority: "urgent" | "action_required" | "informative" | "low" | "spam"
+- category: "billing" | "security" | "infrastructure" | "seo" | "newsletter" | "personal" | "github" | "other"
+- summary: 1-2 sentence summary in Spanish
+- suggested_action: what to do (or null if no action needed), in Spanish
+- estimated_time_minutes: how long the action would take (1, 2, 5, 10, 15, 30)
+- confidence: how sure you are of the priority, from 0.0 (guess) to 1.0 (certain)
+- reasoning: 1-2 sentences on why you chose this priority, in Spanish
+- proposed_times: meeting or call times the email proposes, as "YYYY-MM-DD HH:MM", followed by the UTC offset (e.g. " +02:00") when the email states a timezone (empty list if none)
+- snooze_until: if the email is about something on a future date (a renewal, a deadline, an event, a package's delivery), the day to be reminded of it, as "YYYY-MM-DD", usually that date or a day before (or null)
+
+Priority guidelines:
+- urgent: Production errors, security alerts, billing limits exceeded
+- action_required: Needs response or action but not time-critical
+- informative: Useful info to read later
+- low: Can be archived (marketing, generic newsletters)
+- spam: Irrelevant, delete
+
+A "Bulk mail" line means the headers show the email went to a list or was sent by a machine: it is rarely urgent or personal unless the content clearly says otherwise.
+
+An "Authentication" line gives the SPF, DKIM and DMARC results. Mail that fails DMARC while claiming to come from a bank, payment service or account provider is likely phishing: mark it spam and say so in the summary.
+
+Respond ONLY with valid JSON, no markdown or explanation."#;
+
+const ARTICLE_SUMMARY_PROMPT: &str = r#"You are summarizing an article/email for later reading.
+
+Language: {language}
+
+Requirements:
+1. Create a COMPREHENSIVE summary that covers ALL key points
+2. Do NOT omit any important information
+3. The summary should be detailed enough that the reader doesn't need to read the original
+4. At the end, list 3-7 key takeaways that should stick in the reader's mind
+5. Write in {language}
+
+Respond ONLY with valid JSON, no markdown or explanation:
+{
+  "summary": "comprehensive summary here...",
+  "key_takeaways": ["point 1", "point 2", ...]
+}"#;
+
+const REPLY_PROMPT: &str = r#"You are an email assistant helping a software developer write email replies.
+
+Write a professional, concise reply to the email. Guidelines:
+- Match the tone of the original email (formal/informal)
+- Be helpful and direct
+- Keep it brief (2-4 sentences typically)
+- Write in the same language as the original email
+- Don't use overly formal closings unless the original was formal
+- If it's a notification/no-reply email, write a brief acknowledgment or suggest not replying
+
+Respond with ONLY the reply text, no subject line, no greeting like "Here's a draft", just the email body ready to send."#;
+
+const NUDGE_PROMPT: &str = r#"You are an email assistant helping a software developer follow up on an email they sent that got no answer.
+
+Write a short, friendly follow-up to the email below (which the user wrote). Guidelines:
+- Politely bring the original request back to the recipient's attention
+- Don't repeat the whole original message; one sentence of context is enough
+- Keep it to 1-3 sentences, without sounding impatient
+- Write in the same language as the original email
+
+Respond with ONLY the follow-up text, no subject line, no greeting like "Here's a draft", just the email body ready to send."#;
+
+const SPAM_CHECK_PROMPT: &str = r#"You are reviewing an email that Gmail filed as spam, for a software developer.
+
+Decide whether it is actually legitimate mail the user would want (a real person, a service they use, a receipt, a security notice) or genuine spam/phishing. A failed DMARC check on mail claiming to be from a well-known company is a strong sign of phishing.
+
+Respond ONLY with valid JSON, no markdown or explanation:
+{
+  "legit": true or false,
+  "reason": "one short sentence, in the same language as the email"
+}"#;
+
+const COMMAND_PROMPT: &str = r#"You turn a typed command into actions on a Gmail inbox, for an email triage tool. The user is looking at the email described below.
+
+Respond ONLY with valid JSON, no markdown or explanation:
+{
+  "steps": [ ... ]
+}
+
+Each step is one of:
+- {"action": "archive"}: archive the current email
+- {"action": "delete"}: move the current email to trash
+- {"action": "mark_read"}: mark the current email read, keeping it in the inbox
+- {"action": "label", "label": "name"}: add a label to the current email
+- {"action": "skip"}: move on to the next email without changing this one
+- {"action": "archive_all", "query": "gmail search"}: archive every message matching a Gmail search query
+- {"action": "label_all", "query": "gmail search", "label": "name"}: label every message matching a Gmail search query
+
+Guidelines:
+- "this", "it" or no object means the current email
+- For senders, search with from: and the name or domain the user gave (e.g. "from:amazon"); add in:inbox to archive_all queries
+- Keep the steps in the order the user gave them
+- If the command asks for anything else, return an empty list of steps"#;
+
+const STYLE_PROMPT: &str = r#"You are studying how a person writes email, from a sample of messages they sent.
+
+Describe their writing style so another writer could imitate it. Cover:
+- Tone and formality, and whether it changes with the recipient
+- How they greet and how they sign off (quote their usual sign-off and name exactly)
+- Typical length of messages and sentences
+- Language(s) they write in
+- Habits worth copying: punctuation, emoji, lists, phrases they reuse
+
+Write 5-10 short bullet points in English, plain text, no preamble. Describe the style only; don't repeat the content of the messages."#;
+
+const QUESTION_PROMPT: &str = r#"You are an email assistant for a software developer. Answer their questions about the email below.
+
+Guidelines:
+- Base your answers on the email; say so when it doesn't tell
+- Be brief and concrete (quote amounts, dates and names from the email)
+- Answer in the language of the question
+- Plain text only, no markdown
+
+The email:
+"#;
+
+const EXPENSE_PROMPT: &str = r#"You are reading an invoice, receipt or payment confirmation for an expense ledger.
+
+Extract:
+- vendor: who was paid, as a short company or person name
+- amount: the total charged, taxes included, as a number (null if the email states no amount)
+- currency: ISO 4217 code such as "EUR" or "USD" (null if unknown)
+- date: the invoice or payment date, as "YYYY-MM-DD" (null if not stated)
+- description: what was paid for, in a few words, in the same language as the email
+
+When there are several amounts, use the final total, not a subtotal or a line item. Respond ONLY with valid JSON, no markdown or explanation:
+{
+  "vendor": "...",
+  "amount": 12.5,
+  "currency": "EUR",
+  "date": "2024-07-01",
+  "description": "..."
+}"#;
+
+const CLEANUP_PROMPT: &str = r#"You help someone clean up their Gmail inbox by proposing automatic rules for senders. Below is one line per sender: what they did with the sender's mail during triage, and how many of its messages sit in the inbox now.
+
+Propose a rule only where the numbers make a clear case:
+- archive: mail they consistently archive, or automated mail piling up in the inbox that they never triage
+- mark_read: mail they consistently mark read and leave in the inbox
+- delete: mail they consistently delete
+Leave out senders whose mail they reply to, move to labels or turn into tasks, and people (rather than automated senders) unless the history is overwhelming.
+
+Give each rule a reason of one short sentence in English citing the numbers, e.g. "97% archived historically (31 of 32)" or "automated, 40 in the inbox, never triaged". At most 20 rules, strongest first; an empty list is fine. Respond ONLY with valid JSON, no markdown or explanation:
+{
+  "rules": [
+    {"address": "digest@news.example.com", "action": "archive", "reason": "..."}
+  ]
+}"#;
+
+/// A rule the AI proposes for one sender's mail
+#[derive(Debug, Clone, Deserialize)]
+pub struct CleanupProposal {
+    /// The sender's address, as listed
+    pub address: String,
+    /// "archive", "mark_read" or "delete"
+    pub action: String,
+    /// One sentence citing the history behind it
+    pub reason: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CleanupResponse {
+    rules: Vec<CleanupProposal>,
+}
+
+/// One step of a typed command, as planned by the AI
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum PlanStep {
+    /// Archive the current email
+    Archive,
+    /// Move the current email to trash
+    Delete,
+    /// Mark the current email read, keeping it in the inbox
+    MarkRead,
+    /// Add a label to the current email
+    Label {
+        /// Label name, created if needed
+        label: String,
+    },
+    /// Move on without changing the current email
+    Skip,
+    /// Archive every message matching a search
+    ArchiveAll {
+        /// Gmail search query
+        query: String,
+    },
+    /// Label every message matching a search
+    LabelAll {
+        /// Gmail search query
+        query: String,
+        /// Label name, created if needed
+        label: String,
+    },
+}
+
+impl PlanStep {
+    /// The search the step acts on, for steps that reach beyond the current email
+    pub fn query(&self) -> Option<&str> {
+        match self {
+            PlanStep::ArchiveAll { query } | PlanStep::LabelAll { query, .. } => Some(query),
+            _ => None,
+        }
+    }
+
+    /// One line for the confirmation, e.g. "archive everything matching from:amazon in:inbox"
+    pub fn describe(&self) -> String {
+        match self {
+            PlanStep::Archive => "archive this email".to_string(),
+            PlanStep::Delete => "move this email to trash".to_string(),
+            PlanStep::MarkRead => "mark this email read".to_string(),
+            PlanStep::Label { label } => format!("label this email \"{}\"", label),
+            PlanStep::Skip => "skip to the next email".to_string(),
+            PlanStep::ArchiveAll { query } => format!("archive everything matching {}", query),
+            PlanStep::LabelAll { query, label } => {
+                format!("label everything matching {} \"{}\"", query, label)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct PlanResponse {
+    steps: Vec<PlanStep>,
+}
+
+/// Whether a message in the spam folder looks like it belongs there
+#[derive(Debug, Clone, Deserialize)]
+pub struct SpamVerdict {
+    /// True when the message looks like real mail filed as spam by mistake
+    pub legit: bool,
+    /// One sentence explaining the call
+    pub reason: String,
+}
+
+/// What an invoice or receipt says was paid
+#[derive(Debug, Clone, Deserialize)]
+pub struct Expense {
+    /// Who was paid
+    pub vendor: String,
+    /// Total charged; absent when the email doesn't state one
+    #[serde(default)]
+    pub amount: Option<f64>,
+    /// ISO 4217 currency code, e.g. "EUR"
+    #[serde(default)]
+    pub currency: Option<String>,
+    /// Invoice or payment date, as "YYYY-MM-DD"
+    #[serde(default)]
+    pub date: Option<String>,
+    /// What was paid for, in a few words
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// A newsletter or article boiled down for reading in the terminal
+#[derive(Debug, Clone, Deserialize)]
+pub struct ArticleSummary {
+    /// A short paragraph
+    pub summary: String,
+    /// The main points, one per item
+    pub key_takeaways: Vec<String>,
+}
+
+/// Client for the OpenRouter chat completions API
+pub struct AiClient {
+    http: Client,
+    api_key: String,
+    /// Analysis models, tried in order until one gives a usable answer
+    models: Vec<String>,
+    model_reply: String,
+    model_escalation: String,
+    /// Email body budget per model, overriding the per-request defaults
+    context_tokens: HashMap<String, usize>,
+    /// Lowercase address or domain -> the user's instructions for that sender
+    sender_instructions: Vec<(String, String)>,
+    /// Earlier messages with the sender to send along
+    history_messages: usize,
+    /// How I write, learned from my sent mail (`clinbox style learn`)
+    style: Option<String>,
+    /// Mask personal data before it's sent (`ai.redact_pii`)
+    redact_pii: bool,
+    /// Leave bulk mail to the headers (`ai.skip_bulk`)
+    skip_bulk: bool,
+}
+
+impl AiClient {
+    /// Create a client with the API key, models and network settings from the config
+    pub fn new(config: &Config) -> Result<Self> {
+        Ok(Self {
+            http: config.network.client()?,
+            api_key: config.ai.api_key.clone(),
+            models: config.ai.model_analysis.models().to_vec(),
+            model_reply: config.ai.model_reply.clone(),
+            model_escalation: config
+                .ai
+                .model_escalation
+                .clone()
+                .unwrap_or_else(|| config.ai.model_reply.clone()),
+            context_tokens: config.ai.context_tokens.clone(),
+            sender_instructions: config
+                .ai
+                .sender_instructions
+                .iter()
+                .map(|(sender, text)| {
+                    let sender = sender.trim().trim_start_matches('@').to_lowercase();
+                    (sender, text.trim().to_string())
+                })
+                .collect(),
+            history_messages: config.ai.history_messages,
+            style: Config::style_path()
+                .ok()
+                .and_then(|path| std::fs::read_to_string(path).ok())
+                .map(|style| style.trim().to_string())
+                .filter(|style| !style.is_empty()),
+            redact_pii: config.ai.redact_pii,
+            skip_bulk: config.ai.skip_bulk,
+        })
+    }
+
+    /// Classify an email; `attachment_text` is an excerpt of an attached document,
+    /// included when the body itself says little, and `earlier` are previous
+    /// messages with the sender, oldest first
+    pub async fn analyze_email(
+        &self,
+        email: &Email,
+        attachment_text: Option<&str>,
+        earlier: &[Email],
+    ) -> Result<EmailAnalysis> {
+        self.analyze_with(&self.models, email, attachment_text, earlier)
+            .await
+    }
+
+    /// Classify an email again with the escalation model, for when the cheap
+    /// model's take looks off
+    pub async fn reanalyze_email(
+        &self,
+        email: &Email,
+        attachment_text: Option<&str>,
+        earlier: &[Email],
+    ) -> Result<EmailAnalysis> {
+        self.analyze_with(
+            std::slice::from_ref(&self.model_escalation),
+            email,
+            attachment_text,
+            earlier,
+        )
+        .await
+    }
+
+    /// How many earlier messages with the sender to pass along with an email
+    /// (`ai.history_messages`)
+    pub fn history_messages(&self) -> usize {
+        self.history_messages
+    }
+
+    /// Whether mail whose headers mark it as bulk should be classified without
+    /// the AI (`ai.skip_bulk`)
+    pub fn skip_bulk(&self) -> bool {
+        self.skip_bulk
+    }
+
+    /// Name of the model `reanalyze_email` uses
+    pub fn escalation_model(&self) -> &str {
+        &self.model_escalation
+    }
+
+    async fn analyze_with(
+        &self,
+        models: &[String],
+        email: &Email,
+        attachment_text: Option<&str>,
+        earlier: &[Email],
+    ) -> Result<EmailAnalysis> {
+        let body = email.body_text();
+        let headers = format!("{}{}", bulk_line(email), authentication_line(email));
+        let email_content = |model: &str| {
+            let mut content = format!(
+                "From: {}\nSubject: {}\nDate: {}\nLabels: {}{}\n\nBody:\n{}",
+                email.from,
+                email.subject,
+                email.date.format("%Y-%m-%d %H:%M"),
+                email.labels.join(", "),
+                headers,
+                self.excerpt(&body, model, ANALYSIS_CONTEXT_TOKENS)
+            );
+            if let Some(text) = attachment_text {
+                content.push_str(&format!("\n\nAttached {}", text));
+            }
+            content.push_str(&self.history(earlier, model));
+            content
+        };
+
+        let instructions = self.instructions_for(email);
+        let system_prompt = if instructions.is_empty() {
+            ANALYSIS_PROMPT.to_string()
+        } else {
+            format!(
+                "{}\n\nThe user's instructions for this sender take precedence over the guidelines above:\n{}",
+                ANALYSIS_PROMPT,
+                instructions
+                    .iter()
+                    .map(|text| format!("- {}", text))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            )
+        };
+
+        let (parsed, model): (AnalysisResponse, String) = self
+            .chat_with_fallback(models, &system_prompt, email_content, 0.3, 500, |content| {
+                parse_json(content).context("Failed to parse AI analysis JSON")
+            })
+            .await?;
+
+        Ok(EmailAnalysis {
+            email_id: email.id.clone(),
+            priority: parsed.priority,
+            category: parsed.category,
+            summary: parsed.summary,
+            suggested_action: parsed.suggested_action,
+            estimated_time_minutes: parsed.estimated_time_minutes.unwrap_or(1),
+            confidence: parsed.confidence,
+            reasoning: parsed.reasoning,
+            proposed_times: parsed.proposed_times.unwrap_or_default(),
+            snooze_until: parsed.snooze_until,
+            model: Some(model),
+        })
+    }
+
+    /// The configured instructions whose address or domain matches the sender,
+    /// most specific first
+    fn instructions_for(&self, email: &Email) -> Vec<&str> {
+        let from = email.from.to_lowercase();
+        let address = match (from.rfind('<'), from.rfind('>')) {
+            (Some(start), Some(end)) if start < end => &from[start + 1..end],
+            _ => from.trim(),
+        };
+        let domain = address.rsplit('@').next().unwrap_or_default();
+
+        let mut matches: Vec<&(String, String)> = self
+            .sender_instructions
+            .iter()
+            .filter(|(sender, _)| {
+                sender == address
+                    || (!sender.contains('@')
+                        && (domain == sender || domain.ends_with(&format!(".{}", sender))))
+            })
+            .collect();
+        matches.sort_by_key(|(sender, _)| std::cmp::Reverse(sender.len()));
+        matches.into_iter().map(|(_, text)| text.as_str()).collect()
+    }
+
+    /// Draft a reply to an email, knowing the `earlier` messages with the sender
+    pub async fn generate_reply(&self, email: &Email, earlier: &[Email]) -> Result<String> {
+        self.draft(&self.in_my_style(REPLY_PROMPT), email, earlier)
+            .await
+    }
+
+    /// Draft a reply that suggests other times, since the ones the email
+    /// proposes clash with these events on my calendar
+    pub async fn generate_reply_around(
+        &self,
+        email: &Email,
+        conflicts: &[String],
+        earlier: &[Email],
+    ) -> Result<String> {
+        let prompt = format!(
+            "{}\n\nThe times proposed in the email clash with these events on the user's calendar, so the reply should say so and suggest alternative times:\n{}",
+            self.in_my_style(REPLY_PROMPT),
+            conflicts
+                .iter()
+                .map(|c| format!("- {}", c))
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+        self.draft(&prompt, email, earlier).await
+    }
+
+    /// Draft a polite follow-up to a message of mine that got no answer
+    pub async fn generate_nudge(&self, sent: &Email) -> Result<String> {
+        self.draft(&self.in_my_style(NUDGE_PROMPT), sent, &[]).await
+    }
+
+    async fn draft(&self, prompt: &str, email: &Email, earlier: &[Email]) -> Result<String> {
+        let body = email.body_text();
+        let email_content = |model: &str| {
+            format!(
+                "From: {}\nSubject: {}\nDate: {}\n\nBody:\n{}{}",
+                email.from,
+                email.subject,
+                email.date.format("%Y-%m-%d %H:%M"),
+                self.excerpt(&body, model, REPLY_CONTEXT_TOKENS),
+                self.history(earlier, model)
+            )
+        };
+
+        let (draft, _) = self
+            .chat_with_fallback(&self.models, prompt, email_content, 0.7, 500, |content| {
+                Ok(content.trim().to_string())
+            })
+            .await?;
+        Ok(draft)
+    }
+
+    /// Plan the steps for a typed command such as "archive all from amazon",
+    /// with `email` as the one "this" refers to. An empty plan means the
+    /// command asked for something unsupported.
+    pub async fn plan_command(&self, command: &str, email: &Email) -> Result<Vec<PlanStep>> {
+        let content = |_: &str| {
+            format!(
+                "Current email:\nFrom: {}\nSubject: {}\n\nCommand: {}",
+                email.from, email.subject, command
+            )
+        };
+
+        let (plan, _): (PlanResponse, String) = self
+            .chat_with_fallback(&self.models, COMMAND_PROMPT, content, 0.0, 300, |content| {
+                parse_json(content).context("Failed to parse AI command plan")
+            })
+            .await?;
+        Ok(plan.steps)
+    }
+
+    /// Answer a question about an email, following on from the earlier
+    /// (question, answer) turns of the conversation
+    pub async fn ask_about(
+        &self,
+        email: &Email,
+        turns: &[(String, String)],
+        question: &str,
+    ) -> Result<String> {
+        let system = format!(
+            "{}From: {}\nTo: {}\nSubject: {}\nDate: {}\n\nBody:\n{}",
+            QUESTION_PROMPT,
+            email.from,
+            email.to,
+            email.subject,
+            email.date.format("%Y-%m-%d %H:%M"),
+            self.excerpt(
+                &email.body_text(),
+                &self.model_reply,
+                QUESTION_CONTEXT_TOKENS
+            )
+        );
+
+        let mut messages = vec![ChatMessage {
+            role: "system".to_string(),
+            content: system,
+        }];
+        for (asked, answered) in turns {
+            messages.push(ChatMessage {
+                role: "user".to_string(),
+                content: asked.clone(),
+            });
+            messages.push(ChatMessage {
+                role: "assistant".to_string(),
+                content: answered.clone(),
+            });
+        }
+        messages.push(ChatMessage {
+            role: "user".to_string(),
+            content: question.to_string(),
+        });
+
+        let answer = self.complete(&self.model_reply, messages, 0.3, 800).await?;
+        Ok(answer.trim().to_string())
+    }
+
+    /// A drafting prompt with my writing style profile added, when there is one
+    fn in_my_style(&self, prompt: &str) -> String {
+        match &self.style {
+            Some(style) => format!(
+                "{}\n\nWrite it the way the user writes; this describes their style and takes precedence over the guidelines above on tone, greeting and sign-off:\n{}",
+                prompt, style
+            ),
+            None => prompt.to_string(),
+        }
+    }
+
+    /// Describe my writing style from a sample of mail I sent, for drafts to imitate
+    pub async fn learn_style(&self, sent: &[Email]) -> Result<String> {
+        let messages: Vec<String> = sent
+            .iter()
+            .map(|e| {
+                format!(
+                    "To: {}\nSubject: {}\n\n{}",
+                    e.to,
+                    e.subject,
+                    self.excerpt(&e.body_text(), &self.model_reply, STYLE_CONTEXT_TOKENS)
+                )
+            })
+            .collect();
+        let content = format!("Sent messages:\n\n---\n{}", messages.join("\n\n---\n"));
+
+        let style = self
+            .chat(&self.model_reply, STYLE_PROMPT, &content, 0.3, 600)
+            .await?;
+        Ok(style.trim().to_string())
+    }
+
+    /// Earlier messages with the sender, appended to the email so the model
+    /// sees the conversation so far; empty when there are none
+    fn history(&self, earlier: &[Email], model: &str) -> String {
+        if earlier.is_empty() {
+            return String::new();
+        }
+        let messages: Vec<String> = earlier
+            .iter()
+            .map(|e| {
+                format!(
+                    "From: {}\nDate: {}\nSubject: {}\n\n{}",
+                    e.from,
+                    e.date.format("%Y-%m-%d %H:%M"),
+                    e.subject,
+                    self.excerpt(&e.body_text(), model, HISTORY_CONTEXT_TOKENS)
+                )
+            })
+            .collect();
+        format!(
+            "\n\nEarlier messages with this sender, oldest first, for context:\n\n---\n{}",
+            messages.join("\n\n---\n")
+        )
+    }
+
+    /// Summarize a newsletter or article in `language`
+    pub async fn summarize_article(&self, email: &Email, language: &str) -> Result<ArticleSummary> {
+        let email_content = format!(
+            "From: {}\nSubject: {}\nDate: {}\n\nBody:\n{}",
+            email.from,
+            email.subject,
+            email.date.format("%Y-%m-%d %H:%M"),
+            // Use more content for comprehensive summary
+            self.excerpt(
+                &email.body_text(),
+                &self.model_reply,
+                SUMMARY_CONTEXT_TOKENS
+            )
+        );
+
+        let system_prompt = ARTICLE_SUMMARY_PROMPT.replace("{language}", language);
+
+        // Use higher quality model for summaries
+        let content = self
+            .chat(&self.model_reply, &system_prompt, &email_content, 0.3, 2000)
+            .await?;
+        parse_json(&content).context("Failed to parse AI summary JSON")
+    }
+
+    /// Quick second opinion on a message Gmail filed as spam; only a short excerpt is sent
+    pub async fn check_spam(&self, email: &Email) -> Result<SpamVerdict> {
+        let body = email.body_text();
+        let authentication = authentication_line(email);
+        let email_content = |model: &str| {
+            format!(
+                "From: {}\nSubject: {}{}\n\nBody:\n{}",
+                email.from,
+                email.subject,
+                authentication,
+                self.excerpt(&body, model, SPAM_CHECK_CONTEXT_TOKENS)
+            )
+        };
+
+        let (verdict, _) = self
+            .chat_with_fallback(
+                &self.models,
+                SPAM_CHECK_PROMPT,
+                email_content,
+                0.0,
+                150,
+                |content| parse_json(content).context("Failed to parse AI spam verdict JSON"),
+            )
+            .await?;
+        Ok(verdict)
+    }
+
+    /// Read the vendor, amount, currency and date off an invoice or receipt;
+    /// `attachment_text` is an excerpt of an attached invoice, when there is one
+    pub async fn extract_expense(
+        &self,
+        email: &Email,
+        attachment_text: Option<&str>,
+    ) -> Result<Expense> {
+        let body = email.body_text();
+        let email_content = |model: &str| {
+            let mut content = format!(
+                "From: {}\nSubject: {}\nDate: {}\n\nBody:\n{}",
+                email.from,
+                email.subject,
+                email.date.format("%Y-%m-%d"),
+                self.excerpt(&body, model, EXPENSE_CONTEXT_TOKENS)
+            );
+            if let Some(text) = attachment_text {
+                content.push_str(&format!("\n\nAttached {}", text));
+            }
+            content
+        };
+
+        let (expense, _) = self
+            .chat_with_fallback(
+                &self.models,
+                EXPENSE_PROMPT,
+                email_content,
+                0.0,
+                200,
+                |content| parse_json(content).context("Failed to parse AI expense JSON"),
+            )
+            .await?;
+        Ok(expense)
+    }
+
+    /// Propose rules for senders from `senders`, one line per sender describing
+    /// what I did with its mail and how much of it is in the inbox
+    pub async fn suggest_cleanup(&self, senders: &str) -> Result<Vec<CleanupProposal>> {
+        let content = |_: &str| format!("Senders:\n{}", senders);
+
+        let (response, _): (CleanupResponse, String) = self
+            .chat_with_fallback(
+                &self.models,
+                CLEANUP_PROMPT,
+                content,
+                0.0,
+                1500,
+                |content| parse_json(content).context("Failed to parse AI cleanup rules"),
+            )
+            .await?;
+        Ok(response.rules)
+    }
+
+    /// The email body cut down to `model`'s budget, or `default_tokens` when it has none
+    fn excerpt(&self, body: &str, model: &str, default_tokens: usize) -> String {
+        let tokens = self
+            .context_tokens
+            .get(model)
+            .copied()
+            .unwrap_or(default_tokens);
+        context::excerpt(body, tokens)
+    }
+
+    /// Ask each model in turn until one answers with something `parse` accepts;
+    /// returns the parsed answer and the model that gave it. `user` builds the
+    /// message for a model, so it can fit that model's budget.
+    async fn chat_with_fallback<T>(
+        &self,
+        models: &[String],
+        system: &str,
+        user: impl Fn(&str) -> String,
+        temperature: f32,
+        max_tokens: u32,
+        parse: impl Fn(&str) -> Result<T>,
+    ) -> Result<(T, String)> {
+        let mut failures = Vec::new();
+        for model in models {
+            let result = self
+                .chat(model, system, &user(model), temperature, max_tokens)
+                .await
+                .and_then(|content| parse(&content));
+            match result {
+                Ok(answer) => return Ok((answer, model.clone())),
+                Err(e) => {
+                    tracing::warn!(%model, error = %format!("{:#}", e), "AI model failed");
+                    failures.push(format!("{}: {:#}", model, e));
+                }
+            }
+        }
+        match failures.len() {
+            0 => anyhow::bail!("No AI model configured"),
+            1 => anyhow::bail!("{}", failures.remove(0)),
+            _ => anyhow::bail!("All models failed:\n{}", failures.join("\n")),
+        }
+    }
+
+    /// One chat completion; returns the reply text
+    async fn chat(
+        &self,
+        model: &str,
+        system: &str,
+        user: &str,
+        temperature: f32,
+        max_tokens: u32,
+    ) -> Result<String> {
+        let messages = vec![
+            ChatMessage {
+                role: "system".to_string(),
+                content: system.to_string(),
+            },
+            ChatMessage {
+                role: "user".to_string(),
+                content: user.to_string(),
+            },
+        ];
+        self.complete(model, messages, temperature, max_tokens)
+            .await
+    }
+
+    /// One chat completion over a whole conversation; returns the reply text
+    async fn complete(
+        &self,
+        model: &str,
+        messages: Vec<ChatMessage>,
+        temperature: f32,
+        max_tokens: u32,
+    ) -> Result<String> {
+        // The originals stay here; the model only sees placeholders, and its
+        // answer gets the originals back so summaries still read right
+        let mut redactions = Redactions::new();
+        let messages = if self.redact_pii {
+            let messages: Vec<ChatMessage> = messages
+                .into_iter()
+                .map(|m| ChatMessage {
+                    content: redactions.redact(&m.content),
+                    role: m.role,
+                })
+                .collect();
+            tracing::debug!(masked = redactions.len(), "Redacted personal data");
+            messages
+        } else {
+            messages
+        };
+
+        let request = ChatRequest {
+            model: model.to_string(),
+            messages,
+            temperature: Some(temperature),
+            max_tokens: Some(max_tokens),
+            usage: UsageOptions { include: true },
+        };
+
+        let started = std::time::Instant::now();
+        let response = self
+            .http
+            .post(OPENROUTER_API_URL)
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .header("HTTP-Referer", "https://github.com/clinbox")
+            .header("X-Title", "Clinbox")
+            .json(&request)
+            .send()
+            .await
+            .context("Failed to call AI API")?;
+        timings::record(timings::Kind::Ai, started.elapsed());
+        tracing::debug!(
+            %model,
+            status = response.status().as_u16(),
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "AI request"
+        );
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            diagnostics::record_api_error("ai", status.as_u16(), OPENROUTER_API_URL, &body);
+            anyhow::bail!("AI API error {}: {}", status, body);
+        }
+
+        let chat_response: ChatResponse = response
+            .json()
+            .await
+            .context("Failed to parse AI response")?;
+        if let Some(usage) = &chat_response.usage {
+            timings::record_usage(usage.prompt_tokens, usage.completion_tokens, usage.cost);
+        }
+
+        let content = chat_response
+            .choices
+            .first()
+            .map(|c| c.message.content.clone())
+            .unwrap_or_default();
+        Ok(redactions.restore(&content))
+    }
+}
+
+/// "\nBulk mail: List-Id: ..." for the prompt, or nothing
+fn bulk_line(email: &Email) -> String {
+    email
+        .bulk_signal()
+        .map(|signal| format!("\nBulk mail: {}", signal))
+        .unwrap_or_default()
+}
+
+/// "\nAuthentication: SPF pass · DKIM pass · DMARC fail (header.from example.com)"
+/// for the prompt, or nothing
+fn authentication_line(email: &Email) -> String {
+    email
+        .authentication()
+        .map(|auth| {
+            let domain = auth
+                .from_domain
+                .as_ref()
+                .map(|d| format!(" (header.from {})", d))
+                .unwrap_or_default();
+            format!("\nAuthentication: {}{}", auth.describe(), domain)
+        })
+        .unwrap_or_default()
+}
+
+/// Parse a JSON answer, tolerating a markdown code fence around it
+fn parse_json<T: serde::de::DeserializeOwned>(content: &str) -> Result<T> {
+    let json_str = content
+        .trim()
+        .trim_start_matches("```json")
+        .trim_start_matches("```")
+        .trim_end_matches("```")
+        .trim();
+    Ok(serde_json::from_str(json_str)?)
+}
+
+#[derive(Debug, Serialize)]
+struct ChatRequest {
+    model: String,
+    messages: Vec<ChatMessage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
+    /// Asks OpenRouter to report token counts and cost
+    usage: UsageOptions,
+}
+
+#[derive(Debug, Serialize)]
+struct UsageOptions {
+    include: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct ChatMessage {
+    role: String,
+    content: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatResponse {
+    choices: Vec<Choice>,
+    usage: Option<Usage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Usage {
+    #[serde(default)]
+    prompt_tokens: u64,
+    #[serde(default)]
+    completion_tokens: u64,
+    /// USD, when OpenRouter reports it
+    cost: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Choice {
+    message: ResponseMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct ResponseMessage {
+    content: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnalysisResponse {
+    priority: Priority,
+    category: Category,
+    summary: String,
+    suggested_action: Option<String>,
+    estimated_time_minutes: Option<u32>,
+    confidence: Option<f32>,
+    reasoning: Option<String>,
+    proposed_times: Option<Vec<String>>,
+    snooze_until: Option<String>,
+}
@@ -0,0 +1,78 @@
+//! SPF, DKIM and DMARC verdicts from the Authentication-Results header the
+//! receiving server (Gmail) adds to each message
+
+/// What the receiving server concluded about the sender's authenticity
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Authentication {
+    /// SPF result, e.g. "pass" or "softfail"
+    pub spf: Option<String>,
+    /// DKIM result; with several signatures, a passing one wins
+    pub dkim: Option<String>,
+    /// DMARC result
+    pub dmarc: Option<String>,
+    /// Domain of the From header, as DMARC checked it
+    pub from_domain: Option<String>,
+}
+
+impl Authentication {
+    /// Parse an Authentication-Results header such as `mx.google.com;
+    /// dkim=pass header.i=@example.com; spf=pass ...; dmarc=pass (p=REJECT)
+    /// header.from=example.com`
+    pub fn parse(header: &str) -> Self {
+        let mut auth = Self::default();
+
+        // The first part names the server that checked
+        for result in header.split(';').skip(1) {
+            let mut words = result.split_whitespace();
+            let Some((method, verdict)) = words.next().and_then(|w| w.split_once('=')) else {
+                continue;
+            };
+            let verdict = verdict.to_lowercase();
+            match method.to_lowercase().as_str() {
+                "spf" => {
+                    auth.spf.get_or_insert(verdict);
+                }
+                "dkim" if auth.dkim.as_deref() != Some("pass") => auth.dkim = Some(verdict),
+                "dmarc" => {
+                    auth.dmarc.get_or_insert(verdict);
+                    auth.from_domain =
+                        words.find_map(|w| w.strip_prefix("header.from=")).map(|d| {
+                            d.trim_matches(|c: char| c == '"' || c == ')')
+                                .to_lowercase()
+                        });
+                }
+                _ => {}
+            }
+        }
+        auth
+    }
+
+    /// Whether any of the three was checked
+    pub fn is_empty(&self) -> bool {
+        self.spf.is_none() && self.dkim.is_none() && self.dmarc.is_none()
+    }
+
+    /// Whether the sender failed authentication: DMARC failed, or without a
+    /// DMARC verdict, SPF failed and DKIM didn't pass
+    pub fn failed(&self) -> bool {
+        let fails = |v: &Option<String>| matches!(v.as_deref(), Some("fail" | "softfail"));
+        match self.dmarc.as_deref() {
+            Some("fail") => true,
+            Some("pass") => false,
+            _ => fails(&self.spf) && self.dkim.as_deref() != Some("pass"),
+        }
+    }
+
+    /// "SPF pass · DKIM pass · DMARC fail", leaving out what wasn't checked
+    pub fn describe(&self) -> String {
+        [
+            ("SPF", &self.spf),
+            ("DKIM", &self.dkim),
+            ("DMARC", &self.dmarc),
+        ]
+        .into_iter()
+        .filter_map(|(name, verdict)| Some(format!("{} {}", name, verdict.as_deref()?)))
+        .collect::<Vec<_>>()
+        .join(" · ")
+    }
+}
@@ -0,0 +1,103 @@
+//! Fetched messages kept on disk, so a message seen before costs a small
+//! metadata request instead of a full download
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::config::Config;
+use crate::email::Email;
+
+/// Messages kept per account; the ones fetched longest ago go first
+const MAX_MESSAGES: usize = 2000;
+
+/// A cached message and the Gmail history id it was current at
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedMessage {
+    /// Gmail's `historyId` when the message was fetched; it changes whenever the
+    /// message does (in practice, its labels)
+    pub history_id: String,
+    /// The full message
+    pub email: Email,
+}
+
+/// One account's cache, one JSON file per message in ~/.clinbox/cache/<account>/
+#[derive(Debug, Clone)]
+pub struct MessageCache {
+    dir: PathBuf,
+}
+
+impl MessageCache {
+    /// The cache of an account
+    pub fn for_account(account_id: &str) -> Result<Self> {
+        Ok(Self {
+            dir: Config::message_cache_dir()?.join(account_id),
+        })
+    }
+
+    fn path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", id))
+    }
+
+    /// A cached message, if there is a readable one
+    pub fn get(&self, id: &str) -> Option<CachedMessage> {
+        let content = fs::read_to_string(self.path(id)).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Store a message, readable by the owner only since it holds mail content.
+    /// The directory and file are created with those permissions, so the
+    /// content is never readable by others, even briefly.
+    pub fn put(&self, message: &CachedMessage) -> Result<()> {
+        let mut dir = fs::DirBuilder::new();
+        dir.recursive(true);
+        let mut file = fs::OpenOptions::new();
+        file.write(true).create(true).truncate(true);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt};
+            dir.mode(0o700);
+            file.mode(0o600);
+        }
+
+        dir.create(&self.dir)?;
+        let path = self.path(&message.email.id);
+        let mut file = file.open(&path)?;
+
+        // The modes only apply to new ones; tighten those made before
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&self.dir, fs::Permissions::from_mode(0o700))?;
+            file.set_permissions(fs::Permissions::from_mode(0o600))?;
+        }
+
+        file.write_all(serde_json::to_string(message)?.as_bytes())?;
+        Ok(())
+    }
+
+    /// Drop the oldest files once there are more than `MAX_MESSAGES`
+    pub fn prune(&self) -> Result<()> {
+        if !self.dir.exists() {
+            return Ok(());
+        }
+        let mut files: Vec<(std::time::SystemTime, PathBuf)> = fs::read_dir(&self.dir)?
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let modified = entry.metadata().and_then(|m| m.modified()).ok()?;
+                Some((modified, entry.path()))
+            })
+            .collect();
+        if files.len() <= MAX_MESSAGES {
+            return Ok(());
+        }
+        files.sort();
+        for (_, path) in &files[..files.len() - MAX_MESSAGES] {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
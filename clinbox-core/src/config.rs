@@ -0,0 +1,867 @@
+//! `~/.clinbox/config.json` and the paths of the other files in `~/.clinbox/`
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::email::Priority;
+
+/// Individual Gmail account configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GmailAccount {
+    /// Name used to pick the account, e.g. "work"
+    pub id: String,
+    /// Address of the account, filled in after the first login
+    pub email: Option<String>,
+    /// OAuth client id from Google Cloud Console
+    pub client_id: String,
+    /// OAuth client secret
+    pub client_secret: String,
+    /// Addresses Bcc'd on every reply sent from this account, e.g. a CRM dropbox
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bcc: Vec<String>,
+}
+
+/// Contents of ~/.clinbox/config.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Config {
+    /// Gmail accounts and options
+    pub gmail: GmailConfig,
+    /// AI provider and models
+    pub ai: AiConfig,
+    /// Where tasks are stored
+    pub tasks: TasksConfig,
+    /// Language of AI summaries and drafts (e.g. "en", "es")
+    #[serde(default = "default_language")]
+    pub language: String,
+    /// Terminal UI options
+    #[serde(default)]
+    pub ui: UiConfig,
+    /// Senders that always escalate
+    #[serde(default)]
+    pub vip: VipConfig,
+    /// The user's own alias addresses, with optional per-alias rules
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<AliasConfig>,
+    /// Append each session's decisions to ~/.clinbox/journal/ in this format
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub journal: Option<JournalFormat>,
+    /// Commands run on triage events
+    #[serde(default)]
+    pub hooks: HooksConfig,
+    /// Default command for piping an email from the triage screen, e.g. "pass insert -m mail/{subject}"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pipe_command: Option<String>,
+    /// Bearer token for `clinbox serve`, generated on first use
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub serve_token: Option<String>,
+    /// Browser origins allowed to call `clinbox serve` (CORS), e.g.
+    /// "http://localhost:5173"; "*" allows any
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub serve_origins: Vec<String>,
+    /// Keybinding overrides, action name -> comma-separated keys (e.g. "archive": "e")
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub keys: HashMap<String, String>,
+    /// Proxy and TLS settings for the Gmail and AI connections
+    #[serde(default)]
+    pub network: NetworkConfig,
+    /// Live state of the issues and pull requests GitHub notifications are about
+    #[serde(default)]
+    pub github: GithubConfig,
+    /// Keep a daily log of API calls and errors in ~/.clinbox/logs/ (last 7 days)
+    #[serde(default)]
+    pub log_file: bool,
+    /// Offer groups of similar emails (same sender, similar subjects) as one card
+    /// before one-at-a-time triage
+    #[serde(default = "default_group_similar")]
+    pub group_similar: bool,
+}
+
+fn default_language() -> String {
+    "es".to_string()
+}
+
+fn default_group_similar() -> bool {
+    true
+}
+
+fn default_message_cache() -> bool {
+    true
+}
+
+fn default_history_messages() -> usize {
+    3
+}
+
+fn default_theme() -> String {
+    "default".to_string()
+}
+
+/// Gmail accounts and options
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GmailConfig {
+    /// Configured accounts
+    pub accounts: Vec<GmailAccount>,
+    /// Id of the account used when none is given
+    pub default_account: Option<String>,
+    /// Apply a `clinbox/<category>` label to archived and skipped emails
+    #[serde(default)]
+    pub category_labels: bool,
+    /// Show the sender's Google Contacts entry (needs the contacts.readonly scope)
+    #[serde(default)]
+    pub people_lookup: bool,
+    /// Request full mail access so `clinbox trash` can delete messages for good
+    #[serde(default)]
+    pub permanent_delete: bool,
+    /// Request access to settings so `clinbox cleanup suggest` can create Gmail filters
+    #[serde(default)]
+    pub manage_filters: bool,
+    /// Request Drive access so `clinbox status` and the dashboard can show storage use
+    #[serde(default)]
+    pub storage_quota: bool,
+    /// Request read access to Google Calendar so triage can flag proposed
+    /// meeting times that clash with existing events
+    #[serde(default)]
+    pub calendar_conflicts: bool,
+    /// Keep fetched messages in ~/.clinbox/cache/ and only re-check their labels
+    /// when they're fetched again
+    #[serde(default = "default_message_cache")]
+    pub message_cache: bool,
+    /// How much of the original message replies quote below the answer
+    #[serde(default)]
+    pub reply_quote: ReplyQuote,
+}
+
+/// How much of the original message a reply quotes, below an "On <date>,
+/// <sender> wrote:" line
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReplyQuote {
+    /// The whole original body
+    Full,
+    /// What the sender wrote themselves, without their signature or the
+    /// history they quoted
+    Trimmed,
+    /// Nothing; the reply is just the answer
+    #[default]
+    Off,
+}
+
+impl FromStr for ReplyQuote {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "full" => Ok(ReplyQuote::Full),
+            "trimmed" | "trim" => Ok(ReplyQuote::Trimmed),
+            "off" | "none" => Ok(ReplyQuote::Off),
+            _ => Err(format!(
+                "unknown reply quoting '{}' (expected full, trimmed or off)",
+                s
+            )),
+        }
+    }
+}
+
+/// AI provider and models
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AiConfig {
+    /// Provider name; only "openrouter" is supported
+    pub provider: String,
+    /// Provider API key
+    pub api_key: String,
+    /// Models used to classify emails, tried in order when one fails
+    pub model_analysis: ModelChain,
+    /// Model used to draft replies
+    pub model_reply: String,
+    /// Smarter model for re-analyzing an email on demand; the reply model when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_escalation: Option<String>,
+    /// Tokens of email body to send each model (by name), instead of the
+    /// per-request defaults; raise it for long-context models
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub context_tokens: HashMap<String, usize>,
+    /// Extra analysis instructions for mail from an address or domain (subdomains
+    /// included), e.g. "school.edu": "always action_required; keep dates exact"
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub sender_instructions: HashMap<String, String>,
+    /// Earlier messages with the sender to include when analyzing or drafting a
+    /// reply, so the model knows the ongoing conversation; 0 turns it off
+    #[serde(default = "default_history_messages")]
+    pub history_messages: usize,
+    /// Mask card numbers, ID numbers, phone numbers and street addresses
+    /// before email text goes to the model, restoring them in its answers
+    #[serde(default)]
+    pub redact_pii: bool,
+    /// Classify mail whose headers mark it as bulk (List-Id, Precedence,
+    /// Auto-Submitted, X-Mailer) locally, without an AI call
+    #[serde(default)]
+    pub skip_bulk: bool,
+}
+
+/// One model, or several to fall back through in order; written as a plain
+/// string or an array in the config file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelChain(Vec<String>);
+
+impl ModelChain {
+    /// The model tried first
+    pub fn primary(&self) -> &str {
+        self.0.first().map(String::as_str).unwrap_or_default()
+    }
+
+    /// All models, in the order they are tried
+    pub fn models(&self) -> &[String] {
+        &self.0
+    }
+}
+
+impl From<&str> for ModelChain {
+    fn from(model: &str) -> Self {
+        Self(vec![model.to_string()])
+    }
+}
+
+impl FromStr for ModelChain {
+    type Err = anyhow::Error;
+
+    /// Comma-separated model names, e.g. "google/gemini-2.0-flash-001,openai/gpt-4o-mini"
+    fn from_str(s: &str) -> Result<Self> {
+        let models: Vec<String> = s
+            .split(',')
+            .map(str::trim)
+            .filter(|m| !m.is_empty())
+            .map(str::to_string)
+            .collect();
+        if models.is_empty() {
+            anyhow::bail!("At least one model is required");
+        }
+        Ok(Self(models))
+    }
+}
+
+impl std::fmt::Display for ModelChain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.join(" → "))
+    }
+}
+
+impl Serialize for ModelChain {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.0.as_slice() {
+            [model] => serializer.serialize_str(model),
+            models => models.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ModelChain {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            One(String),
+            Many(Vec<String>),
+        }
+        let models = match Raw::deserialize(deserializer)? {
+            Raw::One(model) => vec![model],
+            Raw::Many(models) => models,
+        };
+        if models.is_empty() {
+            return Err(serde::de::Error::custom(
+                "model_analysis needs at least one model",
+            ));
+        }
+        Ok(Self(models))
+    }
+}
+
+/// Where tasks are stored
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TasksConfig {
+    /// Task backend; only "local" is supported
+    pub provider: String,
+    /// Tasks file, instead of ~/.clinbox/tasks.json
+    pub file_path: Option<PathBuf>,
+}
+
+/// Senders that always escalate, regardless of the AI classification
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VipConfig {
+    /// Addresses or domains (e.g. "boss@acme.com", "bigclient.com")
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub senders: Vec<String>,
+    /// Show a desktop notification when VIP mail is fetched
+    #[serde(default)]
+    pub desktop_notify: bool,
+    /// URL that receives a JSON POST when VIP mail is fetched
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webhook: Option<String>,
+}
+
+/// One of the user's own addresses (plus-address, custom domain or catch-all)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AliasConfig {
+    /// Full address ("me+shopping@gmail.com") or a catch-all domain ("mydomain.dev")
+    pub address: String,
+    /// Priority given to mail sent to this alias, overriding the AI
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<Priority>,
+}
+
+/// Looking up GitHub notifications' issues and pull requests during triage
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GithubConfig {
+    /// Show whether the issue or pull request is still open, merged or closed,
+    /// and its CI result
+    #[serde(default)]
+    pub status: bool,
+    /// Personal access token, for private repositories and a higher rate limit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+}
+
+/// Proxy and TLS settings; HTTPS_PROXY/NO_PROXY from the environment apply too
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NetworkConfig {
+    /// Proxy for all requests (e.g. "http://proxy.corp:3128"), instead of HTTPS_PROXY
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    /// PEM file of extra root certificates, e.g. a TLS-intercepting firewall's CA
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ca_bundle: Option<PathBuf>,
+    /// Seconds before a request is abandoned (default 60; AI summaries can be slow)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+    /// Seconds to wait for a connection to open (default 10)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connect_timeout_secs: Option<u64>,
+}
+
+impl NetworkConfig {
+    /// An HTTP client that goes through the configured proxy, trusts the CA bundle
+    /// and gives up after the configured timeouts
+    pub fn client(&self) -> Result<reqwest::Client> {
+        self.client_builder()?
+            .build()
+            .context("Failed to set up the HTTP client")
+    }
+
+    /// The settings `client` uses, for callers that need more (e.g. no redirects)
+    pub fn client_builder(&self) -> Result<reqwest::ClientBuilder> {
+        let mut builder = reqwest::Client::builder()
+            .timeout(Duration::from_secs(self.timeout_secs.unwrap_or(60)))
+            .connect_timeout(Duration::from_secs(self.connect_timeout_secs.unwrap_or(10)));
+
+        if let Some(url) = &self.proxy {
+            let proxy = reqwest::Proxy::all(url)
+                .with_context(|| format!("Invalid proxy URL: {}", url))?
+                .no_proxy(reqwest::NoProxy::from_env());
+            builder = builder.proxy(proxy);
+        }
+
+        if let Some(path) = &self.ca_bundle {
+            let pem = fs::read(path)
+                .with_context(|| format!("Failed to read CA bundle {}", path.display()))?;
+            let certs = reqwest::Certificate::from_pem_bundle(&pem)
+                .with_context(|| format!("Invalid CA bundle {}", path.display()))?;
+            for cert in certs {
+                builder = builder.add_root_certificate(cert);
+            }
+        }
+
+        Ok(builder)
+    }
+}
+
+/// Commands run on triage events; each gets the email and analysis as JSON on stdin
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HooksConfig {
+    /// Runs when an email is archived
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_archive: Option<String>,
+    /// Runs when an email is deleted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_delete: Option<String>,
+    /// Runs when an email is marked read
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_mark_read: Option<String>,
+    /// Runs when an email is moved to a label
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_move: Option<String>,
+    /// Runs when a task is created from an email
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_task_created: Option<String>,
+    /// Runs when a reply is sent or scheduled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_reply: Option<String>,
+    /// Runs when an email is skipped
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_skip: Option<String>,
+    /// Runs when a sender is blocked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_block: Option<String>,
+    /// Runs once per session for each email the analysis marks urgent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_urgent: Option<String>,
+}
+
+/// File format of the session journal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JournalFormat {
+    /// Human-readable Markdown, one section per session
+    Markdown,
+    /// One JSON object per session
+    Jsonl,
+}
+
+impl JournalFormat {
+    /// File extension of journal files in this format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            JournalFormat::Markdown => "md",
+            JournalFormat::Jsonl => "jsonl",
+        }
+    }
+}
+
+impl FromStr for JournalFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "markdown" | "md" => Ok(JournalFormat::Markdown),
+            "jsonl" | "json" => Ok(JournalFormat::Jsonl),
+            _ => Err(format!(
+                "unknown journal format '{}' (expected markdown or jsonl)",
+                s
+            )),
+        }
+    }
+}
+
+/// Terminal UI appearance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UiConfig {
+    /// Theme preset name (default, solarized, monochrome)
+    #[serde(default = "default_theme")]
+    pub theme: String,
+    /// Per-color overrides applied on top of the preset (e.g. "urgent": "#ff5555")
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub colors: HashMap<String, String>,
+    /// Language of screens and messages
+    #[serde(default)]
+    pub locale: Locale,
+    /// Text badges such as "[URGENT]" instead of emoji, for terminals without an emoji font
+    #[serde(default)]
+    pub ascii: bool,
+}
+
+/// Language of the interface (AI output follows `language` instead)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    /// English
+    #[default]
+    En,
+    /// Spanish
+    Es,
+}
+
+impl FromStr for Locale {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "en" | "english" => Ok(Locale::En),
+            "es" | "spanish" | "español" => Ok(Locale::Es),
+            _ => Err(format!("unknown locale '{}' (expected en or es)", s)),
+        }
+    }
+}
+
+impl Default for UiConfig {
+    fn default() -> Self {
+        Self {
+            theme: default_theme(),
+            colors: HashMap::new(),
+            locale: Locale::default(),
+            ascii: false,
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            gmail: GmailConfig {
+                accounts: Vec::new(),
+                default_account: None,
+                category_labels: false,
+                people_lookup: false,
+                permanent_delete: false,
+                manage_filters: false,
+                storage_quota: false,
+                calendar_conflicts: false,
+                message_cache: true,
+                reply_quote: ReplyQuote::Off,
+            },
+            ai: AiConfig {
+                provider: "openrouter".to_string(),
+                api_key: String::new(),
+                model_analysis: ModelChain::from("google/gemini-2.0-flash-001"),
+                model_reply: "anthropic/claude-sonnet-4".to_string(),
+                model_escalation: None,
+                context_tokens: HashMap::new(),
+                sender_instructions: HashMap::new(),
+                history_messages: default_history_messages(),
+                redact_pii: false,
+                skip_bulk: false,
+            },
+            tasks: TasksConfig {
+                provider: "local".to_string(),
+                file_path: None,
+            },
+            language: default_language(),
+            ui: UiConfig::default(),
+            vip: VipConfig::default(),
+            aliases: Vec::new(),
+            journal: None,
+            pipe_command: None,
+            serve_token: None,
+            serve_origins: Vec::new(),
+            hooks: HooksConfig::default(),
+            keys: HashMap::new(),
+            network: NetworkConfig::default(),
+            github: GithubConfig::default(),
+            log_file: false,
+            group_similar: true,
+        }
+    }
+}
+
+impl Config {
+    /// Returns the config directory path (~/.clinbox)
+    pub fn config_dir() -> Result<PathBuf> {
+        let home = dirs::home_dir().context("Could not find home directory")?;
+        Ok(home.join(".clinbox"))
+    }
+
+    /// Returns the config file path
+    pub fn config_path() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join("config.json"))
+    }
+
+    /// Returns the tokens directory path (~/.clinbox/tokens)
+    pub fn tokens_dir() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join("tokens"))
+    }
+
+    /// Returns the token file path for a specific account
+    pub fn token_path_for_account(account_id: &str) -> Result<PathBuf> {
+        Ok(Self::tokens_dir()?.join(format!("{}.json", account_id)))
+    }
+
+    /// Returns the legacy token file path (for migration)
+    fn legacy_token_path() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join("token.json"))
+    }
+
+    /// Returns the tasks file path
+    pub fn tasks_path() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join("tasks.json"))
+    }
+
+    /// Returns the saved triage session path
+    pub fn session_path() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join("session.json"))
+    }
+
+    /// Returns the path of the seen-messages history
+    pub fn history_path() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join("history.json"))
+    }
+
+    /// Returns the path of the recently used labels
+    pub fn recent_labels_path() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join("labels.json"))
+    }
+
+    /// Returns the path of the local contacts book
+    pub fn contacts_path() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join("contacts.json"))
+    }
+
+    /// Returns the path of the blocked senders list
+    pub fn blocklist_path() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join("blocklist.json"))
+    }
+
+    /// Returns the path of the scheduled replies queue
+    pub fn outbox_path() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join("outbox.json"))
+    }
+
+    /// Returns the path of the writing style profile learned from sent mail
+    pub fn style_path() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join("style.md"))
+    }
+
+    /// Returns the path of the snoozed messages list
+    pub fn snoozed_path() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join("snoozed.json"))
+    }
+
+    /// Returns the path of the read-later list
+    pub fn later_path() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join("later.json"))
+    }
+
+    /// Returns the path of the recent API error responses
+    pub fn api_errors_path() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join("api_errors.json"))
+    }
+
+    /// Returns the log directory path (~/.clinbox/logs)
+    pub fn logs_dir() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join("logs"))
+    }
+
+    /// Returns the path of the recorded disagreements with the AI
+    pub fn feedback_path() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join("feedback.json"))
+    }
+
+    /// Returns the session journal directory path (~/.clinbox/journal)
+    pub fn journal_dir() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join("journal"))
+    }
+
+    /// Returns the fetched-message cache directory (~/.clinbox/cache)
+    pub fn message_cache_dir() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join("cache"))
+    }
+
+    /// Returns the directory of scripted triage rules (~/.clinbox/rules)
+    pub fn rules_dir() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join("rules"))
+    }
+
+    /// Returns the summaries directory path (~/Documents/Clinbox)
+    pub fn summaries_dir() -> Result<PathBuf> {
+        let home = dirs::home_dir().context("Could not find home directory")?;
+        Ok(home.join("Documents").join("Clinbox"))
+    }
+
+    /// Returns the path of the expense ledger filled from invoices during triage
+    pub fn expenses_path() -> Result<PathBuf> {
+        Ok(Self::summaries_dir()?.join("expenses.csv"))
+    }
+
+    /// Returns the directory emails are exported to from the triage screen
+    pub fn exports_dir() -> Result<PathBuf> {
+        Ok(Self::summaries_dir()?.join("exports"))
+    }
+
+    /// Load config from file or create default, with automatic migration
+    pub fn load() -> Result<Self> {
+        let config_path = Self::config_path()?;
+
+        if config_path.exists() {
+            let content = fs::read_to_string(&config_path).context("Failed to read config file")?;
+
+            // Try to parse as new format first
+            if let Ok(config) = serde_json::from_str::<Config>(&content) {
+                return Ok(config);
+            }
+
+            // Try to parse as legacy format and migrate
+            if let Ok(legacy) = serde_json::from_str::<LegacyConfig>(&content) {
+                return Self::migrate_legacy(legacy);
+            }
+
+            // Failed to parse either format
+            anyhow::bail!("Failed to parse config file. Please check the format.");
+        }
+
+        Ok(Config::default())
+    }
+
+    /// Migrate from legacy single-account config to new multi-account format
+    fn migrate_legacy(legacy: LegacyConfig) -> Result<Self> {
+        let mut config = Config {
+            gmail: GmailConfig {
+                accounts: Vec::new(),
+                default_account: None,
+                category_labels: false,
+                people_lookup: false,
+                permanent_delete: false,
+                manage_filters: false,
+                storage_quota: false,
+                calendar_conflicts: false,
+                message_cache: true,
+                reply_quote: ReplyQuote::Off,
+            },
+            ai: legacy.ai,
+            tasks: legacy.tasks,
+            language: default_language(),
+            ui: UiConfig::default(),
+            vip: VipConfig::default(),
+            aliases: Vec::new(),
+            journal: None,
+            pipe_command: None,
+            serve_token: None,
+            serve_origins: Vec::new(),
+            hooks: HooksConfig::default(),
+            keys: HashMap::new(),
+            network: NetworkConfig::default(),
+            github: GithubConfig::default(),
+            log_file: false,
+            group_similar: true,
+        };
+
+        // If legacy had credentials, create a "default" account
+        if !legacy.gmail.client_id.is_empty() && !legacy.gmail.client_secret.is_empty() {
+            let account = GmailAccount {
+                id: "default".to_string(),
+                email: None,
+                client_id: legacy.gmail.client_id,
+                client_secret: legacy.gmail.client_secret,
+                bcc: Vec::new(),
+            };
+            config.gmail.accounts.push(account);
+            config.gmail.default_account = Some("default".to_string());
+
+            // Migrate token file
+            let legacy_token = Self::legacy_token_path()?;
+            if legacy_token.exists() {
+                let tokens_dir = Self::tokens_dir()?;
+                fs::create_dir_all(&tokens_dir)?;
+                let new_token_path = Self::token_path_for_account("default")?;
+                fs::rename(&legacy_token, &new_token_path)
+                    .context("Failed to migrate token file")?;
+            }
+        }
+
+        // Save the migrated config
+        config.save()?;
+
+        Ok(config)
+    }
+
+    /// Save config to file
+    pub fn save(&self) -> Result<()> {
+        let config_dir = Self::config_dir()?;
+        fs::create_dir_all(&config_dir).context("Failed to create config directory")?;
+
+        let config_path = Self::config_path()?;
+        let content = serde_json::to_string_pretty(self).context("Failed to serialize config")?;
+        fs::write(&config_path, content).context("Failed to write config file")?;
+
+        Ok(())
+    }
+
+    /// Check if the config is valid for operation (has at least one account and AI key)
+    pub fn is_valid(&self) -> bool {
+        !self.gmail.accounts.is_empty() && !self.ai.api_key.is_empty()
+    }
+
+    /// Get account by ID
+    pub fn get_account(&self, id: &str) -> Option<&GmailAccount> {
+        self.gmail.accounts.iter().find(|a| a.id == id)
+    }
+
+    /// Get the default account
+    pub fn get_default_account(&self) -> Option<&GmailAccount> {
+        if let Some(default_id) = &self.gmail.default_account {
+            self.get_account(default_id)
+        } else {
+            self.gmail.accounts.first()
+        }
+    }
+
+    /// Add a new account
+    pub fn add_account(&mut self, account: GmailAccount) -> Result<()> {
+        if self.gmail.accounts.iter().any(|a| a.id == account.id) {
+            anyhow::bail!("Account '{}' already exists", account.id);
+        }
+
+        // Set as default if it's the first account
+        if self.gmail.accounts.is_empty() {
+            self.gmail.default_account = Some(account.id.clone());
+        }
+
+        self.gmail.accounts.push(account);
+        self.save()
+    }
+
+    /// Remove an account
+    pub fn remove_account(&mut self, id: &str) -> Result<()> {
+        let initial_len = self.gmail.accounts.len();
+        self.gmail.accounts.retain(|a| a.id != id);
+
+        if self.gmail.accounts.len() == initial_len {
+            anyhow::bail!("Account '{}' not found", id);
+        }
+
+        // Remove token file
+        let token_path = Self::token_path_for_account(id)?;
+        if token_path.exists() {
+            fs::remove_file(&token_path)?;
+        }
+
+        // Update default if needed
+        if self.gmail.default_account.as_deref() == Some(id) {
+            self.gmail.default_account = self.gmail.accounts.first().map(|a| a.id.clone());
+        }
+
+        self.save()
+    }
+
+    /// Set the default account
+    pub fn set_default_account(&mut self, id: &str) -> Result<()> {
+        if !self.gmail.accounts.iter().any(|a| a.id == id) {
+            anyhow::bail!("Account '{}' not found", id);
+        }
+
+        self.gmail.default_account = Some(id.to_string());
+        self.save()
+    }
+
+    /// Set the addresses Bcc'd on every reply from an account
+    pub fn set_account_bcc(&mut self, id: &str, bcc: Vec<String>) -> Result<()> {
+        let Some(account) = self.gmail.accounts.iter_mut().find(|a| a.id == id) else {
+            anyhow::bail!("Account '{}' not found", id);
+        };
+        account.bcc = bcc;
+        self.save()
+    }
+
+    /// Update account email after OAuth
+    pub fn update_account_email(&mut self, id: &str, email: String) -> Result<()> {
+        if let Some(account) = self.gmail.accounts.iter_mut().find(|a| a.id == id) {
+            account.email = Some(email);
+            self.save()?;
+        }
+        Ok(())
+    }
+}
+
+/// Legacy config format for migration
+#[derive(Debug, Deserialize)]
+struct LegacyConfig {
+    gmail: LegacyGmailConfig,
+    ai: AiConfig,
+    tasks: TasksConfig,
+}
+
+#[derive(Debug, Deserialize)]
+struct LegacyGmailConfig {
+    client_id: String,
+    client_secret: String,
+}
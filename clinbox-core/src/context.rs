@@ -0,0 +1,139 @@
+//! Fitting an email body into the context sent to a model
+
+/// Rough size of a token in characters, for budgeting without a tokenizer
+pub const CHARS_PER_TOKEN: usize = 4;
+
+/// Marks where paragraphs were left out
+const OMITTED: &str = "[…]";
+
+/// The part of an email body worth sending to a model, within `max_tokens`.
+///
+/// Quoted history and the signature are dropped. When the rest is still too
+/// long, the opening paragraphs are kept along with the closing ones, which
+/// are usually where the ask is.
+pub fn excerpt(body: &str, max_tokens: usize) -> String {
+    let own = own_text(body);
+    let paragraphs: Vec<&str> = own
+        .split("\n\n")
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .collect();
+    fit(&paragraphs, max_tokens * CHARS_PER_TOKEN)
+}
+
+/// What the sender wrote themselves: lines up to the quoted history or signature,
+/// without `>` quotes. Falls back to the whole body when nothing is left.
+pub fn own_text(body: &str) -> String {
+    let mut lines: Vec<&str> = Vec::new();
+    for line in body.lines() {
+        let trimmed = line.trim();
+        if is_signature_start(line) || is_history_start(trimmed) {
+            break;
+        }
+        if trimmed.ends_with("wrote:") || trimmed.ends_with("escribió:") {
+            // "On <date>, <name> wrote:", often wrapped over two lines
+            if is_attribution_start(trimmed) {
+                break;
+            }
+            if lines
+                .last()
+                .is_some_and(|l| is_attribution_start(l.trim_start()))
+            {
+                lines.pop();
+                break;
+            }
+        }
+        if trimmed.starts_with('>') {
+            continue;
+        }
+        lines.push(line.trim_end());
+    }
+
+    let own = lines.join("\n");
+    if own.trim().is_empty() {
+        body.to_string()
+    } else {
+        own
+    }
+}
+
+/// The "-- " separator, or a mobile client's footer
+fn is_signature_start(line: &str) -> bool {
+    let trimmed = line.trim();
+    trimmed == "--"
+        || ["Sent from my ", "Enviado desde mi ", "Get Outlook for "]
+            .iter()
+            .any(|footer| trimmed.starts_with(footer))
+}
+
+/// "On ..." / "El ...", how reply attributions begin
+fn is_attribution_start(line: &str) -> bool {
+    line.starts_with("On ") || line.starts_with("El ")
+}
+
+/// Separators clients put above the message being replied to
+fn is_history_start(line: &str) -> bool {
+    line.contains("-----Original Message-----")
+        || line.contains("-----Mensaje original-----")
+        || (line.len() >= 10 && line.chars().all(|c| c == '_'))
+}
+
+/// Join paragraphs, leaving out middle ones when they don't fit in `max_chars`
+fn fit(paragraphs: &[&str], max_chars: usize) -> String {
+    let total: usize = paragraphs.iter().map(|p| p.chars().count() + 2).sum();
+    if total <= max_chars + 2 {
+        return paragraphs.join("\n\n");
+    }
+
+    // The closing paragraphs, as many as fit in a third of the budget
+    let mut tail_len = 0;
+    let mut split = paragraphs.len();
+    while split > 1 {
+        let len = paragraphs[split - 1].chars().count() + 2;
+        if tail_len + len > max_chars / 3 {
+            break;
+        }
+        tail_len += len;
+        split -= 1;
+    }
+    let (body, closing) = paragraphs.split_at(split);
+    let reserved = match closing {
+        [] => 0,
+        _ => tail_len + OMITTED.chars().count() + 2,
+    };
+    let budget = max_chars.saturating_sub(reserved);
+
+    let mut kept: Vec<String> = Vec::new();
+    let mut used = 0;
+    for paragraph in body {
+        let len = paragraph.chars().count();
+        if used + len <= budget {
+            kept.push(paragraph.to_string());
+            used += len + 2;
+        } else {
+            if kept.is_empty() {
+                kept.push(cut(paragraph, budget));
+            }
+            break;
+        }
+    }
+
+    if kept.len() < body.len() {
+        kept.push(OMITTED.to_string());
+    }
+    kept.extend(closing.iter().map(|p| p.to_string()));
+    kept.join("\n\n")
+}
+
+/// At most `max_chars` characters of `text`, ending at a word boundary
+fn cut(text: &str, max_chars: usize) -> String {
+    let Some((end, _)) = text.char_indices().nth(max_chars.saturating_sub(1)) else {
+        return text.to_string();
+    };
+    let head = &text[..end];
+    let head = match head.rfind(char::is_whitespace) {
+        Some(i) if i > 0 => &head[..i],
+        _ => head,
+    };
+    format!("{}…", head.trim_end())
+}
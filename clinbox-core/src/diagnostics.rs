@@ -0,0 +1,61 @@
+//! The last few error responses from Gmail and the AI API, kept for bug reports
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+use crate::config::Config;
+
+/// Error responses kept; older ones are dropped
+const MAX_ERRORS: usize = 20;
+
+/// Longest body kept per error, in characters
+const MAX_BODY_CHARS: usize = 2000;
+
+/// One failed API call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiError {
+    /// When the response came in
+    pub at: DateTime<Utc>,
+    /// "gmail" or "ai"
+    pub api: String,
+    /// HTTP status code
+    pub status: u16,
+    /// Request URL, with query values removed
+    pub url: String,
+    /// Start of the response body
+    pub body: String,
+}
+
+/// Remember an error response in ~/.clinbox/api_errors.json. Best effort: a
+/// failure to write is ignored rather than hiding the original error.
+pub fn record_api_error(api: &str, status: u16, url: &str, body: &str) {
+    let mut errors = recent_api_errors().unwrap_or_default();
+    errors.push(ApiError {
+        at: Utc::now(),
+        api: api.to_string(),
+        status,
+        url: url.to_string(),
+        body: body.chars().take(MAX_BODY_CHARS).collect(),
+    });
+    let excess = errors.len().saturating_sub(MAX_ERRORS);
+    errors.drain(..excess);
+
+    let saved = Config::api_errors_path().and_then(|path| {
+        fs::write(path, serde_json::to_string_pretty(&errors)?)?;
+        Ok(())
+    });
+    if let Err(e) = saved {
+        tracing::debug!(error = %e, "Failed to record API error");
+    }
+}
+
+/// Recorded error responses, oldest first
+pub fn recent_api_errors() -> Result<Vec<ApiError>> {
+    let path = Config::api_errors_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
@@ -0,0 +1,404 @@
+//! The `Email` model and its AI `EmailAnalysis`
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::auth::Authentication;
+use crate::config::ReplyQuote;
+
+/// Newsletter and campaign software, as named in X-Mailer
+const BULK_MAILERS: &[&str] = &[
+    "mailchimp",
+    "sendgrid",
+    "mailgun",
+    "sendinblue",
+    "brevo",
+    "hubspot",
+    "marketo",
+    "klaviyo",
+    "mailjet",
+    "constant contact",
+    "campaign monitor",
+    "phplist",
+    "mailerlite",
+    "substack",
+];
+
+/// A Gmail message with its decoded bodies
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Email {
+    /// Gmail message id
+    pub id: String,
+    /// Gmail thread id
+    pub thread_id: String,
+    /// Subject header
+    pub subject: String,
+    /// From header, e.g. `Ana <ana@example.com>`
+    pub from: String,
+    /// To header
+    pub to: String,
+    /// Date the message was received
+    pub date: DateTime<Utc>,
+    /// Short plain-text preview from Gmail
+    pub snippet: String,
+    /// text/plain body, if the message has one
+    pub body_plain: Option<String>,
+    /// text/html body, if the message has one
+    pub body_html: Option<String>,
+    /// Gmail label ids
+    pub labels: Vec<String>,
+    /// Attachments, without their data
+    pub attachments: Vec<Attachment>,
+    /// Whether the message has attachments; known from its MIME type even when
+    /// only metadata was fetched and `attachments` is empty
+    #[serde(default)]
+    pub has_attachments: bool,
+    /// Whether the message has the UNREAD label
+    pub is_unread: bool,
+    /// Size in bytes, attachments included (Gmail's estimate)
+    #[serde(default)]
+    pub size: u64,
+    /// Raw ICS of a calendar invitation, from a text/calendar part or .ics attachment
+    #[serde(default)]
+    pub calendar: Option<String>,
+    /// List-Id header, set on mailing list and newsletter mail
+    #[serde(default)]
+    pub list_id: Option<String>,
+    /// Precedence header, e.g. "bulk" or "list"
+    #[serde(default)]
+    pub precedence: Option<String>,
+    /// Auto-Submitted header, e.g. "auto-generated"; "no" for mail a person sent
+    #[serde(default)]
+    pub auto_submitted: Option<String>,
+    /// X-Mailer header, the software that sent the message
+    #[serde(default)]
+    pub x_mailer: Option<String>,
+    /// Authentication-Results header Gmail added on receipt
+    #[serde(default)]
+    pub authentication_results: Option<String>,
+}
+
+/// A header marking an email as sent in bulk or by a machine
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BulkSignal {
+    /// A mailing list's List-Id
+    List(String),
+    /// Precedence: bulk, list or junk
+    Precedence(String),
+    /// Auto-Submitted other than "no": notifications, auto-replies
+    AutoSubmitted(String),
+    /// An X-Mailer naming newsletter software
+    Mailer(String),
+}
+
+impl BulkSignal {
+    /// Whether it points to a newsletter or mailing list rather than a
+    /// machine-generated notification
+    pub fn is_newsletter(&self) -> bool {
+        !matches!(self, BulkSignal::AutoSubmitted(_))
+    }
+}
+
+impl fmt::Display for BulkSignal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BulkSignal::List(id) => write!(f, "List-Id: {}", id),
+            BulkSignal::Precedence(value) => write!(f, "Precedence: {}", value),
+            BulkSignal::AutoSubmitted(value) => write!(f, "Auto-Submitted: {}", value),
+            BulkSignal::Mailer(name) => write!(f, "X-Mailer: {}", name),
+        }
+    }
+}
+
+/// An attachment's metadata; the bytes are downloaded separately
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Attachment {
+    /// File name as sent
+    pub filename: String,
+    /// MIME type, e.g. `application/pdf`
+    pub mime_type: String,
+    /// Size in bytes
+    pub size: u64,
+    /// Gmail attachment id, used to download the data
+    pub attachment_id: String,
+}
+
+/// The AI's triage of an email
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailAnalysis {
+    /// Id of the analyzed email
+    pub email_id: String,
+    /// How soon the email needs attention
+    pub priority: Priority,
+    /// What the email is about
+    pub category: Category,
+    /// One or two sentence summary
+    pub summary: String,
+    /// What to do about it, if anything
+    pub suggested_action: Option<String>,
+    /// Rough time to deal with it
+    pub estimated_time_minutes: u32,
+    /// How sure the model is of the priority, from 0 to 1; absent in older analyses
+    /// and when a VIP or alias rule overrode the priority
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f32>,
+    /// Why the model chose this priority, in a sentence or two
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning: Option<String>,
+    /// Meeting times the email proposes, as "YYYY-MM-DD HH:MM" followed by a UTC
+    /// offset such as " +02:00" when the email gives a timezone
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub proposed_times: Vec<String>,
+    /// Day to bring the email back when it's about a future date (a renewal, a
+    /// deadline), as "YYYY-MM-DD"; offered when snoozing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snooze_until: Option<String>,
+    /// Model that produced the analysis, which may be a fallback
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+}
+
+impl EmailAnalysis {
+    /// " (85%)" to show after the priority, or nothing when the confidence is unknown
+    pub fn confidence_label(&self) -> String {
+        self.confidence
+            .map(|c| format!(" ({:.0}%)", c.clamp(0.0, 1.0) * 100.0))
+            .unwrap_or_default()
+    }
+}
+
+/// Triage priority, most pressing first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Priority {
+    /// Needs attention now
+    Urgent,
+    /// Needs a reply or action, not right away
+    ActionRequired,
+    /// Worth reading, nothing to do
+    Informative,
+    /// Can be ignored
+    Low,
+    /// Unwanted mail
+    Spam,
+}
+
+impl Priority {
+    /// Colored dot shown next to the email
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            Priority::Urgent => "🔴",
+            Priority::ActionRequired => "🟡",
+            Priority::Informative => "🔵",
+            Priority::Low => "⚪",
+            Priority::Spam => "🗑️",
+        }
+    }
+
+    /// Mark that tells priorities apart without relying on color, e.g. "!!"
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Priority::Urgent => "!!",
+            Priority::ActionRequired => "!",
+            Priority::Informative => "i",
+            Priority::Low => "-",
+            Priority::Spam => "x",
+        }
+    }
+
+    /// Short uppercase label, e.g. "URGENT"
+    pub fn label(&self) -> &'static str {
+        match self {
+            Priority::Urgent => "URGENT",
+            Priority::ActionRequired => "ACTION",
+            Priority::Informative => "INFO",
+            Priority::Low => "LOW",
+            Priority::Spam => "SPAM",
+        }
+    }
+}
+
+/// Topic of an email
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Category {
+    /// Invoices, receipts, payments
+    Billing,
+    /// Logins, alerts, password resets
+    Security,
+    /// Servers, deploys, monitoring
+    Infrastructure,
+    /// Search Console and SEO tools
+    Seo,
+    /// Newsletters and mailing lists
+    Newsletter,
+    /// Mail from people
+    Personal,
+    /// GitHub notifications
+    Github,
+    /// Anything else
+    Other,
+}
+
+impl Category {
+    /// Display name, e.g. "Infra"
+    pub fn label(&self) -> &'static str {
+        match self {
+            Category::Billing => "Billing",
+            Category::Security => "Security",
+            Category::Infrastructure => "Infra",
+            Category::Seo => "SEO",
+            Category::Newsletter => "Newsletter",
+            Category::Personal => "Personal",
+            Category::Github => "GitHub",
+            Category::Other => "Other",
+        }
+    }
+
+    /// Lowercase name, as accepted by `--category` (e.g. "infrastructure")
+    pub fn name(&self) -> &'static str {
+        match self {
+            Category::Billing => "billing",
+            Category::Security => "security",
+            Category::Infrastructure => "infrastructure",
+            Category::Seo => "seo",
+            Category::Newsletter => "newsletter",
+            Category::Personal => "personal",
+            Category::Github => "github",
+            Category::Other => "other",
+        }
+    }
+}
+
+impl FromStr for Priority {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_value(serde_json::Value::String(s.to_lowercase())).map_err(|_| {
+            format!(
+                "unknown priority '{}' (expected urgent, action_required, informative, low, spam)",
+                s
+            )
+        })
+    }
+}
+
+impl FromStr for Category {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_value(serde_json::Value::String(s.to_lowercase())).map_err(|_| {
+            format!(
+                "unknown category '{}' (expected billing, security, infrastructure, seo, newsletter, personal, github, other)",
+                s
+            )
+        })
+    }
+}
+
+impl Email {
+    /// Get the body as plain text
+    pub fn body_text(&self) -> String {
+        if let Some(plain) = &self.body_plain
+            && !plain.is_empty()
+        {
+            return plain.clone();
+        }
+
+        if let Some(html) = &self.body_html
+            && !html.is_empty()
+            && let Some(text) = crate::html::to_text(html, 80)
+        {
+            return text;
+        }
+
+        self.snippet.clone()
+    }
+
+    /// The header marking the email as bulk or automated mail, if any
+    pub fn bulk_signal(&self) -> Option<BulkSignal> {
+        let header = |value: &Option<String>| {
+            value
+                .as_deref()
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+        };
+
+        if let Some(id) = header(&self.list_id) {
+            return Some(BulkSignal::List(id));
+        }
+        if let Some(value) = header(&self.precedence)
+            .filter(|v| matches!(v.to_lowercase().as_str(), "bulk" | "list" | "junk"))
+        {
+            return Some(BulkSignal::Precedence(value));
+        }
+        if let Some(value) = header(&self.auto_submitted).filter(|v| !v.eq_ignore_ascii_case("no"))
+        {
+            return Some(BulkSignal::AutoSubmitted(value));
+        }
+        header(&self.x_mailer)
+            .filter(|name| {
+                let name = name.to_lowercase();
+                BULK_MAILERS.iter().any(|mailer| name.contains(mailer))
+            })
+            .map(BulkSignal::Mailer)
+    }
+
+    /// Hosts of the read-tracking images in the HTML body, if any
+    pub fn trackers(&self) -> Vec<String> {
+        self.body_html
+            .as_deref()
+            .map(crate::html::trackers)
+            .unwrap_or_default()
+    }
+
+    /// SPF, DKIM and DMARC verdicts, if the message was checked
+    pub fn authentication(&self) -> Option<Authentication> {
+        let auth = Authentication::parse(self.authentication_results.as_deref()?);
+        (!auth.is_empty()).then_some(auth)
+    }
+
+    /// The original as a reply quotes it: an "On <date>, <sender> wrote:" line
+    /// and the body with each line behind "> ". None when quoting is off.
+    pub fn quoted(&self, quote: ReplyQuote) -> Option<String> {
+        let body = match quote {
+            ReplyQuote::Full => self.body_text(),
+            ReplyQuote::Trimmed => crate::context::own_text(&self.body_text()),
+            ReplyQuote::Off => return None,
+        };
+        let date = self
+            .date
+            .with_timezone(&chrono::Local)
+            .format("%a, %-d %b %Y at %H:%M");
+        let lines: Vec<String> = body
+            .trim_end()
+            .lines()
+            .map(|line| match line.trim_end() {
+                "" => ">".to_string(),
+                line if line.starts_with('>') => format!(">{}", line),
+                line => format!("> {}", line),
+            })
+            .collect();
+        Some(format!(
+            "On {}, {} wrote:\n{}",
+            date,
+            self.from,
+            lines.join("\n")
+        ))
+    }
+
+    /// Get a short sender name
+    pub fn sender_name(&self) -> String {
+        // Extract name from "Name <email@domain.com>" format
+        if let Some(idx) = self.from.find('<') {
+            let name = self.from[..idx].trim();
+            if !name.is_empty() {
+                return name.trim_matches('"').to_string();
+            }
+        }
+        self.from.clone()
+    }
+}
@@ -0,0 +1,2065 @@
+//! Gmail API client: OAuth, search, fetch, archive/delete/label, replies, drafts and contacts
+
+use anyhow::{Context, Result, bail};
+use base64::{
+    Engine as _,
+    engine::general_purpose::{URL_SAFE, URL_SAFE_NO_PAD},
+};
+use chrono::{DateTime, Utc};
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tokio::sync::{Mutex, watch};
+
+use crate::cache::{CachedMessage, MessageCache};
+use crate::config::{Config, GmailAccount, GmailConfig, ReplyQuote};
+use crate::diagnostics;
+use crate::email::{Attachment, Email};
+use crate::timings;
+
+const GMAIL_AUTH_URL: &str = "https://accounts.google.com/o/oauth2/v2/auth";
+
+/// Write token file with secure permissions (owner read/write only)
+fn write_token_file(path: &std::path::Path, content: &str) -> Result<()> {
+    fs::write(path, content)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let perms = std::fs::Permissions::from_mode(0o600);
+        fs::set_permissions(path, perms)?;
+    }
+
+    Ok(())
+}
+
+/// Record an error response for bug reports, and work out how long to back off
+/// if Gmail is throttling: a 429, or a 403 whose reason is `rateLimitExceeded`/
+/// `userRateLimitExceeded`. Retry-After wins over exponential backoff. Reading
+/// the body consumes it, so the response is rebuilt.
+async fn inspect_error(
+    response: Response,
+    url: &str,
+    attempt: u32,
+) -> Result<(Response, Option<Duration>)> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok((response, None));
+    }
+
+    let headers = response.headers().clone();
+    let body = response.bytes().await?;
+    let text = String::from_utf8_lossy(&body);
+    diagnostics::record_api_error("gmail", status.as_u16(), url, &text);
+
+    let throttled = status == StatusCode::TOO_MANY_REQUESTS
+        || (status == StatusCode::FORBIDDEN && text.to_lowercase().contains("ratelimitexceeded"));
+    let wait = throttled.then(|| {
+        headers
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(Duration::from_secs(2 << attempt))
+            .min(MAX_THROTTLE_WAIT)
+    });
+
+    let mut rebuilt = http::Response::new(body);
+    *rebuilt.status_mut() = status;
+    *rebuilt.headers_mut() = headers;
+    Ok((Response::from(rebuilt), wait))
+}
+
+/// Fail fast with a readable message when Google can't be reached at all,
+/// rather than letting the first real request hang until its timeout
+async fn check_connectivity(http: &Client, config: &Config) -> Result<()> {
+    let result = http
+        .head(GMAIL_API_BASE)
+        .timeout(CONNECTIVITY_TIMEOUT)
+        .send()
+        .await;
+    // Any HTTP answer, even an error status, means the network works
+    let Err(e) = result else {
+        return Ok(());
+    };
+    tracing::warn!(error = %e, "Connectivity check failed");
+
+    let reason = if e.is_timeout() {
+        "timed out"
+    } else {
+        "no connection"
+    };
+    let hint = if config.network.proxy.is_some() {
+        "Check your network connection and the proxy in network.proxy."
+    } else {
+        "Check your network connection (or HTTPS_PROXY, behind a proxy)."
+    };
+    bail!("Can't reach Gmail ({}). {}", reason, hint)
+}
+
+const GMAIL_TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+const GMAIL_API_BASE: &str = "https://gmail.googleapis.com/gmail/v1";
+const PEOPLE_API_BASE: &str = "https://people.googleapis.com/v1";
+const DRIVE_API_BASE: &str = "https://www.googleapis.com/drive/v3";
+const CALENDAR_API_BASE: &str = "https://www.googleapis.com/calendar/v3";
+
+const GMAIL_SCOPES: &str = "https://www.googleapis.com/auth/gmail.modify https://www.googleapis.com/auth/gmail.send https://www.googleapis.com/auth/userinfo.email";
+/// Extra scope requested when Google Contacts lookup is enabled
+const CONTACTS_SCOPE: &str = "https://www.googleapis.com/auth/contacts.readonly";
+/// Needed only for permanent deletion; gmail.modify can trash but not delete
+const FULL_MAIL_SCOPE: &str = "https://mail.google.com/";
+/// Needed only to create Gmail filters (`gmail.manage_filters`)
+const SETTINGS_SCOPE: &str = "https://www.googleapis.com/auth/gmail.settings.basic";
+/// Needed only to read the storage quota (`gmail.storage_quota`); the narrowest
+/// Drive scope that can, and it sees no existing files
+const DRIVE_SCOPE: &str = "https://www.googleapis.com/auth/drive.file";
+/// Needed only to check proposed meeting times against the calendar
+/// (`gmail.calendar_conflicts`)
+const CALENDAR_SCOPE: &str = "https://www.googleapis.com/auth/calendar.events.readonly";
+/// Share of the quota in use at which it counts as nearly full
+const STORAGE_WARNING: f64 = 0.9;
+/// Access tokens last an hour; refresh this many seconds before that
+const REFRESH_MARGIN_SECS: i64 = 5 * 60;
+/// A URL for the log: search terms and other query values can be personal, so
+/// only the parameter names are kept
+fn redact_url(url: &reqwest::Url) -> String {
+    let mut redacted = format!("{}{}", url.origin().ascii_serialization(), url.path());
+    let params: Vec<String> = url
+        .query_pairs()
+        .map(|(name, _)| format!("{}=…", name))
+        .collect();
+    if !params.is_empty() {
+        redacted.push('?');
+        redacted.push_str(&params.join("&"));
+    }
+    redacted
+}
+
+/// How long the startup connectivity check waits for Google to answer
+const CONNECTIVITY_TIMEOUT: Duration = Duration::from_secs(5);
+/// Retries of a throttled request before its 429/403 is returned to the caller
+const MAX_THROTTLE_RETRIES: u32 = 4;
+/// Longest wait between retries, whatever Retry-After asks for
+const MAX_THROTTLE_WAIT: Duration = Duration::from_secs(60);
+/// Most message ids `messages/batchModify` accepts in one call
+const MAX_BATCH_IDS: usize = 1000;
+/// Requests started per second for one account, well under Gmail's per-user quota
+/// (250 units a second, with a message fetch costing 5)
+const MAX_REQUESTS_PER_SECOND: u32 = 25;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredToken {
+    access_token: String,
+    refresh_token: String,
+    expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_in: Option<i64>,
+}
+
+/// An authenticated Gmail API client for one account
+pub struct GmailClient {
+    http: Client,
+    account: GmailAccount,
+    /// Current token; refreshed shortly before it expires so long sessions keep working
+    token: Mutex<StoredToken>,
+    /// Labels fetched on first lookup; the lock also keeps concurrent lookups from
+    /// creating the same label twice
+    labels: Mutex<Option<Vec<Label>>>,
+    /// The People API wants one empty search before real queries return results
+    contacts_warmed: AtomicBool,
+    /// When a throttled request will be retried, while one is waiting
+    throttle: watch::Sender<Option<DateTime<Utc>>>,
+    /// Earliest start of the next request; spaces out this account's requests
+    /// without slowing down other accounts' clients
+    next_request: Mutex<tokio::time::Instant>,
+    /// Messages fetched before, unless `gmail.message_cache` is off
+    cache: Option<MessageCache>,
+    /// How much of the original replies quote
+    reply_quote: ReplyQuote,
+}
+
+impl GmailClient {
+    /// Connect with a stored token, running the OAuth flow if none is usable.
+    /// `config.gmail` decides which optional scopes a fresh authorization asks for;
+    /// `config.network` sets the proxy and extra CA certificates.
+    pub async fn new(account: &GmailAccount, config: &Config) -> Result<Self> {
+        let http = config.network.client()?;
+        check_connectivity(&http, config).await?;
+        let token = Self::get_valid_token(&http, account, &config.gmail).await?;
+        let cache = if config.gmail.message_cache {
+            let cache = MessageCache::for_account(&account.id)?;
+            if let Err(e) = cache.prune() {
+                tracing::debug!(error = %e, "Failed to prune the message cache");
+            }
+            Some(cache)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            http,
+            account: account.clone(),
+            token: Mutex::new(token),
+            labels: Mutex::new(None),
+            contacts_warmed: AtomicBool::new(false),
+            throttle: watch::Sender::new(None),
+            next_request: Mutex::new(tokio::time::Instant::now()),
+            cache,
+            reply_quote: config.gmail.reply_quote,
+        })
+    }
+
+    /// Follows Gmail throttling: holds the time of the next retry while a request
+    /// is backing off after a 429 or rate-limit 403, and `None` otherwise
+    pub fn throttled(&self) -> watch::Receiver<Option<DateTime<Utc>>> {
+        self.throttle.subscribe()
+    }
+
+    async fn get_valid_token(
+        http: &Client,
+        account: &GmailAccount,
+        options: &GmailConfig,
+    ) -> Result<StoredToken> {
+        let token_path = Config::token_path_for_account(&account.id)?;
+
+        if token_path.exists() {
+            let content = fs::read_to_string(&token_path)?;
+            let stored: StoredToken = serde_json::from_str(&content)?;
+
+            let is_expired = stored
+                .expires_at
+                .map(|exp| exp < Utc::now())
+                .unwrap_or(true);
+
+            if !is_expired {
+                return Ok(stored);
+            }
+
+            if let Ok(new_token) = Self::refresh_token(http, account, &stored.refresh_token).await {
+                return Ok(new_token);
+            }
+        }
+
+        Self::authorize(http, account, options).await
+    }
+
+    async fn refresh_token(
+        http: &Client,
+        account: &GmailAccount,
+        refresh_token: &str,
+    ) -> Result<StoredToken> {
+        let params = [
+            ("client_id", account.client_id.as_str()),
+            ("client_secret", account.client_secret.as_str()),
+            ("refresh_token", refresh_token),
+            ("grant_type", "refresh_token"),
+        ];
+
+        let response = http.post(GMAIL_TOKEN_URL).form(&params).send().await?;
+
+        if !response.status().is_success() {
+            return Err(failed("Failed to refresh token", response.status()));
+        }
+
+        let token_response: TokenResponse = response.json().await?;
+
+        let expires_at = token_response
+            .expires_in
+            .map(|secs| Utc::now() + chrono::Duration::seconds(secs));
+
+        let stored = StoredToken {
+            access_token: token_response.access_token,
+            refresh_token: token_response
+                .refresh_token
+                .unwrap_or_else(|| refresh_token.to_string()),
+            expires_at,
+        };
+        let token_path = Config::token_path_for_account(&account.id)?;
+        write_token_file(&token_path, &serde_json::to_string_pretty(&stored)?)?;
+
+        Ok(stored)
+    }
+
+    /// The access token, refreshed first if it expires within `REFRESH_MARGIN_SECS`
+    async fn access_token(&self) -> Result<String> {
+        let mut token = self.token.lock().await;
+        let expiring = token
+            .expires_at
+            .is_some_and(|exp| exp - chrono::Duration::seconds(REFRESH_MARGIN_SECS) < Utc::now());
+        if expiring {
+            *token = self.refresh(&token.refresh_token).await?;
+        }
+        Ok(token.access_token.clone())
+    }
+
+    /// Refresh after Gmail rejected `stale`, unless a concurrent request already did
+    async fn refresh_after_rejection(&self, stale: &str) -> Result<String> {
+        let mut token = self.token.lock().await;
+        if token.access_token == stale {
+            *token = self.refresh(&token.refresh_token).await?;
+        }
+        Ok(token.access_token.clone())
+    }
+
+    async fn refresh(&self, refresh_token: &str) -> Result<StoredToken> {
+        Self::refresh_token(&self.http, &self.account, refresh_token)
+            .await
+            .with_context(|| {
+                format!(
+                    "Gmail session expired; run 'clinbox account reauth {}'",
+                    self.account.id
+                )
+            })
+    }
+
+    /// Send an authorized API request. A 401 (token revoked or expired early) gets
+    /// one retry with a freshly refreshed token; throttling is waited out with backoff.
+    /// Requests are paced to stay under the account's quota.
+    async fn request(&self, build: impl Fn(&Client) -> RequestBuilder) -> Result<Response> {
+        let mut refreshed = false;
+        let mut attempt = 0;
+        loop {
+            self.pace().await;
+            let token = self.access_token().await?;
+            let request = build(&self.http).bearer_auth(&token).build()?;
+            let (method, url) = (request.method().clone(), redact_url(request.url()));
+            let started = std::time::Instant::now();
+            let response = match self.http.execute(request).await {
+                Ok(response) => response,
+                Err(e) => {
+                    tracing::warn!(%method, %url, error = %e, "Gmail request failed");
+                    return Err(e.into());
+                }
+            };
+            let status = response.status();
+            timings::record(timings::Kind::Gmail, started.elapsed());
+            let elapsed_ms = started.elapsed().as_millis() as u64;
+            if status.is_success() {
+                tracing::debug!(%method, %url, status = status.as_u16(), elapsed_ms, "Gmail request");
+            } else {
+                tracing::warn!(%method, %url, status = status.as_u16(), elapsed_ms, "Gmail request");
+            }
+
+            if status == StatusCode::UNAUTHORIZED && !refreshed {
+                tracing::info!(account = %self.account.id, "Access token rejected, refreshing");
+                self.refresh_after_rejection(&token).await?;
+                refreshed = true;
+                continue;
+            }
+
+            let (response, wait) = inspect_error(response, &url, attempt).await?;
+            match wait {
+                Some(wait) if attempt < MAX_THROTTLE_RETRIES => {
+                    attempt += 1;
+                    tracing::warn!(wait_secs = wait.as_secs(), attempt, "Gmail is throttling");
+                    let retry_at = Utc::now() + chrono::Duration::from_std(wait)?;
+                    self.throttle.send_replace(Some(retry_at));
+                    tokio::time::sleep(wait).await;
+                }
+                _ => {
+                    self.throttle.send_if_modified(|t| t.take().is_some());
+                    return Ok(response);
+                }
+            }
+        }
+    }
+
+    /// Wait for this account's next request slot
+    async fn pace(&self) {
+        let start = {
+            let mut next = self.next_request.lock().await;
+            let start = (*next).max(tokio::time::Instant::now());
+            *next = start + Duration::from_secs(1) / MAX_REQUESTS_PER_SECOND;
+            start
+        };
+        tokio::time::sleep_until(start).await;
+    }
+
+    /// Run the browser OAuth flow on a local port and store the tokens; returns the access token
+    pub async fn oauth_flow(account: &GmailAccount, config: &Config) -> Result<String> {
+        let http = config.network.client()?;
+        Ok(Self::authorize(&http, account, &config.gmail)
+            .await?
+            .access_token)
+    }
+
+    async fn authorize(
+        http: &Client,
+        account: &GmailAccount,
+        options: &GmailConfig,
+    ) -> Result<StoredToken> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let port = listener.local_addr()?.port();
+        let redirect_uri = format!("http://localhost:{}", port);
+
+        let mut scopes = GMAIL_SCOPES.to_string();
+        if options.people_lookup {
+            scopes = format!("{} {}", scopes, CONTACTS_SCOPE);
+        }
+        if options.permanent_delete {
+            scopes = format!("{} {}", scopes, FULL_MAIL_SCOPE);
+        }
+        if options.manage_filters {
+            scopes = format!("{} {}", scopes, SETTINGS_SCOPE);
+        }
+        if options.storage_quota {
+            scopes = format!("{} {}", scopes, DRIVE_SCOPE);
+        }
+        if options.calendar_conflicts {
+            scopes = format!("{} {}", scopes, CALENDAR_SCOPE);
+        }
+
+        let auth_url = format!(
+            "{}?client_id={}&redirect_uri={}&response_type=code&scope={}&access_type=offline&prompt=consent",
+            GMAIL_AUTH_URL,
+            urlencoding::encode(&account.client_id),
+            urlencoding::encode(&redirect_uri),
+            urlencoding::encode(&scopes)
+        );
+
+        println!("\nOpening browser for Gmail authorization...");
+        println!("If it doesn't open, visit: {}\n", auth_url);
+        let _ = open::that(&auth_url);
+
+        let (stream, _) = listener.accept()?;
+        let mut reader = BufReader::new(&stream);
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+
+        let code = request_line
+            .split_whitespace()
+            .nth(1)
+            .and_then(|path| {
+                path.split('?')
+                    .nth(1)?
+                    .split('&')
+                    .find(|p| p.starts_with("code="))?
+                    .strip_prefix("code=")
+                    .map(|s| s.to_string())
+            })
+            .context("Failed to extract authorization code")?;
+
+        let response_html = "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\n\r\n\
+            <html><body><h1>Authorization successful!</h1>\
+            <p>You can close this tab and return to the terminal.</p></body></html>";
+        let mut stream = stream;
+        stream.write_all(response_html.as_bytes())?;
+
+        let decoded_code = urlencoding::decode(&code)?.into_owned();
+
+        let params = [
+            ("client_id", account.client_id.as_str()),
+            ("client_secret", account.client_secret.as_str()),
+            ("code", decoded_code.as_str()),
+            ("grant_type", "authorization_code"),
+            ("redirect_uri", redirect_uri.as_str()),
+        ];
+
+        let response = http.post(GMAIL_TOKEN_URL).form(&params).send().await?;
+
+        if !response.status().is_success() {
+            let error = response.text().await?;
+            bail!("Failed to exchange code for token: {}", error);
+        }
+
+        let token_response: TokenResponse = response.json().await?;
+
+        let expires_at = token_response
+            .expires_in
+            .map(|secs| Utc::now() + chrono::Duration::seconds(secs));
+
+        let stored = StoredToken {
+            access_token: token_response.access_token,
+            refresh_token: token_response.refresh_token.unwrap_or_default(),
+            expires_at,
+        };
+        let tokens_dir = Config::tokens_dir()?;
+        fs::create_dir_all(&tokens_dir)?;
+        let token_path = Config::token_path_for_account(&account.id)?;
+        write_token_file(&token_path, &serde_json::to_string_pretty(&stored)?)?;
+
+        println!("Authorization successful!\n");
+        Ok(stored)
+    }
+
+    /// Fetch the authenticated user's email address
+    pub async fn fetch_user_email(&self) -> Result<String> {
+        let url = format!("{}/users/me/profile", GMAIL_API_BASE);
+
+        let response = self.request(|http| http.get(&url)).await?;
+
+        if !response.status().is_success() {
+            return Err(failed("Failed to fetch user profile", response.status()));
+        }
+
+        let profile: UserProfile = response.json().await?;
+        Ok(profile.email_address)
+    }
+
+    /// Fetch messages matching a Gmail search query (e.g. "is:unread from:github.com")
+    pub async fn search(
+        &self,
+        query: &str,
+        max_results: u32,
+        on_progress: impl FnMut(usize, usize),
+    ) -> Result<Vec<Email>> {
+        let url = format!(
+            "{}/users/me/messages?maxResults={}&q={}",
+            GMAIL_API_BASE,
+            max_results,
+            urlencoding::encode(query)
+        );
+
+        self.fetch_list(&url, true, on_progress).await
+    }
+
+    /// Like `search`, but only headers, labels and snippets; bodies and attachment
+    /// lists are left empty for `fetch_email` to fill in later
+    pub async fn search_metadata(
+        &self,
+        query: &str,
+        max_results: u32,
+        on_progress: impl FnMut(usize, usize),
+    ) -> Result<Vec<Email>> {
+        let url = format!(
+            "{}/users/me/messages?maxResults={}&q={}",
+            GMAIL_API_BASE,
+            max_results,
+            urlencoding::encode(query)
+        );
+
+        self.fetch_list(&url, false, on_progress).await
+    }
+
+    /// Fetch the latest messages under a system label that searches skip, such as TRASH or SPAM
+    pub async fn fetch_label(
+        &self,
+        label_id: &str,
+        max_results: u32,
+        on_progress: impl FnMut(usize, usize),
+    ) -> Result<Vec<Email>> {
+        let url = format!(
+            "{}/users/me/messages?maxResults={}&labelIds={}&includeSpamTrash=true",
+            GMAIL_API_BASE,
+            max_results,
+            urlencoding::encode(label_id)
+        );
+
+        self.fetch_list(&url, true, on_progress).await
+    }
+
+    /// List messages at `url` and fetch each one (in full, or only its metadata),
+    /// reporting (fetched, total) as it goes
+    async fn fetch_list(
+        &self,
+        url: &str,
+        full: bool,
+        mut on_progress: impl FnMut(usize, usize),
+    ) -> Result<Vec<Email>> {
+        let response: MessageListResponse =
+            self.request(|http| http.get(url)).await?.json().await?;
+
+        let refs = response.messages.unwrap_or_default();
+        let total = refs.len();
+        on_progress(0, total);
+
+        let mut emails = Vec::new();
+        for (idx, msg_ref) in refs.iter().enumerate() {
+            let email = if full {
+                self.fetch_email(&msg_ref.id).await
+            } else {
+                self.fetch_metadata(&msg_ref.id).await
+            };
+            if let Ok(email) = email {
+                emails.push(email);
+            }
+            on_progress(idx + 1, total);
+        }
+
+        Ok(emails)
+    }
+
+    /// Ids of up to `max_results` messages matching a Gmail search query, without
+    /// fetching the messages; follows result pages past Gmail's 500 per page
+    pub async fn search_ids(&self, query: &str, max_results: u32) -> Result<Vec<String>> {
+        let mut ids = Vec::new();
+        let mut page_token: Option<String> = None;
+
+        loop {
+            let mut url = format!(
+                "{}/users/me/messages?maxResults={}&q={}",
+                GMAIL_API_BASE,
+                (max_results as usize - ids.len()).min(500),
+                urlencoding::encode(query)
+            );
+            if let Some(token) = &page_token {
+                url.push_str(&format!("&pageToken={}", urlencoding::encode(token)));
+            }
+
+            let response = self.request(|http| http.get(&url)).await?;
+            if !response.status().is_success() {
+                return Err(failed("Failed to search messages", response.status()));
+            }
+
+            let list: MessageListResponse = response.json().await?;
+            ids.extend(list.messages.unwrap_or_default().into_iter().map(|m| m.id));
+            page_token = list.next_page_token;
+            if page_token.is_none() || ids.len() >= max_results as usize {
+                return Ok(ids);
+            }
+        }
+    }
+
+    /// Ids of the threads matching a Gmail search query
+    pub async fn search_threads(&self, query: &str, max_results: u32) -> Result<Vec<String>> {
+        let url = format!(
+            "{}/users/me/threads?maxResults={}&q={}",
+            GMAIL_API_BASE,
+            max_results,
+            urlencoding::encode(query)
+        );
+
+        let response = self.request(|http| http.get(&url)).await?;
+
+        if !response.status().is_success() {
+            return Err(failed("Failed to search threads", response.status()));
+        }
+
+        let list: ThreadListResponse = response.json().await?;
+        Ok(list
+            .threads
+            .unwrap_or_default()
+            .into_iter()
+            .map(|t| t.id)
+            .collect())
+    }
+
+    /// Headers and labels of every message in a thread, oldest first
+    pub async fn fetch_thread(&self, thread_id: &str) -> Result<Vec<MessageMeta>> {
+        let url = format!(
+            "{}/users/me/threads/{}?format=metadata&metadataHeaders=To&metadataHeaders=Subject&metadataHeaders=Date",
+            GMAIL_API_BASE, thread_id
+        );
+
+        let response = self.request(|http| http.get(&url)).await?;
+
+        if !response.status().is_success() {
+            return Err(failed("Failed to fetch thread", response.status()));
+        }
+
+        let thread: ThreadResponse = response.json().await?;
+        Ok(thread
+            .messages
+            .unwrap_or_default()
+            .into_iter()
+            .map(|msg| {
+                let headers = msg.payload.headers.unwrap_or_default();
+                let get_header = |name: &str| {
+                    headers
+                        .iter()
+                        .find(|h| h.name.eq_ignore_ascii_case(name))
+                        .map(|h| h.value.clone())
+                        .unwrap_or_default()
+                };
+                MessageMeta {
+                    date: dateparse::parse(&get_header("Date"))
+                        .ok()
+                        .and_then(|ts| DateTime::from_timestamp(ts, 0))
+                        .unwrap_or_else(Utc::now),
+                    to: get_header("To"),
+                    subject: get_header("Subject"),
+                    labels: msg.label_ids.unwrap_or_default(),
+                    id: msg.id,
+                }
+            })
+            .collect())
+    }
+
+    /// Fetch a full message, bodies decoded. A cached message only costs a check of
+    /// its history id, refreshing its labels if they changed.
+    pub async fn fetch_email(&self, id: &str) -> Result<Email> {
+        if let Some(cache) = &self.cache
+            && let Some(cached) = cache.get(id)
+        {
+            return self.refresh_cached(cache, cached).await;
+        }
+
+        let url = format!("{}/users/me/messages/{}?format=full", GMAIL_API_BASE, id);
+
+        let response: MessageResponse = self.request(|http| http.get(&url)).await?.json().await?;
+
+        let history_id = response.history_id.clone();
+        let mut email = self.parse_message(response)?;
+
+        // Invites sent as a plain .ics attachment need a second request
+        if email.calendar.is_none()
+            && let Some(ics) = email.attachments.iter().find(|a| {
+                a.mime_type.starts_with("text/calendar")
+                    || a.filename.to_lowercase().ends_with(".ics")
+            })
+            && let Ok(data) = self.fetch_attachment(&email.id, &ics.attachment_id).await
+        {
+            email.calendar = String::from_utf8(data).ok();
+        }
+
+        if let Some(cache) = &self.cache
+            && let Some(history_id) = history_id
+        {
+            let cached = CachedMessage {
+                history_id,
+                email: email.clone(),
+            };
+            if let Err(e) = cache.put(&cached) {
+                tracing::debug!(error = %e, "Failed to cache message");
+            }
+        }
+
+        Ok(email)
+    }
+
+    /// Bring a cached message's labels up to date; content never changes once sent
+    async fn refresh_cached(
+        &self,
+        cache: &MessageCache,
+        mut cached: CachedMessage,
+    ) -> Result<Email> {
+        let url = format!(
+            "{}/users/me/messages/{}?format=minimal&fields=labelIds,historyId",
+            GMAIL_API_BASE, cached.email.id
+        );
+        let response = self.request(|http| http.get(&url)).await?;
+        if !response.status().is_success() {
+            return Err(failed("Failed to fetch message", response.status()));
+        }
+        let current: MessageState = response.json().await?;
+
+        if current.history_id != cached.history_id {
+            let labels = current.label_ids.unwrap_or_default();
+            cached.email.is_unread = labels.iter().any(|l| l == "UNREAD");
+            cached.email.labels = labels;
+            cached.history_id = current.history_id;
+            if let Err(e) = cache.put(&cached) {
+                tracing::debug!(error = %e, "Failed to cache message");
+            }
+        }
+        Ok(cached.email)
+    }
+
+    /// Fetch a message's headers, labels and snippet, without bodies or attachments
+    pub async fn fetch_metadata(&self, id: &str) -> Result<Email> {
+        let url = format!(
+            "{}/users/me/messages/{}?format=metadata&metadataHeaders=From&metadataHeaders=To&metadataHeaders=Subject&metadataHeaders=Date&metadataHeaders=List-Id&metadataHeaders=Precedence&metadataHeaders=Auto-Submitted&metadataHeaders=X-Mailer&metadataHeaders=Authentication-Results",
+            GMAIL_API_BASE, id
+        );
+
+        let response: MessageResponse = self.request(|http| http.get(&url)).await?.json().await?;
+        self.parse_message(response)
+    }
+
+    /// All of a message's headers as (name, value), in the order they appear:
+    /// the Received chain, Return-Path, authentication results and the rest
+    pub async fn fetch_headers(&self, id: &str) -> Result<Vec<(String, String)>> {
+        let url = format!(
+            "{}/users/me/messages/{}?format=metadata",
+            GMAIL_API_BASE, id
+        );
+
+        let response: MessageResponse = self.request(|http| http.get(&url)).await?.json().await?;
+        Ok(response
+            .payload
+            .headers
+            .unwrap_or_default()
+            .into_iter()
+            .map(|h| (h.name, h.value))
+            .collect())
+    }
+
+    fn parse_message(&self, msg: MessageResponse) -> Result<Email> {
+        let headers = msg.payload.headers.clone().unwrap_or_default();
+
+        let get_header = |name: &str| -> String {
+            headers
+                .iter()
+                .find(|h| h.name.eq_ignore_ascii_case(name))
+                .map(|h| h.value.clone())
+                .unwrap_or_default()
+        };
+
+        let date = get_header("Date");
+        let parsed_date = dateparse::parse(&date)
+            .map(|ts| DateTime::from_timestamp(ts, 0).unwrap_or_default())
+            .unwrap_or_else(|_| Utc::now());
+
+        let (body_plain, body_html) = self.extract_body(&msg.payload);
+        let attachments = self.extract_attachments(&msg.payload);
+        // Metadata fetches have no parts to look at, only the top-level type
+        let has_attachments = match &msg.payload.parts {
+            Some(_) => !attachments.is_empty(),
+            None => msg.payload.mime_type.as_deref() == Some("multipart/mixed"),
+        };
+        let calendar = self.extract_calendar(&msg.payload);
+        let is_unread = msg
+            .label_ids
+            .as_ref()
+            .is_some_and(|l| l.contains(&"UNREAD".to_string()));
+
+        Ok(Email {
+            id: msg.id,
+            thread_id: msg.thread_id,
+            subject: get_header("Subject"),
+            from: get_header("From"),
+            to: get_header("To"),
+            date: parsed_date,
+            snippet: msg.snippet.unwrap_or_default(),
+            body_plain,
+            body_html,
+            labels: msg.label_ids.unwrap_or_default(),
+            attachments,
+            has_attachments,
+            is_unread,
+            size: msg.size_estimate.unwrap_or_default(),
+            calendar,
+            list_id: Some(get_header("List-Id")).filter(|v| !v.is_empty()),
+            precedence: Some(get_header("Precedence")).filter(|v| !v.is_empty()),
+            auto_submitted: Some(get_header("Auto-Submitted")).filter(|v| !v.is_empty()),
+            x_mailer: Some(get_header("X-Mailer")).filter(|v| !v.is_empty()),
+            authentication_results: Some(get_header("Authentication-Results"))
+                .filter(|v| !v.is_empty()),
+        })
+    }
+
+    fn extract_body(&self, payload: &MessagePart) -> (Option<String>, Option<String>) {
+        let mut plain = None;
+        let mut html = None;
+
+        fn process_part(part: &MessagePart, plain: &mut Option<String>, html: &mut Option<String>) {
+            let mime = part.mime_type.as_deref().unwrap_or("");
+
+            if mime == "text/plain" {
+                if let Some(data) = part.body.as_ref().and_then(|b| b.data.as_ref())
+                    && let Ok(decoded) = URL_SAFE.decode(data)
+                {
+                    *plain = String::from_utf8(decoded).ok();
+                }
+            } else if mime == "text/html"
+                && let Some(data) = part.body.as_ref().and_then(|b| b.data.as_ref())
+                && let Ok(decoded) = URL_SAFE.decode(data)
+            {
+                *html = String::from_utf8(decoded).ok();
+            }
+
+            if let Some(parts) = &part.parts {
+                for p in parts {
+                    process_part(p, plain, html);
+                }
+            }
+        }
+
+        process_part(payload, &mut plain, &mut html);
+        (plain, html)
+    }
+
+    /// Inline text/calendar part, as sent by most calendar apps alongside the HTML invite
+    fn extract_calendar(&self, part: &MessagePart) -> Option<String> {
+        if part.mime_type.as_deref() == Some("text/calendar")
+            && let Some(data) = part.body.as_ref().and_then(|b| b.data.as_ref())
+            && let Ok(decoded) = URL_SAFE_NO_PAD.decode(data.trim_end_matches('='))
+        {
+            return String::from_utf8(decoded).ok();
+        }
+
+        part.parts
+            .iter()
+            .flatten()
+            .find_map(|p| self.extract_calendar(p))
+    }
+
+    fn extract_attachments(&self, payload: &MessagePart) -> Vec<Attachment> {
+        let mut attachments = Vec::new();
+
+        fn process_part(part: &MessagePart, attachments: &mut Vec<Attachment>) {
+            if let Some(filename) = &part.filename
+                && !filename.is_empty()
+            {
+                attachments.push(Attachment {
+                    filename: filename.clone(),
+                    mime_type: part.mime_type.clone().unwrap_or_default(),
+                    size: part.body.as_ref().and_then(|b| b.size).unwrap_or(0),
+                    attachment_id: part
+                        .body
+                        .as_ref()
+                        .and_then(|b| b.attachment_id.clone())
+                        .unwrap_or_default(),
+                });
+            }
+
+            if let Some(parts) = &part.parts {
+                for p in parts {
+                    process_part(p, attachments);
+                }
+            }
+        }
+
+        process_part(payload, &mut attachments);
+        attachments
+    }
+
+    /// Remove a message from the inbox and mark it read
+    pub async fn archive(&self, id: &str) -> Result<()> {
+        let url = format!("{}/users/me/messages/{}/modify", GMAIL_API_BASE, id);
+
+        let body = serde_json::json!({
+            "removeLabelIds": ["INBOX", "UNREAD"]
+        });
+
+        let response = self.request(|http| http.post(&url).json(&body)).await?;
+
+        if !response.status().is_success() {
+            return Err(failed("Failed to archive email", response.status()));
+        }
+
+        Ok(())
+    }
+
+    /// Move a message to the trash
+    pub async fn delete(&self, id: &str) -> Result<()> {
+        let url = format!("{}/users/me/messages/{}/trash", GMAIL_API_BASE, id);
+
+        let response = self
+            .request(|http| http.post(&url).header("Content-Length", "0"))
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(failed("Failed to delete email", response.status()));
+        }
+
+        Ok(())
+    }
+
+    /// Move a message out of the trash, back to the labels it had
+    pub async fn untrash(&self, id: &str) -> Result<()> {
+        let url = format!("{}/users/me/messages/{}/untrash", GMAIL_API_BASE, id);
+
+        let response = self
+            .request(|http| http.post(&url).header("Content-Length", "0"))
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(failed("Failed to restore email", response.status()));
+        }
+
+        Ok(())
+    }
+
+    /// Delete a message for good, skipping the trash. Needs the full mail scope
+    /// (`gmail.permanent_delete`).
+    pub async fn delete_permanently(&self, id: &str) -> Result<()> {
+        let url = format!("{}/users/me/messages/{}", GMAIL_API_BASE, id);
+
+        let response = self.request(|http| http.delete(&url)).await?;
+
+        if response.status() == StatusCode::FORBIDDEN {
+            bail!(
+                "Permanent deletion needs full mail access: run 'clinbox config gmail.permanent_delete true' and 'clinbox account reauth <id>'"
+            );
+        }
+        if !response.status().is_success() {
+            return Err(failed("Failed to delete email", response.status()));
+        }
+
+        Ok(())
+    }
+
+    /// Remove UNREAD but keep the message in the inbox
+    pub async fn mark_read(&self, id: &str) -> Result<()> {
+        let url = format!("{}/users/me/messages/{}/modify", GMAIL_API_BASE, id);
+
+        let body = serde_json::json!({
+            "removeLabelIds": ["UNREAD"]
+        });
+
+        let response = self.request(|http| http.post(&url).json(&body)).await?;
+
+        if !response.status().is_success() {
+            return Err(failed("Failed to mark email as read", response.status()));
+        }
+
+        Ok(())
+    }
+
+    /// Download an attachment's bytes
+    pub async fn fetch_attachment(&self, message_id: &str, attachment_id: &str) -> Result<Vec<u8>> {
+        if attachment_id.is_empty() {
+            bail!("Attachment has no downloadable content");
+        }
+
+        let url = format!(
+            "{}/users/me/messages/{}/attachments/{}",
+            GMAIL_API_BASE, message_id, attachment_id
+        );
+
+        let response = self.request(|http| http.get(&url)).await?;
+
+        if !response.status().is_success() {
+            return Err(failed("Failed to download attachment", response.status()));
+        }
+
+        let body: MessageBody = response.json().await?;
+        let data = body.data.context("Attachment response had no data")?;
+        URL_SAFE_NO_PAD
+            .decode(data.trim_end_matches('='))
+            .context("Failed to decode attachment")
+    }
+
+    /// Download the full RFC 822 message, headers and attachments included
+    pub async fn fetch_raw(&self, id: &str) -> Result<Vec<u8>> {
+        let url = format!("{}/users/me/messages/{}?format=raw", GMAIL_API_BASE, id);
+
+        let response = self.request(|http| http.get(&url)).await?;
+
+        if !response.status().is_success() {
+            return Err(failed("Failed to download message", response.status()));
+        }
+
+        let body: RawMessageResponse = response.json().await?;
+        URL_SAFE_NO_PAD
+            .decode(body.raw.trim_end_matches('='))
+            .context("Failed to decode message")
+    }
+
+    /// The latest drafts, each with its message fetched in full
+    pub async fn list_drafts(&self, max_results: u32) -> Result<Vec<Draft>> {
+        let url = format!(
+            "{}/users/me/drafts?maxResults={}",
+            GMAIL_API_BASE, max_results
+        );
+
+        let response = self.request(|http| http.get(&url)).await?;
+
+        if !response.status().is_success() {
+            return Err(failed("Failed to list drafts", response.status()));
+        }
+
+        let list: DraftListResponse = response.json().await?;
+        let mut drafts = Vec::new();
+        for draft in list.drafts.unwrap_or_default() {
+            if let Ok(message) = self.fetch_email(&draft.message.id).await {
+                drafts.push(Draft {
+                    id: draft.id,
+                    message,
+                });
+            }
+        }
+
+        Ok(drafts)
+    }
+
+    /// Replace a draft's body with plain text, keeping its recipients, subject,
+    /// threading headers and attachments; an HTML version is replaced by the
+    /// text. Returns the draft with its new message.
+    pub async fn update_draft(&self, draft: &Draft, body_text: &str) -> Result<Draft> {
+        let raw = self.fetch_raw(&draft.message.id).await?;
+        let message = mime::replace_text_body(&String::from_utf8_lossy(&raw), body_text)?;
+
+        let url = format!("{}/users/me/drafts/{}", GMAIL_API_BASE, draft.id);
+        let payload = serde_json::json!({
+            "id": draft.id,
+            "message": {
+                "raw": URL_SAFE_NO_PAD.encode(message.as_bytes()),
+                "threadId": draft.message.thread_id
+            }
+        });
+
+        let response = self.request(|http| http.put(&url).json(&payload)).await?;
+
+        if !response.status().is_success() {
+            let error = response.text().await.unwrap_or_default();
+            bail!("Failed to update draft: {}", error);
+        }
+
+        let updated: DraftRef = response.json().await?;
+        Ok(Draft {
+            id: updated.id,
+            message: self.fetch_email(&updated.message.id).await?,
+        })
+    }
+
+    /// Send a draft as it is
+    pub async fn send_draft(&self, draft_id: &str) -> Result<()> {
+        let url = format!("{}/users/me/drafts/send", GMAIL_API_BASE);
+
+        let response = self
+            .request(|http| http.post(&url).json(&serde_json::json!({ "id": draft_id })))
+            .await?;
+
+        if !response.status().is_success() {
+            let error = response.text().await.unwrap_or_default();
+            bail!("Failed to send draft: {}", error);
+        }
+
+        Ok(())
+    }
+
+    /// Delete a draft; drafts skip the trash, so this is final
+    pub async fn discard_draft(&self, draft_id: &str) -> Result<()> {
+        let url = format!("{}/users/me/drafts/{}", GMAIL_API_BASE, draft_id);
+
+        let response = self.request(|http| http.delete(&url)).await?;
+
+        if !response.status().is_success() {
+            return Err(failed("Failed to discard draft", response.status()));
+        }
+
+        Ok(())
+    }
+
+    /// Move a message out of spam and back to the inbox
+    pub async fn not_spam(&self, id: &str) -> Result<()> {
+        let url = format!("{}/users/me/messages/{}/modify", GMAIL_API_BASE, id);
+
+        let body = serde_json::json!({
+            "addLabelIds": ["INBOX"],
+            "removeLabelIds": ["SPAM"]
+        });
+
+        let response = self.request(|http| http.post(&url).json(&body)).await?;
+
+        if !response.status().is_success() {
+            return Err(failed("Failed to move email to inbox", response.status()));
+        }
+
+        Ok(())
+    }
+
+    /// Archive and apply a label in one request ("file under Receipts")
+    pub async fn move_to_label(&self, id: &str, label_id: &str) -> Result<()> {
+        let url = format!("{}/users/me/messages/{}/modify", GMAIL_API_BASE, id);
+
+        let body = serde_json::json!({
+            "addLabelIds": [label_id],
+            "removeLabelIds": ["INBOX", "UNREAD"]
+        });
+
+        let response = self.request(|http| http.post(&url).json(&body)).await?;
+
+        if !response.status().is_success() {
+            return Err(failed("Failed to move email to label", response.status()));
+        }
+
+        Ok(())
+    }
+
+    /// List the account's labels (system and user-created)
+    pub async fn list_labels(&self) -> Result<Vec<Label>> {
+        let url = format!("{}/users/me/labels", GMAIL_API_BASE);
+
+        let response = self.request(|http| http.get(&url)).await?;
+
+        if !response.status().is_success() {
+            return Err(failed("Failed to list labels", response.status()));
+        }
+
+        let list: LabelListResponse = response.json().await?;
+        Ok(list.labels.unwrap_or_default())
+    }
+
+    /// Create a Gmail filter that applies to future mail from `from`. Needs the
+    /// settings scope (`gmail.manage_filters`).
+    pub async fn create_filter(&self, from: &str, add: &[&str], remove: &[&str]) -> Result<()> {
+        let url = format!("{}/users/me/settings/filters", GMAIL_API_BASE);
+
+        let body = serde_json::json!({
+            "criteria": { "from": from },
+            "action": {
+                "addLabelIds": add,
+                "removeLabelIds": remove
+            }
+        });
+
+        let response = self.request(|http| http.post(&url).json(&body)).await?;
+
+        if response.status() == StatusCode::FORBIDDEN {
+            bail!(
+                "Creating Gmail filters needs access to settings: run 'clinbox config gmail.manage_filters true' and 'clinbox account reauth <id>'"
+            );
+        }
+        if !response.status().is_success() {
+            return Err(failed("Failed to create filter", response.status()));
+        }
+
+        Ok(())
+    }
+
+    /// Find a label by name (case-insensitive), creating it if it doesn't exist yet
+    pub async fn find_or_create_label(&self, name: &str) -> Result<Label> {
+        let mut cache = self.labels.lock().await;
+        if cache.is_none() {
+            *cache = Some(self.list_labels().await?);
+        }
+        let labels = cache.get_or_insert_with(Vec::new);
+
+        if let Some(label) = labels.iter().find(|l| l.name.eq_ignore_ascii_case(name)) {
+            return Ok(label.clone());
+        }
+
+        let url = format!("{}/users/me/labels", GMAIL_API_BASE);
+        let body = serde_json::json!({
+            "name": name,
+            "labelListVisibility": "labelShow",
+            "messageListVisibility": "show"
+        });
+
+        let response = self.request(|http| http.post(&url).json(&body)).await?;
+
+        if !response.status().is_success() {
+            let error = response.text().await.unwrap_or_default();
+            bail!("Failed to create label '{}': {}", name, error);
+        }
+
+        let label: Label = response.json().await?;
+        labels.push(label.clone());
+        Ok(label)
+    }
+
+    /// Apply a label without touching the message's other labels
+    pub async fn add_label(&self, id: &str, label_id: &str) -> Result<()> {
+        let url = format!("{}/users/me/messages/{}/modify", GMAIL_API_BASE, id);
+
+        let body = serde_json::json!({
+            "addLabelIds": [label_id]
+        });
+
+        let response = self.request(|http| http.post(&url).json(&body)).await?;
+
+        if !response.status().is_success() {
+            return Err(failed("Failed to label email", response.status()));
+        }
+
+        Ok(())
+    }
+
+    /// Archive many messages (and mark them read) in as few requests as possible
+    pub async fn archive_many(&self, ids: &[String]) -> BatchResult {
+        self.batch_modify(ids, &[], &["INBOX", "UNREAD"]).await
+    }
+
+    /// Move many messages to the trash in as few requests as possible
+    pub async fn trash_many(&self, ids: &[String]) -> BatchResult {
+        self.batch_modify(ids, &["TRASH"], &[]).await
+    }
+
+    /// Apply a label to many messages in as few requests as possible
+    pub async fn add_label_many(&self, ids: &[String], label_id: &str) -> BatchResult {
+        self.batch_modify(ids, &[label_id], &[]).await
+    }
+
+    /// Add and remove labels on many messages with `messages/batchModify`, up to
+    /// 1000 ids per call. A failed call fails only its own ids; the rest go ahead.
+    pub async fn batch_modify(&self, ids: &[String], add: &[&str], remove: &[&str]) -> BatchResult {
+        let url = format!("{}/users/me/messages/batchModify", GMAIL_API_BASE);
+        let mut result = BatchResult::default();
+
+        for chunk in ids.chunks(MAX_BATCH_IDS) {
+            let body = serde_json::json!({
+                "ids": chunk,
+                "addLabelIds": add,
+                "removeLabelIds": remove,
+            });
+            let outcome = match self.request(|http| http.post(&url).json(&body)).await {
+                Ok(response) if response.status().is_success() => Ok(()),
+                Ok(response) => Err(format!("Gmail answered {}", response.status())),
+                Err(e) => Err(format!("{:#}", e)),
+            };
+            match outcome {
+                Ok(()) => result.succeeded.extend(chunk.iter().cloned()),
+                Err(error) => {
+                    tracing::warn!(count = chunk.len(), %error, "Batch modify failed");
+                    result
+                        .failed
+                        .extend(chunk.iter().map(|id| (id.clone(), error.clone())));
+                }
+            }
+        }
+
+        result
+    }
+
+    /// The account's storage use, shared by Gmail, Drive and Photos. Needs the
+    /// Drive scope (`gmail.storage_quota`).
+    pub async fn storage_quota(&self) -> Result<StorageQuota> {
+        let url = format!("{}/about?fields=storageQuota", DRIVE_API_BASE);
+
+        let response = self.request(|http| http.get(&url)).await?;
+
+        if response.status() == StatusCode::FORBIDDEN {
+            bail!(
+                "Reading the storage quota needs Drive access: run 'clinbox config gmail.storage_quota true' and 'clinbox account reauth <id>'"
+            );
+        }
+        if !response.status().is_success() {
+            return Err(failed("Failed to read storage quota", response.status()));
+        }
+
+        let about: AboutResponse = response.json().await?;
+        let parse = |n: Option<String>| n.and_then(|n| n.parse().ok());
+        Ok(StorageQuota {
+            usage: parse(about.storage_quota.usage).unwrap_or_default(),
+            limit: parse(about.storage_quota.limit),
+        })
+    }
+
+    /// Events on the primary calendar overlapping `from..to`, soonest first.
+    /// All-day events, free-time events and ones I declined are left out.
+    /// Needs the Calendar scope (`gmail.calendar_conflicts`).
+    pub async fn calendar_events(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<CalendarEvent>> {
+        let url = format!(
+            "{}/calendars/primary/events?timeMin={}&timeMax={}&singleEvents=true&orderBy=startTime&maxResults=250",
+            CALENDAR_API_BASE,
+            urlencoding::encode(&from.to_rfc3339()),
+            urlencoding::encode(&to.to_rfc3339())
+        );
+
+        let response = self.request(|http| http.get(&url)).await?;
+
+        if response.status() == StatusCode::FORBIDDEN {
+            bail!(
+                "Checking the calendar needs Calendar access: run 'clinbox config gmail.calendar_conflicts true' and 'clinbox account reauth <id>'"
+            );
+        }
+        if !response.status().is_success() {
+            return Err(failed("Failed to read calendar events", response.status()));
+        }
+
+        let events: EventListResponse = response.json().await?;
+        Ok(events
+            .items
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|e| e.transparency.as_deref() != Some("transparent"))
+            .filter(|e| {
+                !e.attendees.iter().flatten().any(|a| {
+                    a.is_self.unwrap_or(false) && a.response_status.as_deref() == Some("declined")
+                })
+            })
+            .filter_map(|e| {
+                Some(CalendarEvent {
+                    summary: e.summary.unwrap_or_else(|| "(busy)".to_string()),
+                    start: e.start.date_time?,
+                    end: e.end.date_time?,
+                })
+            })
+            .collect())
+    }
+
+    /// Look up an address in the user's Google Contacts (needs contacts.readonly)
+    pub async fn lookup_contact(&self, address: &str) -> Result<Option<Person>> {
+        if !self.contacts_warmed.swap(true, Ordering::Relaxed) {
+            self.search_contacts("").await?;
+        }
+
+        let people = self.search_contacts(address).await?;
+        Ok(people.into_iter().next().map(Person::from))
+    }
+
+    async fn search_contacts(&self, query: &str) -> Result<Vec<PersonResponse>> {
+        let url = format!(
+            "{}/people:searchContacts?query={}&readMask=names,organizations,biographies",
+            PEOPLE_API_BASE,
+            urlencoding::encode(query)
+        );
+
+        let response = self.request(|http| http.get(&url)).await?;
+
+        if response.status() == StatusCode::FORBIDDEN {
+            bail!("Google Contacts access not granted. Run 'clinbox account reauth <id>'");
+        }
+        if !response.status().is_success() {
+            return Err(failed("Failed to search contacts", response.status()));
+        }
+
+        let results: SearchContactsResponse = response.json().await?;
+        Ok(results
+            .results
+            .unwrap_or_default()
+            .into_iter()
+            .map(|r| r.person)
+            .collect())
+    }
+
+    /// The quoted original that replies to `original` end with, per
+    /// `gmail.reply_quote`
+    pub fn reply_quote(&self, original: &Email) -> Option<String> {
+        original.quoted(self.reply_quote)
+    }
+
+    /// The full text of a reply: the answer, then the quoted original
+    pub fn reply_body(&self, original: &Email, body_text: &str) -> String {
+        match self.reply_quote(original) {
+            Some(quote) => format!("{}\n\n{}", body_text.trim_end(), quote),
+            None => body_text.to_string(),
+        }
+    }
+
+    /// Send a reply to an email, quoting the original per `gmail.reply_quote`
+    pub async fn send_reply(
+        &self,
+        original: &Email,
+        body_text: &str,
+        copies: &Copies,
+    ) -> Result<()> {
+        self.send_reply_to(
+            &original.from,
+            &original.subject,
+            &original.id,
+            &original.thread_id,
+            &self.reply_body(original, body_text),
+            copies,
+        )
+        .await
+    }
+
+    /// Addresses Bcc'd on every reply from this account
+    pub fn auto_bcc(&self) -> &[String] {
+        &self.account.bcc
+    }
+
+    /// Send a reply from its parts, e.g. a queued reply whose original isn't loaded.
+    /// The account's automatic Bcc is added to `copies`.
+    pub async fn send_reply_to(
+        &self,
+        to_address: &str,
+        original_subject: &str,
+        reply_to_id: &str,
+        thread_id: &str,
+        body_text: &str,
+        copies: &Copies,
+    ) -> Result<()> {
+        let url = format!("{}/users/me/messages/send", GMAIL_API_BASE);
+
+        // Build subject with Re: prefix if not already present
+        let subject = if original_subject.starts_with("Re:") || original_subject.starts_with("RE:")
+        {
+            original_subject.to_string()
+        } else {
+            format!("Re: {}", original_subject)
+        };
+
+        // Gmail delivers to the Bcc header's addresses and strips it from the sent copy
+        let mut bcc: Vec<&str> = copies.bcc.iter().map(String::as_str).collect();
+        for address in &self.account.bcc {
+            if !bcc.iter().any(|b| b.eq_ignore_ascii_case(address)) {
+                bcc.push(address);
+            }
+        }
+        let mut recipients = String::new();
+        if !copies.cc.is_empty() {
+            recipients.push_str(&format!("Cc: {}\r\n", copies.cc.join(", ")));
+        }
+        if !bcc.is_empty() {
+            recipients.push_str(&format!("Bcc: {}\r\n", bcc.join(", ")));
+        }
+
+        // Build RFC 2822 message
+        let message = format!(
+            "To: {}\r\n\
+             {}\
+             Subject: {}\r\n\
+             In-Reply-To: {}\r\n\
+             References: {}\r\n\
+             Content-Type: text/plain; charset=utf-8\r\n\
+             \r\n\
+             {}",
+            to_address, recipients, subject, reply_to_id, reply_to_id, body_text
+        );
+
+        // Encode as base64url
+        let encoded = URL_SAFE_NO_PAD.encode(message.as_bytes());
+
+        let payload = serde_json::json!({
+            "raw": encoded,
+            "threadId": thread_id
+        });
+
+        let response = self.request(|http| http.post(&url).json(&payload)).await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error = response.text().await.unwrap_or_default();
+            return Err(StatusError {
+                status,
+                message: format!("Failed to send reply: {}", error),
+            }
+            .into());
+        }
+
+        Ok(())
+    }
+}
+
+/// A Gmail call answered with an error status
+#[derive(Debug)]
+pub struct StatusError {
+    /// The HTTP status Gmail answered with
+    pub status: StatusCode,
+    /// What failed, with Gmail's explanation
+    pub message: String,
+}
+
+impl std::fmt::Display for StatusError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for StatusError {}
+
+/// The error of a call Gmail answered with `status`, e.g. "Failed to fetch
+/// message: 404 Not Found"
+fn failed(what: &str, status: StatusCode) -> anyhow::Error {
+    StatusError {
+        status,
+        message: format!("{}: {}", what, status),
+    }
+    .into()
+}
+
+/// Whether a failed call may work if tried again later: Google couldn't be
+/// reached, or answered 429 or 5xx. Other errors (a bad address, a message
+/// too large) come back the same every time.
+pub fn is_transient(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        if let Some(e) = cause.downcast_ref::<StatusError>() {
+            return e.status == StatusCode::TOO_MANY_REQUESTS || e.status.is_server_error();
+        }
+        cause
+            .downcast_ref::<reqwest::Error>()
+            .is_some_and(|e| e.is_connect() || e.is_timeout() || e.is_request())
+    })
+}
+
+/// Recipients added to a reply besides the sender being answered
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Copies {
+    /// Cc addresses
+    pub cc: Vec<String>,
+    /// Bcc addresses, on top of the account's automatic ones
+    pub bcc: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UserProfile {
+    email_address: String,
+}
+
+/// A Google Contacts entry, reduced to what the email screen shows
+#[derive(Debug, Clone)]
+pub struct Person {
+    /// Display name
+    pub name: Option<String>,
+    /// Company
+    pub organization: Option<String>,
+    /// Job title
+    pub title: Option<String>,
+    /// Free-form notes on the contact
+    pub notes: Option<String>,
+}
+
+impl Person {
+    /// One-line description such as "Ana Pérez · CTO at Acme · Met at RustConf"
+    pub fn describe(&self) -> String {
+        let role = match (&self.title, &self.organization) {
+            (Some(title), Some(org)) => Some(format!("{} at {}", title, org)),
+            (Some(title), None) => Some(title.clone()),
+            (None, Some(org)) => Some(org.clone()),
+            (None, None) => None,
+        };
+        let notes = self
+            .notes
+            .as_ref()
+            .map(|n| n.lines().next().unwrap_or_default().to_string());
+
+        [self.name.clone(), role, notes]
+            .into_iter()
+            .flatten()
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+            .join(" · ")
+    }
+}
+
+impl From<PersonResponse> for Person {
+    fn from(person: PersonResponse) -> Self {
+        let organization = person.organizations.and_then(|o| o.into_iter().next());
+        Self {
+            name: person
+                .names
+                .and_then(|n| n.into_iter().next())
+                .and_then(|n| n.display_name),
+            title: organization.as_ref().and_then(|o| o.title.clone()),
+            organization: organization.and_then(|o| o.name),
+            notes: person
+                .biographies
+                .and_then(|b| b.into_iter().next())
+                .map(|b| b.value),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchContactsResponse {
+    results: Option<Vec<SearchResult>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchResult {
+    person: PersonResponse,
+}
+
+#[derive(Debug, Deserialize)]
+struct PersonResponse {
+    names: Option<Vec<PersonName>>,
+    organizations: Option<Vec<Organization>>,
+    biographies: Option<Vec<Biography>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PersonName {
+    display_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Organization {
+    name: Option<String>,
+    title: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Biography {
+    value: String,
+}
+
+/// Headers of a message in a thread, without its body
+#[derive(Debug, Clone)]
+pub struct MessageMeta {
+    /// Message id
+    pub id: String,
+    /// To header
+    pub to: String,
+    /// Subject header
+    pub subject: String,
+    /// Date sent or received
+    pub date: DateTime<Utc>,
+    /// Gmail label ids
+    pub labels: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ThreadListResponse {
+    threads: Option<Vec<ThreadRef>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ThreadRef {
+    id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ThreadResponse {
+    messages: Option<Vec<MessageResponse>>,
+}
+
+/// How a bulk change went, message by message
+#[derive(Debug, Default)]
+pub struct BatchResult {
+    /// Ids that were changed
+    pub succeeded: Vec<String>,
+    /// Ids that weren't, with the error of the request they were in
+    pub failed: Vec<(String, String)>,
+}
+
+impl BatchResult {
+    /// The first error, to show when some ids failed
+    pub fn first_error(&self) -> Option<&str> {
+        self.failed.first().map(|(_, e)| e.as_str())
+    }
+}
+
+/// Storage used by the account
+#[derive(Debug, Clone, Copy)]
+pub struct StorageQuota {
+    /// Bytes in use across Gmail, Drive and Photos
+    pub usage: u64,
+    /// Bytes available in all; `None` for unlimited plans
+    pub limit: Option<u64>,
+}
+
+impl StorageQuota {
+    /// Share of the limit in use, from 0 to 1
+    pub fn fraction(&self) -> Option<f64> {
+        self.limit
+            .filter(|&limit| limit > 0)
+            .map(|limit| self.usage as f64 / limit as f64)
+    }
+
+    /// Whether little space is left; new mail bounces once it runs out
+    pub fn is_nearly_full(&self) -> bool {
+        self.fraction().is_some_and(|f| f >= STORAGE_WARNING)
+    }
+}
+
+/// A timed event on the user's calendar
+#[derive(Debug, Clone)]
+pub struct CalendarEvent {
+    /// Event title, or "(busy)" when it has none
+    pub summary: String,
+    /// When it starts
+    pub start: DateTime<Utc>,
+    /// When it ends
+    pub end: DateTime<Utc>,
+}
+
+/// A Gmail label
+#[derive(Debug, Clone, Deserialize)]
+pub struct Label {
+    /// Label id, e.g. "INBOX" or "Label_12"
+    pub id: String,
+    /// Display name, with "/" separating nested labels
+    pub name: String,
+}
+
+/// A Gmail draft
+#[derive(Debug, Clone)]
+pub struct Draft {
+    /// Draft id, used to update, send or discard it
+    pub id: String,
+    /// The draft's current message; its id changes on every update
+    pub message: Email,
+}
+
+#[derive(Debug, Deserialize)]
+struct DraftListResponse {
+    drafts: Option<Vec<DraftRef>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DraftRef {
+    id: String,
+    message: MessageRef,
+}
+
+#[derive(Debug, Deserialize)]
+struct LabelListResponse {
+    labels: Option<Vec<Label>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AboutResponse {
+    storage_quota: QuotaResponse,
+}
+
+/// Drive reports byte counts as strings
+#[derive(Debug, Deserialize)]
+struct QuotaResponse {
+    limit: Option<String>,
+    usage: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EventListResponse {
+    items: Option<Vec<EventResponse>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EventResponse {
+    summary: Option<String>,
+    start: EventTime,
+    end: EventTime,
+    /// "transparent" when the event doesn't block time
+    transparency: Option<String>,
+    attendees: Option<Vec<AttendeeResponse>>,
+}
+
+/// All-day events have a `date` instead of a `dateTime`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EventTime {
+    date_time: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AttendeeResponse {
+    #[serde(rename = "self")]
+    is_self: Option<bool>,
+    response_status: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MessageListResponse {
+    messages: Option<Vec<MessageRef>>,
+    next_page_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessageRef {
+    id: String,
+}
+
+/// A message's labels and history id, from a `format=minimal` fetch
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MessageState {
+    history_id: String,
+    label_ids: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MessageResponse {
+    id: String,
+    thread_id: String,
+    history_id: Option<String>,
+    label_ids: Option<Vec<String>>,
+    snippet: Option<String>,
+    size_estimate: Option<u64>,
+    payload: MessagePart,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MessagePart {
+    mime_type: Option<String>,
+    headers: Option<Vec<Header>>,
+    body: Option<MessageBody>,
+    parts: Option<Vec<MessagePart>>,
+    filename: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Header {
+    name: String,
+    value: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawMessageResponse {
+    raw: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MessageBody {
+    data: Option<String>,
+    size: Option<u64>,
+    attachment_id: Option<String>,
+}
+
+mod dateparse {
+    use chrono::DateTime;
+
+    pub fn parse(s: &str) -> Result<i64, ()> {
+        if let Ok(dt) = DateTime::parse_from_rfc2822(s) {
+            return Ok(dt.timestamp());
+        }
+
+        let formats = [
+            "%a, %d %b %Y %H:%M:%S %z",
+            "%d %b %Y %H:%M:%S %z",
+            "%Y-%m-%d %H:%M:%S",
+        ];
+
+        for fmt in formats {
+            if let Ok(dt) = DateTime::parse_from_str(s, fmt) {
+                return Ok(dt.timestamp());
+            }
+        }
+
+        Err(())
+    }
+}
+
+/// Rewriting a draft's text while leaving its other MIME parts alone
+mod mime {
+    use anyhow::{Result, bail};
+
+    const TEXT_PART: &str = "Content-Type: text/plain; charset=utf-8\r\n\
+                             Content-Transfer-Encoding: 8bit";
+
+    /// The message with its text replaced. Attachments and other parts of a
+    /// multipart/mixed message are kept as they were; an HTML alternative
+    /// can't be kept in step with edited text, so it gives way to the text.
+    pub fn replace_text_body(raw: &str, text: &str) -> Result<String> {
+        let (headers, body) = split(raw);
+        let content_type = header(headers, "content-type").unwrap_or_default();
+        let kind = content_type.to_ascii_lowercase();
+
+        if kind.starts_with("multipart/") && !kind.starts_with("multipart/alternative") {
+            let Some(boundary) = boundary(&content_type) else {
+                bail!("The draft's MIME structure has no boundary; edit it in Gmail");
+            };
+            let parts = replace_text_part(body, &boundary, text)?;
+            return Ok(format!("{}\r\n\r\n{}", headers, parts));
+        }
+
+        // A single part: drop the old MIME headers (and their folded lines)
+        let mut message = String::new();
+        let mut skipping = false;
+        for line in headers.lines() {
+            if !line.starts_with([' ', '\t']) {
+                let name = line
+                    .split(':')
+                    .next()
+                    .unwrap_or_default()
+                    .to_ascii_lowercase();
+                skipping = name.starts_with("content-") || name == "mime-version";
+            }
+            if !skipping {
+                message.push_str(line);
+                message.push_str("\r\n");
+            }
+        }
+        message.push_str("MIME-Version: 1.0\r\n");
+        message.push_str(TEXT_PART);
+        message.push_str("\r\n\r\n");
+        message.push_str(text);
+        Ok(message)
+    }
+
+    /// The parts between `boundary` lines with the first text part (or
+    /// text/html alternative) replaced, or the text added as the first part
+    fn replace_text_part(body: &str, boundary: &str, text: &str) -> Result<String> {
+        let delimiter = format!("--{}", boundary);
+        if text.contains(&delimiter) {
+            bail!("The edited text contains the draft's MIME boundary; edit it in Gmail");
+        }
+        let new_part = format!("\r\n{}\r\n\r\n{}\r\n", TEXT_PART, text);
+
+        let mut sections: Vec<String> = body.split(&delimiter).map(str::to_string).collect();
+        let text_part = sections
+            .iter()
+            .enumerate()
+            .skip(1)
+            .take_while(|(_, section)| !section.starts_with("--"))
+            .find(|(_, section)| {
+                let (headers, _) = split(section.trim_start_matches(['\r', '\n']));
+                let kind = header(headers, "content-type")
+                    .unwrap_or_else(|| "text/plain".to_string())
+                    .to_ascii_lowercase();
+                let disposition = header(headers, "content-disposition")
+                    .unwrap_or_default()
+                    .to_ascii_lowercase();
+                !disposition.starts_with("attachment")
+                    && (kind.starts_with("text/plain")
+                        || kind.starts_with("text/html")
+                        || kind.starts_with("multipart/alternative"))
+            })
+            .map(|(i, _)| i);
+
+        match text_part {
+            Some(i) => sections[i] = new_part,
+            None if sections.len() > 1 => sections.insert(1, new_part),
+            None => bail!("The draft's MIME structure couldn't be read; edit it in Gmail"),
+        }
+        Ok(sections.join(&delimiter))
+    }
+
+    /// Headers and body of a MIME entity
+    fn split(raw: &str) -> (&str, &str) {
+        raw.split_once("\r\n\r\n")
+            .or_else(|| raw.split_once("\n\n"))
+            .unwrap_or((raw, ""))
+    }
+
+    /// A header's value with folded lines joined
+    fn header(headers: &str, name: &str) -> Option<String> {
+        let mut value: Option<String> = None;
+        for line in headers.lines() {
+            if line.starts_with([' ', '\t']) {
+                if let Some(value) = value.as_mut() {
+                    value.push(' ');
+                    value.push_str(line.trim());
+                }
+                continue;
+            }
+            if value.is_some() {
+                break;
+            }
+            if let Some((key, rest)) = line.split_once(':')
+                && key.trim().eq_ignore_ascii_case(name)
+            {
+                value = Some(rest.trim().to_string());
+            }
+        }
+        value
+    }
+
+    /// The boundary parameter of a multipart Content-Type, quotes removed
+    fn boundary(content_type: &str) -> Option<String> {
+        content_type.split(';').find_map(|param| {
+            let (key, value) = param.split_once('=')?;
+            key.trim()
+                .eq_ignore_ascii_case("boundary")
+                .then(|| value.trim().trim_matches('"').to_string())
+        })
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn attachments_survive_an_edit() {
+            let raw = "To: ana@example.com\r\n\
+                       Content-Type: multipart/mixed; boundary=\"b1\"\r\n\
+                       \r\n\
+                       --b1\r\n\
+                       Content-Type: multipart/alternative; boundary=b2\r\n\
+                       \r\n\
+                       --b2\r\n\
+                       Content-Type: text/plain\r\n\r\nold\r\n\
+                       --b2\r\n\
+                       Content-Type: text/html\r\n\r\n<p>old</p>\r\n\
+                       --b2--\r\n\
+                       --b1\r\n\
+                       Content-Type: application/pdf; name=a.pdf\r\n\
+                       Content-Disposition: attachment; filename=a.pdf\r\n\r\nJVBERi0=\r\n\
+                       --b1--\r\n";
+            let edited = replace_text_body(raw, "new text").unwrap();
+            assert!(edited.starts_with("To: ana@example.com\r\n"));
+            assert!(edited.contains("boundary=\"b1\""));
+            assert!(edited.contains("\r\n\r\nnew text\r\n--b1\r\n"));
+            assert!(edited.contains("filename=a.pdf\r\n\r\nJVBERi0=\r\n--b1--"));
+            assert!(!edited.contains("old"));
+        }
+
+        #[test]
+        fn a_plain_draft_stays_plain() {
+            let raw = "To: ana@example.com\r\nContent-Type: text/plain\r\n\r\nold";
+            let edited = replace_text_body(raw, "new").unwrap();
+            assert_eq!(
+                edited,
+                format!(
+                    "To: ana@example.com\r\nMIME-Version: 1.0\r\n{}\r\n\r\nnew",
+                    TEXT_PART
+                )
+            );
+        }
+    }
+}
@@ -0,0 +1,236 @@
+//! HTML sanitizing and HTML-to-text rendering used for email bodies, and
+//! spotting the read-tracking images in them
+
+/// Characters email templates use to pad preheaders or defeat tracking; invisible in a terminal
+const INVISIBLE_CHARS: &[char] = &[
+    '\u{200b}', '\u{200c}', '\u{200d}', '\u{2060}', '\u{feff}', '\u{034f}', '\u{00ad}',
+];
+
+/// Hosts of mail trackers and the email platforms' open-tracking endpoints
+const TRACKER_HOSTS: &[&str] = &[
+    "list-manage.com",
+    "mailchimp.com",
+    "sendgrid.net",
+    "mandrillapp.com",
+    "mailgun.org",
+    "sparkpostmail.com",
+    "hubspotemail.net",
+    "hs-analytics.net",
+    "createsend.com",
+    "cmail19.com",
+    "cmail20.com",
+    "exacttarget.com",
+    "rs6.net",
+    "klclick.com",
+    "klaviyomail.com",
+    "mailjet.com",
+    "customeriomail.com",
+    "intercom-mail.com",
+    "convertkit-mail.com",
+    "mailtrack.io",
+    "mailfoogae.appspot.com",
+    "yesware.com",
+    "bananatag.com",
+    "getnotify.com",
+    "mixmax.com",
+    "superhuman.com",
+    "pixel.wp.com",
+];
+
+/// Path fragments of open-tracking images on hosts not listed above
+const TRACKER_PATHS: &[&str] = &[
+    "/open.gif",
+    "/open?",
+    "/track/open",
+    "/wf/open",
+    "/trk?",
+    "/pixel",
+    "/beacon",
+];
+
+/// Render an HTML body as readable plain text.
+///
+/// The body is sanitized first. Lists keep their bullets, tables keep column
+/// alignment, and blockquotes (quoted replies) are prefixed with `> `.
+/// Elements hidden with `display: none` (newsletter preheaders) are dropped.
+pub fn to_text(html: &str, width: usize) -> Option<String> {
+    let html = sanitize(html);
+    let rendered = html2text::config::plain()
+        .use_doc_css()
+        .link_footnotes(false)
+        .allow_width_overflow()
+        .string_from_read(html.as_bytes(), width)
+        .or_else(|_| html2text::from_read(html.as_bytes(), width))
+        .ok()?;
+
+    Some(tidy(&rendered))
+}
+
+/// An HTML body without what's dangerous or just noise in a terminal: scripts,
+/// forms, external stylesheets, `data:` URIs, comments and unknown tags such
+/// as Outlook's `<o:p>`. `<style>` blocks and the attributes their rules
+/// select on stay, so hidden preheaders stay hidden.
+pub fn sanitize(html: &str) -> String {
+    ammonia::Builder::default()
+        .add_tags(&["style"])
+        .rm_clean_content_tags(&["style"])
+        .add_clean_content_tags(&["form", "select", "textarea", "noscript"])
+        .add_generic_attributes(&["style", "class", "id", "hidden"])
+        .clean(html)
+        .to_string()
+}
+
+/// Remove invisible characters, trailing whitespace and runs of blank lines
+fn tidy(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut blank_run = 0;
+
+    for line in text.lines() {
+        let line: String = line
+            .chars()
+            .filter(|c| !INVISIBLE_CHARS.contains(c))
+            .collect();
+        let line = line.trim_end();
+
+        if line.trim().is_empty() {
+            blank_run += 1;
+            if blank_run > 1 || out.is_empty() {
+                continue;
+            }
+            out.push('\n');
+        } else {
+            blank_run = 0;
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+
+    out.trim_end().to_string()
+}
+
+/// Hosts of the read-tracking images in an HTML body: remote 1x1 or hidden
+/// images, and images served by known trackers. Each host is listed once.
+pub fn trackers(html: &str) -> Vec<String> {
+    let lower = html.to_ascii_lowercase();
+    let mut hosts: Vec<String> = Vec::new();
+
+    for (start, _) in lower.match_indices("<img") {
+        let tag = match lower[start..].find('>') {
+            Some(end) => &lower[start..start + end],
+            None => &lower[start..],
+        };
+        let Some(src) = attribute(tag, "src") else {
+            continue;
+        };
+        let Some(host) = remote_host(src) else {
+            continue;
+        };
+
+        let tiny =
+            |name: &str| attribute(tag, name).is_some_and(|v| matches!(v, "0" | "1" | "1px"));
+        let style = attribute(tag, "style").unwrap_or_default().replace(' ', "");
+        let hidden = (tiny("width") && tiny("height"))
+            || style.contains("display:none")
+            || (style.contains("width:1px") && style.contains("height:1px"));
+        let known = TRACKER_HOSTS
+            .iter()
+            .any(|tracker| host == *tracker || host.ends_with(&format!(".{}", tracker)))
+            || TRACKER_PATHS.iter().any(|path| src.contains(path));
+
+        if (hidden || known) && !hosts.contains(&host.to_string()) {
+            hosts.push(host.to_string());
+        }
+    }
+    hosts
+}
+
+/// Links in an HTML body as (target, text) pairs, in order. The text has its
+/// tags stripped, entities decoded and whitespace collapsed.
+pub fn links(html: &str) -> Vec<(String, String)> {
+    let lower = html.to_ascii_lowercase();
+    let mut links = Vec::new();
+
+    for (start, _) in lower.match_indices("<a") {
+        if !lower[start + 2..].starts_with(char::is_whitespace) {
+            continue;
+        }
+        let Some(tag_len) = lower[start..].find('>') else {
+            break;
+        };
+        let Some(href) = attribute(&lower[start..start + tag_len], "href") else {
+            continue;
+        };
+        // Same offsets in the original, which keeps the URL's case
+        let at = href.as_ptr() as usize - lower.as_ptr() as usize;
+        let target = unescape(html[at..at + href.len()].trim());
+
+        let content_start = start + tag_len + 1;
+        let content_end = lower[content_start..]
+            .find("</a")
+            .map_or(lower.len(), |end| content_start + end);
+        let text = unescape(&strip_tags(&html[content_start..content_end]));
+        links.push((
+            target,
+            text.split_whitespace().collect::<Vec<_>>().join(" "),
+        ));
+    }
+    links
+}
+
+/// Text between tags; no space is put in their place, so "www.<b>bank</b>.com"
+/// stays one word
+fn strip_tags(html: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => out.push(c),
+            _ => {}
+        }
+    }
+    out
+}
+
+/// The handful of entities found in links and their text
+fn unescape(text: &str) -> String {
+    text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Value of an attribute in an opening tag, without its quotes
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let mut rest = tag;
+    loop {
+        let at = rest.find(name)?;
+        let before = rest[..at].chars().next_back();
+        let after = rest[at + name.len()..].trim_start();
+        rest = &rest[at + name.len()..];
+        if !before.is_some_and(char::is_whitespace) {
+            continue;
+        }
+        let Some(value) = after.strip_prefix('=') else {
+            continue;
+        };
+        let value = value.trim_start();
+        return Some(match value.chars().next() {
+            Some(quote @ ('"' | '\'')) => value[1..].split(quote).next().unwrap_or_default(),
+            _ => value.split(char::is_whitespace).next().unwrap_or_default(),
+        });
+    }
+}
+
+/// Host of an http(s) URL, or None for data: and cid: images
+fn remote_host(url: &str) -> Option<&str> {
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+        .or_else(|| url.strip_prefix("//"))?;
+    let host = rest.split(['/', '?', '#', ':']).next()?;
+    (!host.is_empty()).then_some(host)
+}
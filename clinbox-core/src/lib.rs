@@ -0,0 +1,51 @@
+//! Gmail, AI and configuration plumbing behind the `clinbox` email client,
+//! for tools (bots, dashboards) that want the same triage without scraping
+//! CLI output.
+//!
+//! Configuration and OAuth tokens are shared with the CLI in `~/.clinbox/`,
+//! so an account added with `clinbox account add` works here as-is.
+//!
+//! ```no_run
+//! use clinbox_core::{ai::AiClient, config::Config, gmail::GmailClient};
+//!
+//! # async fn run() -> anyhow::Result<()> {
+//! let config = Config::load()?;
+//! let account = config.get_default_account().expect("no account configured");
+//! let gmail = GmailClient::new(account, &config).await?;
+//! let ai = AiClient::new(&config)?;
+//!
+//! for email in gmail.search("is:unread", 10, |_, _| {}).await? {
+//!     let analysis = ai.analyze_email(&email, None, &[]).await?;
+//!     println!("{} {}: {}", analysis.priority.emoji(), email.subject, analysis.summary);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! - [`gmail`]: OAuth, search, fetch, archive/delete/label, replies, drafts, contacts
+//! - [`ai`]: analysis, reply drafts, article summaries and expense extraction via OpenRouter
+//! - [`auth`]: SPF, DKIM and DMARC results from the Authentication-Results header
+//! - [`cache`]: fetched messages kept on disk between runs
+//! - [`context`]: fitting email bodies into a model's token budget
+//! - [`diagnostics`]: recent API error responses, for bug reports
+//! - [`email`]: the `Email` model and its AI `EmailAnalysis`
+//! - [`config`]: `~/.clinbox/config.json` and the paths of the other stores
+//! - [`tasks`]: the local task list
+//! - [`redact`]: masking personal data before it reaches the AI
+//! - [`timings`]: time spent in Gmail and AI calls, and AI token use
+//! - [`html`]: HTML sanitizing and HTML-to-text rendering used for email bodies, and read-tracking detection
+
+#![warn(missing_docs)]
+
+pub mod ai;
+pub mod auth;
+pub mod cache;
+pub mod config;
+pub mod context;
+pub mod diagnostics;
+pub mod email;
+pub mod gmail;
+pub mod html;
+pub mod redact;
+pub mod tasks;
+pub mod timings;
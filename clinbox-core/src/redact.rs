@@ -0,0 +1,369 @@
+//! Masking personal data (card numbers, ID numbers, phone numbers, street
+//! addresses) in text sent to the AI, and putting it back into the answers
+
+/// Words that end a street name, e.g. "12 Baker Street"
+const STREET_SUFFIXES: &[&str] = &[
+    "street",
+    "st",
+    "avenue",
+    "ave",
+    "road",
+    "rd",
+    "boulevard",
+    "blvd",
+    "lane",
+    "ln",
+    "drive",
+    "dr",
+    "way",
+    "court",
+    "ct",
+    "place",
+    "pl",
+    "square",
+    "sq",
+    "terrace",
+    "parkway",
+    "highway",
+];
+
+/// Words that start a street name, e.g. "Calle Mayor 12"
+const STREET_PREFIXES: &[&str] = &[
+    "calle", "avenida", "avda", "av", "paseo", "plaza", "camino", "carrera", "rua",
+];
+
+/// Most words between a house number and the street suffix (or the street
+/// prefix and the house number)
+const MAX_STREET_WORDS: usize = 4;
+
+/// Placeholders standing in for the personal data masked so far; the
+/// originals never leave this map
+#[derive(Debug, Default)]
+pub struct Redactions {
+    /// (placeholder, original), in the order they were found
+    entries: Vec<(String, String)>,
+}
+
+impl Redactions {
+    /// An empty map
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of distinct values masked
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether nothing was masked
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// `text` with card numbers, ID numbers, phone numbers and street
+    /// addresses replaced by placeholders such as "[PHONE-1]". The same value
+    /// gets the same placeholder every time.
+    pub fn redact(&mut self, text: &str) -> String {
+        let mut spans: Vec<(usize, usize, &'static str)> = number_spans(text);
+        for span in address_spans(text) {
+            // An address's house number may already be a span; the address wins
+            spans.retain(|s| s.1 <= span.0 || s.0 >= span.1);
+            spans.push(span);
+        }
+        spans.sort();
+
+        let mut out = String::with_capacity(text.len());
+        let mut last = 0;
+        for (start, end, kind) in spans {
+            if start < last {
+                continue;
+            }
+            out.push_str(&text[last..start]);
+            out.push_str(&self.placeholder(kind, &text[start..end]));
+            last = end;
+        }
+        out.push_str(&text[last..]);
+        out
+    }
+
+    /// `text` with the placeholders put back to the original values
+    pub fn restore(&self, text: &str) -> String {
+        // Longest first, so "[ID-10]" isn't caught by "[ID-1"
+        let mut entries: Vec<&(String, String)> = self.entries.iter().collect();
+        entries.sort_by_key(|(placeholder, _)| std::cmp::Reverse(placeholder.len()));
+        entries
+            .into_iter()
+            .fold(text.to_string(), |text, (placeholder, original)| {
+                text.replace(placeholder, original)
+            })
+    }
+
+    fn placeholder(&mut self, kind: &str, original: &str) -> String {
+        if let Some((placeholder, _)) = self.entries.iter().find(|(_, o)| o == original) {
+            return placeholder.clone();
+        }
+        let n = self
+            .entries
+            .iter()
+            .filter(|(p, _)| p.starts_with(&format!("[{}-", kind)))
+            .count()
+            + 1;
+        let placeholder = format!("[{}-{}]", kind, n);
+        self.entries
+            .push((placeholder.clone(), original.to_string()));
+        placeholder
+    }
+}
+
+/// Card numbers, ID numbers and phone numbers: runs of digits, possibly
+/// split by spaces, dashes, dots or parentheses
+fn number_spans(text: &str) -> Vec<(usize, usize, &'static str)> {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let mut spans = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let (start, c) = chars[i];
+        let starts_number = c.is_ascii_digit()
+            || (matches!(c, '+' | '(')
+                && chars.get(i + 1).is_some_and(|(_, n)| n.is_ascii_digit()));
+        // Only whole numbers: "A123" or "x1" are codes, not numbers
+        let glued = i > 0 && chars[i - 1].1.is_alphanumeric();
+        if !starts_number || glued {
+            i += 1;
+            continue;
+        }
+
+        let mut j = i + 1;
+        let mut end = start + c.len_utf8();
+        while j < chars.len() {
+            let (at, c) = chars[j];
+            let next_is_digit = chars.get(j + 1).is_some_and(|(_, n)| n.is_ascii_digit());
+            // Phones split groups with spaces; after a date ("2024-07-01 10:30")
+            // a space ends the number
+            let continues = c.is_ascii_digit()
+                || (matches!(c, '-' | '.' | '(') && next_is_digit)
+                || (c == ')'
+                    && chars
+                        .get(j + 1)
+                        .is_some_and(|(_, n)| *n == ' ' || n.is_ascii_digit()))
+                || (c == ' ' && next_is_digit && !text[start..at].contains(['-', '.']));
+            if !continues {
+                break;
+            }
+            if c.is_ascii_digit() || c == ')' {
+                end = at + c.len_utf8();
+            }
+            j += 1;
+        }
+
+        let raw = &text[start..end];
+        let letter_follows = text[end..].starts_with(|c: char| c.is_ascii_alphabetic());
+        match classify_number(raw, letter_follows) {
+            // A national ID's check letter, e.g. "12345678Z"
+            Some("ID") if letter_follows => spans.push((start, end + 1, "ID")),
+            Some(kind) => spans.push((start, end, kind)),
+            None => {}
+        }
+        while j < chars.len() && chars[j].0 < end {
+            j += 1;
+        }
+        i = j.max(i + 1);
+    }
+
+    spans
+}
+
+/// What a run of digits looks like, if it looks like personal data
+fn classify_number(raw: &str, letter_follows: bool) -> Option<&'static str> {
+    let digits: String = raw.chars().filter(char::is_ascii_digit).collect();
+    let separated = raw.contains([' ', '-', '.', '(']);
+
+    if (13..=19).contains(&digits.len()) && luhn(&digits) {
+        return Some("CARD");
+    }
+    if letter_follows {
+        return (digits.len() == 8 && !separated).then_some("ID");
+    }
+    if is_ssn(raw) {
+        return Some("ID");
+    }
+    if is_ipv4(raw) {
+        return None;
+    }
+    let phone_length = if raw.starts_with('+') { 8..=15 } else { 9..=15 };
+    if phone_length.contains(&digits.len()) && (separated || raw.starts_with('+')) {
+        return Some("PHONE");
+    }
+    None
+}
+
+/// "123-45-6789"
+fn is_ssn(raw: &str) -> bool {
+    let parts: Vec<&str> = raw.split('-').collect();
+    parts.len() == 3
+        && [3, 2, 4]
+            .iter()
+            .zip(&parts)
+            .all(|(&len, part)| part.len() == len && part.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// "192.168.1.20", which would otherwise pass for a phone number
+fn is_ipv4(raw: &str) -> bool {
+    let parts: Vec<&str> = raw.split('.').collect();
+    parts.len() == 4
+        && parts
+            .iter()
+            .all(|p| (1..=3).contains(&p.len()) && p.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// The Luhn checksum card numbers carry
+fn luhn(digits: &str) -> bool {
+    let sum: u32 = digits
+        .chars()
+        .rev()
+        .filter_map(|c| c.to_digit(10))
+        .enumerate()
+        .map(|(i, d)| {
+            if i % 2 == 1 {
+                let doubled = d * 2;
+                if doubled > 9 { doubled - 9 } else { doubled }
+            } else {
+                d
+            }
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+/// Street addresses: "12 Baker Street", "1600 Amphitheatre Pkwy" or "Calle Mayor 12"
+fn address_spans(text: &str) -> Vec<(usize, usize, &'static str)> {
+    let words: Vec<(usize, &str)> = text
+        .split_whitespace()
+        .map(|w| (w.as_ptr() as usize - text.as_ptr() as usize, w))
+        .collect();
+    let bare = |w: &str| {
+        w.trim_matches(|c: char| !c.is_alphanumeric())
+            .to_lowercase()
+    };
+    let is_number = |w: &str| {
+        let w = w.trim_end_matches([',', '.']);
+        (1..=6).contains(&w.len()) && w.chars().all(|c| c.is_ascii_digit())
+    };
+    let end_of = |(at, w): (usize, &str)| at + w.trim_end_matches([',', '.', ';', ':']).len();
+
+    let mut spans = Vec::new();
+    for (i, &(_, word)) in words.iter().enumerate() {
+        let name = bare(word);
+        if STREET_SUFFIXES.contains(&name.as_str()) && i > 0 {
+            // Number, then capitalized words, then the suffix
+            let from = i.saturating_sub(MAX_STREET_WORDS + 1);
+            if let Some(n) = (from..i).rev().find(|&n| is_number(words[n].1))
+                && n < i - 1
+                && words[n + 1..i]
+                    .iter()
+                    .all(|(_, w)| w.starts_with(|c: char| c.is_uppercase()))
+            {
+                spans.push((words[n].0, end_of(words[i]), "ADDRESS"));
+            }
+        } else if STREET_PREFIXES.contains(&name.as_str())
+            && word.starts_with(|c: char| c.is_uppercase())
+        {
+            // Prefix, then the name, then the number
+            let to = (i + MAX_STREET_WORDS + 1).min(words.len() - 1);
+            if let Some(n) = (i + 2..=to).find(|&n| is_number(words[n].1)) {
+                spans.push((words[i].0, end_of(words[n]), "ADDRESS"));
+            }
+        }
+    }
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let text = "Card 4111 1111 1111 1111, call +34 612 345 678 or write to 12 Baker Street.";
+        let mut redactions = Redactions::new();
+        let redacted = redactions.redact(text);
+        assert_eq!(
+            redacted,
+            "Card [CARD-1], call [PHONE-1] or write to [ADDRESS-1]."
+        );
+        assert_eq!(redactions.len(), 3);
+        assert_eq!(redactions.restore(&redacted), text);
+    }
+
+    #[test]
+    fn same_value_same_placeholder() {
+        let mut redactions = Redactions::new();
+        assert_eq!(
+            redactions.redact("12345678Z and 12345678Z"),
+            "[ID-1] and [ID-1]"
+        );
+        assert_eq!(redactions.len(), 1);
+    }
+
+    #[test]
+    fn restore_tells_id_1_from_id_10() {
+        let mut redactions = Redactions::new();
+        let ids: Vec<String> = (0..10).map(|n| format!("1234567{}Z", n)).collect();
+        let redacted = redactions.redact(&ids.join(" "));
+        assert!(redacted.ends_with("[ID-10]"));
+        assert_eq!(
+            redactions.restore("[ID-10] then [ID-1]"),
+            format!("{} then {}", ids[9], ids[0])
+        );
+    }
+
+    #[test]
+    fn leaves_dates_times_ips_and_orders() {
+        let mut redactions = Redactions::new();
+        for text in [
+            "Meeting on 2024-07-01 10:30",
+            "Delivered 01.07.2024 at 9:45",
+            "Login from 192.168.1.20",
+            "Order 123456789 has shipped",
+            "Your order #40321",
+        ] {
+            assert_eq!(redactions.redact(text), text);
+        }
+        assert!(redactions.is_empty());
+    }
+
+    #[test]
+    fn cards_with_spaces_and_dashes() {
+        let mut redactions = Redactions::new();
+        assert_eq!(
+            redactions.redact("4111-1111-1111-1111 / 5500 0000 0000 0004"),
+            "[CARD-1] / [CARD-2]"
+        );
+        // Fails the Luhn check, and is too long for a phone number
+        let text = "4111 1111 1111 1112";
+        assert_eq!(redactions.redact(text), text);
+    }
+
+    #[test]
+    fn national_ids() {
+        let mut redactions = Redactions::new();
+        assert_eq!(redactions.redact("DNI 12345678Z."), "DNI [ID-1].");
+        assert_eq!(redactions.redact("SSN 123-45-6789"), "SSN [ID-2]");
+        // Glued to letters it's a code, not an ID
+        assert_eq!(redactions.redact("ref A12345678Z"), "ref A12345678Z");
+    }
+
+    #[test]
+    fn addresses_next_to_non_ascii_text() {
+        let mut redactions = Redactions::new();
+        assert_eq!(
+            redactions.redact("Envío a Calle Mayor 12, Madrid (España)"),
+            "Envío a [ADDRESS-1], Madrid (España)"
+        );
+        assert_eq!(
+            redactions.redact("Dirección: 221 Baker Street — ¡gracias!"),
+            "Dirección: [ADDRESS-2] — ¡gracias!"
+        );
+    }
+}
@@ -1,3 +1,5 @@
+//! The local task list
+
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -5,21 +7,33 @@ use std::fs;
 
 use crate::config::Config;
 
+/// A to-do item, usually created from an email
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Task {
+    /// Random id
     pub id: String,
+    /// Short title
     pub title: String,
+    /// Longer notes, e.g. the AI's suggested action
     pub description: Option<String>,
+    /// Gmail id of the email the task came from
     pub source_email_id: Option<String>,
+    /// Subject of that email
     pub source_email_subject: Option<String>,
+    /// When the task was created
     pub created_at: DateTime<Utc>,
+    /// Optional deadline
     pub due_date: Option<DateTime<Utc>>,
+    /// Whether the task is done
     pub completed: bool,
+    /// When it was marked done
     pub completed_at: Option<DateTime<Utc>>,
 }
 
+/// The local task list, stored in ~/.clinbox/tasks.json
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct TaskStore {
+    /// All tasks, done or not
     pub tasks: Vec<Task>,
 }
 
@@ -81,7 +95,6 @@ impl TaskStore {
     }
 
     /// Mark a task as completed
-    #[allow(dead_code)]
     pub fn complete(&mut self, id: &str) -> Result<()> {
         if let Some(task) = self.tasks.iter_mut().find(|t| t.id == id) {
             task.completed = true;
@@ -92,7 +105,6 @@ impl TaskStore {
     }
 
     /// Delete a task
-    #[allow(dead_code)]
     pub fn delete(&mut self, id: &str) -> Result<()> {
         self.tasks.retain(|t| t.id != id);
         self.save()?;
@@ -0,0 +1,108 @@
+//! Time spent in Gmail calls, AI calls and screen redraws, plus AI token use,
+//! summed over the process so a session can report where its time went
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// What the time was spent on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// Gmail and People API requests
+    Gmail,
+    /// AI chat completions
+    Ai,
+    /// Drawing the terminal UI
+    Render,
+}
+
+impl Kind {
+    /// Short name for reports
+    pub fn label(&self) -> &'static str {
+        match self {
+            Kind::Gmail => "Gmail",
+            Kind::Ai => "AI",
+            Kind::Render => "Rendering",
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Totals for one kind of work
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Totals {
+    /// Number of calls (or redraws)
+    pub calls: u64,
+    /// Time spent in all of them
+    pub total: Duration,
+    /// The longest single one
+    pub slowest: Duration,
+}
+
+impl Totals {
+    /// Average time per call
+    pub fn average(&self) -> Duration {
+        if self.calls == 0 {
+            Duration::ZERO
+        } else {
+            self.total / self.calls as u32
+        }
+    }
+}
+
+/// Everything recorded so far
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Report {
+    /// Gmail, AI and rendering totals, in that order
+    pub totals: [Totals; 3],
+    /// Prompt tokens across all AI calls
+    pub prompt_tokens: u64,
+    /// Completion tokens across all AI calls
+    pub completion_tokens: u64,
+    /// Cost in USD as reported by OpenRouter, when it reported one
+    pub cost: Option<f64>,
+}
+
+impl Report {
+    /// Totals for one kind of work
+    pub fn get(&self, kind: Kind) -> Totals {
+        self.totals[kind.index()]
+    }
+}
+
+static REPORT: Mutex<Report> = Mutex::new(Report {
+    totals: [Totals {
+        calls: 0,
+        total: Duration::ZERO,
+        slowest: Duration::ZERO,
+    }; 3],
+    prompt_tokens: 0,
+    completion_tokens: 0,
+    cost: None,
+});
+
+/// Add one call's duration
+pub fn record(kind: Kind, elapsed: Duration) {
+    let mut report = REPORT.lock().unwrap_or_else(|e| e.into_inner());
+    let totals = &mut report.totals[kind.index()];
+    totals.calls += 1;
+    totals.total += elapsed;
+    totals.slowest = totals.slowest.max(elapsed);
+}
+
+/// Add one AI call's token use and cost
+pub fn record_usage(prompt_tokens: u64, completion_tokens: u64, cost: Option<f64>) {
+    let mut report = REPORT.lock().unwrap_or_else(|e| e.into_inner());
+    report.prompt_tokens += prompt_tokens;
+    report.completion_tokens += completion_tokens;
+    if let Some(cost) = cost {
+        report.cost = Some(report.cost.unwrap_or(0.0) + cost);
+    }
+}
+
+/// What has been recorded so far
+pub fn report() -> Report {
+    *REPORT.lock().unwrap_or_else(|e| e.into_inner())
+}
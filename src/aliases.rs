@@ -0,0 +1,80 @@
+use clinbox_core::config::AliasConfig;
+use clinbox_core::email::{Email, EmailAnalysis};
+
+use crate::contacts::{normalize_sender, parse_addresses};
+
+/// The user's own alias addresses, used to tell which one a message was sent to
+pub struct Aliases {
+    entries: Vec<AliasConfig>,
+    /// Account address, so plus-addressed variants ("me+shop@") are recognized without config
+    own: Option<String>,
+}
+
+/// The alias a message was delivered to, and the rule configured for it
+pub struct AliasMatch<'a> {
+    /// Recipient address as it appears on the message
+    pub address: String,
+    pub rule: Option<&'a AliasConfig>,
+}
+
+impl Aliases {
+    pub fn new(entries: &[AliasConfig], own: Option<&str>) -> Self {
+        Self {
+            entries: entries
+                .iter()
+                .map(|a| AliasConfig {
+                    address: normalize_sender(&a.address),
+                    ..a.clone()
+                })
+                .collect(),
+            own: own.map(|o| o.to_lowercase()),
+        }
+    }
+
+    /// Find which alias (if any) the message was sent to
+    pub fn matched(&self, email: &Email) -> Option<AliasMatch<'_>> {
+        for (_, recipient) in parse_addresses(&email.to) {
+            let address = recipient.to_lowercase();
+            let (local, domain) = address.rsplit_once('@')?;
+            let base = match local.split_once('+') {
+                Some((base, _)) => format!("{}@{}", base, domain),
+                None => address.clone(),
+            };
+
+            // Exact entries win over catch-all domains and plus-address bases
+            let rule = self
+                .entries
+                .iter()
+                .find(|a| a.address == address)
+                .or_else(|| self.entries.iter().find(|a| a.address == domain))
+                .or_else(|| self.entries.iter().find(|a| a.address == base));
+            if rule.is_some() {
+                return Some(AliasMatch {
+                    address: recipient,
+                    rule,
+                });
+            }
+
+            if base != address && self.own.as_deref() == Some(base.as_str()) {
+                return Some(AliasMatch {
+                    address: recipient,
+                    rule: None,
+                });
+            }
+        }
+        None
+    }
+
+    /// Apply the priority configured for the alias the message was sent to
+    pub fn apply(&self, email: &Email, analysis: &mut EmailAnalysis) {
+        if let Some(priority) = self
+            .matched(email)
+            .and_then(|m| m.rule)
+            .and_then(|rule| rule.priority)
+            && analysis.priority != priority
+        {
+            analysis.priority = priority;
+            analysis.confidence = None;
+        }
+    }
+}
@@ -0,0 +1,238 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use clinbox_core::email::{Attachment, Email};
+use clinbox_core::gmail::GmailClient;
+
+/// Extensions that run code when opened
+const EXECUTABLE_EXTENSIONS: &[&str] = &[
+    "exe", "scr", "com", "pif", "bat", "cmd", "msi", "msp", "cpl", "hta", "jar", "js", "jse",
+    "vbs", "vbe", "wsf", "wsh", "ps1", "psm1", "lnk", "reg", "apk", "app", "dmg", "iso", "img",
+    "sh", "command",
+];
+
+/// Office formats that can carry macros
+const MACRO_EXTENSIONS: &[&str] = &[
+    "docm", "dotm", "xlsm", "xltm", "xlam", "pptm", "potm", "ppam", "ppsm", "sldm",
+];
+
+/// Harmless-looking extensions used to disguise the real one ("invoice.pdf.exe")
+const DECOY_EXTENSIONS: &[&str] = &[
+    "pdf", "doc", "docx", "xls", "xlsx", "ppt", "pptx", "txt", "rtf", "csv", "jpg", "jpeg", "png",
+    "gif", "mp3", "mp4", "zip", "html",
+];
+
+/// MIME types a well-behaved sender uses for common extensions
+const EXPECTED_MIME: &[(&str, &[&str])] = &[
+    ("pdf", &["application/pdf"]),
+    ("jpg", &["image/jpeg", "image/jpg"]),
+    ("jpeg", &["image/jpeg", "image/jpg"]),
+    ("png", &["image/png"]),
+    ("gif", &["image/gif"]),
+    ("txt", &["text/plain"]),
+    (
+        "csv",
+        &["text/csv", "text/plain", "application/vnd.ms-excel"],
+    ),
+    ("html", &["text/html"]),
+    ("zip", &["application/zip", "application/x-zip-compressed"]),
+    (
+        "docx",
+        &["application/vnd.openxmlformats-officedocument.wordprocessingml.document"],
+    ),
+    (
+        "xlsx",
+        &["application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"],
+    ),
+    (
+        "pptx",
+        &["application/vnd.openxmlformats-officedocument.presentationml.presentation"],
+    ),
+    ("ics", &["text/calendar", "application/ics"]),
+];
+
+/// MIME types that declare a Windows executable
+const EXECUTABLE_MIME: &[&str] = &[
+    "application/x-msdownload",
+    "application/x-msdos-program",
+    "application/x-dosexec",
+    "application/x-executable",
+    "application/vnd.microsoft.portable-executable",
+];
+
+/// Bodies shorter than this ("see attached") get attachment text added to the analysis
+const SHORT_BODY_CHARS: usize = 200;
+/// Larger attachments aren't downloaded just for the analysis
+const MAX_EXTRACT_BYTES: u64 = 10 * 1024 * 1024;
+const EXCERPT_CHARS: usize = 1500;
+
+/// Reasons an attachment looks dangerous to open; empty when it looks fine
+pub fn risks(attachment: &Attachment) -> Vec<&'static str> {
+    let name = attachment.filename.trim().to_lowercase();
+    let mime = attachment.mime_type.to_lowercase();
+    let mut parts = name.rsplit('.');
+    let ext = parts.next().unwrap_or_default();
+    let inner = parts.next().filter(|_| name.matches('.').count() >= 2);
+
+    let mut risks = Vec::new();
+    let executable = EXECUTABLE_EXTENSIONS.contains(&ext);
+
+    if executable {
+        risks.push("executable file");
+    }
+    if MACRO_EXTENSIONS.contains(&ext) {
+        risks.push("macro-enabled Office document");
+    }
+    if let Some(inner) = inner
+        && DECOY_EXTENSIONS.contains(&inner)
+        && (executable || MACRO_EXTENSIONS.contains(&ext))
+    {
+        risks.push("double extension");
+    }
+
+    if EXECUTABLE_MIME.contains(&mime.as_str()) && !executable {
+        risks.push("executable disguised by its filename");
+    } else if let Some((_, expected)) = EXPECTED_MIME.iter().find(|(e, _)| *e == ext)
+        && mime != "application/octet-stream"
+        && !expected.contains(&mime.as_str())
+    {
+        risks.push("file type doesn't match its name");
+    }
+
+    risks
+}
+
+/// Warning lines for the email's risky attachments, e.g. "⚠️ invoice.pdf.exe: executable file"
+pub fn warnings(email: &Email) -> Vec<String> {
+    email
+        .attachments
+        .iter()
+        .filter_map(|a| {
+            let risks = risks(a);
+            (!risks.is_empty()).then(|| format!("⚠️  {}: {}", a.filename, risks.join(", ")))
+        })
+        .collect()
+}
+
+/// Human-readable size such as "1.2 MB"
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: &[&str] = &["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+/// Write `data` into `dir` under the attachment's name, adding " (2)", " (3)", ...
+/// instead of overwriting an existing file
+pub fn save_unique(dir: &Path, filename: &str, data: &[u8]) -> Result<PathBuf> {
+    let name = sanitize_filename(filename);
+    let (stem, ext) = match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem.to_string(), format!(".{}", ext)),
+        _ => (name.clone(), String::new()),
+    };
+
+    let mut n = 1;
+    loop {
+        let candidate = if n == 1 {
+            dir.join(&name)
+        } else {
+            dir.join(format!("{} ({}){}", stem, n, ext))
+        };
+
+        // create_new fails on an existing file, so two exports can't race onto one name
+        match fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&candidate)
+        {
+            Ok(mut file) => {
+                file.write_all(data)
+                    .with_context(|| format!("Failed to write {}", candidate.display()))?;
+                return Ok(candidate);
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => n += 1,
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to create {}", candidate.display()));
+            }
+        }
+    }
+}
+
+/// Strip path separators and control characters so a filename can't escape the directory
+fn sanitize_filename(filename: &str) -> String {
+    let name: String = filename
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    let name = name.trim().trim_start_matches('.').to_string();
+
+    if name.is_empty() {
+        "attachment".to_string()
+    } else {
+        name
+    }
+}
+
+/// Text excerpt of the first readable attachment (PDF or plain text), for emails
+/// whose body says little more than "see attached"
+pub async fn text_excerpt(gmail: &GmailClient, email: &Email) -> Option<String> {
+    let attachment = excerpt_candidate(email)?;
+    let data = gmail
+        .fetch_attachment(&email.id, &attachment.attachment_id)
+        .await
+        .ok()?;
+    extract_excerpt(attachment, data).await
+}
+
+/// The attachment worth reading when the body is too short to analyze on its own
+pub fn excerpt_candidate(email: &Email) -> Option<&Attachment> {
+    if email.body_text().trim().chars().count() >= SHORT_BODY_CHARS {
+        return None;
+    }
+
+    email.attachments.iter().find(|a| {
+        a.size <= MAX_EXTRACT_BYTES && (is_pdf(a) || a.mime_type.starts_with("text/plain"))
+    })
+}
+
+/// Text of a PDF or plain-text attachment, whitespace-collapsed and cut to an excerpt
+pub async fn extract_excerpt(attachment: &Attachment, data: Vec<u8>) -> Option<String> {
+    let text = if is_pdf(attachment) {
+        // Parsing is CPU-bound, keep it off the async workers
+        tokio::task::spawn_blocking(move || pdf_extract::extract_text_from_mem(&data).ok())
+            .await
+            .ok()??
+    } else {
+        String::from_utf8_lossy(&data).into_owned()
+    };
+
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.is_empty() {
+        return None;
+    }
+
+    Some(format!(
+        "{}:\n{}",
+        attachment.filename,
+        text.chars().take(EXCERPT_CHARS).collect::<String>()
+    ))
+}
+
+fn is_pdf(attachment: &Attachment) -> bool {
+    attachment.mime_type == "application/pdf"
+        || attachment.filename.to_lowercase().ends_with(".pdf")
+}
@@ -0,0 +1,70 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+use clinbox_core::config::Config;
+use clinbox_core::email::Email;
+
+use crate::contacts::{normalize_sender, sender_matches};
+
+/// Senders whose mail is trashed on fetch: full addresses or bare domains
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Blocklist {
+    pub entries: Vec<String>,
+}
+
+impl Blocklist {
+    /// Load the blocklist from file
+    pub fn load() -> Result<Self> {
+        let path = Config::blocklist_path()?;
+
+        if path.exists() {
+            let content = fs::read_to_string(&path).context("Failed to read blocklist file")?;
+            let blocklist: Blocklist =
+                serde_json::from_str(&content).context("Failed to parse blocklist file")?;
+            Ok(blocklist)
+        } else {
+            Ok(Blocklist::default())
+        }
+    }
+
+    /// Save the blocklist to file
+    pub fn save(&self) -> Result<()> {
+        let path = Config::blocklist_path()?;
+        fs::create_dir_all(path.parent().unwrap())?;
+
+        let content =
+            serde_json::to_string_pretty(self).context("Failed to serialize blocklist")?;
+        fs::write(&path, content).context("Failed to write blocklist file")?;
+
+        Ok(())
+    }
+
+    /// Add an address or domain; returns false if it was already blocked
+    pub fn add(&mut self, entry: &str) -> Result<bool> {
+        let entry = normalize_sender(entry);
+        if self.entries.contains(&entry) {
+            return Ok(false);
+        }
+        self.entries.push(entry);
+        self.save()?;
+        Ok(true)
+    }
+
+    /// Remove an address or domain; returns false if it wasn't blocked
+    pub fn remove(&mut self, entry: &str) -> Result<bool> {
+        let entry = normalize_sender(entry);
+        let before = self.entries.len();
+        self.entries.retain(|e| *e != entry);
+        if self.entries.len() == before {
+            return Ok(false);
+        }
+        self.save()?;
+        Ok(true)
+    }
+
+    /// Whether the email's sender address or its domain is blocked
+    pub fn matches(&self, email: &Email) -> bool {
+        sender_matches(email, &self.entries)
+    }
+}
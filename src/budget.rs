@@ -0,0 +1,127 @@
+use clap::Args;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use crate::i18n::tr;
+
+/// Flags that bound a triage session, which summarizes and exits when one is reached
+#[derive(Debug, Default, Args)]
+pub struct Limits {
+    /// End the session after this long (e.g. 15m, 1h, 90s), with a countdown in the header
+    #[arg(long, value_name = "DURATION", conflicts_with = "list")]
+    pub budget: Option<Budget>,
+
+    /// End the session once this many emails are dealt with
+    #[arg(long, value_name = "N", conflicts_with = "list", value_parser = parse_target)]
+    pub target: Option<usize>,
+}
+
+fn parse_target(s: &str) -> Result<usize, String> {
+    match s.trim().parse::<usize>() {
+        Ok(0) => Err("the target must be at least 1".to_string()),
+        Ok(n) => Ok(n),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Longest budget accepted, a day, which no triage session needs more than
+const MAX_BUDGET: Duration = Duration::from_secs(24 * 3600);
+
+/// How long a session may run
+#[derive(Debug, Clone, Copy)]
+pub struct Budget(pub Duration);
+
+impl FromStr for Budget {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let invalid = || format!("invalid duration '{}' (use 90s, 15m or 1h, up to 24h)", s);
+        let unit = s.chars().last().ok_or_else(invalid)?;
+        let amount: u64 = s[..s.len() - unit.len_utf8()]
+            .parse()
+            .map_err(|_| invalid())?;
+
+        let secs = match unit {
+            's' => Some(amount),
+            'm' => amount.checked_mul(60),
+            'h' => amount.checked_mul(3600),
+            _ => None,
+        }
+        .ok_or_else(invalid)?;
+        let budget = Duration::from_secs(secs);
+        if secs == 0 || budget > MAX_BUDGET {
+            return Err(invalid());
+        }
+        Ok(Self(budget))
+    }
+}
+
+/// A running session's limits and how far it has got
+#[derive(Debug, Clone, Copy)]
+pub struct Progress {
+    deadline: Option<Instant>,
+    target: Option<usize>,
+    /// Emails dealt with before this run, e.g. in a resumed session
+    start: usize,
+    done: usize,
+}
+
+impl Progress {
+    /// Start the clock, or None without limits; `done` counts what the
+    /// session already dealt with, which the target doesn't include
+    pub fn start(limits: &Limits, done: usize) -> Option<Self> {
+        if limits.budget.is_none() && limits.target.is_none() {
+            return None;
+        }
+        Some(Self {
+            deadline: limits.budget.and_then(|b| Instant::now().checked_add(b.0)),
+            target: limits.target,
+            start: done,
+            done,
+        })
+    }
+
+    /// Update the session's count of emails dealt with
+    pub fn set_done(&mut self, done: usize) {
+        self.done = done;
+    }
+
+    /// When the budget runs out
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    /// Seconds left on the budget, for the countdown
+    pub fn seconds_left(&self) -> Option<u64> {
+        self.deadline
+            .map(|d| d.saturating_duration_since(Instant::now()).as_secs())
+    }
+
+    /// Enough emails dealt with in this run
+    pub fn target_reached(&self) -> bool {
+        self.target
+            .is_some_and(|target| self.done.saturating_sub(self.start) >= target)
+    }
+
+    /// "⏱ 12:34 left  🎯 5/20"
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(secs) = self.seconds_left() {
+            let clock = if secs >= 3600 {
+                format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+            } else {
+                format!("{}:{:02}", secs / 60, secs % 60)
+            };
+            parts.push(tr("⏱ {} left", &[&clock]));
+        }
+        if let Some(target) = self.target {
+            parts.push(format!(
+                "🎯 {}/{}",
+                self.done.saturating_sub(self.start).min(target),
+                target
+            ));
+        }
+        parts.join("  ")
+    }
+}
@@ -0,0 +1,234 @@
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+
+/// A calendar invitation parsed from a text/calendar part
+#[derive(Debug, Clone)]
+pub struct Event {
+    /// REQUEST, CANCEL, REPLY, ... (what the sender wants done with the event)
+    pub method: Option<String>,
+    pub summary: String,
+    pub start: Option<String>,
+    pub end: Option<String>,
+    /// Start and end as instants, for checking against my calendar; unknown for
+    /// all-day events and TZID times, which would need a timezone database
+    pub span: Option<(DateTime<Utc>, DateTime<Utc>)>,
+    pub location: Option<String>,
+    pub organizer: Option<String>,
+    pub attendees: Vec<Attendee>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Attendee {
+    pub name: Option<String>,
+    pub email: String,
+    /// ACCEPTED, DECLINED, TENTATIVE, NEEDS-ACTION
+    pub status: Option<String>,
+}
+
+impl Attendee {
+    pub fn display(&self) -> String {
+        let who = self.name.clone().unwrap_or_else(|| self.email.clone());
+        match self.status.as_deref() {
+            Some("ACCEPTED") => format!("{} ✓", who),
+            Some("DECLINED") => format!("{} ✗", who),
+            Some("TENTATIVE") => format!("{} ?", who),
+            _ => who,
+        }
+    }
+}
+
+impl Event {
+    /// Lines for the invitation panel
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = vec![self.summary.clone()];
+
+        let when = match (&self.start, &self.end) {
+            (Some(start), Some(end)) => Some(format!("{} → {}", start, end)),
+            (Some(start), None) => Some(start.clone()),
+            _ => None,
+        };
+        if let Some(when) = when {
+            lines.push(format!("🕒 {}", when));
+        }
+        if let Some(location) = &self.location {
+            lines.push(format!("📍 {}", location));
+        }
+        if let Some(organizer) = &self.organizer {
+            lines.push(format!("👤 Organizer: {}", organizer));
+        }
+        if !self.attendees.is_empty() {
+            let attendees: Vec<_> = self.attendees.iter().map(Attendee::display).collect();
+            lines.push(format!("👥 {}", attendees.join(", ")));
+        }
+        lines
+    }
+
+    /// Panel title reflecting the METHOD, e.g. " 📅 Invitation " or " 📅 Cancelled "
+    pub fn title(&self) -> &'static str {
+        match self.method.as_deref() {
+            Some("CANCEL") => " 📅 Cancelled ",
+            Some("REPLY") => " 📅 RSVP ",
+            _ => " 📅 Invitation ",
+        }
+    }
+}
+
+/// Parse the first VEVENT of an ICS document
+pub fn parse(ics: &str) -> Option<Event> {
+    let lines = unfold(ics);
+    let method = lines
+        .iter()
+        .map(|l| split_property(l))
+        .find(|(name, _, _)| name == "METHOD")
+        .map(|(_, _, value)| value.to_uppercase());
+
+    let mut in_event = false;
+    let (mut starts_at, mut ends_at) = (None, None);
+    let mut event = Event {
+        method,
+        summary: String::new(),
+        start: None,
+        end: None,
+        span: None,
+        location: None,
+        organizer: None,
+        attendees: Vec::new(),
+    };
+
+    for line in &lines {
+        let (name, params, value) = split_property(line);
+        match name.as_str() {
+            "BEGIN" if value == "VEVENT" => in_event = true,
+            "END" if value == "VEVENT" => {
+                event.span = starts_at.zip(ends_at);
+                return Some(event);
+            }
+            _ if !in_event => {}
+            "SUMMARY" => event.summary = unescape(&value),
+            "LOCATION" if !value.is_empty() => event.location = Some(unescape(&value)),
+            "DTSTART" => {
+                event.start = format_time(&params, &value);
+                starts_at = instant(&params, &value);
+            }
+            "DTEND" => {
+                event.end = format_time(&params, &value);
+                ends_at = instant(&params, &value);
+            }
+            "ORGANIZER" => event.organizer = Some(person(&params, &value).0),
+            "ATTENDEE" => {
+                let (label, email) = person(&params, &value);
+                event.attendees.push(Attendee {
+                    name: (label != email).then_some(label),
+                    email,
+                    status: param(&params, "PARTSTAT").map(|s| s.to_uppercase()),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Join folded lines (continuations start with a space or tab)
+fn unfold(ics: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in ics.lines() {
+        let raw = raw.trim_end_matches('\r');
+        match (raw.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(raw.to_string()),
+        }
+    }
+    lines
+}
+
+/// Split "NAME;PARAM=x;PARAM2=y:value" into (NAME, [params], value)
+fn split_property(line: &str) -> (String, Vec<(String, String)>, String) {
+    // The value starts at the first colon outside a quoted parameter
+    let mut quoted = false;
+    let split = line.char_indices().find(|&(_, c)| {
+        if c == '"' {
+            quoted = !quoted;
+        }
+        c == ':' && !quoted
+    });
+    let (head, value) = match split {
+        Some((i, _)) => (&line[..i], &line[i + 1..]),
+        None => (line, ""),
+    };
+
+    let mut parts = head.split(';');
+    let name = parts.next().unwrap_or_default().to_uppercase();
+    let params = parts
+        .filter_map(|p| p.split_once('='))
+        .map(|(k, v)| (k.to_uppercase(), v.trim_matches('"').to_string()))
+        .collect();
+    (name, params, value.to_string())
+}
+
+fn param<'a>(params: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    params
+        .iter()
+        .find(|(k, _)| k == name)
+        .map(|(_, v)| v.as_str())
+}
+
+/// "Name <email>" (or just the email) from a CN parameter and a mailto: value
+fn person(params: &[(String, String)], value: &str) -> (String, String) {
+    let email = value
+        .strip_prefix("mailto:")
+        .or_else(|| value.strip_prefix("MAILTO:"))
+        .unwrap_or(value)
+        .to_string();
+    match param(params, "CN") {
+        Some(name) if !name.is_empty() && name != email => (name.to_string(), email),
+        _ => (email.clone(), email),
+    }
+}
+
+/// Render a DTSTART/DTEND value: UTC times in local time, floating or TZID times as written
+fn format_time(params: &[(String, String)], value: &str) -> Option<String> {
+    if param(params, "VALUE") == Some("DATE") || value.len() == 8 {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+        return Some(date.format("%a %Y-%m-%d (all day)").to_string());
+    }
+
+    if let Some(utc) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        let local: DateTime<Local> = Utc.from_utc_datetime(&naive).with_timezone(&Local);
+        return Some(local.format("%a %Y-%m-%d %H:%M").to_string());
+    }
+
+    let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    let formatted = naive.format("%a %Y-%m-%d %H:%M").to_string();
+    Some(match param(params, "TZID") {
+        Some(tz) => format!("{} ({})", formatted, tz),
+        None => formatted,
+    })
+}
+
+/// A DTSTART/DTEND value as an instant: UTC times as given, floating times in local time
+fn instant(params: &[(String, String)], value: &str) -> Option<DateTime<Utc>> {
+    if param(params, "VALUE") == Some("DATE") || param(params, "TZID").is_some() {
+        return None;
+    }
+    if let Some(utc) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some(Utc.from_utc_datetime(&naive));
+    }
+    let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|local| local.with_timezone(&Utc))
+}
+
+/// Undo ICS text escaping (\, \; \n)
+fn unescape(value: &str) -> String {
+    value
+        .replace("\\n", " ")
+        .replace("\\N", " ")
+        .replace("\\,", ",")
+        .replace("\\;", ";")
+        .replace("\\\\", "\\")
+}
@@ -0,0 +1,254 @@
+use std::collections::HashMap;
+
+use clinbox_core::ai::CleanupProposal;
+use clinbox_core::email::Email;
+
+use crate::clusters::is_automated;
+use crate::contacts::parse_addresses;
+use crate::feedback::SenderRecord;
+use crate::rules::RuleAction;
+
+/// Fewest decisions on a sender's mail before its history says anything
+const MIN_DECISIONS: u32 = 5;
+
+/// Share of a sender's mail that got the same treatment for it to become a rule
+const MIN_SHARE: f64 = 0.9;
+
+/// Inbox messages from an automated sender, with no history, worth a suggestion
+const MIN_INBOX: usize = 10;
+
+/// A rule proposed for one sender
+#[derive(Debug, Clone)]
+pub struct Suggestion {
+    /// Sender's address, lowercased
+    pub address: String,
+    pub action: RuleAction,
+    /// Why, e.g. "97% archived historically (31 of 32)"
+    pub reason: String,
+    /// Messages from the sender in the inbox right now
+    pub in_inbox: usize,
+}
+
+impl Suggestion {
+    /// One line such as "auto-archive calendar@example.com — 97% archived historically (31 of 32), 12 in the inbox"
+    pub fn describe(&self) -> String {
+        let verb = match self.action {
+            RuleAction::MarkRead => "auto-mark-read",
+            RuleAction::Delete => "auto-delete",
+            _ => "auto-archive",
+        };
+        let inbox = match self.in_inbox {
+            0 => String::new(),
+            n => format!(", {} in the inbox", n),
+        };
+        format!("{} {} — {}{}", verb, self.address, self.reason, inbox)
+    }
+
+    /// Rule file name, e.g. "cleanup-calendar-example-com.rhai"
+    pub fn rule_file_name(&self) -> String {
+        let slug: String = self
+            .address
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect();
+        format!("cleanup-{}.rhai", slug)
+    }
+
+    /// A Rhai rule that applies the action to the sender's mail
+    pub fn rule_script(&self) -> String {
+        let address = self.address.replace('\\', "\\\\").replace('"', "\\\"");
+        format!(
+            "// Suggested by clinbox cleanup: {}\nif email.sender == \"{}\" {{\n    \"{}\"\n}}\n",
+            self.reason,
+            address,
+            self.action.name()
+        )
+    }
+
+    /// Labels a Gmail filter adds and removes for the action
+    pub fn filter_labels(&self) -> (&'static [&'static str], &'static [&'static str]) {
+        match self.action {
+            RuleAction::Delete => (&["TRASH"], &[]),
+            RuleAction::MarkRead => (&[], &["UNREAD"]),
+            _ => (&[], &["INBOX"]),
+        }
+    }
+}
+
+/// Senders listed for the AI, busiest first
+const MAX_OVERVIEW: usize = 200;
+
+/// Fewest decisions, or messages in the inbox, for a sender to be listed for the AI
+const MIN_LISTED: usize = 3;
+
+/// Messages per sender address (lowercased) in `inbox`
+fn inbox_counts(inbox: &[Email]) -> HashMap<String, usize> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for email in inbox {
+        if let Some((_, address)) = parse_addresses(&email.from).into_iter().next() {
+            *counts.entry(address.to_lowercase()).or_default() += 1;
+        }
+    }
+    counts
+}
+
+/// One line per sender with some history or mail in the inbox, for the AI to
+/// propose rules from, e.g. "digest@news.example.com (News) — 32 decisions:
+/// 31 archived, 1 skipped; newsletter; 12 in the inbox; automated"
+pub fn overview(senders: &HashMap<String, SenderRecord>, inbox: &[Email]) -> String {
+    let in_inbox = inbox_counts(inbox);
+    let mut addresses: Vec<&String> = senders.keys().chain(in_inbox.keys()).collect();
+    addresses.sort();
+    addresses.dedup();
+
+    let mut lines: Vec<(usize, String)> = addresses
+        .into_iter()
+        .filter_map(|address| {
+            let record = senders.get(address);
+            let total = record.map_or(0, |r| r.total() as usize);
+            let inbox = in_inbox.get(address).copied().unwrap_or(0);
+            if total < MIN_LISTED && inbox < MIN_LISTED {
+                return None;
+            }
+
+            let mut line = address.clone();
+            if let Some(record) = record.filter(|r| !r.name.eq_ignore_ascii_case(address)) {
+                line.push_str(&format!(" ({})", record.name));
+            }
+            match record.filter(|_| total > 0) {
+                Some(record) => {
+                    let actions: Vec<String> = record
+                        .actions
+                        .iter()
+                        .map(|(action, n)| format!("{} {}", n, action))
+                        .collect();
+                    line.push_str(&format!(" — {} decisions: {}", total, actions.join(", ")));
+                }
+                None => line.push_str(" — never triaged"),
+            }
+            if let Some(category) = record.and_then(|r| r.category) {
+                line.push_str(&format!("; {}", category.name()));
+            }
+            line.push_str(&format!("; {} in the inbox", inbox));
+            if is_automated(address) {
+                line.push_str("; automated");
+            }
+            Some((total + inbox, line))
+        })
+        .collect();
+
+    lines.sort_by_key(|(weight, _)| std::cmp::Reverse(*weight));
+    lines
+        .into_iter()
+        .take(MAX_OVERVIEW)
+        .map(|(_, line)| line)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The AI's proposals that name a listed sender and an action a cleanup rule
+/// can take, most of the current inbox first
+pub fn from_proposals(
+    proposals: Vec<CleanupProposal>,
+    senders: &HashMap<String, SenderRecord>,
+    inbox: &[Email],
+) -> Vec<Suggestion> {
+    let in_inbox = inbox_counts(inbox);
+    let mut suggestions: Vec<Suggestion> = Vec::new();
+    for proposal in proposals {
+        let address = proposal.address.trim().to_lowercase();
+        let known = senders.contains_key(&address) || in_inbox.contains_key(&address);
+        let action =
+            RuleAction::parse(proposal.action.trim()).filter(|action| *action != RuleAction::Skip);
+        let repeated = suggestions.iter().any(|s| s.address == address);
+        if let Some(action) = action.filter(|_| known && !repeated) {
+            suggestions.push(Suggestion {
+                in_inbox: in_inbox.get(&address).copied().unwrap_or(0),
+                address,
+                action,
+                reason: proposal.reason.trim().to_string(),
+            });
+        }
+    }
+    sort(&mut suggestions);
+    suggestions
+}
+
+/// Without the AI: senders I almost always treat the same way, then automated
+/// senders piling up in the inbox. Most of the current inbox first.
+pub fn from_counts(senders: &HashMap<String, SenderRecord>, inbox: &[Email]) -> Vec<Suggestion> {
+    let in_inbox = inbox_counts(inbox);
+    let count = |address: &str| in_inbox.get(address).copied().unwrap_or(0);
+
+    let mut suggestions: Vec<Suggestion> = senders
+        .iter()
+        .filter_map(|(address, record)| {
+            let (action, reason) = from_history(record)?;
+            Some(Suggestion {
+                address: address.clone(),
+                action,
+                reason,
+                in_inbox: count(address),
+            })
+        })
+        .collect();
+
+    for (address, &n) in &in_inbox {
+        let known = senders.contains_key(address);
+        if n >= MIN_INBOX && !known && is_automated(address) {
+            suggestions.push(Suggestion {
+                address: address.clone(),
+                action: RuleAction::Archive,
+                reason: "automated sender, never triaged".to_string(),
+                in_inbox: n,
+            });
+        }
+    }
+
+    sort(&mut suggestions);
+    suggestions
+}
+
+fn sort(suggestions: &mut [Suggestion]) {
+    suggestions.sort_by(|a, b| {
+        b.in_inbox
+            .cmp(&a.in_inbox)
+            .then_with(|| a.address.cmp(&b.address))
+    });
+}
+
+/// The action taken on at least `MIN_SHARE` of a sender's mail, and why
+fn from_history(record: &SenderRecord) -> Option<(RuleAction, String)> {
+    let total = record.total();
+    if total < MIN_DECISIONS {
+        return None;
+    }
+
+    [
+        ("archived", RuleAction::Archive),
+        ("deleted", RuleAction::Delete),
+        ("marked read", RuleAction::MarkRead),
+    ]
+    .into_iter()
+    .find_map(|(done, action)| {
+        let count = record.actions.get(done).copied().unwrap_or(0);
+        let share = f64::from(count) / f64::from(total);
+        (share >= MIN_SHARE).then(|| {
+            let category = record
+                .category
+                .map(|c| format!(" [{}]", c.name()))
+                .unwrap_or_default();
+            (
+                action,
+                format!(
+                    "{:.0}% {} historically ({} of {}){}",
+                    share * 100.0,
+                    done,
+                    count,
+                    total,
+                    category
+                ),
+            )
+        })
+    })
+}
@@ -0,0 +1,42 @@
+use anyhow::Result;
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use std::io::Write;
+use std::sync::Mutex;
+
+/// Kept open for the whole run: on X11 and Wayland the copied text is served
+/// by this process, so it would go when the clipboard is dropped
+static CLIPBOARD: Mutex<Option<arboard::Clipboard>> = Mutex::new(None);
+
+/// Where copied text went
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Copied {
+    /// The system clipboard
+    Clipboard,
+    /// Handed to the terminal as an OSC 52 sequence, which most terminals
+    /// support but none confirm
+    Terminal,
+}
+
+/// Copy text to the system clipboard. Over SSH, or without a clipboard to
+/// reach, the terminal is asked to do it with an OSC 52 sequence instead.
+pub fn copy(text: &str) -> Result<Copied> {
+    let remote =
+        std::env::var_os("SSH_CONNECTION").is_some() || std::env::var_os("SSH_TTY").is_some();
+    if !remote && copy_local(text).is_ok() {
+        return Ok(Copied::Clipboard);
+    }
+
+    let mut stdout = std::io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", STANDARD.encode(text))?;
+    stdout.flush()?;
+    Ok(Copied::Terminal)
+}
+
+fn copy_local(text: &str) -> Result<(), arboard::Error> {
+    let mut open = CLIPBOARD.lock().unwrap_or_else(|e| e.into_inner());
+    let clipboard = match open.as_mut() {
+        Some(clipboard) => clipboard,
+        None => open.insert(arboard::Clipboard::new()?),
+    };
+    clipboard.set_text(text)
+}
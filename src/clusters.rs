@@ -0,0 +1,142 @@
+use std::collections::HashSet;
+
+use clinbox_core::email::Email;
+
+use crate::contacts::parse_addresses;
+
+/// Fewest emails worth grouping into one card
+const MIN_SIZE: usize = 3;
+
+/// Share of subject words two emails from one sender need in common to group
+const MIN_SIMILARITY: f64 = 0.5;
+
+/// Address parts of senders that only send notifications; their mail groups
+/// whatever the subjects say
+const AUTOMATED: &[&str] = &[
+    "noreply",
+    "no-reply",
+    "donotreply",
+    "do-not-reply",
+    "notification",
+    "notify",
+    "alert",
+    "jira",
+    "updates",
+];
+
+/// Similar emails from one sender, e.g. "14 Dependabot alerts"
+#[derive(Debug, Clone)]
+pub struct Cluster {
+    /// Sender's display name
+    pub sender: String,
+    /// Sender's address, lowercased
+    pub address: String,
+    /// Subject with the parts that differ between emails replaced by "…"
+    pub template: String,
+    /// Gmail ids, in queue order
+    pub ids: Vec<String>,
+}
+
+struct Draft<'a> {
+    address: String,
+    words: Vec<String>,
+    emails: Vec<&'a Email>,
+}
+
+/// Group the queue by sender and subject similarity; groups smaller than
+/// `MIN_SIZE` are left out. Largest groups first.
+pub fn find(emails: &[Email]) -> Vec<Cluster> {
+    let mut drafts: Vec<Draft> = Vec::new();
+
+    for email in emails {
+        let Some((_, address)) = parse_addresses(&email.from).into_iter().next() else {
+            continue;
+        };
+        let address = address.to_lowercase();
+        let words = template_words(&email.subject);
+        let automated = is_automated(&address);
+
+        let joined = drafts.iter_mut().find(|d| {
+            d.address == address && (automated || similarity(&d.words, &words) >= MIN_SIMILARITY)
+        });
+        match joined {
+            Some(draft) => draft.emails.push(email),
+            None => drafts.push(Draft {
+                address,
+                words,
+                emails: vec![email],
+            }),
+        }
+    }
+
+    let mut clusters: Vec<Cluster> = drafts
+        .into_iter()
+        .filter(|d| d.emails.len() >= MIN_SIZE)
+        .map(|d| Cluster {
+            sender: d.emails[0].sender_name(),
+            template: template(&d.emails),
+            address: d.address,
+            ids: d.emails.iter().map(|e| e.id.clone()).collect(),
+        })
+        .collect();
+    clusters.sort_by_key(|c| std::cmp::Reverse(c.ids.len()));
+    clusters
+}
+
+/// Whether an address looks like it only sends notifications
+pub fn is_automated(address: &str) -> bool {
+    let local = address.split('@').next().unwrap_or_default();
+    AUTOMATED.iter().any(|part| local.contains(part))
+}
+
+/// Lowercased subject words without reply prefixes; words with digits (ids,
+/// versions, counts) all read as "#"
+fn template_words(subject: &str) -> Vec<String> {
+    subject
+        .split_whitespace()
+        .map(|w| w.to_lowercase())
+        .skip_while(|w| matches!(w.as_str(), "re:" | "fwd:" | "fw:"))
+        .map(|w| {
+            if w.chars().any(|c| c.is_ascii_digit()) {
+                "#".to_string()
+            } else {
+                w
+            }
+        })
+        .collect()
+}
+
+/// Jaccard similarity of two word lists
+fn similarity(a: &[String], b: &[String]) -> f64 {
+    let a: HashSet<&String> = a.iter().collect();
+    let b: HashSet<&String> = b.iter().collect();
+    let union = a.union(&b).count();
+    if union == 0 {
+        return 1.0;
+    }
+    a.intersection(&b).count() as f64 / union as f64
+}
+
+/// The first subject, keeping the words every email shares and collapsing the rest into "…"
+fn template(emails: &[&Email]) -> String {
+    let shared: Vec<HashSet<String>> = emails
+        .iter()
+        .map(|e| {
+            e.subject
+                .split_whitespace()
+                .map(|w| w.to_lowercase())
+                .collect()
+        })
+        .collect();
+
+    let mut parts: Vec<&str> = Vec::new();
+    for word in emails[0].subject.split_whitespace() {
+        let lower = word.to_lowercase();
+        if shared.iter().all(|words| words.contains(&lower)) {
+            parts.push(word);
+        } else if parts.last() != Some(&"…") {
+            parts.push("…");
+        }
+    }
+    parts.join(" ")
+}
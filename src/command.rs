@@ -0,0 +1,122 @@
+use std::collections::HashSet;
+
+use anyhow::anyhow;
+use clinbox_core::ai::PlanStep;
+use clinbox_core::email::Email;
+use clinbox_core::gmail::GmailClient;
+
+use crate::hooks::HookEvent;
+
+/// Most messages one search step acts on
+pub const MAX_MATCHES: u32 = 5000;
+
+/// What a plan did to the current email
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Done {
+    Archived,
+    Deleted,
+    MarkedRead,
+    Skipped,
+}
+
+impl Done {
+    /// Name for the journal, e.g. "marked read"
+    pub fn name(&self) -> &'static str {
+        match self {
+            Done::Archived => "archived",
+            Done::Deleted => "deleted",
+            Done::MarkedRead => "marked read",
+            Done::Skipped => "skipped",
+        }
+    }
+
+    pub fn hook(&self) -> HookEvent {
+        match self {
+            Done::Archived => HookEvent::Archive,
+            Done::Deleted => HookEvent::Delete,
+            Done::MarkedRead => HookEvent::MarkRead,
+            Done::Skipped => HookEvent::Skip,
+        }
+    }
+}
+
+/// What carrying out a plan did
+#[derive(Debug, Default)]
+pub struct Outcome {
+    /// What happened to the current email, if anything
+    pub current: Option<Done>,
+    /// Messages archived by search steps
+    pub archived: HashSet<String>,
+    /// Steps carried out, described
+    pub done: Vec<String>,
+    /// The step that failed and why; the ones after it didn't run
+    pub error: Option<String>,
+}
+
+/// Carry out the steps in order, stopping at the first failure. `matches`
+/// holds each step's search results, found before asking to confirm.
+pub async fn run(
+    gmail: &GmailClient,
+    email: &Email,
+    steps: &[PlanStep],
+    matches: &[Vec<String>],
+) -> Outcome {
+    let mut outcome = Outcome::default();
+
+    for (step, ids) in steps.iter().zip(matches) {
+        let result = match step {
+            PlanStep::Archive => gmail
+                .archive(&email.id)
+                .await
+                .map(|()| Some(Done::Archived)),
+            PlanStep::Delete => gmail.delete(&email.id).await.map(|()| Some(Done::Deleted)),
+            PlanStep::MarkRead => gmail
+                .mark_read(&email.id)
+                .await
+                .map(|()| Some(Done::MarkedRead)),
+            PlanStep::Label { label } => match gmail.find_or_create_label(label).await {
+                Ok(label) => gmail.add_label(&email.id, &label.id).await.map(|()| None),
+                Err(e) => Err(e),
+            },
+            PlanStep::Skip => Ok(Some(Done::Skipped)),
+            PlanStep::ArchiveAll { .. } => {
+                let result = gmail.archive_many(ids).await;
+                outcome.archived.extend(result.succeeded.iter().cloned());
+                match result.first_error() {
+                    Some(error) => Err(anyhow!("{}", error)),
+                    None => Ok(None),
+                }
+            }
+            PlanStep::LabelAll { label, .. } => match gmail.find_or_create_label(label).await {
+                Ok(label) => {
+                    let result = gmail.add_label_many(ids, &label.id).await;
+                    match result.first_error() {
+                        Some(error) => Err(anyhow!("{}", error)),
+                        None => Ok(None),
+                    }
+                }
+                Err(e) => Err(e),
+            },
+        };
+
+        match result {
+            Ok(done) => {
+                // The first thing done to the email is what counts; a later
+                // "skip" doesn't undo an archive
+                if outcome.current.is_none() {
+                    outcome.current = done;
+                }
+                outcome.done.push(step.describe());
+            }
+            Err(e) => {
+                outcome.error = Some(format!("{}: {:#}", step.describe(), e));
+                break;
+            }
+        }
+    }
+
+    if outcome.current.is_none() && outcome.archived.contains(&email.id) {
+        outcome.current = Some(Done::Archived);
+    }
+    outcome
+}
@@ -0,0 +1,95 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Local, NaiveDateTime, TimeZone, Utc};
+use clinbox_core::email::{Email, EmailAnalysis};
+use clinbox_core::gmail::{CalendarEvent, GmailClient};
+
+use crate::calendar;
+
+/// Length assumed for a proposed time, since emails rarely say when it ends
+const DEFAULT_MINUTES: i64 = 30;
+
+/// A time the email proposes
+#[derive(Debug, Clone)]
+pub struct Slot {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// Title of the invitation it comes from; the event is likely on my
+    /// calendar already and shouldn't clash with itself
+    invite: Option<String>,
+}
+
+/// The invitation's time, or else the times the analysis found in the text.
+/// Times already past are left out.
+pub fn slots(email: &Email, analysis: Option<&EmailAnalysis>) -> Vec<Slot> {
+    let now = Utc::now();
+    let invite = email
+        .calendar
+        .as_deref()
+        .and_then(calendar::parse)
+        .filter(|e| e.method.as_deref() != Some("CANCEL"));
+
+    let slots: Vec<Slot> = match invite.and_then(|e| Some((e.span?, e.summary))) {
+        Some(((start, end), summary)) => vec![Slot {
+            start,
+            end,
+            invite: Some(summary),
+        }],
+        None => analysis
+            .map(|a| a.proposed_times.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|time| parse_time(time))
+            .map(|start| Slot {
+                start,
+                end: start + Duration::minutes(DEFAULT_MINUTES),
+                invite: None,
+            })
+            .collect(),
+    };
+    slots.into_iter().filter(|s| s.end > now).collect()
+}
+
+/// Calendar events overlapping any of the slots, in one calendar request
+pub async fn find(gmail: &GmailClient, slots: &[Slot]) -> Result<Vec<CalendarEvent>> {
+    let (Some(from), Some(to)) = (
+        slots.iter().map(|s| s.start).min(),
+        slots.iter().map(|s| s.end).max(),
+    ) else {
+        return Ok(Vec::new());
+    };
+
+    let events = gmail.calendar_events(from, to).await?;
+    Ok(events
+        .into_iter()
+        .filter(|event| {
+            slots.iter().any(|slot| {
+                let itself = slot.invite.as_deref() == Some(event.summary.as_str())
+                    && slot.start == event.start
+                    && slot.end == event.end;
+                !itself && event.start < slot.end && slot.start < event.end
+            })
+        })
+        .collect())
+}
+
+/// "Standup 10:00", in local time
+pub fn describe(event: &CalendarEvent) -> String {
+    format!(
+        "{} {}",
+        event.summary,
+        event.start.with_timezone(&Local).format("%H:%M")
+    )
+}
+
+/// "2024-06-03 10:00", in local time, or "2024-06-03 10:00 +02:00"
+fn parse_time(time: &str) -> Option<DateTime<Utc>> {
+    let time = time.trim();
+    if let Ok(with_offset) = DateTime::parse_from_str(time, "%Y-%m-%d %H:%M %:z") {
+        return Some(with_offset.with_timezone(&Utc));
+    }
+    let naive = NaiveDateTime::parse_from_str(time, "%Y-%m-%d %H:%M").ok()?;
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|local| local.with_timezone(&Utc))
+}
@@ -0,0 +1,230 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+
+use clinbox_core::config::Config;
+use clinbox_core::email::Email;
+
+use crate::history::format_age;
+
+/// Someone seen as a sender or recipient
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Contact {
+    pub address: String,
+    pub name: Option<String>,
+    /// Emails received from this address
+    pub received: u32,
+    /// Emails where this address was a fellow recipient
+    pub copied: u32,
+    /// Replies sent to this address from Clinbox
+    pub replied: u32,
+    pub last_seen: DateTime<Utc>,
+}
+
+impl Contact {
+    /// Rough interaction count used to rank suggestions
+    pub fn score(&self) -> u32 {
+        self.received + self.copied + 3 * self.replied
+    }
+
+    /// "Name <address>", or just the address when no name is known
+    pub fn display(&self) -> String {
+        match &self.name {
+            Some(name) => format!("{} <{}>", name, self.address),
+            None => self.address.clone(),
+        }
+    }
+
+    /// Short interaction hint such as "12 emails, you replied 3×, last 2 days ago"
+    pub fn describe(&self) -> String {
+        let mut parts = vec![format!(
+            "{} email{}",
+            self.received,
+            if self.received == 1 { "" } else { "s" }
+        )];
+        if self.replied > 0 {
+            parts.push(format!("you replied {}×", self.replied));
+        }
+        parts.push(format!("last {}", format_age(self.last_seen)));
+        parts.join(", ")
+    }
+}
+
+/// Message ids remembered as counted; older ones are long out of the inbox
+const COUNTED_LIMIT: usize = 5000;
+
+/// Local address book built from mail seen during triage, keyed by lowercase address
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ContactBook {
+    pub contacts: HashMap<String, Contact>,
+    /// Ids of messages already counted, oldest first, so mail still unread on
+    /// the next run isn't counted again
+    #[serde(default)]
+    counted: Vec<String>,
+}
+
+impl ContactBook {
+    /// Load contacts from file
+    pub fn load() -> Result<Self> {
+        let path = Config::contacts_path()?;
+
+        if path.exists() {
+            let content = fs::read_to_string(&path).context("Failed to read contacts file")?;
+            let book: ContactBook =
+                serde_json::from_str(&content).context("Failed to parse contacts file")?;
+            Ok(book)
+        } else {
+            Ok(ContactBook::default())
+        }
+    }
+
+    /// Save contacts to file
+    pub fn save(&self) -> Result<()> {
+        let path = Config::contacts_path()?;
+        fs::create_dir_all(path.parent().unwrap())?;
+
+        let content = serde_json::to_string(self).context("Failed to serialize contacts")?;
+        fs::write(&path, content).context("Failed to write contacts file")?;
+
+        Ok(())
+    }
+
+    /// Look up a contact by address (case-insensitive)
+    pub fn get(&self, address: &str) -> Option<&Contact> {
+        self.contacts.get(&address.to_lowercase())
+    }
+
+    /// Count the sender and fellow recipients of fetched emails not counted
+    /// before, skipping `own_address`
+    pub fn record_emails(&mut self, emails: &[Email], own_address: Option<&str>) -> Result<()> {
+        let counted: HashSet<&String> = self.counted.iter().collect();
+        let new: Vec<&Email> = emails.iter().filter(|e| !counted.contains(&e.id)).collect();
+        if new.is_empty() {
+            return Ok(());
+        }
+
+        for email in &new {
+            for (name, address) in parse_addresses(&email.from) {
+                self.touch(name, &address, email.date).received += 1;
+            }
+
+            for (name, address) in parse_addresses(&email.to) {
+                let own = own_address.is_some_and(|own| own.eq_ignore_ascii_case(&address));
+                if !own {
+                    self.touch(name, &address, email.date).copied += 1;
+                }
+            }
+        }
+
+        self.counted.extend(new.iter().map(|e| e.id.clone()));
+        let excess = self.counted.len().saturating_sub(COUNTED_LIMIT);
+        self.counted.drain(..excess);
+        self.save()
+    }
+
+    /// Count a reply sent to the email's sender
+    pub fn record_reply(&mut self, email: &Email) -> Result<()> {
+        for (name, address) in parse_addresses(&email.from) {
+            self.touch(name, &address, Utc::now()).replied += 1;
+        }
+        self.save()
+    }
+
+    /// Contacts whose name or address contains `query`, most frequent first
+    pub fn suggest(&self, query: &str, limit: usize) -> Vec<&Contact> {
+        let query = query.to_lowercase();
+        let mut matches: Vec<&Contact> = self
+            .contacts
+            .values()
+            .filter(|c| {
+                c.address.contains(&query)
+                    || c.name
+                        .as_ref()
+                        .is_some_and(|n| n.to_lowercase().contains(&query))
+            })
+            .collect();
+
+        matches.sort_by(|a, b| {
+            b.score()
+                .cmp(&a.score())
+                .then(b.last_seen.cmp(&a.last_seen))
+        });
+        matches.truncate(limit);
+        matches
+    }
+
+    fn touch(&mut self, name: Option<String>, address: &str, seen: DateTime<Utc>) -> &mut Contact {
+        let contact = self
+            .contacts
+            .entry(address.to_lowercase())
+            .or_insert_with(|| Contact {
+                address: address.to_lowercase(),
+                name: None,
+                received: 0,
+                copied: 0,
+                replied: 0,
+                last_seen: seen,
+            });
+        if name.is_some() {
+            contact.name = name;
+        }
+        contact.last_seen = contact.last_seen.max(seen);
+        contact
+    }
+}
+
+/// Lowercase and strip a leading "@" so "@Example.com" and "example.com" match alike
+pub fn normalize_sender(entry: &str) -> String {
+    entry.trim().trim_start_matches('@').to_lowercase()
+}
+
+/// Whether the sender's address, or its domain (subdomains included), is one of
+/// `entries` (lowercase addresses or bare domains)
+pub fn sender_matches(email: &Email, entries: &[String]) -> bool {
+    parse_addresses(&email.from).iter().any(|(_, address)| {
+        let address = address.to_lowercase();
+        let domain = address.rsplit('@').next().unwrap_or_default();
+        entries.iter().any(|entry| {
+            *entry == address
+                || (!entry.contains('@')
+                    && (domain == entry || domain.ends_with(&format!(".{}", entry))))
+        })
+    })
+}
+
+/// Split an address header ("Ana <ana@x.com>, bob@y.com") into (name, address) pairs
+pub fn parse_addresses(header: &str) -> Vec<(Option<String>, String)> {
+    let mut entries = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+
+    for c in header.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                current.push(c);
+            }
+            ',' if !quoted => entries.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    entries.push(current);
+
+    entries
+        .iter()
+        .filter_map(|entry| {
+            let entry = entry.trim();
+            match (entry.find('<'), entry.rfind('>')) {
+                (Some(start), Some(end)) if start < end => {
+                    let address = entry[start + 1..end].trim();
+                    let name = entry[..start].trim().trim_matches('"').trim();
+                    let name = (!name.is_empty()).then(|| name.to_string());
+                    address.contains('@').then(|| (name, address.to_string()))
+                }
+                _ => entry.contains('@').then(|| (None, entry.to_string())),
+            }
+        })
+        .collect()
+}
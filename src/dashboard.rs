@@ -0,0 +1,289 @@
+use std::collections::{HashMap, HashSet};
+
+use clinbox_core::email::{Email, EmailAnalysis, Priority};
+use clinbox_core::gmail::StorageQuota;
+use clinbox_core::tasks::Task;
+
+use crate::session::Stats;
+
+/// Messages above and below the cursor whose bodies are fetched ahead of time
+const PREFETCH_AROUND: usize = 3;
+
+/// The dashboard panes that can take focus; stats are display-only
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pane {
+    Messages,
+    Preview,
+    Tasks,
+}
+
+/// Which panes the dashboard shows and whether it may change mail
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Messages, preview, tasks and stats
+    Full,
+    /// Messages and preview only, as `clinbox --list` does
+    List,
+    /// Messages and preview of any label, with nothing that changes mail (`clinbox browse`)
+    Browse,
+}
+
+/// Everything the dashboard shows, redrawn as a whole after each change
+#[derive(Debug, Clone)]
+pub struct Dashboard {
+    /// Gmail search the message list comes from
+    pub query: String,
+    /// Messages in the list; only metadata until their body is fetched
+    pub emails: Vec<Email>,
+    /// Ids of messages whose body has been fetched, or asked for
+    requested: HashSet<String>,
+    /// Fetch (and so analyze) every listed message, not just those near the
+    /// cursor, so category/priority filters see the whole list
+    pub prefetch_all: bool,
+    /// Ids of messages whose body has arrived
+    loaded: HashSet<String>,
+    /// Analyses by message id, filled in as they arrive
+    pub analyses: HashMap<String, EmailAnalysis>,
+    pub tasks: Vec<Task>,
+    pub focus: Pane,
+    pub mode: Mode,
+    pub selected: usize,
+    pub selected_task: usize,
+    /// Lines scrolled down in the preview pane
+    pub scroll: u16,
+    /// Actions taken since the dashboard opened
+    pub stats: Stats,
+    /// Account storage use, with `gmail.storage_quota` on
+    pub storage: Option<StorageQuota>,
+}
+
+impl Dashboard {
+    pub fn new(query: &str, emails: Vec<Email>, tasks: Vec<Task>, mode: Mode) -> Self {
+        Self {
+            query: query.to_string(),
+            emails,
+            requested: HashSet::new(),
+            prefetch_all: false,
+            loaded: HashSet::new(),
+            analyses: HashMap::new(),
+            tasks,
+            focus: Pane::Messages,
+            mode,
+            selected: 0,
+            selected_task: 0,
+            scroll: 0,
+            stats: Stats::default(),
+            storage: None,
+        }
+    }
+
+    /// List and preview only, without the tasks and stats panes
+    pub fn split(&self) -> bool {
+        self.mode != Mode::Full
+    }
+
+    /// Archive, delete, reply and the like are off
+    pub fn read_only(&self) -> bool {
+        self.mode == Mode::Browse
+    }
+
+    /// Panes that take focus, in Tab order
+    fn panes(&self) -> &'static [Pane] {
+        if self.split() {
+            &[Pane::Messages, Pane::Preview]
+        } else {
+            &[Pane::Messages, Pane::Preview, Pane::Tasks]
+        }
+    }
+
+    pub fn next_pane(&mut self) {
+        let panes = self.panes();
+        let i = panes.iter().position(|&p| p == self.focus).unwrap_or(0);
+        self.focus = panes[(i + 1) % panes.len()];
+    }
+
+    pub fn prev_pane(&mut self) {
+        let panes = self.panes();
+        let i = panes.iter().position(|&p| p == self.focus).unwrap_or(0);
+        self.focus = panes[(i + panes.len() - 1) % panes.len()];
+    }
+
+    pub fn selected_email(&self) -> Option<&Email> {
+        self.emails.get(self.selected)
+    }
+
+    /// Whether the message's body and attachment list have been fetched
+    pub fn is_loaded(&self, email: &Email) -> bool {
+        self.loaded.contains(&email.id)
+    }
+
+    /// Ids near the cursor (or anywhere, with `prefetch_all`) whose body hasn't
+    /// been asked for yet, marking them asked
+    pub fn wanted_bodies(&mut self) -> Vec<String> {
+        let (start, end) = if self.prefetch_all {
+            (0, self.emails.len())
+        } else {
+            (
+                self.selected.saturating_sub(PREFETCH_AROUND),
+                (self.selected + PREFETCH_AROUND + 1).min(self.emails.len()),
+            )
+        };
+        let wanted: Vec<String> = self.emails[start.min(end)..end]
+            .iter()
+            .filter(|e| !self.requested.contains(&e.id))
+            .map(|e| e.id.clone())
+            .collect();
+        self.requested.extend(wanted.iter().cloned());
+        wanted
+    }
+
+    /// Swap in a message with its body once fetched
+    pub fn set_body(&mut self, email: Email) {
+        if let Some(listed) = self.emails.iter_mut().find(|e| e.id == email.id) {
+            self.loaded.insert(email.id.clone());
+            *listed = email;
+        }
+    }
+
+    pub fn analysis(&self, email: &Email) -> Option<&EmailAnalysis> {
+        self.analyses.get(&email.id)
+    }
+
+    pub fn selected_task(&self) -> Option<&Task> {
+        self.tasks.get(self.selected_task)
+    }
+
+    /// Move the selection in the focused pane; the preview scrolls instead
+    pub fn move_by(&mut self, delta: isize) {
+        match self.focus {
+            Pane::Messages => {
+                self.selected = step(self.selected, delta, self.emails.len());
+                self.scroll = 0;
+            }
+            Pane::Preview => self.scroll = self.scroll.saturating_add_signed(delta as i16),
+            Pane::Tasks => self.selected_task = step(self.selected_task, delta, self.tasks.len()),
+        }
+    }
+
+    /// Drop a message from the list after it was archived, deleted or turned into a task
+    pub fn remove_selected(&mut self) -> Option<Email> {
+        if self.selected >= self.emails.len() {
+            return None;
+        }
+        let email = self.emails.remove(self.selected);
+        self.selected = self.selected.min(self.emails.len().saturating_sub(1));
+        self.scroll = 0;
+        Some(email)
+    }
+
+    /// Put back a message whose archive, delete or mark-read was undone, selecting it
+    pub fn restore(&mut self, index: usize, email: Email) {
+        match self.emails.iter().position(|e| e.id == email.id) {
+            // Marked read, so still listed
+            Some(listed) => {
+                self.emails[listed].is_unread = email.is_unread;
+                self.selected = listed;
+            }
+            None => {
+                self.selected = index.min(self.emails.len());
+                self.emails.insert(self.selected, email);
+            }
+        }
+        self.scroll = 0;
+    }
+
+    /// Drop a message that no longer belongs in the list, e.g. filtered out by its analysis
+    pub fn remove(&mut self, id: &str) {
+        let Some(index) = self.emails.iter().position(|e| e.id == id) else {
+            return;
+        };
+        self.emails.remove(index);
+        if index < self.selected {
+            self.selected -= 1;
+        } else if index == self.selected {
+            self.scroll = 0;
+        }
+        self.selected = self.selected.min(self.emails.len().saturating_sub(1));
+    }
+
+    /// Drop analyzed messages whose analysis fails `keep` (the category/priority filters)
+    pub fn retain_analyzed(&mut self, keep: impl Fn(&EmailAnalysis) -> bool) {
+        let dropped: Vec<String> = self
+            .emails
+            .iter()
+            .filter(|e| self.analysis(e).is_some_and(|a| !keep(a)))
+            .map(|e| e.id.clone())
+            .collect();
+        for id in dropped {
+            self.remove(&id);
+        }
+    }
+
+    /// Replace the message list after a refresh, keeping the selection on the same
+    /// message and the bodies already fetched
+    pub fn set_emails(&mut self, emails: Vec<Email>) {
+        let current = self.selected_email().map(|e| e.id.clone());
+        let mut old: HashMap<String, Email> = std::mem::take(&mut self.emails)
+            .into_iter()
+            .filter(|e| self.loaded.contains(&e.id))
+            .map(|e| (e.id.clone(), e))
+            .collect();
+        self.emails = emails
+            .into_iter()
+            .map(|e| match old.remove(&e.id) {
+                // Labels may have changed since the body was fetched
+                Some(body) => Email {
+                    labels: e.labels,
+                    is_unread: e.is_unread,
+                    ..body
+                },
+                None => e,
+            })
+            .collect();
+        let listed: HashSet<&String> = self.emails.iter().map(|e| &e.id).collect();
+        self.loaded.retain(|id| listed.contains(id));
+        // Bodies whose fetch failed get another try
+        self.requested.retain(|id| self.loaded.contains(id));
+        self.selected = current
+            .and_then(|id| self.emails.iter().position(|e| e.id == id))
+            .unwrap_or(0);
+    }
+
+    pub fn set_tasks(&mut self, tasks: Vec<Task>) {
+        self.tasks = tasks;
+        self.selected_task = self.selected_task.min(self.tasks.len().saturating_sub(1));
+    }
+
+    pub fn unread(&self) -> usize {
+        self.emails.iter().filter(|e| e.is_unread).count()
+    }
+
+    /// Number of listed messages per priority, most pressing first
+    pub fn priority_counts(&self) -> Vec<(Priority, usize)> {
+        [
+            Priority::Urgent,
+            Priority::ActionRequired,
+            Priority::Informative,
+            Priority::Low,
+            Priority::Spam,
+        ]
+        .into_iter()
+        .map(|priority| {
+            let count = self
+                .emails
+                .iter()
+                .filter_map(|e| self.analysis(e))
+                .filter(|a| a.priority == priority)
+                .count();
+            (priority, count)
+        })
+        .collect()
+    }
+}
+
+/// Move an index by `delta`, staying within `0..len`
+fn step(index: usize, delta: isize, len: usize) -> usize {
+    index
+        .saturating_add_signed(delta)
+        .min(len.saturating_sub(1))
+}
@@ -0,0 +1,149 @@
+use anyhow::{Context, Result};
+use flate2::{Compression, write::GzEncoder};
+use serde_json::Value;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+use clinbox_core::config::Config;
+use clinbox_core::diagnostics;
+
+/// Most recent log files included in a bundle
+const MAX_LOG_FILES: usize = 3;
+
+/// Config fields holding secrets or personal data; hooks (`on_*`) are matched by prefix
+const REDACTED_FIELDS: &[&str] = &[
+    "api_key",
+    "client_id",
+    "client_secret",
+    "serve_token",
+    "token",
+    "email",
+    "address",
+    "senders",
+    "sender_instructions",
+    "webhook",
+    "proxy",
+    "ca_bundle",
+    "file_path",
+    "pipe_command",
+];
+
+/// Write a .tar.gz with version info, the config without secrets, recent logs and
+/// the last API error responses. Returns the path written and a summary of its contents.
+pub fn bundle(out: Option<&Path>) -> Result<(PathBuf, Vec<String>)> {
+    let path = out.map(Path::to_path_buf).unwrap_or_else(|| {
+        PathBuf::from(format!(
+            "clinbox-debug-{}.tar.gz",
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        ))
+    });
+    let file =
+        File::create(&path).with_context(|| format!("Failed to create {}", path.display()))?;
+    let mut archive = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+    let mut contents = Vec::new();
+
+    let config = Config::load()?;
+    append(
+        &mut archive,
+        "version.txt",
+        version_info(&config)?.as_bytes(),
+    )?;
+    contents.push("version.txt".to_string());
+
+    let mut config = serde_json::to_value(&config)?;
+    redact(&mut config);
+    append(
+        &mut archive,
+        "config.json",
+        serde_json::to_string_pretty(&config)?.as_bytes(),
+    )?;
+    contents.push("config.json (secrets and addresses removed)".to_string());
+
+    let errors = diagnostics::recent_api_errors()?;
+    append(
+        &mut archive,
+        "api_errors.json",
+        serde_json::to_string_pretty(&errors)?.as_bytes(),
+    )?;
+    contents.push(format!("{} recent API error(s)", errors.len()));
+
+    let logs = recent_logs()?;
+    for log in &logs {
+        let name = log.file_name().unwrap_or_default().to_string_lossy();
+        archive.append_path_with_name(log, format!("logs/{}", name))?;
+    }
+    contents.push(match logs.len() {
+        0 => "no log files (turn them on with 'clinbox config log_file true')".to_string(),
+        n => format!("{} log file(s)", n),
+    });
+
+    archive.into_inner()?.finish()?;
+    Ok((path, contents))
+}
+
+/// Version, platform and which accounts have a stored token
+fn version_info(config: &Config) -> Result<String> {
+    let mut info = format!(
+        "clinbox {}\nplatform: {} {}\ncreated: {}\naccounts: {}\n",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        chrono::Utc::now().to_rfc3339(),
+        config.gmail.accounts.len()
+    );
+    for (i, account) in config.gmail.accounts.iter().enumerate() {
+        let token = Config::token_path_for_account(&account.id)?.exists();
+        info.push_str(&format!(
+            "account {}: token {}\n",
+            i + 1,
+            if token { "stored" } else { "missing" }
+        ));
+    }
+    Ok(info)
+}
+
+/// Blank out secret and personal fields anywhere in the config
+fn redact(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if value.is_null() {
+                    continue;
+                }
+                if REDACTED_FIELDS.contains(&key.as_str()) || key.starts_with("on_") {
+                    *value = Value::String("[redacted]".to_string());
+                } else {
+                    redact(value);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+/// The newest files in ~/.clinbox/logs/
+fn recent_logs() -> Result<Vec<PathBuf>> {
+    let dir = Config::logs_dir()?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut logs: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file())
+        .collect();
+    // Daily files are named clinbox.<date>.log, so names sort by age
+    logs.sort();
+    let skip = logs.len().saturating_sub(MAX_LOG_FILES);
+    Ok(logs.split_off(skip))
+}
+
+fn append(archive: &mut tar::Builder<GzEncoder<File>>, name: &str, data: &[u8]) -> Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(chrono::Utc::now().timestamp().max(0) as u64);
+    header.set_cksum();
+    archive.append_data(&mut header, name, data)?;
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use anyhow::{Context, Result, bail};
+
+use crate::pipe;
+
+/// Let the user edit `text` in `$VISUAL`/`$EDITOR` (vi or notepad if unset) and
+/// return the result. The terminal must be free, i.e. the TUI suspended.
+pub fn edit(text: &str) -> Result<String> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| if cfg!(windows) { "notepad" } else { "vi" }.to_string());
+
+    // Created private and unguessable, and removed when dropped
+    let file = tempfile::Builder::new()
+        .prefix("clinbox-draft-")
+        .suffix(".txt")
+        .tempfile()
+        .context("Failed to create a temporary file")?;
+    let path = file.path();
+    std::fs::write(path, text).with_context(|| format!("Failed to write {}", path.display()))?;
+
+    let status = pipe::shell(&format!(
+        "{} {}",
+        editor,
+        pipe::shell_quote(&path.to_string_lossy())
+    ))
+    .status()
+    .with_context(|| format!("Failed to run '{}'", editor));
+
+    // Editors may save by replacing the file, so read it back by path
+    let edited = std::fs::read_to_string(path);
+    drop(file);
+
+    let status = status?;
+    if !status.success() {
+        bail!("'{}' exited with {}", editor, status);
+    }
+    edited.context("Failed to read the edited draft")
+}
@@ -0,0 +1,155 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use mail_parser::{Address, MessageParser, MimeHeaders};
+use std::path::Path;
+
+use clinbox_core::email::{Attachment, Email};
+use clinbox_core::gmail::GmailClient;
+
+/// A message read from a local .eml file. Attachments have no Gmail id, so their
+/// bytes are kept alongside, in the same order as `email.attachments`.
+pub struct LocalMessage {
+    pub email: Email,
+    pub attachment_data: Vec<Vec<u8>>,
+}
+
+impl LocalMessage {
+    /// Bytes of one of the message's attachments
+    pub fn data(&self, attachment: &Attachment) -> Option<&[u8]> {
+        let index = self
+            .email
+            .attachments
+            .iter()
+            .position(|a| std::ptr::eq(a, attachment))?;
+        self.attachment_data.get(index).map(Vec::as_slice)
+    }
+}
+
+/// Read and parse an RFC 822 message from disk
+pub fn load(path: &Path) -> Result<LocalMessage> {
+    let raw = std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    parse(&raw).with_context(|| format!("{} is not a valid email message", path.display()))
+}
+
+/// Parse an RFC 822 message into an `Email`
+pub fn parse(raw: &[u8]) -> Result<LocalMessage> {
+    let message = MessageParser::default()
+        .parse(raw)
+        .context("Failed to parse message")?;
+
+    let date = message
+        .date()
+        .and_then(|d| DateTime::<Utc>::from_timestamp(d.to_timestamp(), 0))
+        .unwrap_or_else(Utc::now);
+
+    let body_plain = message.body_text(0).map(|b| b.into_owned());
+    let body_html = message.body_html(0).map(|b| b.into_owned());
+    let snippet = body_plain
+        .as_deref()
+        .unwrap_or_default()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .take(200)
+        .collect();
+
+    let mut attachments = Vec::new();
+    let mut attachment_data = Vec::new();
+    let mut calendar = None;
+    for part in message.attachments() {
+        let mime_type = part
+            .content_type()
+            .map(|ct| match ct.subtype() {
+                Some(subtype) => format!("{}/{}", ct.ctype(), subtype),
+                None => ct.ctype().to_string(),
+            })
+            .unwrap_or_else(|| "application/octet-stream".to_string());
+        let filename = part.attachment_name().unwrap_or_default().to_string();
+
+        if calendar.is_none()
+            && (mime_type.starts_with("text/calendar") || filename.to_lowercase().ends_with(".ics"))
+        {
+            calendar = Some(String::from_utf8_lossy(part.contents()).into_owned());
+        }
+
+        attachments.push(Attachment {
+            filename,
+            mime_type,
+            size: part.contents().len() as u64,
+            attachment_id: String::new(),
+        });
+        attachment_data.push(part.contents().to_vec());
+    }
+
+    let email = Email {
+        id: message.message_id().unwrap_or_default().to_string(),
+        thread_id: String::new(),
+        subject: message.subject().unwrap_or_default().to_string(),
+        from: message.from().map(format_address).unwrap_or_default(),
+        to: message.to().map(format_address).unwrap_or_default(),
+        date,
+        snippet,
+        body_plain,
+        body_html,
+        labels: Vec::new(),
+        has_attachments: !attachments.is_empty(),
+        attachments,
+        is_unread: false,
+        size: raw.len() as u64,
+        calendar,
+        list_id: message.header_raw("List-Id").map(|v| v.trim().to_string()),
+        precedence: message
+            .header_raw("Precedence")
+            .map(|v| v.trim().to_string()),
+        auto_submitted: message
+            .header_raw("Auto-Submitted")
+            .map(|v| v.trim().to_string()),
+        x_mailer: message.header_raw("X-Mailer").map(|v| v.trim().to_string()),
+        authentication_results: message
+            .header_raw("Authentication-Results")
+            .map(|v| v.trim().to_string()),
+    };
+
+    Ok(LocalMessage {
+        email,
+        attachment_data,
+    })
+}
+
+/// Whether Gmail's structured payload gave no body to show, as happens with
+/// tricky MIME (bodies nested in message/rfc822 parts, unusual encodings)
+pub fn body_missing(email: &Email) -> bool {
+    let empty = |body: &Option<String>| body.as_deref().is_none_or(|b| b.trim().is_empty());
+    empty(&email.body_plain) && empty(&email.body_html)
+}
+
+/// `email` with its bodies parsed from the raw RFC 822 source instead, when
+/// the structured payload had none; unchanged if the raw message has none either
+pub async fn with_raw_body(gmail: &GmailClient, email: &Email) -> Result<Email> {
+    let mut email = email.clone();
+    if !body_missing(&email) {
+        return Ok(email);
+    }
+    let parsed = parse(&gmail.fetch_raw(&email.id).await?)?.email;
+    if !body_missing(&parsed) {
+        email.body_plain = parsed.body_plain;
+        email.body_html = parsed.body_html;
+    }
+    Ok(email)
+}
+
+/// Render an address header the way Gmail returns it: `Name <addr>, ...`
+fn format_address(address: &Address) -> String {
+    address
+        .iter()
+        .map(|addr| match (addr.name(), addr.address()) {
+            (Some(name), Some(address)) => format!("{} <{}>", name, address),
+            (None, Some(address)) => address.to_string(),
+            (Some(name), None) => name.to_string(),
+            (None, None) => String::new(),
+        })
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
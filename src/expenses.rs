@@ -0,0 +1,253 @@
+use anyhow::{Context, Result, anyhow, bail};
+use chrono::{Local, NaiveDate};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use clinbox_core::ai::Expense;
+use clinbox_core::config::Config;
+use clinbox_core::email::Email;
+
+const HEADER: &str = "date,vendor,amount,currency,description,subject,message_id";
+
+/// A line of the expense ledger
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub date: NaiveDate,
+    pub vendor: String,
+    pub amount: f64,
+    pub currency: String,
+    pub description: String,
+    pub subject: String,
+    pub message_id: String,
+}
+
+impl Entry {
+    /// An entry from what the AI read off the email; the email's own date and
+    /// no currency fill in for what the invoice doesn't say
+    pub fn from_expense(email: &Email, expense: Expense) -> Result<Self> {
+        let amount = expense
+            .amount
+            .ok_or_else(|| anyhow!("no amount found in this email"))?;
+        let date = expense
+            .date
+            .as_deref()
+            .and_then(|d| NaiveDate::parse_from_str(d.trim(), "%Y-%m-%d").ok())
+            .unwrap_or_else(|| email.date.with_timezone(&Local).date_naive());
+        Ok(Self {
+            date,
+            vendor: expense.vendor.trim().replace(';', ","),
+            amount,
+            currency: expense.currency.unwrap_or_default().trim().to_uppercase(),
+            description: expense.description.unwrap_or_default().trim().to_string(),
+            subject: email.subject.replace(['\r', '\n'], " "),
+            message_id: email.id.clone(),
+        })
+    }
+
+    /// "Acme; 12.50; EUR; 2024-07-01", the form edited before saving
+    pub fn editable(&self) -> String {
+        format!(
+            "{}; {:.2}; {}; {}",
+            self.vendor, self.amount, self.currency, self.date
+        )
+    }
+
+    /// Take back an edited "vendor; amount; currency; date" line
+    pub fn apply_edit(&mut self, line: &str) -> Result<()> {
+        let parts: Vec<&str> = line.split(';').map(str::trim).collect();
+        let [vendor, amount, currency, date] = parts[..] else {
+            bail!("expected vendor; amount; currency; date");
+        };
+        if vendor.is_empty() {
+            bail!("the vendor is missing");
+        }
+        self.amount = amount
+            .replace(',', ".")
+            .parse()
+            .map_err(|_| anyhow!("not an amount: {}", amount))?;
+        self.date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|_| anyhow!("not a YYYY-MM-DD date: {}", date))?;
+        self.vendor = vendor.to_string();
+        self.currency = currency.to_uppercase();
+        Ok(())
+    }
+
+    /// "2024-07-01  Acme  12.50 EUR  Hosting"
+    pub fn describe(&self) -> String {
+        let mut line = format!(
+            "{}  {}  {:.2} {}",
+            self.date, self.vendor, self.amount, self.currency
+        );
+        if !self.description.is_empty() {
+            line.push_str(&format!("  {}", self.description));
+        }
+        line.trim_end().to_string()
+    }
+
+    fn to_record(&self) -> [String; 7] {
+        [
+            self.date.to_string(),
+            self.vendor.clone(),
+            format!("{:.2}", self.amount),
+            self.currency.clone(),
+            self.description.clone(),
+            self.subject.clone(),
+            self.message_id.clone(),
+        ]
+    }
+
+    fn from_record(record: &csv::StringRecord) -> Option<Self> {
+        let [
+            date,
+            vendor,
+            amount,
+            currency,
+            description,
+            subject,
+            message_id,
+        ] = record.iter().collect::<Vec<_>>()[..]
+        else {
+            return None;
+        };
+        Some(Self {
+            date: NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?,
+            vendor: vendor.to_string(),
+            amount: amount.parse().ok()?,
+            currency: currency.to_string(),
+            description: description.to_string(),
+            subject: subject.to_string(),
+            message_id: message_id.to_string(),
+        })
+    }
+}
+
+/// The ledger, a CSV file that opens in any spreadsheet, in the order entries
+/// were recorded
+pub fn load() -> Result<Vec<Entry>> {
+    let path = Config::expenses_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path).context("Failed to read the expense ledger")?;
+    parse(&content, &path)
+}
+
+/// Every row of the ledger. A row that doesn't parse (e.g. after editing the
+/// file in a spreadsheet) is an error rather than skipped, so it isn't lost
+/// the next time the ledger is written.
+fn parse(content: &str, path: &Path) -> Result<Vec<Entry>> {
+    let mut reader = csv::Reader::from_reader(content.as_bytes());
+    reader
+        .records()
+        .map(|record| {
+            let record = record.context("Failed to read the expense ledger")?;
+            let line = record.position().map_or(0, |p| p.line());
+            Entry::from_record(&record).with_context(|| {
+                format!(
+                    "Line {} of the expense ledger isn't a valid entry; fix or remove it in {}",
+                    line,
+                    path.display()
+                )
+            })
+        })
+        .collect()
+}
+
+/// Add an entry, replacing an earlier one from the same email; true when it
+/// replaced one. New entries are appended; the ledger is only rewritten to
+/// replace one, and not at all while any of its rows doesn't parse.
+pub fn record(entry: Entry) -> Result<bool> {
+    let path = Config::expenses_path()?;
+    fs::create_dir_all(path.parent().unwrap())?;
+
+    let content = if path.exists() {
+        fs::read_to_string(&path).context("Failed to read the expense ledger")?
+    } else {
+        String::new()
+    };
+    let mut entries = parse(&content, &path)?;
+
+    if let Some(earlier) = entries
+        .iter_mut()
+        .find(|e| e.message_id == entry.message_id)
+    {
+        *earlier = entry;
+        let mut rows = csv_rows(&entries)?;
+        rows.splice(0..0, format!("{}\n", HEADER).into_bytes());
+        fs::write(&path, rows).context("Failed to write the expense ledger")?;
+        return Ok(true);
+    }
+
+    let mut rows = csv_rows([&entry])?;
+    if content.trim().is_empty() {
+        rows.splice(0..0, format!("{}\n", HEADER).into_bytes());
+    } else if !content.ends_with('\n') {
+        rows.insert(0, b'\n');
+    }
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .context("Failed to open the expense ledger")?;
+    file.write_all(&rows)
+        .context("Failed to write the expense ledger")?;
+    Ok(false)
+}
+
+/// Entries as CSV rows, quoted where needed
+fn csv_rows<'a>(entries: impl IntoIterator<Item = &'a Entry>) -> Result<Vec<u8>> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for e in entries {
+        writer.write_record(e.to_record())?;
+    }
+    writer
+        .into_inner()
+        .context("Failed to write the expense ledger")
+}
+
+/// Totals per currency, e.g. [("EUR", 120.5), ("USD", 30.0)]
+pub fn totals<'a>(entries: impl IntoIterator<Item = &'a Entry>) -> Vec<(String, f64)> {
+    let mut totals: BTreeMap<String, f64> = BTreeMap::new();
+    for e in entries {
+        *totals.entry(e.currency.clone()).or_default() += e.amount;
+    }
+    totals.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(vendor: &str, subject: &str) -> Entry {
+        Entry {
+            date: NaiveDate::from_ymd_opt(2024, 7, 1).unwrap(),
+            vendor: vendor.to_string(),
+            amount: 12.5,
+            currency: "EUR".to_string(),
+            description: String::new(),
+            subject: subject.to_string(),
+            message_id: "18f0a".to_string(),
+        }
+    }
+
+    #[test]
+    fn rows_round_trip() {
+        let written = entry("Acme, Inc.", "Your \"July\" invoice");
+        let mut content = format!("{}\n", HEADER).into_bytes();
+        content.extend(csv_rows([&written]).unwrap());
+        let read = parse(std::str::from_utf8(&content).unwrap(), Path::new("x")).unwrap();
+        assert_eq!(read.len(), 1);
+        assert_eq!(read[0].vendor, "Acme, Inc.");
+        assert_eq!(read[0].subject, "Your \"July\" invoice");
+        assert_eq!(read[0].amount, 12.5);
+    }
+
+    #[test]
+    fn unparsable_rows_are_errors() {
+        let content = format!("{}\n2024-07-01,Acme,twelve,EUR,,Invoice,18f0a\n", HEADER);
+        let error = parse(&content, Path::new("x")).unwrap_err();
+        assert!(error.to_string().contains("Line 2"));
+    }
+}
@@ -0,0 +1,314 @@
+use anyhow::{Context, Result, bail};
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use mail_parser::{MessageParser, MimeHeaders};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use clinbox_core::email::Email;
+use clinbox_core::gmail::GmailClient;
+
+use crate::attachments;
+use crate::contacts::parse_addresses;
+
+/// Subjects are cut to this many characters in file names
+const MAX_SUBJECT_CHARS: usize = 80;
+
+/// Output format of `clinbox export`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// One RFC 822 `.eml` file per message
+    Eml,
+    /// All messages appended to a single mboxrd file
+    Mbox,
+    /// One standalone HTML page per message, inline images embedded
+    Html,
+    /// The HTML page converted to PDF (needs wkhtmltopdf or Chrome/Chromium)
+    Pdf,
+}
+
+impl ExportFormat {
+    /// Where to write when `--out` isn't given
+    pub fn default_out(&self) -> PathBuf {
+        match self {
+            ExportFormat::Mbox => PathBuf::from("export.mbox"),
+            _ => PathBuf::from("."),
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "eml" => Ok(ExportFormat::Eml),
+            "mbox" => Ok(ExportFormat::Mbox),
+            "html" => Ok(ExportFormat::Html),
+            "pdf" => Ok(ExportFormat::Pdf),
+            _ => Err(format!(
+                "unknown export format '{}' (expected eml, mbox, html or pdf)",
+                s
+            )),
+        }
+    }
+}
+
+/// File name for an exported message, e.g. "2024-07-01 Invoice 42.eml"
+pub fn export_filename(email: &Email, extension: &str) -> String {
+    let subject: String = email
+        .subject
+        .trim()
+        .chars()
+        .take(MAX_SUBJECT_CHARS)
+        .collect();
+    let subject = if subject.is_empty() {
+        "(no subject)"
+    } else {
+        subject.trim_end()
+    };
+    format!(
+        "{} {}.{}",
+        email.date.format("%Y-%m-%d"),
+        subject,
+        extension
+    )
+}
+
+/// Save one message in a per-message format (everything but mbox) in `dir`
+pub async fn save(
+    gmail: &GmailClient,
+    email: &Email,
+    format: ExportFormat,
+    dir: &Path,
+) -> Result<PathBuf> {
+    match format {
+        ExportFormat::Eml => save_eml(gmail, email, dir).await,
+        ExportFormat::Html => save_html(gmail, email, dir).await,
+        ExportFormat::Pdf => save_pdf(gmail, email, dir).await,
+        ExportFormat::Mbox => bail!("mbox export writes a single file, use Mbox::append"),
+    }
+}
+
+/// Download the raw message and save it as an .eml file in `dir`, never overwriting
+async fn save_eml(gmail: &GmailClient, email: &Email, dir: &Path) -> Result<PathBuf> {
+    let raw = gmail.fetch_raw(&email.id).await?;
+    std::fs::create_dir_all(dir)?;
+    attachments::save_unique(dir, &export_filename(email, "eml"), &raw)
+}
+
+/// Render the message as a standalone HTML page and save it in `dir`
+async fn save_html(gmail: &GmailClient, email: &Email, dir: &Path) -> Result<PathBuf> {
+    let raw = gmail.fetch_raw(&email.id).await?;
+    let html = render_html(email, &raw)?;
+    std::fs::create_dir_all(dir)?;
+    attachments::save_unique(dir, &export_filename(email, "html"), html.as_bytes())
+}
+
+/// Render the message to HTML, convert it to PDF and save it in `dir`
+async fn save_pdf(gmail: &GmailClient, email: &Email, dir: &Path) -> Result<PathBuf> {
+    let raw = gmail.fetch_raw(&email.id).await?;
+    let html = render_html(email, &raw)?;
+
+    // A private directory, removed when dropped
+    let work = tempfile::tempdir().context("Failed to create a temporary directory")?;
+    let html_path = work.path().join("email.html");
+    let pdf_path = work.path().join("email.pdf");
+    std::fs::write(&html_path, html)?;
+
+    let converted = tokio::task::spawn_blocking({
+        let (html_path, pdf_path) = (html_path.clone(), pdf_path.clone());
+        move || html_to_pdf(&html_path, &pdf_path)
+    })
+    .await?
+    .and_then(|()| std::fs::read(&pdf_path).context("Failed to read converted PDF"));
+    drop(work);
+
+    std::fs::create_dir_all(dir)?;
+    attachments::save_unique(dir, &export_filename(email, "pdf"), &converted?)
+}
+
+/// A self-contained HTML page: header block plus the sanitized body, with
+/// `cid:` inline images replaced by data URIs and remote images left out
+fn render_html(email: &Email, raw: &[u8]) -> Result<String> {
+    let message = MessageParser::default()
+        .parse(raw)
+        .context("Failed to parse message")?;
+
+    let body = match message.body_html(0) {
+        Some(html) => {
+            let mut html = html.into_owned();
+            for part in message.parts.iter() {
+                let Some(cid) = part.content_id() else {
+                    continue;
+                };
+                let mime_type = part
+                    .content_type()
+                    .and_then(|ct| Some(format!("{}/{}", ct.ctype(), ct.subtype()?)))
+                    .unwrap_or_else(|| "application/octet-stream".to_string());
+                let data_uri = format!(
+                    "data:{};base64,{}",
+                    mime_type,
+                    STANDARD.encode(part.contents())
+                );
+                html = html.replace(&format!("cid:{}", cid.trim_matches(['<', '>'])), &data_uri);
+            }
+
+            // Remote images (tracking pixels among them) are dropped, so the
+            // PDF renderer never fetches them
+            ammonia::Builder::default()
+                .add_url_schemes(&["data"])
+                .add_generic_attributes(&["style", "bgcolor"])
+                .attribute_filter(|element, attribute, value| match (element, attribute) {
+                    ("img", "src") if !value.starts_with("data:") => None,
+                    (_, "style") if value.to_ascii_lowercase().contains("url(") => None,
+                    _ => Some(value.into()),
+                })
+                .clean(&html)
+                .to_string()
+        }
+        None => format!(
+            "<pre style=\"white-space: pre-wrap\">{}</pre>",
+            ammonia::clean_text(&email.body_text())
+        ),
+    };
+
+    let header = [
+        ("From", &email.from),
+        ("To", &email.to),
+        ("Subject", &email.subject),
+    ]
+    .iter()
+    .filter(|(_, value)| !value.is_empty())
+    .map(|(name, value)| format!("<b>{}:</b> {}<br>", name, ammonia::clean_text(value)))
+    .collect::<String>();
+
+    Ok(format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+</head>
+<body>
+<div style="font-family: sans-serif; font-size: 14px; border-bottom: 1px solid #ccc; padding-bottom: 8px; margin-bottom: 16px">
+{header}<b>Date:</b> {date}
+</div>
+{body}
+</body>
+</html>
+"#,
+        title = ammonia::clean_text(&email.subject),
+        header = header,
+        date = email
+            .date
+            .with_timezone(&chrono::Local)
+            .format("%Y-%m-%d %H:%M"),
+        body = body,
+    ))
+}
+
+/// Convert an HTML file with the first PDF renderer found on the system
+fn html_to_pdf(html: &Path, pdf: &Path) -> Result<()> {
+    use std::process::{Command, Stdio};
+
+    let chrome_args = [
+        "--headless".to_string(),
+        "--disable-gpu".to_string(),
+        "--no-pdf-header-footer".to_string(),
+        format!("--print-to-pdf={}", pdf.display()),
+        format!("file://{}", html.display()),
+    ];
+    let candidates: [(&str, Vec<String>); 5] = [
+        (
+            "wkhtmltopdf",
+            vec![
+                "--quiet".to_string(),
+                html.display().to_string(),
+                pdf.display().to_string(),
+            ],
+        ),
+        ("chromium", chrome_args.to_vec()),
+        ("chromium-browser", chrome_args.to_vec()),
+        ("google-chrome", chrome_args.to_vec()),
+        (
+            "/Applications/Google Chrome.app/Contents/MacOS/Google Chrome",
+            chrome_args.to_vec(),
+        ),
+    ];
+
+    for (program, args) in candidates {
+        let status = Command::new(program)
+            .args(&args)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+        match status {
+            Ok(s) if s.success() && pdf.exists() => return Ok(()),
+            Ok(s) => bail!("{} failed to convert the email: {}", program, s),
+            Err(_) => continue,
+        }
+    }
+
+    bail!("PDF export needs wkhtmltopdf or Chrome/Chromium installed")
+}
+
+/// An mbox file that messages are appended to
+pub struct Mbox {
+    file: File,
+}
+
+impl Mbox {
+    /// Open (or create) an mbox file for appending
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        Ok(Self { file })
+    }
+
+    /// Download the raw message and append it
+    pub async fn append(&mut self, gmail: &GmailClient, email: &Email) -> Result<()> {
+        let raw = gmail.fetch_raw(&email.id).await?;
+        self.file
+            .write_all(&mbox_entry(email, &raw))
+            .context("Failed to write mbox file")
+    }
+}
+
+/// One mboxrd entry: a "From " separator line, the message with LF line endings and
+/// "From " lines quoted with '>', then a blank line
+fn mbox_entry(email: &Email, raw: &[u8]) -> Vec<u8> {
+    let sender = parse_addresses(&email.from)
+        .into_iter()
+        .next()
+        .map(|(_, address)| address)
+        .filter(|a| !a.is_empty() && !a.contains(char::is_whitespace))
+        .unwrap_or_else(|| "MAILER-DAEMON".to_string());
+
+    let mut out = format!(
+        "From {} {}\n",
+        sender,
+        email.date.format("%a %b %e %H:%M:%S %Y")
+    )
+    .into_bytes();
+
+    let text = raw.strip_suffix(b"\n").unwrap_or(raw);
+    for line in text.split(|&b| b == b'\n') {
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        let quotes = line.iter().take_while(|&&b| b == b'>').count();
+        if line[quotes..].starts_with(b"From ") {
+            out.push(b'>');
+        }
+        out.extend_from_slice(line);
+        out.push(b'\n');
+    }
+    out.push(b'\n');
+    out
+}
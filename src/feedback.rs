@@ -0,0 +1,175 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+
+use clinbox_core::config::Config;
+use clinbox_core::email::{Category, Priority};
+
+use crate::contacts::parse_addresses;
+use crate::journal::Decision;
+
+/// Most disagreements kept; older ones still count in the totals
+const MAX_DISAGREEMENTS: usize = 500;
+
+/// An action that contradicted the AI's priority
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Disagreement {
+    pub at: DateTime<Utc>,
+    pub email_id: String,
+    pub from: String,
+    pub subject: String,
+    /// What the AI said
+    pub priority: Priority,
+    pub category: Option<Category>,
+    pub confidence: Option<f32>,
+    /// What I did, as in the journal ("deleted", "replied", ...)
+    pub action: String,
+}
+
+/// What I did with one sender's mail, for `clinbox cleanup suggest`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SenderRecord {
+    /// Display name from the latest email
+    pub name: String,
+    /// Number of emails per action ("archived", "deleted", "moved", ...)
+    pub actions: BTreeMap<String, u32>,
+    /// The AI category of the latest analyzed email
+    pub category: Option<Category>,
+    pub last_at: Option<DateTime<Utc>>,
+}
+
+impl SenderRecord {
+    /// Decisions on this sender's mail, skips included
+    pub fn total(&self) -> u32 {
+        self.actions.values().sum()
+    }
+}
+
+/// How often triage decisions agreed with the AI, for `clinbox feedback`, and
+/// what I do with each sender's mail
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Feedback {
+    /// Decisions on analyzed emails, agreeing or not
+    pub judged: u32,
+    /// Decisions that contradicted the AI
+    pub disagreed: u32,
+    /// The latest disagreements, oldest first
+    pub disagreements: Vec<Disagreement>,
+    /// Decisions per sender address, lowercased
+    #[serde(default)]
+    pub senders: HashMap<String, SenderRecord>,
+}
+
+impl Feedback {
+    pub fn load() -> Result<Self> {
+        let path = Config::feedback_path()?;
+
+        if path.exists() {
+            let content = fs::read_to_string(&path).context("Failed to read feedback file")?;
+            serde_json::from_str(&content).context("Failed to parse feedback file")
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Config::feedback_path()?;
+        fs::create_dir_all(path.parent().unwrap())?;
+
+        let content = serde_json::to_string_pretty(self).context("Failed to serialize feedback")?;
+        fs::write(&path, content).context("Failed to write feedback file")?;
+
+        Ok(())
+    }
+
+    /// Count a decision, keeping it if it contradicts the AI, and add it to the
+    /// sender's record. Skips and decisions on unanalyzed (or heuristically
+    /// guessed) emails don't count towards agreement. Call `save` afterwards.
+    pub fn record(&mut self, decision: &Decision) {
+        self.record_sender(decision);
+
+        let Some(priority) = decision.priority.filter(|_| !decision.guessed) else {
+            return;
+        };
+        if decision.action == "skipped" {
+            return;
+        }
+
+        self.judged += 1;
+        if !contradicts(priority, &decision.action) {
+            return;
+        }
+
+        self.disagreed += 1;
+        self.disagreements.push(Disagreement {
+            at: decision.at,
+            email_id: decision.email_id.clone(),
+            from: decision.from.clone(),
+            subject: decision.subject.clone(),
+            priority,
+            category: decision.category,
+            confidence: decision.confidence,
+            action: decision.action.clone(),
+        });
+        let excess = self.disagreements.len().saturating_sub(MAX_DISAGREEMENTS);
+        self.disagreements.drain(..excess);
+    }
+
+    /// The sender records with `decisions` (e.g. the whole triage journal)
+    /// counted in. The records here only go back to when they were added, so
+    /// for each sender the one covering more of its mail wins.
+    pub fn senders_with(&self, decisions: &[Decision]) -> HashMap<String, SenderRecord> {
+        let mut counted = Feedback::default();
+        for decision in decisions {
+            counted.record_sender(decision);
+        }
+
+        let mut senders = self.senders.clone();
+        for (address, mut record) in counted.senders {
+            let known = senders.get(&address);
+            if known.is_none_or(|known| known.total() < record.total()) {
+                record.category = record.category.or(known.and_then(|k| k.category));
+                senders.insert(address, record);
+            }
+        }
+        senders
+    }
+
+    fn record_sender(&mut self, decision: &Decision) {
+        let Some((name, address)) = parse_addresses(&decision.from).into_iter().next() else {
+            return;
+        };
+        let record = self.senders.entry(address.to_lowercase()).or_default();
+        record.name = name.unwrap_or(address);
+        // "moved to <label>" counts as "moved"
+        let action = if decision.action.starts_with("moved to ") {
+            "moved"
+        } else {
+            decision.action.as_str()
+        };
+        *record.actions.entry(action.to_string()).or_default() += 1;
+        if decision.category.is_some() {
+            record.category = decision.category;
+        }
+        record.last_at = Some(decision.at);
+    }
+
+    /// Share of decisions that agreed with the AI, from 0 to 1
+    pub fn agreement(&self) -> Option<f32> {
+        (self.judged > 0).then(|| 1.0 - self.disagreed as f32 / self.judged as f32)
+    }
+}
+
+/// Whether an action goes against the priority: throwing away something the AI
+/// thought mattered, or acting on something it thought didn't
+fn contradicts(priority: Priority, action: &str) -> bool {
+    let discarded = matches!(action, "deleted" | "blocked");
+    let acted_on = matches!(action, "task created" | "replied" | "reply scheduled");
+    match priority {
+        Priority::Urgent | Priority::ActionRequired => discarded,
+        Priority::Low | Priority::Spam => acted_on,
+        Priority::Informative => false,
+    }
+}
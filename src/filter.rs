@@ -0,0 +1,221 @@
+use chrono::{Local, Months, NaiveDate};
+use clap::Args;
+use std::str::FromStr;
+
+use clinbox_core::email::{Category, EmailAnalysis, Priority};
+
+/// Flags that narrow down which emails a triage session shows
+#[derive(Debug, Default, Args)]
+pub struct Filters {
+    /// Only show emails in these AI categories (comma-separated, e.g. billing,security)
+    #[arg(long, value_delimiter = ',')]
+    pub category: Vec<Category>,
+
+    /// Only show emails with these AI priorities (comma-separated, e.g. urgent,action_required)
+    #[arg(long, value_delimiter = ',')]
+    pub priority: Vec<Priority>,
+
+    /// Only urgent and action-required emails, for a quick check before a meeting
+    #[arg(long, conflicts_with = "priority")]
+    pub focus: bool,
+
+    /// Hide emails in these AI categories (comma-separated)
+    #[arg(long, value_delimiter = ',')]
+    pub skip_category: Vec<Category>,
+
+    /// Only fetch emails whose sender matches (address or name; repeatable)
+    #[arg(long = "from", value_name = "PATTERN")]
+    pub from: Vec<String>,
+
+    /// Only fetch emails sent from this domain (repeatable, e.g. github.com)
+    #[arg(long)]
+    pub domain: Vec<String>,
+
+    /// Only fetch emails received on or after a date (YYYY-MM-DD) or within a period (3d, 2w, 1m, 1y)
+    #[arg(long, value_name = "DATE")]
+    pub since: Option<DateBound>,
+
+    /// Triage a specific Gmail label instead of the inbox (e.g. Receipts)
+    #[arg(long)]
+    pub label: Option<String>,
+
+    /// Only fetch emails received before a date (YYYY-MM-DD) or older than a period (3d, 2w, 1m, 1y)
+    #[arg(long, value_name = "DATE")]
+    pub before: Option<DateBound>,
+
+    /// Only fetch emails with attachments, e.g. for a session filing invoices and documents
+    #[arg(long)]
+    pub has_attachment: bool,
+
+    /// Hide emails already skipped or viewed in earlier sessions
+    #[arg(long)]
+    pub unseen: bool,
+}
+
+/// A calendar date given either absolutely or relative to today
+#[derive(Debug, Clone, Copy)]
+pub struct DateBound(pub NaiveDate);
+
+impl FromStr for DateBound {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+            return Ok(Self(date));
+        }
+
+        let invalid = || format!("invalid date '{}' (use YYYY-MM-DD or 3d, 2w, 1m, 1y)", s);
+        let unit = s.chars().last().ok_or_else(invalid)?;
+        let amount: u32 = s[..s.len() - unit.len_utf8()]
+            .parse()
+            .map_err(|_| invalid())?;
+        let today = Local::now().date_naive();
+
+        let date = match unit {
+            'd' => today.checked_sub_days(chrono::Days::new(amount.into())),
+            'w' => today.checked_sub_days(chrono::Days::new(u64::from(amount) * 7)),
+            'm' => today.checked_sub_months(Months::new(amount)),
+            'y' => amount
+                .checked_mul(12)
+                .and_then(|months| today.checked_sub_months(Months::new(months))),
+            _ => None,
+        };
+
+        date.map(Self).ok_or_else(invalid)
+    }
+}
+
+impl Filters {
+    /// Whether any filter needs the AI analysis before the queue is shown
+    pub fn needs_analysis(&self) -> bool {
+        !self.category.is_empty()
+            || !self.priority.is_empty()
+            || !self.skip_category.is_empty()
+            || self.focus
+    }
+
+    /// Build the Gmail search query: unread mail (or the whole inbox with `include_all`),
+    /// scoped to `--label` when given, plus the sender and date filters
+    pub fn gmail_query(&self, include_all: bool) -> String {
+        let mut terms = Vec::new();
+
+        if let Some(label) = &self.label {
+            terms.push(label_query(label));
+        } else if include_all {
+            terms.push("in:inbox".to_string());
+        }
+        if !include_all {
+            terms.push("is:unread".to_string());
+        }
+
+        let senders: Vec<String> = self
+            .from
+            .iter()
+            .chain(&self.domain)
+            .map(|s| format!("from:{}", quote_term(s)))
+            .collect();
+        match senders.len() {
+            0 => {}
+            1 => terms.extend(senders),
+            _ => terms.push(format!("{{{}}}", senders.join(" "))),
+        }
+
+        if self.has_attachment {
+            terms.push("has:attachment".to_string());
+        }
+        if let Some(DateBound(date)) = self.since {
+            terms.push(format!("after:{}", date.format("%Y/%m/%d")));
+        }
+        if let Some(DateBound(date)) = self.before {
+            terms.push(format!("before:{}", date.format("%Y/%m/%d")));
+        }
+
+        terms.join(" ")
+    }
+
+    /// Whether any filter needs an email's analysis to decide
+    pub fn by_analysis(&self) -> bool {
+        self.focus
+            || !self.category.is_empty()
+            || !self.priority.is_empty()
+            || !self.skip_category.is_empty()
+    }
+
+    /// Check an analysis against the category/priority filters
+    pub fn matches(&self, analysis: &EmailAnalysis) -> bool {
+        if !self.category.is_empty() && !self.category.contains(&analysis.category) {
+            return false;
+        }
+        if !self.priority.is_empty() && !self.priority.contains(&analysis.priority) {
+            return false;
+        }
+        if self.focus
+            && !matches!(
+                analysis.priority,
+                Priority::Urgent | Priority::ActionRequired
+            )
+        {
+            return false;
+        }
+        !self.skip_category.contains(&analysis.category)
+    }
+}
+
+/// Quote a query value if it contains spaces so Gmail treats it as one term
+fn quote_term(value: &str) -> String {
+    let value = value.trim().trim_start_matches('@');
+    if value.contains(char::is_whitespace) {
+        format!("\"{}\"", value.replace('"', ""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Gmail search term for a label, system (SENT, STARRED) or custom
+pub fn label_query(label: &str) -> String {
+    format!("label:{}", label_term(label))
+}
+
+/// Gmail search syntax writes label names with spaces and slashes as dashes
+fn label_term(label: &str) -> String {
+    label
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_whitespace() || c == '/' {
+                '-'
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn date_bounds() {
+        assert_eq!(
+            "2024-07-01".parse::<DateBound>().unwrap().0,
+            NaiveDate::from_ymd_opt(2024, 7, 1).unwrap()
+        );
+        let today = Local::now().date_naive();
+        assert_eq!(
+            "3d".parse::<DateBound>().unwrap().0,
+            today - chrono::Days::new(3)
+        );
+        assert!("1y".parse::<DateBound>().is_ok());
+        assert!("5x".parse::<DateBound>().is_err());
+    }
+
+    #[test]
+    fn huge_amounts_are_invalid_not_a_panic() {
+        assert!("4294967295y".parse::<DateBound>().is_err());
+        assert!("400000000y".parse::<DateBound>().is_err());
+        assert!("4294967295d".parse::<DateBound>().is_err());
+    }
+}
@@ -0,0 +1,275 @@
+use anyhow::{Context, Result, bail};
+use reqwest::header::{ACCEPT, AUTHORIZATION, USER_AGENT};
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+
+use clinbox_core::config::Config;
+use clinbox_core::email::Email;
+
+const API_URL: &str = "https://api.github.com";
+
+/// The issue or pull request a GitHub notification is about
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reference {
+    pub owner: String,
+    pub repo: String,
+    pub number: u64,
+    pub pull: bool,
+}
+
+impl Reference {
+    /// "owner/repo#12"
+    pub fn name(&self) -> String {
+        format!("{}/{}#{}", self.owner, self.repo, self.number)
+    }
+}
+
+/// Where an issue or pull request stands now
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum State {
+    Open,
+    Draft,
+    Merged,
+    /// Closed as done, or a pull request closed without merging
+    Closed,
+    /// An issue closed as not planned
+    NotPlanned,
+}
+
+/// Combined result of a pull request's checks and commit statuses
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ci {
+    Passing,
+    Failing,
+    Running,
+}
+
+/// Live state of the issue or pull request behind a notification
+#[derive(Debug, Clone)]
+pub struct Status {
+    pub reference: Reference,
+    pub state: State,
+    /// Checks on an open pull request's head commit, when it has any
+    pub ci: Option<Ci>,
+}
+
+impl Status {
+    /// "🐙 owner/repo#12: merged", "🐙 owner/repo#12: open · CI failing"
+    pub fn describe(&self) -> String {
+        let state = match self.state {
+            State::Open => "open",
+            State::Draft => "draft",
+            State::Merged => "merged",
+            State::Closed => "closed",
+            State::NotPlanned => "closed as not planned",
+        };
+        let ci = match self.ci {
+            Some(Ci::Passing) => " · ✅ CI passing",
+            Some(Ci::Failing) => " · ❌ CI failing",
+            Some(Ci::Running) => " · ⏳ CI running",
+            None => "",
+        };
+        let resolved = if self.is_resolved() {
+            " — nothing left to do"
+        } else {
+            ""
+        };
+        format!("🐙 {}: {}{}{}", self.reference.name(), state, ci, resolved)
+    }
+
+    /// Merged or closed, so the notification can go
+    pub fn is_resolved(&self) -> bool {
+        matches!(
+            self.state,
+            State::Merged | State::Closed | State::NotPlanned
+        )
+    }
+}
+
+/// The issue or pull request a GitHub notification links to. Only mail from
+/// github.com counts, so links in other mail don't trigger API calls.
+pub fn reference(email: &Email) -> Option<Reference> {
+    if !email.from.to_lowercase().contains("@github.com") {
+        return None;
+    }
+    let body = email.body_text();
+    body.split(|c: char| c.is_whitespace() || "<>()[]\"'".contains(c))
+        .find_map(parse_url)
+}
+
+/// "https://github.com/owner/repo/pull/12#issuecomment-1" as a reference
+fn parse_url(url: &str) -> Option<Reference> {
+    let path = url.strip_prefix("https://github.com/")?;
+    let mut parts = path.split(['/', '#', '?']);
+    let owner = parts.next().filter(|s| !s.is_empty())?;
+    let repo = parts.next().filter(|s| !s.is_empty())?;
+    let pull = match parts.next()? {
+        "pull" => true,
+        "issues" => false,
+        _ => return None,
+    };
+    let number = parts.next()?.parse().ok()?;
+    Some(Reference {
+        owner: owner.to_string(),
+        repo: repo.to_string(),
+        number,
+        pull,
+    })
+}
+
+#[derive(Deserialize)]
+struct Issue {
+    state: String,
+    #[serde(default)]
+    state_reason: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Pull {
+    state: String,
+    #[serde(default)]
+    merged: bool,
+    #[serde(default)]
+    draft: bool,
+    head: Head,
+}
+
+#[derive(Deserialize)]
+struct Head {
+    sha: String,
+}
+
+#[derive(Deserialize)]
+struct CheckRuns {
+    check_runs: Vec<CheckRun>,
+}
+
+#[derive(Deserialize)]
+struct CheckRun {
+    status: String,
+    #[serde(default)]
+    conclusion: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct CombinedStatus {
+    state: String,
+    total_count: u32,
+}
+
+/// Client for the GitHub REST API, with the token from `github.token` when set
+pub struct GitHub {
+    http: reqwest::Client,
+    token: Option<String>,
+}
+
+impl GitHub {
+    pub fn new(config: &Config) -> Result<Self> {
+        Ok(Self {
+            http: config.network.client()?,
+            token: config.github.token.clone(),
+        })
+    }
+
+    /// The current state of an issue or pull request, with CI for open pull requests
+    pub async fn status(&self, reference: &Reference) -> Result<Status> {
+        let base = format!("{}/repos/{}/{}", API_URL, reference.owner, reference.repo);
+
+        if !reference.pull {
+            let issue: Issue = self
+                .get(&format!("{}/issues/{}", base, reference.number))
+                .await?;
+            let state = match (issue.state.as_str(), issue.state_reason.as_deref()) {
+                ("open", _) => State::Open,
+                (_, Some("not_planned")) => State::NotPlanned,
+                _ => State::Closed,
+            };
+            return Ok(Status {
+                reference: reference.clone(),
+                state,
+                ci: None,
+            });
+        }
+
+        let pull: Pull = self
+            .get(&format!("{}/pulls/{}", base, reference.number))
+            .await?;
+        let state = if pull.merged {
+            State::Merged
+        } else if pull.state != "open" {
+            State::Closed
+        } else if pull.draft {
+            State::Draft
+        } else {
+            State::Open
+        };
+        let ci = if matches!(state, State::Open | State::Draft) {
+            self.ci(&base, &pull.head.sha).await?
+        } else {
+            None
+        };
+        Ok(Status {
+            reference: reference.clone(),
+            state,
+            ci,
+        })
+    }
+
+    /// Check runs (GitHub Actions and apps) and commit statuses (older CI
+    /// services) of a commit, folded into one result
+    async fn ci(&self, base: &str, sha: &str) -> Result<Option<Ci>> {
+        let runs_url = format!("{}/commits/{}/check-runs", base, sha);
+        let status_url = format!("{}/commits/{}/status", base, sha);
+        let (runs, combined) = tokio::try_join!(
+            self.get::<CheckRuns>(&runs_url),
+            self.get::<CombinedStatus>(&status_url),
+        )?;
+
+        let mut results: Vec<Ci> = runs
+            .check_runs
+            .iter()
+            .map(
+                |run| match (run.status.as_str(), run.conclusion.as_deref()) {
+                    ("completed", Some("success" | "neutral" | "skipped")) => Ci::Passing,
+                    ("completed", _) => Ci::Failing,
+                    _ => Ci::Running,
+                },
+            )
+            .collect();
+        if combined.total_count > 0 {
+            results.push(match combined.state.as_str() {
+                "success" => Ci::Passing,
+                "pending" => Ci::Running,
+                _ => Ci::Failing,
+            });
+        }
+
+        Ok([Ci::Failing, Ci::Running, Ci::Passing]
+            .into_iter()
+            .find(|ci| results.contains(ci)))
+    }
+
+    async fn get<T: DeserializeOwned>(&self, url: &str) -> Result<T> {
+        let mut request = self
+            .http
+            .get(url)
+            .header(USER_AGENT, "clinbox")
+            .header(ACCEPT, "application/vnd.github+json");
+        if let Some(token) = &self.token {
+            request = request.header(AUTHORIZATION, format!("Bearer {}", token));
+        }
+
+        let response = request.send().await.context("GitHub request failed")?;
+        let status = response.status();
+        if !status.is_success() {
+            if status == reqwest::StatusCode::NOT_FOUND && self.token.is_none() {
+                bail!("{} (private repository? set github.token)", status);
+            }
+            bail!("{}", status);
+        }
+        response
+            .json()
+            .await
+            .context("Failed to parse the GitHub response")
+    }
+}
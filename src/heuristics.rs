@@ -0,0 +1,206 @@
+use clinbox_core::email::{Category, Email, EmailAnalysis, Priority};
+
+use crate::clusters::is_automated;
+use crate::contacts::parse_addresses;
+use crate::feedback::Feedback;
+use crate::phishing;
+
+/// Shown where the model name goes, and how a guess is told apart from an AI
+/// analysis in the history cache
+pub const MODEL: &str = "heuristics";
+
+/// Fewest decisions on a sender's mail before its history counts
+const MIN_DECISIONS: u32 = 3;
+
+/// Longest summary, taken from the start of the snippet
+const MAX_SUMMARY: usize = 200;
+
+/// Words in the subject or snippet that point to a category, checked in order
+const CATEGORY_WORDS: &[(Category, &[&str])] = &[
+    (
+        Category::Security,
+        &[
+            "password",
+            "security alert",
+            "sign-in",
+            "sign in attempt",
+            "new login",
+            "verification code",
+            "two-factor",
+            "2fa",
+            "contraseña",
+        ],
+    ),
+    (
+        Category::Billing,
+        &[
+            "invoice",
+            "receipt",
+            "payment",
+            "billing",
+            "your order",
+            "subscription renew",
+            "factura",
+            "recibo",
+            "pago",
+        ],
+    ),
+    (
+        Category::Infrastructure,
+        &[
+            "deploy",
+            "outage",
+            "downtime",
+            "incident",
+            "build failed",
+            "disk space",
+            "certificate expir",
+            "monitor",
+        ],
+    ),
+    (Category::Seo, &["search console", "indexing", "backlink"]),
+    (
+        Category::Newsletter,
+        &["newsletter", "digest", "weekly", "unsubscribe", "webinar"],
+    ),
+];
+
+/// Words that make mail urgent
+const URGENT_WORDS: &[&str] = &["urgent", "asap", "immediately", "urgente", "inmediato"];
+
+/// Words that ask me to do something
+const ACTION_WORDS: &[&str] = &[
+    "action required",
+    "please review",
+    "can you",
+    "could you",
+    "deadline",
+    "due date",
+    "expires",
+    "por favor",
+    "puedes",
+];
+
+/// Guess an analysis without the AI, from the sender's history with me, the
+/// bulk-mail headers, Gmail's own labels and keywords in the subject and
+/// snippet. Good enough to sort the queue; the summary is the snippet.
+pub fn classify(email: &Email, feedback: &Feedback) -> EmailAnalysis {
+    let address = parse_addresses(&email.from)
+        .into_iter()
+        .next()
+        .map(|(_, address)| address.to_lowercase())
+        .unwrap_or_default();
+    let text = format!("{} {}", email.subject, email.snippet).to_lowercase();
+    let has = |words: &[&str]| words.iter().any(|w| text.contains(w));
+    let label = |name: &str| email.labels.iter().any(|l| l == name);
+    let mut reasons: Vec<String> = Vec::new();
+
+    let signal = email.bulk_signal();
+    let bulk = signal.as_ref().is_some_and(|s| s.is_newsletter());
+    let automated = signal.is_some() || is_automated(&address);
+    match &signal {
+        Some(signal) => reasons.push(signal.to_string()),
+        None if automated => reasons.push("automated sender".to_string()),
+        None => {}
+    }
+
+    let category = if address.ends_with("@github.com") {
+        Category::Github
+    } else if let Some((category, _)) = CATEGORY_WORDS.iter().find(|(_, words)| has(words)) {
+        reasons.push(format!("mentions {}", category.name()));
+        *category
+    } else if bulk || label("CATEGORY_PROMOTIONS") || label("CATEGORY_FORUMS") {
+        Category::Newsletter
+    } else if automated || label("CATEGORY_UPDATES") || label("CATEGORY_SOCIAL") {
+        Category::Other
+    } else {
+        Category::Personal
+    };
+
+    let failed_auth = email.authentication().is_some_and(|a| a.failed());
+    let mut priority = if label("SPAM") {
+        reasons.push("Gmail marked it spam".to_string());
+        Priority::Spam
+    } else if failed_auth
+        && (matches!(category, Category::Billing | Category::Security)
+            || phishing::looks_sensitive(email, None))
+    {
+        reasons.push("fails DMARC while posing as a bank or account provider".to_string());
+        Priority::Spam
+    } else if has(URGENT_WORDS) && !bulk {
+        reasons.push("urgent wording".to_string());
+        Priority::Urgent
+    } else if category == Category::Security && !bulk {
+        Priority::ActionRequired
+    } else if has(ACTION_WORDS) && !bulk {
+        reasons.push("asks for action".to_string());
+        Priority::ActionRequired
+    } else if category == Category::Newsletter || label("CATEGORY_PROMOTIONS") {
+        Priority::Low
+    } else if category == Category::Personal && label("IMPORTANT") {
+        reasons.push("Gmail marked it important".to_string());
+        Priority::ActionRequired
+    } else {
+        Priority::Informative
+    };
+
+    // What I usually do with the sender's mail outweighs the wording
+    if let Some(record) = feedback.senders.get(&address)
+        && record.total() >= MIN_DECISIONS
+        && priority != Priority::Spam
+    {
+        let total = record.total();
+        let count = |actions: &[&str]| -> u32 {
+            actions
+                .iter()
+                .map(|a| record.actions.get(*a).copied().unwrap_or(0))
+                .sum()
+        };
+        let acted_on = count(&["replied", "reply scheduled", "task created"]);
+        let dismissed = count(&["archived", "deleted", "marked read", "blocked"]);
+        if acted_on * 2 >= total {
+            reasons.push(format!("usually answered ({} of {})", acted_on, total));
+            if priority != Priority::Urgent {
+                priority = Priority::ActionRequired;
+            }
+        } else if dismissed * 10 >= total * 9 {
+            reasons.push(format!("usually dismissed ({} of {})", dismissed, total));
+            priority = Priority::Low;
+        }
+    }
+
+    let summary = match email.snippet.trim() {
+        "" => email.subject.clone(),
+        snippet if snippet.chars().count() > MAX_SUMMARY => {
+            let cut: String = snippet.chars().take(MAX_SUMMARY).collect();
+            format!("{}…", cut.trim_end())
+        }
+        snippet => snippet.to_string(),
+    };
+
+    EmailAnalysis {
+        email_id: email.id.clone(),
+        priority,
+        category,
+        summary,
+        suggested_action: match priority {
+            Priority::Low | Priority::Spam => Some("Archive".to_string()),
+            _ => None,
+        },
+        estimated_time_minutes: match priority {
+            Priority::Urgent | Priority::ActionRequired => 5,
+            Priority::Informative => 2,
+            Priority::Low | Priority::Spam => 1,
+        },
+        confidence: None,
+        reasoning: (!reasons.is_empty()).then(|| reasons.join("; ")),
+        proposed_times: Vec::new(),
+        snooze_until: None,
+        model: Some(MODEL.to_string()),
+    }
+}
+
+/// Whether an analysis is a guess from `classify` rather than the AI's
+pub fn is_guess(analysis: &EmailAnalysis) -> bool {
+    analysis.model.as_deref() == Some(MODEL)
+}
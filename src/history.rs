@@ -0,0 +1,144 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+
+use clinbox_core::config::Config;
+use clinbox_core::email::EmailAnalysis;
+
+/// Entries not seen again within this many days are dropped
+const RETENTION_DAYS: i64 = 90;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SeenAction {
+    Skipped,
+    Viewed,
+}
+
+impl SeenAction {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SeenAction::Skipped => "skipped",
+            SeenAction::Viewed => "viewed",
+        }
+    }
+}
+
+/// A message that was shown in an earlier session but left in place
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub last_action: SeenAction,
+    pub seen_at: DateTime<Utc>,
+    pub times_seen: u32,
+    /// Cached so the same mail isn't re-analyzed every session
+    pub analysis: Option<EmailAnalysis>,
+}
+
+impl HistoryEntry {
+    /// Short description such as "skipped 2 days ago (3×)"
+    pub fn describe(&self) -> String {
+        let times = if self.times_seen > 1 {
+            format!(" ({}×)", self.times_seen)
+        } else {
+            String::new()
+        };
+        format!(
+            "{} {}{}",
+            self.last_action.label(),
+            format_age(self.seen_at),
+            times
+        )
+    }
+}
+
+/// Messages skipped or viewed across runs, keyed by Gmail message id
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct History {
+    pub messages: HashMap<String, HistoryEntry>,
+}
+
+impl History {
+    /// Load history from file
+    pub fn load() -> Result<Self> {
+        let path = Config::history_path()?;
+
+        if path.exists() {
+            let content = fs::read_to_string(&path).context("Failed to read history file")?;
+            let history: History =
+                serde_json::from_str(&content).context("Failed to parse history file")?;
+            Ok(history)
+        } else {
+            Ok(History::default())
+        }
+    }
+
+    /// Save history to file, dropping stale entries
+    pub fn save(&mut self) -> Result<()> {
+        let cutoff = Utc::now() - Duration::days(RETENTION_DAYS);
+        self.messages.retain(|_, entry| entry.seen_at > cutoff);
+
+        let path = Config::history_path()?;
+        fs::create_dir_all(path.parent().unwrap())?;
+
+        let content = serde_json::to_string(self).context("Failed to serialize history")?;
+        fs::write(&path, content).context("Failed to write history file")?;
+
+        Ok(())
+    }
+
+    pub fn get(&self, id: &str) -> Option<&HistoryEntry> {
+        self.messages.get(id)
+    }
+
+    /// Record that a message was left in place
+    pub fn record(
+        &mut self,
+        id: &str,
+        action: SeenAction,
+        analysis: Option<&EmailAnalysis>,
+    ) -> Result<()> {
+        let times_seen = self.messages.get(id).map_or(0, |e| e.times_seen) + 1;
+        let analysis = analysis
+            .cloned()
+            .or_else(|| self.messages.get(id).and_then(|e| e.analysis.clone()));
+
+        self.messages.insert(
+            id.to_string(),
+            HistoryEntry {
+                last_action: action,
+                seen_at: Utc::now(),
+                times_seen,
+                analysis,
+            },
+        );
+        self.save()
+    }
+
+    /// Drop a message once it has been dealt with
+    pub fn forget(&mut self, id: &str) -> Result<()> {
+        if self.messages.remove(id).is_some() {
+            self.save()?;
+        }
+        Ok(())
+    }
+}
+
+/// Human-friendly age such as "3 hours ago"
+pub fn format_age(at: DateTime<Utc>) -> String {
+    let age = Utc::now() - at;
+
+    let (amount, unit) = if age.num_days() > 0 {
+        (age.num_days(), "day")
+    } else if age.num_hours() > 0 {
+        (age.num_hours(), "hour")
+    } else if age.num_minutes() > 0 {
+        (age.num_minutes(), "minute")
+    } else {
+        return "just now".to_string();
+    };
+
+    let plural = if amount == 1 { "" } else { "s" };
+    format!("{} {}{} ago", amount, unit, plural)
+}
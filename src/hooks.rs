@@ -0,0 +1,117 @@
+use anyhow::{Context, Result, bail};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::task::JoinSet;
+
+use clinbox_core::config::HooksConfig;
+use clinbox_core::email::{Email, EmailAnalysis};
+
+use crate::pipe;
+
+/// Hooks that run longer than this are killed
+const HOOK_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookEvent {
+    Archive,
+    Delete,
+    MarkRead,
+    Move,
+    TaskCreated,
+    Reply,
+    Skip,
+    Block,
+    Urgent,
+}
+
+impl HookEvent {
+    /// Name sent in the JSON payload, e.g. "task_created"
+    pub fn name(&self) -> &'static str {
+        match self {
+            HookEvent::Archive => "archive",
+            HookEvent::Delete => "delete",
+            HookEvent::MarkRead => "mark_read",
+            HookEvent::Move => "move",
+            HookEvent::TaskCreated => "task_created",
+            HookEvent::Reply => "reply",
+            HookEvent::Skip => "skip",
+            HookEvent::Block => "block",
+            HookEvent::Urgent => "urgent",
+        }
+    }
+}
+
+/// User commands run on triage events, each receiving the email and its
+/// analysis as JSON on stdin
+pub struct Hooks {
+    config: HooksConfig,
+}
+
+impl Hooks {
+    pub fn new(config: &HooksConfig) -> Self {
+        Self {
+            config: config.clone(),
+        }
+    }
+
+    fn command(&self, event: HookEvent) -> Option<&str> {
+        let command = match event {
+            HookEvent::Archive => &self.config.on_archive,
+            HookEvent::Delete => &self.config.on_delete,
+            HookEvent::MarkRead => &self.config.on_mark_read,
+            HookEvent::Move => &self.config.on_move,
+            HookEvent::TaskCreated => &self.config.on_task_created,
+            HookEvent::Reply => &self.config.on_reply,
+            HookEvent::Skip => &self.config.on_skip,
+            HookEvent::Block => &self.config.on_block,
+            HookEvent::Urgent => &self.config.on_urgent,
+        };
+        command.as_deref().filter(|c| !c.trim().is_empty())
+    }
+
+    /// Run the event's hook, if configured, alongside the other background work
+    pub fn fire(
+        &self,
+        background: &mut JoinSet<Result<()>>,
+        event: HookEvent,
+        email: &Email,
+        analysis: Option<&EmailAnalysis>,
+    ) {
+        let Some(command) = self.command(event) else {
+            return;
+        };
+
+        let payload = serde_json::json!({
+            "event": event.name(),
+            "email": email,
+            "analysis": analysis,
+        });
+        let command = command.to_string();
+        background.spawn(async move { run(&command, event, payload.to_string()).await });
+    }
+}
+
+async fn run(command: &str, event: HookEvent, payload: String) -> Result<()> {
+    let mut child = tokio::process::Command::from(pipe::shell(command))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .with_context(|| format!("Failed to run on_{} hook", event.name()))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // A hook that ignores its input may close the pipe early
+        let _ = stdin.write_all(payload.as_bytes()).await;
+    }
+
+    let status = match tokio::time::timeout(HOOK_TIMEOUT, child.wait()).await {
+        Ok(status) => status?,
+        Err(_) => bail!("on_{} hook timed out", event.name()),
+    };
+    if !status.success() {
+        bail!("on_{} hook exited with {}", event.name(), status);
+    }
+    Ok(())
+}
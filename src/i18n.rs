@@ -0,0 +1,1024 @@
+//! Interface strings in the user's locale (`ui.locale`)
+//!
+//! Strings are written in English where they are used and looked up in a
+//! catalog here; anything missing from a catalog is shown in English. With
+//! `ui.ascii`, emoji are swapped for text badges on the way out.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::{LazyLock, Mutex, OnceLock};
+
+use clinbox_core::config::Locale;
+
+static LOCALE: OnceLock<Locale> = OnceLock::new();
+static ASCII: OnceLock<bool> = OnceLock::new();
+
+/// Set the interface language and whether to avoid emoji; later calls are ignored
+pub fn init(locale: Locale, ascii: bool) {
+    let _ = LOCALE.set(locale);
+    let _ = ASCII.set(ascii);
+}
+
+/// Whether emoji are replaced with text badges
+pub fn ascii() -> bool {
+    ASCII.get().copied().unwrap_or_default()
+}
+
+/// `text` in the current locale
+pub fn t(text: &'static str) -> &'static str {
+    let text = translate(text);
+    if !ascii() {
+        return text;
+    }
+    // Badged copies of the (finite) catalog strings, made once each
+    static PLAIN: LazyLock<Mutex<HashMap<&'static str, &'static str>>> =
+        LazyLock::new(Mutex::default);
+    let mut cache = PLAIN.lock().unwrap_or_else(|e| e.into_inner());
+    cache
+        .entry(text)
+        .or_insert_with(|| Box::leak(plain(text).into_owned().into_boxed_str()))
+}
+
+/// Translate `template` and fill its `{}` placeholders with `args`, in order
+pub fn tr(template: &'static str, args: &[&dyn Display]) -> String {
+    let mut parts = translate(template).split("{}");
+    let mut out = parts.next().unwrap_or_default().to_string();
+    for (i, part) in parts.enumerate() {
+        if let Some(arg) = args.get(i) {
+            out.push_str(&arg.to_string());
+        }
+        out.push_str(part);
+    }
+    if ascii() {
+        plain(&out).into_owned()
+    } else {
+        out
+    }
+}
+
+/// `text` with emoji replaced by badges ("✅ Archived" -> "[OK] Archived") in
+/// ASCII mode; unchanged otherwise
+pub fn plain(text: &str) -> Cow<'_, str> {
+    if !ascii() || text.is_ascii() {
+        return Cow::Borrowed(text);
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        if let Some((emoji, word)) = BADGES.iter().find(|(emoji, _)| rest.starts_with(emoji)) {
+            let word = translate(word);
+            out.push_str(&format!("[{}]", word));
+            rest = rest[emoji.len()..].trim_start_matches(VARIATION);
+            // "🔴 URGENT" would read "[URGENT] URGENT"
+            if let Some(after) = rest.trim_start().strip_prefix(word) {
+                rest = after;
+            }
+        } else if let Some((emoji, symbol)) =
+            SYMBOLS.iter().find(|(emoji, _)| rest.starts_with(emoji))
+        {
+            out.push_str(symbol);
+            rest = rest[emoji.len()..].trim_start_matches(VARIATION);
+            if symbol.is_empty() {
+                rest = rest.trim_start_matches(' ');
+            }
+        } else if is_emoji(c) {
+            rest = rest[c.len_utf8()..]
+                .trim_start_matches(VARIATION)
+                .trim_start_matches(' ');
+        } else {
+            out.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    Cow::Owned(out)
+}
+
+fn translate(text: &'static str) -> &'static str {
+    let catalog = match LOCALE.get().copied().unwrap_or_default() {
+        Locale::En => return text,
+        Locale::Es => ES,
+    };
+    catalog
+        .iter()
+        .find(|(en, _)| *en == text)
+        .map_or(text, |(_, translated)| translated)
+}
+
+/// Emoji presentation selector and zero-width joiner, left over after an emoji
+const VARIATION: &[char] = &['\u{fe0f}', '\u{200d}'];
+
+/// Pictographs that don't render without an emoji font; other symbols
+/// (arrows, box drawing, accents) are left alone
+fn is_emoji(c: char) -> bool {
+    matches!(c as u32, 0x2300..=0x23FF | 0x2600..=0x27BF | 0x2B00..=0x2BFF | 0x1F000..=0x1FAFF)
+}
+
+/// Emoji that carry meaning, shown as "[WORD]"
+const BADGES: &[(&str, &str)] = &[
+    ("🔴", "URGENT"),
+    ("🟡", "ACTION"),
+    ("🔵", "INFO"),
+    ("⚪", "LOW"),
+    ("✅", "OK"),
+    ("❌", "ERROR"),
+    ("⚠", "WARN"),
+    ("⛔", "BLOCKED"),
+    ("🚫", "SPAM"),
+    ("🗑", "DEL"),
+    ("🔥", "DEL"),
+    ("⭐", "VIP"),
+    ("🤖", "AI"),
+    ("⏳", "WAIT"),
+    ("📎", "FILES"),
+    ("💭", "WHY"),
+    ("🕒", "LATER"),
+];
+
+/// Emoji with a plain-text stand-in
+const SYMBOLS: &[(&str, &str)] = &[("☐", "[ ]"), ("➡", "->"), ("▶", ">"), ("↩", "")];
+
+/// Spanish, keyed by the English text
+const ES: &[(&str, &str)] = &[
+    // Screens
+    (
+        "Terminal too small ({}x{}). Resize to at least {}x{}.",
+        "Terminal demasiado pequeña ({}x{}). Agrándala a {}x{} como mínimo.",
+    ),
+    ("{} (Esc to cancel)", "{} (Esc para cancelar)"),
+    (
+        "⏹️ Interrupted, finishing up…",
+        "⏹️ Interrumpido, terminando…",
+    ),
+    (
+        "⏳ Gmail is throttling, retrying in {}s…",
+        "⏳ Gmail está limitando las peticiones, reintento en {}s…",
+    ),
+    ("From:", "De:"),
+    ("To:", "Para:"),
+    ("Date:", "Fecha:"),
+    ("Subject:", "Asunto:"),
+    ("Attachments:", "Adjuntos:"),
+    ("🤖 AI Analysis", "🤖 Análisis IA"),
+    ("💤 Snoozed until {}", "💤 Pospuesto hasta {}"),
+    ("🤖 Planning...", "🤖 Planificando..."),
+    ("🤖 Thinking...", "🤖 Pensando..."),
+    ("⚙️ Running...", "⚙️ Ejecutando..."),
+    ("⚠️  Stopped at {}", "⚠️  Detenido en {}"),
+    ("✅ Done: {}", "✅ Hecho: {}"),
+    (
+        "🤷 Couldn't turn that into actions",
+        "🤷 No se pudo convertir en acciones",
+    ),
+    (
+        "💤 {} snoozed messages back in the inbox",
+        "💤 {} correos pospuestos han vuelto a la bandeja de entrada",
+    ),
+    ("🔄 Analyzing email...", "🔄 Analizando el correo..."),
+    ("🔄 Analyzing...", "🔄 Analizando..."),
+    ("Preview", "Vista previa"),
+    ("New Task", "Nueva tarea"),
+    (
+        "Creating task from email:\n\nSubject: {}\n\nTask title: {}\n\nPress [Enter] to confirm, [Esc] to cancel",
+        "Creando tarea desde el correo:\n\nAsunto: {}\n\nTítulo de la tarea: {}\n\nPulsa [Enter] para confirmar, [Esc] para cancelar",
+    ),
+    (
+        "[Enter] confirm, [Esc] cancel",
+        "[Enter] confirmar, [Esc] cancelar",
+    ),
+    (
+        "[Tab] complete, [Enter] confirm, [Esc] cancel",
+        "[Tab] completar, [Enter] confirmar, [Esc] cancelar",
+    ),
+    ("Label: {}▏", "Etiqueta: {}▏"),
+    ("No recent labels yet", "Aún no hay etiquetas recientes"),
+    ("Recent:", "Recientes:"),
+    (
+        "Type a label or pick a recent one — [Enter] move, [Esc] cancel",
+        "Escribe una etiqueta o elige una reciente — [Enter] mover, [Esc] cancelar",
+    ),
+    ("Move to Label", "Mover a etiqueta"),
+    (
+        "Full Email - [h] headers, any other key to go back",
+        "Correo completo - [h] cabeceras, cualquier otra tecla para volver",
+    ),
+    (
+        "Full Email - [h] headers, [a] attachments, any other key to go back",
+        "Correo completo - [h] cabeceras, [a] adjuntos, cualquier otra tecla para volver",
+    ),
+    (
+        "Attachments - number to view, any other key to go back",
+        "Adjuntos - número para ver, cualquier otra tecla para volver",
+    ),
+    ("{} - [q] back", "{} - [q] volver"),
+    ("📥 Downloading {}...", "📥 Descargando {}..."),
+    ("📂 Saved to {} and opened", "📂 Guardado en {} y abierto"),
+    (
+        "⚠️  Saved to {}, not opened",
+        "⚠️  Guardado en {}, sin abrir",
+    ),
+    ("📥 Fetching headers...", "📥 Obteniendo cabeceras..."),
+    (
+        "🔑 Code {} — [{}] to copy",
+        "🔑 Código {} — [{}] para copiar",
+    ),
+    (
+        "🔑 No verification code found",
+        "🔑 No se encontró ningún código de verificación",
+    ),
+    ("📋 Copied {}", "📋 Copiado {}"),
+    (
+        "📋 Asked the terminal to copy {} (needs OSC 52 support; paste to check)",
+        "📋 Se pidió a la terminal copiar {} (requiere soporte de OSC 52; pega para comprobarlo)",
+    ),
+    ("❌ Couldn't copy: {}", "❌ No se pudo copiar: {}"),
+    (
+        "Links - any key to go back",
+        "Enlaces - cualquier tecla para volver",
+    ),
+    ("🔗 Expanding links...", "🔗 Expandiendo enlaces..."),
+    (
+        "🔗 No links in this email",
+        "🔗 Este correo no tiene enlaces",
+    ),
+    ("    Couldn't expand: {}", "    No se pudo expandir: {}"),
+    (
+        "⚠️  Shows {} but goes to {}",
+        "⚠️  Muestra {} pero lleva a {}",
+    ),
+    (
+        "❌ Failed to fetch headers: {}",
+        "❌ No se pudieron obtener las cabeceras: {}",
+    ),
+    ("📊 Session Summary", "📊 Resumen de la sesión"),
+    ("Total emails processed: {}", "Correos procesados: {}"),
+    ("✅ Archived: {}", "✅ Archivados: {}"),
+    ("🗑️  Deleted: {}", "🗑️  Eliminados: {}"),
+    ("📝 Tasks created: {}", "📝 Tareas creadas: {}"),
+    ("💬 Replied: {}", "💬 Respondidos: {}"),
+    ("🏷️  Moved to label: {}", "🏷️  Movidos a etiqueta: {}"),
+    ("👁️  Marked read: {}", "👁️  Marcados como leídos: {}"),
+    ("📓 Summaries saved: {}", "📓 Resúmenes guardados: {}"),
+    (
+        "📚 Saved to read later: {}",
+        "📚 Guardados para leer después: {}",
+    ),
+    ("⏭️  Skipped: {}", "⏭️  Omitidos: {}"),
+    ("Press any key to exit", "Pulsa cualquier tecla para salir"),
+    (
+        "📝 Reply Draft (AI Generated)",
+        "📝 Borrador de respuesta (IA)",
+    ),
+    ("Draft", "Borrador"),
+    (
+        "📝 Article Summary (AI Generated)",
+        "📝 Resumen del artículo (IA)",
+    ),
+    ("Summary", "Resumen"),
+    ("Key Takeaways", "Puntos clave"),
+    (
+        "[Enter] Save to Notion  [Esc] Cancel",
+        "[Enter] Guardar en Notion  [Esc] Cancelar",
+    ),
+    (
+        "⏳ Waiting for a reply ({} threads)",
+        "⏳ Esperando respuesta ({} hilos)",
+    ),
+    (
+        "[↑/↓] select  [n]udge  [o]pen  [q]uit",
+        "[↑/↓] elegir  [n] recordar  [o] abrir  [q] salir",
+    ),
+    ("🗑️  Trash ({} messages)", "🗑️  Papelera ({} mensajes)"),
+    (
+        "[↑/↓] select  [r]estore  [D]elete forever  [v]iew  [o]pen  [q]uit",
+        "[↑/↓] elegir  [r] restaurar  [D] eliminar para siempre  [v] ver  [o] abrir  [q] salir",
+    ),
+    ("🤔 Looks legit: {}", "🤔 Parece legítimo: {}"),
+    ("🚫 Spam: {}", "🚫 Spam: {}"),
+    ("❔ Not checked", "❔ Sin revisar"),
+    ("🚫 Spam ({} messages)", "🚫 Spam ({} mensajes)"),
+    (
+        "[↑/↓] select  [n]ot spam  [D]elete forever  [v]iew  [o]pen  [q]uit",
+        "[↑/↓] elegir  [n] no es spam  [D] eliminar para siempre  [v] ver  [o] abrir  [q] salir",
+    ),
+    ("(no subject)", "(sin asunto)"),
+    ("(empty)", "(vacío)"),
+    ("✏️  Drafts ({})", "✏️  Borradores ({})"),
+    ("📚 Read later ({})", "📚 Para leer después ({})"),
+    (
+        "[↑/↓] select  [r]ead  [d]one  [o]pen in Gmail  [q]uit",
+        "[↑/↓] elegir  [r] leer  [d] hecho  [o] abrir en Gmail  [q] salir",
+    ),
+    ("{} min", "{} min"),
+    (
+        "✅ Done, removed from the list",
+        "✅ Hecho, quitado de la lista",
+    ),
+    (
+        "[↑/↓] select  [e]dit  [s]end  [D]iscard  [v]iew  [o]pen  [q]uit",
+        "[↑/↓] elegir  [e] editar  [s] enviar  [D] descartar  [v] ver  [o] abrir  [q] salir",
+    ),
+    ("📝 Tasks ({})", "📝 Tareas ({})"),
+    ("📭 Nothing here", "📭 No hay nada"),
+    ("📊 Stats", "📊 Estadísticas"),
+    (
+        "📬 {} messages, {} unread, {} analyzed",
+        "📬 {} mensajes, {} sin leer, {} analizados",
+    ),
+    (
+        "✅ {} archived  🗑️  {} deleted  👁️  {} read",
+        "✅ {} archivados  🗑️  {} eliminados  👁️  {} leídos",
+    ),
+    (
+        "📝 {} tasks  💬 {} replied",
+        "📝 {} tareas  💬 {} respondidos",
+    ),
+    (
+        "[Tab] pane  [↑/↓] select  [Enter] view  [/] search  [{}] export  [{}] open  [R] refresh  [{}] quit",
+        "[Tab] panel  [↑/↓] elegir  [Enter] ver  [/] buscar  [{}] exportar  [{}] abrir  [R] actualizar  [{}] salir",
+    ),
+    (
+        "[Tab] pane  [↑/↓] select  [Enter] view  [{}] archive  [{}] delete  [{}] mark read  [{}] task  [{}] reply  [{}] open  [R] refresh  [{}] quit",
+        "[Tab] panel  [↑/↓] elegir  [Enter] ver  [{}] archivar  [{}] eliminar  [{}] marcar leído  [{}] tarea  [{}] responder  [{}] abrir  [R] actualizar  [{}] salir",
+    ),
+    (
+        "[Tab] pane  [↑/↓] scroll  [{}] archive  [{}] reply  [{}] open  [{}] quit",
+        "[Tab] panel  [↑/↓] desplazar  [{}] archivar  [{}] responder  [{}] abrir  [{}] salir",
+    ),
+    (
+        "[Tab] pane  [↑/↓] select  [Enter] complete  [{}] delete  [R] refresh  [{}] quit",
+        "[Tab] panel  [↑/↓] elegir  [Enter] completar  [{}] eliminar  [R] actualizar  [{}] salir",
+    ),
+    // Messages
+    ("AI analysis failed: {}", "Falló el análisis IA: {}"),
+    ("Archive email? [y/n]", "¿Archivar el correo? [y/n]"),
+    (
+        "Block {} and move this email to trash?\n\nFuture mail from this sender is trashed on fetch. [y/n]",
+        "¿Bloquear a {} y mover este correo a la papelera?\n\nEl correo futuro de este remitente irá a la papelera al descargarlo. [y/n]",
+    ),
+    (
+        "Configuration incomplete. Run 'clinbox status' for details.",
+        "Configuración incompleta. Ejecuta 'clinbox status' para ver los detalles.",
+    ),
+    (
+        "Connecting to Gmail ({})...",
+        "Conectando con Gmail ({})...",
+    ),
+    (
+        "Calendar check failed: {}",
+        "Falló la consulta del calendario: {}",
+    ),
+    ("GitHub lookup failed: {}", "Falló la consulta a GitHub: {}"),
+    (
+        "Contact lookup failed: {}",
+        "Falló la búsqueda del contacto: {}",
+    ),
+    ("⚠️ Conflicts with {}", "⚠️ Choca con {}"),
+    (
+        "Delete \"{}\" for good? This cannot be undone. [y/n]",
+        "¿Eliminar \"{}\" para siempre? No se puede deshacer. [y/n]",
+    ),
+    (
+        "Discard \"{}\"? Drafts don't go to the trash. [y/n]",
+        "¿Descartar \"{}\"? Los borradores no pasan por la papelera. [y/n]",
+    ),
+    (
+        "Export as [e]ml, [h]tml or [p]df? (Esc cancels)",
+        "¿Exportar como [e]ml, [h]tml o [p]df? (Esc cancela)",
+    ),
+    (
+        "Finishing pending changes...",
+        "Terminando los cambios pendientes...",
+    ),
+    ("Generated by Clinbox", "Generado por Clinbox"),
+    (
+        "Gmail search for the message list",
+        "Búsqueda de Gmail para la lista de mensajes",
+    ),
+    (
+        "No sender address to block",
+        "No hay dirección de remitente que bloquear",
+    ),
+    (
+        "No sender address to sweep",
+        "No hay dirección de remitente que archivar",
+    ),
+    (
+        "No inbox mail from {}",
+        "No hay correo de {} en la bandeja de entrada",
+    ),
+    (
+        "🔍 Looking for mail from {}...",
+        "🔍 Buscando correo de {}...",
+    ),
+    (
+        "Archive all {} inbox emails from {}? [y/n]",
+        "¿Archivar los {} correos de {} en la bandeja de entrada? [y/n]",
+    ),
+    ("Nothing to undo", "Nada que deshacer"),
+    ("Pipe to Command", "Canalizar a un comando"),
+    ("Search", "Buscar"),
+    ("Send Later", "Enviar más tarde"),
+    (
+        "Addresses, separated by commas:",
+        "Direcciones, separadas por comas:",
+    ),
+    ("Send \"{}\" to {}? [y/n]", "¿Enviar \"{}\" a {}? [y/n]"),
+    (
+        "Send at (2h, 17:30, tomorrow 9:00, 2024-07-01 09:00):",
+        "Enviar a las (2h, 17:30, tomorrow 9:00, 2024-07-01 09:00):",
+    ),
+    (
+        "Ask anything about this email, e.g. what exactly they're asking for",
+        "Pregunta lo que quieras sobre este correo, p. ej. qué te piden exactamente",
+    ),
+    ("Command", "Comando"),
+    ("[Enter] ask, [Esc] back", "[Enter] preguntar, [Esc] volver"),
+    (
+        "What to do (e.g. archive all from amazon, label this receipts and skip):",
+        "Qué hacer (p. ej. archive all from amazon, label this receipts and skip):",
+    ),
+    (
+        "Run this plan?\n\n{}\n\n[y/n]",
+        "¿Ejecutar este plan?\n\n{}\n\n[y/n]",
+    ),
+    ("{} emails", "{} correos"),
+    ("Snooze", "Posponer"),
+    (
+        "Snooze until (2h, tomorrow 9:00, 2024-07-01):",
+        "Posponer hasta (2h, tomorrow 9:00, 2024-07-01):",
+    ),
+    (
+        "The body goes to stdin; {} are replaced with the email's fields",
+        "El cuerpo va a stdin; {} se sustituyen por los campos del correo",
+    ),
+    (
+        "\nPress Enter to return to clinbox...",
+        "\nPulsa Enter para volver a clinbox...",
+    ),
+    (
+        "{}\n\nOpen in browser anyway? [y/n]",
+        "{}\n\n¿Abrir en el navegador de todos modos? [y/n]",
+    ),
+    ("{}⏭️ Skipped", "{}⏭️ Omitido"),
+    (
+        "{}✅ Archived — press {} to undo",
+        "{}✅ Archivado — pulsa {} para deshacer",
+    ),
+    (
+        "{}👁️ Marked read — press {} to undo",
+        "{}👁️ Marcado como leído — pulsa {} para deshacer",
+    ),
+    (
+        "{}🗑️ Deleted — press {} to undo",
+        "{}🗑️ Eliminado — pulsa {} para deshacer",
+    ),
+    ("↩️ Undone", "↩️ Deshecho"),
+    (
+        "▶️  Resuming session from {} ({} of {} emails left)\n",
+        "▶️  Retomando la sesión de {} (quedan {} de {} correos)\n",
+    ),
+    ("♻️  Restored", "♻️  Restaurado"),
+    ("♻️  Restoring...", "♻️  Restaurando..."),
+    (
+        "⚠️  Failed to label {} emails as {}: {}",
+        "⚠️  No se pudieron etiquetar {} correos como {}: {}",
+    ),
+    ("⛔ Blocked {} & deleted", "⛔ {} bloqueado y eliminado"),
+    (
+        "⛔ Trashed {} emails from blocked senders",
+        "⛔ {} correos de remitentes bloqueados a la papelera",
+    ),
+    ("✅ Archived", "✅ Archivado"),
+    ("✅ Follow-up sent", "✅ Recordatorio enviado"),
+    (
+        "✅ Reply sent & archived",
+        "✅ Respuesta enviada y archivado",
+    ),
+    ("✅ Sent", "✅ Enviado"),
+    ("✅ Task completed", "✅ Tarea completada"),
+    ("✏️  Loading drafts...", "✏️  Cargando borradores..."),
+    ("✏️  No changes", "✏️  Sin cambios"),
+    (
+        "❌ Background task failed: {}",
+        "❌ Falló una tarea en segundo plano: {}",
+    ),
+    ("❌ Export failed: {}", "❌ Falló la exportación: {}"),
+    (
+        "❌ Failed to generate draft: {}",
+        "❌ No se pudo generar el borrador: {}",
+    ),
+    (
+        "❌ Failed to generate summary: {}",
+        "❌ No se pudo generar el resumen: {}",
+    ),
+    ("❌ Failed to save: {}", "❌ No se pudo guardar: {}"),
+    (
+        "📮 Couldn't send ({}); reply queued in the outbox",
+        "📮 No se pudo enviar ({}); respuesta guardada en la bandeja de salida",
+    ),
+    (
+        "❌ Failed to send reply: {}",
+        "❌ No se pudo enviar la respuesta: {}",
+    ),
+    (
+        "📤 Sent queued reply to {} ({})",
+        "📤 Respuesta en cola enviada a {} ({})",
+    ),
+    (
+        "❌ Failed to send queued reply to {}: {} (kept in the outbox)",
+        "❌ No se pudo enviar la respuesta en cola a {}: {} (sigue en la bandeja de salida)",
+    ),
+    (
+        "❌ Failed to send queued reply to {}: {} (held in the outbox; retry with `clinbox outbox flush`)",
+        "❌ No se pudo enviar la respuesta en cola a {}: {} (retenida en la bandeja de salida; reinténtalo con `clinbox outbox flush`)",
+    ),
+    ("Nothing due to send.", "Nada pendiente de enviar."),
+    ("⚠️  Not sent yet: {}", "⚠️  Aún sin enviar: {}"),
+    (
+        "⏸  Held after {} failed attempts; `clinbox outbox flush` tries again",
+        "⏸  Retenida tras {} intentos fallidos; `clinbox outbox flush` lo vuelve a intentar",
+    ),
+    ("❌ Re-analysis failed: {}", "❌ Falló el reanálisis: {}"),
+    ("⭐ VIP: {} — {}", "⭐ VIP: {} — {}"),
+    ("🌐 Opened in browser", "🌐 Abierto en el navegador"),
+    ("🎉 No drafts.", "🎉 No hay borradores."),
+    (
+        "This draft has an HTML version, which editing here replaces with plain text (attachments are kept). Edit anyway? [y/n]",
+        "Este borrador tiene una versión HTML, que al editarlo aquí se sustituye por texto plano (los adjuntos se conservan). ¿Editarlo de todos modos? [y/n]",
+    ),
+    (
+        "📚 Nothing saved to read later.",
+        "📚 No hay nada guardado para leer después.",
+    ),
+    (
+        "🎉 Nobody owes you a reply.",
+        "🎉 Nadie te debe una respuesta.",
+    ),
+    (
+        "🎉 Nothing urgent or waiting on you.",
+        "🎉 Nada urgente ni pendiente de ti.",
+    ),
+    (
+        "🎉 Spam folder is empty.",
+        "🎉 La carpeta de spam está vacía.",
+    ),
+    ("🎉 Trash is empty.", "🎉 La papelera está vacía."),
+    (
+        "🏷️  Labeled {} emails left for later",
+        "🏷️  {} correos etiquetados para más tarde",
+    ),
+    ("🏷️ Looking up label...", "🏷️ Buscando la etiqueta..."),
+    (
+        "🏷️ Moved to {} — press {} to undo",
+        "🏷️ Movido a {} — pulsa {} para deshacer",
+    ),
+    (
+        "👀 Browsing is read-only",
+        "👀 La navegación es de solo lectura",
+    ),
+    ("👁️ Marked read", "👁️ Marcado como leído"),
+    (
+        "💡 Discarding unfinished session from {} (use --resume to continue it)",
+        "💡 Descartando la sesión sin terminar de {} (usa --resume para continuarla)",
+    ),
+    ("💾 Draft saved", "💾 Borrador guardado"),
+    ("💾 Exporting...", "💾 Exportando..."),
+    ("💾 Saved {}", "💾 Guardado {}"),
+    ("💾 Saving draft...", "💾 Guardando el borrador..."),
+    (
+        "📝 Saved: {}\n\nOpen file? [y/n]",
+        "📝 Guardado: {}\n\n¿Abrir el archivo? [y/n]",
+    ),
+    (
+        "📝 Task created & email archived",
+        "📝 Tarea creada y correo archivado",
+    ),
+    (
+        "{}📚 Saved to read later — press {} to undo",
+        "{}📚 Guardado para leer después — pulsa {} para deshacer",
+    ),
+    (
+        "📤 Checking sent mail from the last {} days...",
+        "📤 Revisando el correo enviado de los últimos {} días...",
+    ),
+    ("📤 Piped", "📤 Canalizado"),
+    ("📤 Sending...", "📤 Enviando..."),
+    ("📥 Fetching '{}'...", "📥 Descargando '{}'..."),
+    ("📥 Loading message...", "📥 Cargando mensaje..."),
+    ("📦 Archiving {} emails...", "📦 Archivando {} correos..."),
+    ("✅ Archived {} emails", "✅ {} correos archivados"),
+    (
+        "⚠️  Archived {}; {} failed and stay in the queue: {}",
+        "⚠️  Archivados {}; {} fallaron y siguen en la cola: {}",
+    ),
+    (
+        "📚 Group {} of {}: {} similar emails from {} — {}",
+        "📚 Grupo {} de {}: {} correos parecidos de {} — {}",
+    ),
+    (
+        "[a]rchive all  [t]riage individually  [↑/↓] select  [v]iew  [o]pen list  [q] no more groups",
+        "[a]rchivar todo  [t]riar uno a uno  [↑/↓] elegir  [v]er  [o] abrir lista  [q] sin más grupos",
+    ),
+    (
+        "📥 Fetching latest {} emails{}...",
+        "📥 Descargando los últimos {} correos{}...",
+    ),
+    (
+        "📥 Fetching unread emails{}...",
+        "📥 Descargando correos sin leer{}...",
+    ),
+    ("📥 Loading message...", "📥 Cargando el mensaje..."),
+    ("📥 Moved to inbox", "📥 Movido a la bandeja de entrada"),
+    (
+        "📥 Moving to inbox...",
+        "📥 Moviendo a la bandeja de entrada...",
+    ),
+    ("📥 Refreshing...", "📥 Actualizando..."),
+    (
+        "📧 Found {} unread emails. Starting triage...\n",
+        "📧 {} correos sin leer. Empezando la clasificación...\n",
+    ),
+    (
+        "📧 Opened in browser for editing",
+        "📧 Abierto en el navegador para editar",
+    ),
+    (
+        "📭 No emails match the filters.",
+        "📭 Ningún correo coincide con los filtros.",
+    ),
+    (
+        "📭 No unread emails. Inbox zero! 🎉",
+        "📭 No hay correos sin leer. ¡Bandeja a cero! 🎉",
+    ),
+    ("🔄 Still analyzing", "🔄 Aún analizando"),
+    ("🧮 heuristic mode", "🧮 modo heurístico"),
+    ("⏱ {} left", "⏱ quedan {}"),
+    ("⏱ Time's up", "⏱ Se acabó el tiempo"),
+    ("🎯 Target reached", "🎯 Objetivo alcanzado"),
+    (
+        "⚠️  The AI keeps failing ({}); switching to heuristic mode",
+        "⚠️  La IA sigue fallando ({}); se pasa al modo heurístico",
+    ),
+    (
+        "⚠️  AI analysis failed for {} emails; guessed with heuristics",
+        "⚠️  El análisis IA falló en {} correos; se estimaron con heurísticas",
+    ),
+    (
+        "🚫 AI is off for this session (--no-ai)",
+        "🚫 La IA está desactivada en esta sesión (--no-ai)",
+    ),
+    ("🔍 Searching...", "🔍 Buscando..."),
+    ("🔥 Deleted for good", "🔥 Eliminado para siempre"),
+    ("🔥 Deleting...", "🔥 Eliminando..."),
+    ("🕒 Follow-up scheduled", "🕒 Recordatorio programado"),
+    (
+        "🕒 Reply scheduled for {} & archived",
+        "🕒 Respuesta programada para {} y archivado",
+    ),
+    ("🗑️  Discarding...", "🗑️  Descartando..."),
+    ("🗑️  Draft discarded", "🗑️  Borrador descartado"),
+    ("🗑️  Loading trash...", "🗑️  Cargando la papelera..."),
+    ("🗑️ Deleted", "🗑️ Eliminado"),
+    ("🗑️ Deleting...", "🗑️ Eliminando..."),
+    (
+        "🗑️  Moved {} messages ({}) to trash; the space is freed when the trash is emptied (after 30 days, or from clinbox trash)",
+        "🗑️  {} mensajes ({}) movidos a la papelera; el espacio se libera al vaciarla (a los 30 días, o desde clinbox trash)",
+    ),
+    (
+        "Move \"{}\" ({}) to trash? [y/n]",
+        "¿Mover \"{}\" ({}) a la papelera? [y/n]",
+    ),
+    ("No attachments to save", "No hay adjuntos que guardar"),
+    ("{} (no limit)", "{} (sin límite)"),
+    ("{} of {} ({}%)", "{} de {} ({}%)"),
+    ("⚠️ nearly full", "⚠️ casi lleno"),
+    (
+        "No attachments; the size is in the body",
+        "Sin adjuntos; el tamaño está en el cuerpo",
+    ),
+    (
+        "[↑/↓] select  [x] save attachments  [a]rchive  [d]elete  [v]iew  [o]pen  [q]uit",
+        "[↑/↓] elegir  [x] guardar adjuntos  [a] archivar  [d] eliminar  [v] ver  [o] abrir  [q] salir",
+    ),
+    (
+        "🎉 No messages larger than {}.",
+        "🎉 No hay mensajes de más de {}.",
+    ),
+    (
+        "💾 Large messages ({}, {} in all)",
+        "💾 Mensajes grandes ({}, {} en total)",
+    ),
+    (
+        "💾 Looking for messages larger than {}...",
+        "💾 Buscando mensajes de más de {}...",
+    ),
+    (
+        "💾 Saved {} attachments to {}",
+        "💾 {} adjuntos guardados en {}",
+    ),
+    ("💾 Saving attachments...", "💾 Guardando adjuntos..."),
+    (
+        "📦 Archived (it still counts towards storage)",
+        "📦 Archivado (sigue ocupando espacio)",
+    ),
+    ("📦 Archiving...", "📦 Archivando..."),
+    ("🗑️ Task deleted", "🗑️ Tarea eliminada"),
+    ("🚫 Loading spam...", "🚫 Cargando el spam..."),
+    (
+        "🤖 Analyzing {} emails to apply filters...",
+        "🤖 Analizando {} correos para aplicar los filtros...",
+    ),
+    (
+        "🤖 Drafting a follow-up...",
+        "🤖 Redactando un recordatorio...",
+    ),
+    (
+        "🤖 Generating comprehensive summary...",
+        "🤖 Generando el resumen completo...",
+    ),
+    (
+        "🤖 Generating reply draft...",
+        "🤖 Generando el borrador de respuesta...",
+    ),
+    ("🧠 Re-analyzed", "🧠 Reanalizado"),
+    ("🧠 Re-analyzing with {}...", "🧠 Reanalizando con {}..."),
+    // Priorities and categories
+    ("URGENT", "URGENTE"),
+    ("ACTION", "ACCIÓN"),
+    ("INFO", "INFO"),
+    ("LOW", "BAJA"),
+    ("Billing", "Facturación"),
+    ("Security", "Seguridad"),
+    ("Other", "Otro"),
+    // Key footers
+    ("archive", "archivar"),
+    ("delete", "eliminar"),
+    ("mark read", "marcar leído"),
+    ("label", "etiquetar"),
+    ("snooze", "posponer"),
+    ("command", "comando"),
+    ("ask", "preguntar"),
+    ("task", "tarea"),
+    ("reply", "responder"),
+    ("note", "nota"),
+    ("open", "abrir"),
+    ("view", "ver"),
+    ("links", "enlaces"),
+    ("copy code", "copiar código"),
+    ("expense", "gasto"),
+    ("Expense", "Gasto"),
+    (
+        "Vendor; amount; currency; date:",
+        "Proveedor; importe; moneda; fecha:",
+    ),
+    (
+        "❌ Couldn't read the invoice: {}",
+        "❌ No se pudo leer la factura: {}",
+    ),
+    ("🧾 Reading the invoice...", "🧾 Leyendo la factura..."),
+    (
+        "🧾 This email isn't filed as billing. Read it as an invoice anyway? [y/n]",
+        "🧾 Este correo no está clasificado como facturación. ¿Leerlo como factura de todos modos? [y/n]",
+    ),
+    (
+        "🧾 Added to the ledger: {}",
+        "🧾 Añadido al registro de gastos: {}",
+    ),
+    (
+        "🧾 Updated in the ledger: {}",
+        "🧾 Actualizado en el registro de gastos: {}",
+    ),
+    ("🧽 Style profile cleared", "🧽 Perfil de estilo borrado"),
+    (
+        "🗑️  Cancelled reply to {} ({})",
+        "🗑️  Respuesta a {} cancelada ({})",
+    ),
+    ("📦 Wrote {}", "📦 Escrito {}"),
+    (
+        "Look it over before attaching it to an issue.",
+        "Revísalo antes de adjuntarlo a una incidencia.",
+    ),
+    ("Logging to {}", "Registrando en {}"),
+    (
+        "Google Contacts needs an extra permission: run 'clinbox account reauth <id>' for each account.",
+        "Google Contacts necesita un permiso adicional: ejecuta 'clinbox account reauth <id>' para cada cuenta.",
+    ),
+    (
+        "Deleting for good needs full mail access: run 'clinbox account reauth <id>' for each account.",
+        "Eliminar definitivamente necesita acceso completo al correo: ejecuta 'clinbox account reauth <id>' para cada cuenta.",
+    ),
+    (
+        "Creating Gmail filters needs an extra permission: run 'clinbox account reauth <id>' for each account.",
+        "Crear filtros de Gmail necesita un permiso adicional: ejecuta 'clinbox account reauth <id>' para cada cuenta.",
+    ),
+    (
+        "Reading the storage quota needs an extra permission: run 'clinbox account reauth <id>' for each account.",
+        "Leer la cuota de almacenamiento necesita un permiso adicional: ejecuta 'clinbox account reauth <id>' para cada cuenta.",
+    ),
+    (
+        "Checking your calendar needs an extra permission: run 'clinbox account reauth <id>' for each account.",
+        "Consultar tu calendario necesita un permiso adicional: ejecuta 'clinbox account reauth <id>' para cada cuenta.",
+    ),
+    (
+        "✅ Account '{}' re-authorized.",
+        "✅ Cuenta '{}' reautorizada.",
+    ),
+    (
+        "Replies from '{}' are no longer Bcc'd.",
+        "Las respuestas desde '{}' ya no llevan CCO.",
+    ),
+    (
+        "Replies from '{}' will be Bcc'd to {}.",
+        "Las respuestas desde '{}' llevarán CCO a {}.",
+    ),
+    ("No blocked senders.", "No hay remitentes bloqueados."),
+    ("⛔ Blocked senders:", "⛔ Remitentes bloqueados:"),
+    (
+        "⛔ Blocked {}. Matching mail will be trashed on fetch.",
+        "⛔ {} bloqueado. El correo que coincida irá a la papelera al descargarse.",
+    ),
+    ("{} is already blocked.", "{} ya está bloqueado."),
+    ("✅ Unblocked {}", "✅ {} desbloqueado"),
+    ("{} is not blocked.", "{} no está bloqueado."),
+    ("🔍 Searching '{}'...", "🔍 Buscando '{}'..."),
+    ("📭 No attachments found.", "📭 No se encontraron adjuntos."),
+    (
+        "📎 Saved {} of {} attachments to {}",
+        "📎 Guardados {} de {} adjuntos en {}",
+    ),
+    ("From: {}", "De: {}"),
+    ("Date: {}", "Fecha: {}"),
+    ("🤖 Analyzing...", "🤖 Analizando..."),
+    (
+        "Priority: {} {}{} | Category: {}",
+        "Prioridad: {} {}{} | Categoría: {}",
+    ),
+    ("Model: {}", "Modelo: {}"),
+    (
+        "Suggested action: {} (~{} min)",
+        "Acción sugerida: {} (~{} min)",
+    ),
+    ("📝 Saved: {}", "📝 Guardado: {}"),
+    ("📭 No messages match.", "📭 Ningún mensaje coincide."),
+    (
+        "💾 Saved {} of {} messages to {}",
+        "💾 Guardados {} de {} mensajes en {}",
+    ),
+    (
+        "🗑️  Moved {} exported messages to trash",
+        "🗑️  {} mensajes exportados movidos a la papelera",
+    ),
+    ("📭 Nothing to sweep.", "📭 Nada que barrer."),
+    ("Nothing archived.", "No se archivó nada."),
+    ("📦 Archived {} messages", "📦 {} mensajes archivados"),
+    (
+        "⚠️  {} could not be archived: {}",
+        "⚠️  {} no se pudieron archivar: {}",
+    ),
+    ("✍️  Writing style ({}):", "✍️  Estilo de escritura ({}):"),
+    (
+        "📭 No style profile yet. Build one with 'clinbox style learn'.",
+        "📭 Aún no hay perfil de estilo. Crea uno con 'clinbox style learn'.",
+    ),
+    ("📤 Reading sent mail...", "📤 Leyendo el correo enviado..."),
+    (
+        "📭 No sent mail to learn from.",
+        "📭 No hay correo enviado del que aprender.",
+    ),
+    ("🤖 Studying {} messages...", "🤖 Estudiando {} mensajes..."),
+    (
+        "✅ Saved to {}; reply drafts now follow it. Edit the file to adjust.",
+        "✅ Guardado en {}; los borradores de respuesta ya lo siguen. Edita el archivo para ajustarlo.",
+    ),
+    (
+        "🔍 Looking through the inbox...",
+        "🔍 Revisando la bandeja de entrada...",
+    ),
+    (
+        "✨ Nothing stands out yet. Triage a few more sessions and ask again.",
+        "✨ Aún no destaca nada. Haz unas cuantas sesiones más y vuelve a preguntar.",
+    ),
+    ("📜 Wrote {}", "📜 Escrito {}"),
+    (
+        "✅ Gmail filter created for new mail",
+        "✅ Filtro de Gmail creado para el correo nuevo",
+    ),
+    (
+        "📜 {} rule(s) and {} Gmail filter(s) created",
+        "📜 {} regla(s) y {} filtro(s) de Gmail creados",
+    ),
+    (
+        "Mail already in the inbox stays put; clear it with: clinbox sweep --query \"from:<address>\" --older-than 1d",
+        "El correo que ya está en la bandeja se queda donde está; límpialo con: clinbox sweep --query \"from:<address>\" --older-than 1d",
+    ),
+    (
+        "No rules yet. Add .rhai scripts to {}",
+        "Aún no hay reglas. Añade scripts .rhai en {}",
+    ),
+    (
+        "📜 Rules in {} (run in this order):",
+        "📜 Reglas en {} (se ejecutan en este orden):",
+    ),
+    (
+        "🔍 Searching '{}' in {} account(s)...",
+        "🔍 Buscando '{}' en {} cuenta(s)...",
+    ),
+    ("No messages found.", "No se encontraron mensajes."),
+    (
+        "{} message(s). Read one with: clinbox read <id> --account <account>",
+        "{} mensaje(s). Lee uno con: clinbox read <id> --account <account>",
+    ),
+    (
+        "📭 No expenses recorded for {}. Press $ on an invoice during triage to add one.",
+        "📭 No hay gastos registrados en {}. Pulsa $ en una factura durante el triaje para añadir uno.",
+    ),
+    (
+        "📭 No expenses recorded. Press $ on an invoice during triage to add one.",
+        "📭 No hay gastos registrados. Pulsa $ en una factura durante el triaje para añadir uno.",
+    ),
+    ("🧾 Expenses ({}):", "🧾 Gastos ({}):"),
+    ("Total: {} {}", "Total: {} {}"),
+    (
+        "📭 No triage decisions recorded yet.",
+        "📭 Aún no hay decisiones de triaje registradas.",
+    ),
+    (
+        "🎯 Agreed with the AI on {}% of {} decisions ({} disagreements)",
+        "🎯 De acuerdo con la IA en el {}% de {} decisiones ({} desacuerdos)",
+    ),
+    (
+        "Average AI confidence when you disagreed: {}%",
+        "Confianza media de la IA cuando no estuviste de acuerdo: {}%",
+    ),
+    (
+        "📭 The outbox is empty.",
+        "📭 La bandeja de salida está vacía.",
+    ),
+    ("📮 Outbox:", "📮 Bandeja de salida:"),
+    (
+        "⚠️  {} snoozed messages stay snoozed: {}",
+        "⚠️  {} mensajes pospuestos siguen pospuestos: {}",
+    ),
+    (
+        "⚠️  Account '{}' no longer exists; its replies stay queued",
+        "⚠️  La cuenta '{}' ya no existe; sus respuestas siguen en cola",
+    ),
+    (
+        "No matching contacts. Contacts are collected from mail seen during triage.",
+        "No hay contactos que coincidan. Los contactos se recogen del correo visto durante el triaje.",
+    ),
+    ("👤 Contacts:", "👤 Contactos:"),
+    ("Storage: {}", "Almacenamiento: {}"),
+    (
+        "⚠️  Nearly full: new mail bounces once it is. Free space with 'clinbox storage'",
+        "⚠️  Casi lleno: el correo nuevo rebotará cuando lo esté. Libera espacio con 'clinbox storage'",
+    ),
+    (
+        "Storage: unavailable ({})",
+        "Almacenamiento: no disponible ({})",
+    ),
+    ("📓 Session logged to {}", "📓 Sesión registrada en {}"),
+    ("🌐 Serving {} on {}", "🌐 Sirviendo {} en {}"),
+    ("🔑 Token: {}", "🔑 Token: {}"),
+    (
+        "Send it as 'Authorization: Bearer <token>'. Ctrl+C to stop.",
+        "Envíalo como 'Authorization: Bearer <token>'. Ctrl+C para parar.",
+    ),
+    (
+        "⚠️  Plain HTTP: put a TLS proxy in front before exposing this beyond your network.",
+        "⚠️  HTTP sin cifrar: pon un proxy TLS delante antes de exponerlo fuera de tu red.",
+    ),
+    (
+        "⚠️  {} messages couldn't be moved to the trash: {}",
+        "⚠️  {} mensajes no se pudieron mover a la papelera: {}",
+    ),
+    (
+        "⚠️  The AI couldn't suggest rules ({}); going by the counts instead.",
+        "⚠️  La IA no pudo sugerir reglas ({}); se usan los recuentos.",
+    ),
+    (
+        "📊 Without the AI, suggesting senders treated the same way 90% of the time.",
+        "📊 Sin la IA, se sugieren remitentes tratados igual el 90% de las veces.",
+    ),
+    (
+        "🤖 Asking the AI for rules...",
+        "🤖 Pidiendo reglas a la IA...",
+    ),
+    ("why", "por qué"),
+    ("re-analyze", "reanalizar"),
+    ("export", "exportar"),
+    ("pipe", "canalizar"),
+    ("skip", "omitir"),
+    ("block", "bloquear"),
+    ("archive sender", "archivar remitente"),
+    ("undo", "deshacer"),
+    ("quit", "salir"),
+    ("send", "enviar"),
+    ("send later", "enviar luego"),
+    ("edit in browser", "editar en el navegador"),
+    ("Cc", "Cc"),
+    ("Bcc", "CCO"),
+    ("cancel", "cancelar"),
+];
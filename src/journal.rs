@@ -0,0 +1,284 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs::{self, OpenOptions};
+use std::io::Write as _;
+use std::path::PathBuf;
+
+use clinbox_core::config::{Config, JournalFormat};
+use clinbox_core::email::{Category, Email, EmailAnalysis, Priority};
+
+use crate::heuristics;
+
+/// What was done with one email
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Decision {
+    pub at: DateTime<Utc>,
+    /// e.g. "archived", "replied", "moved to Receipts"
+    pub action: String,
+    pub email_id: String,
+    pub from: String,
+    pub subject: String,
+    pub priority: Option<Priority>,
+    pub category: Option<Category>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f32>,
+    pub summary: Option<String>,
+    /// The analysis was a heuristic guess, not the AI's
+    #[serde(skip)]
+    pub guessed: bool,
+}
+
+impl Decision {
+    pub fn new(email: &Email, analysis: Option<&EmailAnalysis>, action: impl Into<String>) -> Self {
+        Self {
+            at: Utc::now(),
+            action: action.into(),
+            email_id: email.id.clone(),
+            from: email.from.clone(),
+            subject: email.subject.clone(),
+            priority: analysis.map(|a| a.priority),
+            category: analysis.map(|a| a.category),
+            confidence: analysis.and_then(|a| a.confidence),
+            summary: analysis.map(|a| a.summary.clone()),
+            guessed: analysis.is_some_and(heuristics::is_guess),
+        }
+    }
+}
+
+/// One triage run, as appended to the journal
+#[derive(Debug, Serialize)]
+pub struct JournalEntry<'a> {
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+    pub account: &'a str,
+    /// Number of emails per action
+    pub counts: BTreeMap<&'a str, usize>,
+    pub decisions: &'a [Decision],
+}
+
+impl<'a> JournalEntry<'a> {
+    pub fn new(account: &'a str, started_at: DateTime<Utc>, decisions: &'a [Decision]) -> Self {
+        let mut counts = BTreeMap::new();
+        for decision in decisions {
+            // "moved to <label>" counts as "moved"
+            let action = decision.action.split(' ').next().unwrap_or_default();
+            *counts.entry(action).or_default() += 1;
+        }
+
+        Self {
+            started_at,
+            ended_at: Utc::now(),
+            account,
+            counts,
+            decisions,
+        }
+    }
+
+    fn to_markdown(&self) -> String {
+        let mut out = format!(
+            "## {} — {}\n\n",
+            self.started_at
+                .with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M"),
+            self.account
+        );
+
+        let counts: Vec<_> = self
+            .counts
+            .iter()
+            .map(|(action, n)| format!("{} {}", n, action))
+            .collect();
+        let _ = writeln!(
+            out,
+            "{} emails: {}\n",
+            self.decisions.len(),
+            counts.join(", ")
+        );
+
+        for d in self.decisions {
+            let _ = write!(
+                out,
+                "- {} **{}** — {}: {}",
+                d.at.with_timezone(&Local).format("%H:%M"),
+                d.action,
+                d.from,
+                d.subject
+            );
+            if let (Some(priority), Some(category)) = (d.priority, d.category) {
+                let _ = write!(
+                    out,
+                    " ({} {}{}, {})",
+                    priority.emoji(),
+                    priority.label(),
+                    d.confidence
+                        .map(|c| format!(" {:.0}%", c * 100.0))
+                        .unwrap_or_default(),
+                    category.label()
+                );
+            }
+            out.push('\n');
+            if let Some(summary) = &d.summary {
+                let _ = writeln!(out, "  > {}", summary.replace('\n', " "));
+            }
+        }
+        out.push('\n');
+        out
+    }
+
+    /// Append the entry to this month's journal file
+    pub fn append(&self, format: JournalFormat) -> Result<PathBuf> {
+        let dir = Config::journal_dir()?;
+        fs::create_dir_all(&dir).context("Failed to create journal directory")?;
+
+        let path = dir.join(format!(
+            "{}.{}",
+            self.started_at.with_timezone(&Local).format("%Y-%m"),
+            format.extension()
+        ));
+
+        let content = match format {
+            JournalFormat::Markdown => self.to_markdown(),
+            JournalFormat::Jsonl => {
+                serde_json::to_string(self).context("Failed to serialize journal entry")? + "\n"
+            }
+        };
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .context("Failed to open journal file")?;
+        file.write_all(content.as_bytes())
+            .context("Failed to write journal file")?;
+
+        Ok(path)
+    }
+}
+
+/// A run read back from a JSONL journal; only its decisions are needed
+#[derive(Deserialize)]
+struct LoggedRun {
+    decisions: Vec<Decision>,
+}
+
+/// Every decision in the journal files, oldest month first. Markdown journals
+/// only give back the time, action, sender and subject; lines that can't be
+/// read are skipped.
+pub fn past_decisions() -> Result<Vec<Decision>> {
+    let dir = Config::journal_dir()?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut paths: Vec<PathBuf> = fs::read_dir(&dir)
+        .context("Failed to read journal directory")?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .collect();
+    paths.sort();
+
+    let mut decisions = Vec::new();
+    for path in paths {
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("jsonl") => decisions.extend(
+                content
+                    .lines()
+                    .filter_map(|line| serde_json::from_str::<LoggedRun>(line).ok())
+                    .flat_map(|run| run.decisions),
+            ),
+            Some("md") => decisions.extend(from_markdown(&content)),
+            _ => {}
+        }
+    }
+    Ok(decisions)
+}
+
+/// Decisions from a markdown journal: "## 2024-07-01 14:30 — personal"
+/// headings, then "- 14:31 **archived** — From: Subject (…)" lines
+fn from_markdown(content: &str) -> Vec<Decision> {
+    let mut day = None;
+    let mut decisions = Vec::new();
+    for line in content.lines() {
+        if let Some(heading) = line.strip_prefix("## ") {
+            day = heading
+                .get(..10)
+                .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok());
+            continue;
+        }
+        let (Some(day), Some(line)) = (day, line.strip_prefix("- ")) else {
+            continue;
+        };
+        decisions.extend(markdown_decision(day, line));
+    }
+    decisions
+}
+
+/// "14:31 **archived** — From: Subject (…)", on `day`
+fn markdown_decision(day: NaiveDate, line: &str) -> Option<Decision> {
+    let (time, rest) = line.split_once(" **")?;
+    let (action, rest) = rest.split_once("** — ")?;
+    let (from, subject) = rest.split_once(": ")?;
+    let time = NaiveTime::parse_from_str(time, "%H:%M").ok()?;
+    let at = Local.from_local_datetime(&day.and_time(time)).earliest()?;
+    Some(Decision {
+        at: at.with_timezone(&Utc),
+        action: action.to_string(),
+        email_id: String::new(),
+        from: from.to_string(),
+        subject: subject.to_string(),
+        priority: None,
+        category: None,
+        confidence: None,
+        summary: None,
+        guessed: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markdown_journal_reads_back() {
+        let decisions = [
+            Decision {
+                at: Utc::now(),
+                action: "moved to Receipts".to_string(),
+                email_id: "18f0a".to_string(),
+                from: "Shop <orders@shop.example>".to_string(),
+                subject: "Order: shipped".to_string(),
+                priority: Some(Priority::Low),
+                category: Some(Category::Billing),
+                confidence: None,
+                summary: Some("Your order shipped".to_string()),
+                guessed: false,
+            },
+            Decision {
+                at: Utc::now(),
+                action: "archived".to_string(),
+                email_id: "18f0b".to_string(),
+                from: "digest@news.example".to_string(),
+                subject: "Weekly".to_string(),
+                priority: None,
+                category: None,
+                confidence: None,
+                summary: None,
+                guessed: false,
+            },
+        ];
+        let entry = JournalEntry::new("personal", Utc::now(), &decisions);
+
+        let read = from_markdown(&entry.to_markdown());
+        assert_eq!(read.len(), 2);
+        assert_eq!(read[0].action, "moved to Receipts");
+        assert_eq!(read[0].from, "Shop <orders@shop.example>");
+        assert_eq!(read[1].action, "archived");
+        assert_eq!(read[1].from, "digest@news.example");
+        assert_eq!(read[1].subject, "Weekly");
+    }
+}
@@ -0,0 +1,215 @@
+use anyhow::{Result, bail};
+use crossterm::event::KeyCode;
+use std::collections::HashMap;
+
+use crate::i18n::t;
+use crate::tui::{Action, ReplyAction};
+
+/// Triage actions: config name, footer label, action, default keys
+const ACTIONS: &[(&str, &str, Action, &[&str])] = &[
+    ("archive", "archive", Action::Archive, &["a"]),
+    ("delete", "delete", Action::Delete, &["d"]),
+    ("mark_read", "mark read", Action::MarkRead, &["m"]),
+    ("move_to_label", "label", Action::MoveToLabel, &["l"]),
+    ("snooze", "snooze", Action::Snooze, &["z"]),
+    ("task", "task", Action::Task, &["t"]),
+    ("read_later", "read later", Action::ReadLater, &["L"]),
+    ("reply", "reply", Action::Reply, &["r"]),
+    ("note", "note", Action::Summary, &["n"]),
+    ("open", "open", Action::Open, &["o"]),
+    ("view", "view", Action::ViewFull, &["v"]),
+    ("links", "links", Action::Links, &["k"]),
+    ("copy_code", "copy code", Action::CopyCode, &["y"]),
+    ("expense", "expense", Action::Expense, &["$"]),
+    ("reasoning", "why", Action::Reasoning, &["w"]),
+    ("reanalyze", "re-analyze", Action::Reanalyze, &["A"]),
+    ("export", "export", Action::Export, &["x"]),
+    ("pipe", "pipe", Action::Pipe, &["|"]),
+    ("skip", "skip", Action::Skip, &["s"]),
+    ("block", "block", Action::Block, &["b"]),
+    (
+        "sweep_sender",
+        "archive sender",
+        Action::SweepSender,
+        &["S"],
+    ),
+    ("chat", "ask", Action::Chat, &["c"]),
+    ("command", "command", Action::Command, &[":"]),
+    ("undo", "undo", Action::Undo, &["u"]),
+    ("quit", "quit", Action::Quit, &["q", "esc"]),
+];
+
+/// Reply draft actions: config name, footer label, action, default keys
+const REPLY_ACTIONS: &[(&str, &str, ReplyAction, &[&str])] = &[
+    ("reply.send", "send", ReplyAction::Send, &["s"]),
+    (
+        "reply.schedule",
+        "send later",
+        ReplyAction::Schedule,
+        &["l"],
+    ),
+    ("reply.edit", "edit in browser", ReplyAction::Edit, &["e"]),
+    ("reply.cc", "Cc", ReplyAction::Cc, &["C"]),
+    ("reply.bcc", "Bcc", ReplyAction::Bcc, &["B"]),
+    ("reply.cancel", "cancel", ReplyAction::Cancel, &["c", "esc"]),
+];
+
+/// Key bindings for the triage screens, built from defaults plus config overrides
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    main: Vec<(KeyCode, Action)>,
+    reply: Vec<(KeyCode, ReplyAction)>,
+    main_footer: String,
+    reply_footer: String,
+}
+
+impl Keymap {
+    /// Build the keymap, applying overrides such as `"archive": "e"` or `"quit": "q,esc"`.
+    /// Fails on unknown action names, unparseable keys, or two actions sharing a key.
+    pub fn from_config(overrides: &HashMap<String, String>) -> Result<Self> {
+        for name in overrides.keys() {
+            let known = ACTIONS.iter().any(|(n, ..)| n == name)
+                || REPLY_ACTIONS.iter().any(|(n, ..)| n == name);
+            if !known {
+                bail!("Unknown keybinding action: keys.{}", name);
+            }
+        }
+
+        let (main, main_footer) = build_context(ACTIONS, overrides)?;
+        let (reply, reply_footer) = build_context(REPLY_ACTIONS, overrides)?;
+
+        Ok(Self {
+            main,
+            reply,
+            main_footer,
+            reply_footer,
+        })
+    }
+
+    pub fn action(&self, code: KeyCode) -> Option<Action> {
+        lookup(&self.main, code)
+    }
+
+    pub fn reply_action(&self, code: KeyCode) -> Option<ReplyAction> {
+        lookup(&self.reply, code)
+    }
+
+    /// Display name of the first key bound to a triage action
+    pub fn key_label(&self, action: Action) -> String {
+        self.main
+            .iter()
+            .find(|(_, a)| *a == action)
+            .map(|(key, _)| key_name(*key))
+            .unwrap_or_default()
+    }
+
+    /// Footer text listing the triage bindings, e.g. " [a]rchive [d]elete ... "
+    pub fn footer(&self) -> &str {
+        &self.main_footer
+    }
+
+    /// Footer text listing the reply draft bindings
+    pub fn reply_footer(&self) -> &str {
+        &self.reply_footer
+    }
+}
+
+fn lookup<A: Copy>(bindings: &[(KeyCode, A)], code: KeyCode) -> Option<A> {
+    bindings
+        .iter()
+        .find(|(key, _)| *key == code)
+        .map(|(_, action)| *action)
+}
+
+fn build_context<A: Copy>(
+    table: &[(&str, &'static str, A, &[&str])],
+    overrides: &HashMap<String, String>,
+) -> Result<(Vec<(KeyCode, A)>, String)> {
+    let mut bindings: Vec<(KeyCode, A)> = Vec::new();
+    let mut owners: HashMap<KeyCode, &str> = HashMap::new();
+    let mut footer = Vec::new();
+
+    for (name, label, action, defaults) in table {
+        let keys = match overrides.get(*name) {
+            Some(value) => value
+                .split(',')
+                .map(|k| parse_key(k.trim()))
+                .collect::<Result<Vec<_>>>()?,
+            None => defaults
+                .iter()
+                .map(|k| parse_key(k))
+                .collect::<Result<Vec<_>>>()?,
+        };
+
+        if keys.is_empty() {
+            bail!("No key bound to action '{}'", name);
+        }
+
+        for key in &keys {
+            if let Some(other) = owners.insert(*key, name) {
+                bail!(
+                    "Keybinding conflict: '{}' is bound to both '{}' and '{}'",
+                    key_name(*key),
+                    other,
+                    name
+                );
+            }
+            bindings.push((*key, *action));
+        }
+
+        footer.push(footer_entry(keys[0], t(label)));
+    }
+
+    Ok((bindings, format!(" {} ", footer.join(" "))))
+}
+
+/// Render "[a]rchive" when the key is the label's first letter, "[e] archive" otherwise
+fn footer_entry(key: KeyCode, label: &str) -> String {
+    if let KeyCode::Char(c) = key
+        && let Some(rest) = label.strip_prefix(c)
+    {
+        return format!("[{}]{}", c, rest);
+    }
+    format!("[{}] {}", key_name(key), label)
+}
+
+/// Parse a key name from config ("a", "E", "esc", "enter", "space", ...)
+fn parse_key(s: &str) -> Result<KeyCode> {
+    let mut chars = s.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Ok(KeyCode::Char(c));
+    }
+
+    let code = match s.to_lowercase().as_str() {
+        "esc" | "escape" => KeyCode::Esc,
+        "enter" | "return" => KeyCode::Enter,
+        "tab" => KeyCode::Tab,
+        "space" => KeyCode::Char(' '),
+        "comma" => KeyCode::Char(','),
+        "backspace" => KeyCode::Backspace,
+        "delete" | "del" => KeyCode::Delete,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        _ => bail!("Invalid key '{}' in keybindings", s),
+    };
+    Ok(code)
+}
+
+fn key_name(code: KeyCode) -> String {
+    match code {
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::Esc => "Esc".to_string(),
+        KeyCode::Enter => "Enter".to_string(),
+        KeyCode::Tab => "Tab".to_string(),
+        KeyCode::Backspace => "Backspace".to_string(),
+        KeyCode::Delete => "Del".to_string(),
+        KeyCode::Up => "Up".to_string(),
+        KeyCode::Down => "Down".to_string(),
+        KeyCode::Left => "Left".to_string(),
+        KeyCode::Right => "Right".to_string(),
+        other => format!("{:?}", other),
+    }
+}
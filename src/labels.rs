@@ -0,0 +1,59 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+
+use clinbox_core::config::Config;
+
+/// How many labels are offered for quick re-use
+const MAX_RECENT: usize = 9;
+
+/// Labels recently used with "move to label", most recent first, per account
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct RecentLabels {
+    pub accounts: HashMap<String, Vec<String>>,
+}
+
+impl RecentLabels {
+    /// Load recent labels from file
+    pub fn load() -> Result<Self> {
+        let path = Config::recent_labels_path()?;
+
+        if path.exists() {
+            let content = fs::read_to_string(&path).context("Failed to read labels file")?;
+            let labels: RecentLabels =
+                serde_json::from_str(&content).context("Failed to parse labels file")?;
+            Ok(labels)
+        } else {
+            Ok(RecentLabels::default())
+        }
+    }
+
+    /// Save recent labels to file
+    pub fn save(&self) -> Result<()> {
+        let path = Config::recent_labels_path()?;
+        fs::create_dir_all(path.parent().unwrap())?;
+
+        let content = serde_json::to_string_pretty(self).context("Failed to serialize labels")?;
+        fs::write(&path, content).context("Failed to write labels file")?;
+
+        Ok(())
+    }
+
+    /// Recent labels for an account, most recent first
+    pub fn get(&self, account_id: &str) -> &[String] {
+        self.accounts
+            .get(account_id)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Move a label to the front of the account's list and save
+    pub fn record(&mut self, account_id: &str, label: &str) -> Result<()> {
+        let recent = self.accounts.entry(account_id.to_string()).or_default();
+        recent.retain(|l| !l.eq_ignore_ascii_case(label));
+        recent.insert(0, label.to_string());
+        recent.truncate(MAX_RECENT);
+        self.save()
+    }
+}
@@ -0,0 +1,96 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+use clinbox_core::config::Config;
+use clinbox_core::email::{Email, EmailAnalysis};
+
+/// An email kept to read at leisure, its text stored so it reads offline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Item {
+    pub message_id: String,
+    /// The account the email is in, for opening it in Gmail; empty for items
+    /// saved before it was recorded
+    #[serde(default)]
+    pub account_id: String,
+    pub subject: String,
+    pub from: String,
+    pub date: DateTime<Utc>,
+    pub saved_at: DateTime<Utc>,
+    /// The AI's summary, when the email had been analyzed
+    #[serde(default)]
+    pub summary: Option<String>,
+    /// The body as plain text, HTML newsletters converted
+    pub content: String,
+}
+
+impl Item {
+    pub fn new(email: &Email, analysis: Option<&EmailAnalysis>, account_id: &str) -> Self {
+        Self {
+            message_id: email.id.clone(),
+            account_id: account_id.to_string(),
+            subject: email.subject.clone(),
+            from: email.sender_name(),
+            date: email.date,
+            saved_at: Utc::now(),
+            summary: analysis
+                .map(|a| a.summary.clone())
+                .filter(|s| !s.is_empty()),
+            content: email.body_text(),
+        }
+    }
+
+    /// Rough reading time at 200 words a minute, at least a minute
+    pub fn minutes(&self) -> usize {
+        (self.content.split_whitespace().count() / 200).max(1)
+    }
+}
+
+/// The read-later queue: reading material set aside during triage, kept apart
+/// from tasks, which are things to do
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ReadLater {
+    pub items: Vec<Item>,
+}
+
+impl ReadLater {
+    /// Load the list from file
+    pub fn load() -> Result<Self> {
+        let path = Config::later_path()?;
+
+        if path.exists() {
+            let content = fs::read_to_string(&path).context("Failed to read read-later file")?;
+            let list: ReadLater =
+                serde_json::from_str(&content).context("Failed to parse read-later file")?;
+            Ok(list)
+        } else {
+            Ok(ReadLater::default())
+        }
+    }
+
+    /// Save the list to file
+    pub fn save(&self) -> Result<()> {
+        let path = Config::later_path()?;
+        fs::create_dir_all(path.parent().unwrap())?;
+
+        let content =
+            serde_json::to_string_pretty(self).context("Failed to serialize read-later list")?;
+        fs::write(&path, content).context("Failed to write read-later file")?;
+
+        Ok(())
+    }
+
+    /// Add an item and save, replacing an earlier copy of the same email
+    pub fn add(&mut self, item: Item) -> Result<()> {
+        self.items.retain(|i| i.message_id != item.message_id);
+        self.items.push(item);
+        self.save()
+    }
+
+    /// Drop an item once read and save
+    pub fn remove(&mut self, message_id: &str) -> Result<()> {
+        self.items.retain(|i| i.message_id != message_id);
+        self.save()
+    }
+}
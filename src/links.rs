@@ -0,0 +1,205 @@
+use anyhow::{Context, Result, anyhow, bail};
+use clinbox_core::config::NetworkConfig;
+use clinbox_core::email::Email;
+use futures::future::join_all;
+use reqwest::header::LOCATION;
+use reqwest::redirect::Policy;
+use reqwest::{Client, StatusCode, Url};
+
+/// URL shorteners whose links are expanded
+const SHORTENERS: &[&str] = &[
+    "bit.ly",
+    "bitly.com",
+    "t.co",
+    "tinyurl.com",
+    "goo.gl",
+    "ow.ly",
+    "is.gd",
+    "buff.ly",
+    "rebrand.ly",
+    "cutt.ly",
+    "shorturl.at",
+    "t.ly",
+    "lnkd.in",
+    "tiny.cc",
+    "rb.gy",
+    "s.id",
+    "bl.ink",
+    "qrco.de",
+    "amzn.to",
+];
+
+/// Most redirects followed through chained shorteners
+const MAX_HOPS: usize = 5;
+
+/// A link in an email and where it really leads
+#[derive(Debug, Clone)]
+pub struct Link {
+    /// What the link says, for links in an HTML body
+    pub text: Option<String>,
+    /// The URL as written in the email
+    pub url: String,
+    /// Where a shortened URL redirects to
+    pub expanded: Option<String>,
+    /// Why a shortened URL couldn't be expanded
+    pub error: Option<String>,
+}
+
+impl Link {
+    /// The expanded URL, or the URL itself
+    pub fn destination(&self) -> &str {
+        self.expanded.as_deref().unwrap_or(&self.url)
+    }
+
+    /// (shown, actual) when the text names a domain the link doesn't go to,
+    /// e.g. "www.mybank.com" pointing at another site
+    pub fn mismatch(&self) -> Option<(String, String)> {
+        let shown = self.text.as_deref().and_then(shown_domain)?;
+        let actual = host(self.destination())?;
+        (!same_site(&shown, &actual)).then_some((shown, actual))
+    }
+}
+
+/// The links in an email: anchors in the HTML body, or URLs in the plain one.
+/// Each URL is listed once; mailto: and in-page links are left out.
+pub fn extract(email: &Email) -> Vec<Link> {
+    let found: Vec<(String, Option<String>)> = match email.body_html.as_deref() {
+        Some(html) if !html.is_empty() => clinbox_core::html::links(html)
+            .into_iter()
+            .map(|(url, text)| (url, Some(text).filter(|t| !t.is_empty())))
+            .collect(),
+        _ => email
+            .body_text()
+            .split_whitespace()
+            .filter_map(|word| {
+                let start = word.find("https://").or_else(|| word.find("http://"))?;
+                let url = word[start..].trim_end_matches(|c: char| ".,;:!?)]>'\"".contains(c));
+                Some((url.to_string(), None))
+            })
+            .collect(),
+    };
+
+    let mut links: Vec<Link> = Vec::new();
+    for (url, text) in found {
+        if !(url.starts_with("http://") || url.starts_with("https://"))
+            || links.iter().any(|l| l.url == url)
+        {
+            continue;
+        }
+        links.push(Link {
+            text,
+            url,
+            expanded: None,
+            error: None,
+        });
+    }
+    links
+}
+
+/// A client for expanding links: it doesn't follow redirects by itself, so
+/// the destination behind a shortener is read, never visited
+pub fn client(network: &NetworkConfig) -> Result<Client> {
+    network
+        .client_builder()?
+        .redirect(Policy::none())
+        .build()
+        .context("Failed to set up the HTTP client")
+}
+
+/// Expand the shortened links, all at once
+pub async fn expand_all(client: &Client, mut links: Vec<Link>) -> Vec<Link> {
+    let expansions = join_all(links.iter().map(|link| async move {
+        if is_shortened(&link.url) {
+            Some(expand(client, &link.url).await)
+        } else {
+            None
+        }
+    }))
+    .await;
+
+    for (link, expansion) in links.iter_mut().zip(expansions) {
+        match expansion {
+            Some(Ok(url)) => link.expanded = Some(url),
+            Some(Err(e)) => link.error = Some(format!("{:#}", e)),
+            None => {}
+        }
+    }
+    links
+}
+
+/// Follow a shortened URL's redirects with HEAD requests, stopping at the
+/// first URL that isn't on a shortener
+async fn expand(client: &Client, url: &str) -> Result<String> {
+    let mut current = Url::parse(url).context("Invalid URL")?;
+    for _ in 0..MAX_HOPS {
+        let mut response = client.head(current.clone()).send().await?;
+        // Some shorteners only answer GET
+        if matches!(
+            response.status(),
+            StatusCode::METHOD_NOT_ALLOWED | StatusCode::NOT_IMPLEMENTED
+        ) {
+            response = client.get(current.clone()).send().await?;
+        }
+
+        let status = response.status();
+        if !status.is_redirection() {
+            if status.is_success() {
+                return Ok(current.to_string());
+            }
+            bail!("{}", status);
+        }
+
+        let location = response
+            .headers()
+            .get(LOCATION)
+            .and_then(|value| value.to_str().ok())
+            .ok_or_else(|| anyhow!("redirect without a Location header"))?;
+        current = current.join(location).context("Invalid redirect")?;
+        if !is_shortened(current.as_str()) {
+            return Ok(current.to_string());
+        }
+    }
+    bail!("more than {} redirects", MAX_HOPS)
+}
+
+/// Whether the URL is on a known shortener
+fn is_shortened(url: &str) -> bool {
+    host(url).is_some_and(|host| SHORTENERS.contains(&host.as_str()))
+}
+
+/// Lowercase host of a URL, without "www."
+fn host(url: &str) -> Option<String> {
+    let url = Url::parse(url).ok()?;
+    let host = url.host_str()?.to_lowercase();
+    Some(host.strip_prefix("www.").unwrap_or(&host).to_string())
+}
+
+/// The domain a link's text shows, e.g. "https://mybank.com/login" or
+/// "www.mybank.com"
+fn shown_domain(text: &str) -> Option<String> {
+    text.split_whitespace().find_map(|word| {
+        let word = word.trim_matches(|c: char| "()[]<>,;:\"'".contains(c));
+        if word.starts_with("http://") || word.starts_with("https://") {
+            return host(word);
+        }
+        if word.contains('@') {
+            return None;
+        }
+        let name = word.split('/').next()?.trim_end_matches('.').to_lowercase();
+        let labels: Vec<&str> = name.split('.').collect();
+        let tld = labels.last()?;
+        let looks_like_domain = labels.len() >= 2
+            && (2..=6).contains(&tld.len())
+            && tld.chars().all(|c| c.is_ascii_alphabetic())
+            && labels.iter().all(|label| {
+                !label.is_empty() && label.chars().all(|c| c.is_alphanumeric() || c == '-')
+            });
+        looks_like_domain.then(|| name.strip_prefix("www.").unwrap_or(&name).to_string())
+    })
+}
+
+/// Whether two hosts belong to the same site, e.g. "mybank.com" and
+/// "login.mybank.com"
+fn same_site(a: &str, b: &str) -> bool {
+    a == b || a.ends_with(&format!(".{}", b)) || b.ends_with(&format!(".{}", a))
+}
@@ -0,0 +1,48 @@
+use anyhow::{Context, Result};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{Builder, Rotation};
+use tracing_subscriber::EnvFilter;
+
+use clinbox_core::config::Config;
+
+/// Daily log files kept under ~/.clinbox/logs/
+const KEEP_DAYS: usize = 7;
+
+/// Start logging at `-v` info, `-vv` debug or `-vvv` trace (`RUST_LOG` overrides).
+/// With `log_file` on, logs go to ~/.clinbox/logs/ (info and up even without `-v`);
+/// otherwise to stderr, and only when verbose. Hold the guard until exit so the
+/// file gets flushed.
+pub fn init(verbose: u8, log_file: bool) -> Result<Option<WorkerGuard>> {
+    let level = match verbose {
+        0 if log_file => "info",
+        0 => return Ok(None),
+        1 => "info",
+        2 => "debug",
+        _ => "trace",
+    };
+    let filter = EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::new(format!("warn,clinbox={level},clinbox_core={level}")));
+
+    if !log_file {
+        tracing_subscriber::fmt()
+            .with_env_filter(filter)
+            .with_writer(std::io::stderr)
+            .init();
+        return Ok(None);
+    }
+
+    let appender = Builder::new()
+        .rotation(Rotation::DAILY)
+        .filename_prefix("clinbox")
+        .filename_suffix("log")
+        .max_log_files(KEEP_DAYS)
+        .build(Config::logs_dir()?)
+        .context("Failed to open the log file")?;
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(writer)
+        .with_ansi(false)
+        .init();
+    Ok(Some(guard))
+}
@@ -1,18 +1,110 @@
-mod ai;
-mod config;
-mod email;
-mod gmail;
-mod tasks;
+/// Console output for the whole crate, with emoji swapped for text badges when
+/// `ui.ascii` is on (see `i18n::plain`)
+macro_rules! println {
+    () => { ::std::println!() };
+    ($($arg:tt)*) => { ::std::println!("{}", $crate::i18n::plain(&format!($($arg)*))) };
+}
+
+macro_rules! eprintln {
+    () => { ::std::eprintln!() };
+    ($($arg:tt)*) => { ::std::eprintln!("{}", $crate::i18n::plain(&format!($($arg)*))) };
+}
+
+mod aliases;
+mod attachments;
+mod blocklist;
+mod budget;
+mod calendar;
+mod cleanup;
+mod clipboard;
+mod clusters;
+mod command;
+mod conflicts;
+mod contacts;
+mod dashboard;
+mod debug;
+mod editor;
+mod eml;
+mod expenses;
+mod export;
+mod feedback;
+mod filter;
+mod github;
+mod heuristics;
+mod history;
+mod hooks;
+mod i18n;
+mod journal;
+mod keymap;
+mod labels;
+mod later;
+mod links;
+mod logging;
+mod otp;
+mod outbox;
+mod phishing;
+mod pipe;
+mod related;
+mod rules;
+mod serve;
+mod session;
+mod shipping;
+mod snooze;
+mod text;
+mod theme;
+mod tracking;
 mod tui;
+mod viewer;
+mod vip;
+mod waiting;
 
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
+use crossterm::event::KeyCode;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::task::JoinSet;
 
-use crate::ai::AiClient;
-use crate::config::{Config, GmailAccount};
-use crate::gmail::GmailClient;
-use crate::tasks::TaskStore;
-use crate::tui::{Action, ReplyAction, Tui};
+use clinbox_core::ai::{AiClient, SpamVerdict};
+use clinbox_core::config::{Config, GmailAccount, JournalFormat};
+use clinbox_core::email::{Category, Email, EmailAnalysis, Priority};
+use clinbox_core::gmail::{BatchResult, Copies, GmailClient, Person, StorageQuota, is_transient};
+use clinbox_core::tasks::TaskStore;
+use clinbox_core::timings;
+
+use crate::aliases::Aliases;
+use crate::blocklist::Blocklist;
+use crate::budget::{Limits, Progress};
+use crate::contacts::{ContactBook, parse_addresses};
+use crate::dashboard::{Dashboard, Mode as DashboardMode, Pane};
+use crate::export::ExportFormat;
+use crate::feedback::Feedback;
+use crate::filter::{DateBound, Filters};
+use crate::github::GitHub;
+use crate::history::{History, SeenAction};
+use crate::hooks::{HookEvent, Hooks};
+use crate::i18n::{t, tr};
+use crate::journal::{Decision, JournalEntry};
+use crate::keymap::Keymap;
+use crate::labels::RecentLabels;
+use crate::later::ReadLater;
+use crate::outbox::{Outbox, QueuedReply};
+use crate::rules::{RuleAction, Rules};
+use crate::session::{PendingChange, PendingKind, Session, Stats, UNDO_WINDOW};
+use crate::snooze::SnoozeList;
+use crate::theme::Theme;
+use crate::tui::{
+    Action, ClusterAction, DashboardAction, DraftAction, LaterAction, ReplyAction, ReviewAction,
+    StorageAction, Tui, WaitingAction,
+};
+use crate::vip::Vip;
+
+/// Most inbox emails one "archive everything from this sender" takes
+const MAX_SWEEP: u32 = 5000;
+
+/// AI analyses failing in a row before triage carries on with heuristics
+const AI_FAILURES_BEFORE_HEURISTICS: u32 = 3;
 
 #[derive(Parser)]
 #[command(name = "clinbox")]
@@ -33,13 +125,44 @@ struct Cli {
     /// Gmail account to use (by ID)
     #[arg(long, global = true)]
     account: Option<String>,
+
+    /// Log API calls and timings to stderr (or the log file): -v info, -vv debug
+    #[arg(short = 'v', long, action = clap::ArgAction::Count, global = true)]
+    verbose: u8,
+
+    /// On exit, report time spent in Gmail calls, AI calls and rendering, and AI token use
+    #[arg(long, global = true)]
+    timings: bool,
+
+    /// Continue the last unfinished triage session
+    #[arg(long)]
+    resume: bool,
+
+    /// Append this session's decisions to ~/.clinbox/journal/ (markdown or jsonl)
+    #[arg(long, value_name = "FORMAT")]
+    journal: Option<JournalFormat>,
+
+    /// Show the queue as a list with a preview pane instead of one email at a time
+    #[arg(long, conflicts_with = "resume")]
+    list: bool,
+
+    /// Triage without the AI: guess priorities and categories from headers,
+    /// sender history and keywords
+    #[arg(long, conflicts_with = "list")]
+    no_ai: bool,
+
+    #[command(flatten)]
+    filters: Filters,
+
+    #[command(flatten)]
+    limits: Limits,
 }
 
 #[derive(Subcommand)]
 enum Commands {
     /// Configure Clinbox
     Config {
-        /// Configuration key (ai.api_key, ai.model)
+        /// Configuration key (ai.api_key, ai.model, ai.escalation_model, ai.history_messages, ai.redact_pii, ai.skip_bulk, language, ui.locale, ui.ascii, ui.theme, journal, pipe_command, serve_token, serve_origins, network.proxy, network.ca_bundle, network.timeout, network.connect_timeout, log_file, group_similar, gmail.category_labels, gmail.people_lookup, gmail.permanent_delete, gmail.manage_filters, gmail.storage_quota, gmail.calendar_conflicts, gmail.message_cache, gmail.reply_quote, github.status, github.token)
         key: String,
         /// Value to set
         value: String,
@@ -51,10 +174,281 @@ enum Commands {
     },
     /// Show pending tasks
     Tasks,
+    /// Browse emails saved to read later: read them here, open them in Gmail, or mark them done
+    Later,
+    /// Block a sender address or domain; their mail is trashed on fetch
+    Block {
+        /// Address or domain to block (lists blocked senders when omitted)
+        entry: Option<String>,
+    },
+    /// Remove an address or domain from the blocklist
+    Unblock {
+        /// Address or domain to unblock
+        entry: String,
+    },
+    /// Work with attachments outside the triage screen
+    Attachments {
+        #[command(subcommand)]
+        action: AttachmentsAction,
+    },
+    /// Print an email's body to stdout, for piping into other tools
+    Read {
+        /// Gmail message id
+        id: String,
+        /// Print From/To/Subject/Date headers before the body
+        #[arg(long)]
+        headers: bool,
+        /// Print the raw RFC 822 message, headers and MIME parts as received
+        #[arg(long, conflicts_with = "headers")]
+        raw: bool,
+    },
+    /// Analyze a local .eml file with the AI, without touching Gmail
+    Analyze {
+        /// Path to an RFC 822 message (.eml)
+        file: PathBuf,
+        /// Also generate a comprehensive summary and save it as markdown
+        #[arg(long)]
+        summary: bool,
+    },
+    /// Save the messages matching a Gmail search as .eml, HTML or PDF files, or an mbox archive
+    Export {
+        /// Gmail search query (e.g. "from:bank older_than:1y")
+        #[arg(long)]
+        query: String,
+        /// Output format (eml, mbox, html or pdf)
+        #[arg(long, default_value = "eml")]
+        format: ExportFormat,
+        /// Directory to save into (default: current directory), or the mbox file to append to (default: export.mbox)
+        #[arg(long)]
+        out: Option<PathBuf>,
+        /// Maximum number of messages to export
+        #[arg(short = 'n', long, default_value = "100")]
+        max_emails: u32,
+        /// Move exported messages to trash afterwards
+        #[arg(long)]
+        delete: bool,
+    },
+    /// Archive old inbox mail in clinbox categories (e.g. newsletters) after a preview
+    Sweep {
+        /// Categories to sweep, as labeled by clinbox (comma-separated, e.g. newsletter,marketing)
+        #[arg(long, value_delimiter = ',')]
+        category: Vec<Category>,
+        /// Only sweep mail received before a date (YYYY-MM-DD) or older than a period (3d, 2w, 1m, 1y)
+        #[arg(long, value_name = "AGE", default_value = "14d")]
+        older_than: DateBound,
+        /// Extra Gmail search terms (e.g. "from:news@example.com"); enough on its own without --category
+        #[arg(short, long)]
+        query: Option<String>,
+        /// Maximum number of messages to sweep
+        #[arg(short = 'n', long, default_value = "500")]
+        max_emails: u32,
+        /// Archive without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Learn my writing style from sent mail, so reply drafts sound like me
+    Style {
+        #[command(subcommand)]
+        action: Option<StyleAction>,
+    },
+    /// Propose rules from triage history and the inbox, and create the ones I accept
+    Cleanup {
+        #[command(subcommand)]
+        action: CleanupAction,
+    },
+    /// List scripted triage rules, or dry-run them against a Gmail search
+    Rules {
+        #[command(subcommand)]
+        action: Option<RulesAction>,
+    },
+    /// Search every configured account at once (or just --account) and list the hits together
+    Search {
+        /// Gmail search query (e.g. "invoice newer_than:30d")
+        query: String,
+        /// Maximum number of messages per account
+        #[arg(short = 'n', long, default_value = "20")]
+        max_emails: u32,
+    },
+    /// Full-screen dashboard with message list, preview, tasks and stats panes
+    Dashboard {
+        /// Gmail search for the message list
+        #[arg(short, long, default_value = "in:inbox")]
+        query: String,
+        /// Maximum number of messages to list
+        #[arg(short = 'n', long, default_value = "50")]
+        max_emails: u32,
+    },
+    /// Browse any label (Sent, custom labels) read-only in the dashboard's list and preview
+    Browse {
+        /// Label to browse, e.g. SENT, STARRED or a custom label name
+        #[arg(default_value = "SENT")]
+        label: String,
+        /// Extra Gmail search terms within the label
+        #[arg(short, long)]
+        query: Option<String>,
+        /// Maximum number of messages to list
+        #[arg(short = 'n', long, default_value = "50")]
+        max_emails: u32,
+    },
+    /// List threads still waiting on a reply to my last message, and nudge them
+    Waiting {
+        /// How far back to look for sent mail, in days
+        #[arg(long, default_value = "30")]
+        days: u32,
+        /// Maximum number of sent threads to check
+        #[arg(short = 'n', long, default_value = "50")]
+        max_threads: u32,
+    },
+    /// Review recently trashed messages: restore them or delete them for good
+    Trash {
+        /// Maximum number of trashed messages to list
+        #[arg(short = 'n', long, default_value = "50")]
+        max_emails: u32,
+    },
+    /// List the biggest messages to archive, delete, or save attachments from, reclaiming storage
+    Storage {
+        /// Size threshold in Gmail's syntax: bytes, or with K or M (e.g. 500K, 10M)
+        #[arg(long, default_value = "10M")]
+        larger_than: String,
+        /// Maximum number of messages to list
+        #[arg(short = 'n', long, default_value = "50")]
+        max_emails: u32,
+    },
+    /// Review the spam folder with an AI check for legit mail caught by mistake
+    Spam {
+        /// Maximum number of spam messages to list
+        #[arg(short = 'n', long, default_value = "50")]
+        max_emails: u32,
+    },
+    /// Finish Gmail drafts: edit them in $EDITOR, send or discard them
+    Drafts {
+        /// Maximum number of drafts to list
+        #[arg(short = 'n', long, default_value = "50")]
+        max_drafts: u32,
+    },
+    /// List, cancel or send scheduled replies and replies that failed to send
+    Outbox {
+        #[command(subcommand)]
+        action: Option<OutboxAction>,
+    },
+    /// Look up contacts seen in mail, most frequent first
+    Contacts {
+        /// Part of a name or address to match (all contacts when omitted)
+        query: Option<String>,
+        /// Maximum number of contacts to show
+        #[arg(long, default_value = "10")]
+        limit: usize,
+    },
+    /// Serve a token-protected REST API (emails, analysis, archive, tasks) for web frontends
+    Serve {
+        /// Port to listen on
+        #[arg(long, default_value = "8787")]
+        port: u16,
+        /// Address to bind; use 0.0.0.0 to reach it from other devices on your network
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+    },
+    /// List the expense ledger filled from invoices during triage, with totals per currency
+    Expenses {
+        /// Only this month, as YYYY-MM (e.g. 2024-07)
+        #[arg(long)]
+        month: Option<String>,
+    },
+    /// How often my triage decisions agreed with the AI, and the latest disagreements
+    Feedback {
+        /// Number of recent disagreements to list
+        #[arg(short = 'n', long, default_value = "10")]
+        limit: usize,
+    },
+    /// Tools for bug reports
+    Debug {
+        #[command(subcommand)]
+        action: DebugAction,
+    },
     /// Show configuration status
     Status,
 }
 
+#[derive(Subcommand)]
+enum DebugAction {
+    /// Pack version info, the config without secrets, recent logs and API errors into a .tar.gz
+    Bundle {
+        /// File to write (default: clinbox-debug-<date>.tar.gz in the current directory)
+        #[arg(short, long)]
+        out: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum AttachmentsAction {
+    /// Download every attachment of the messages matching a Gmail search
+    Export {
+        /// Gmail search query (e.g. "from:accountant has:attachment after:2024/06/01")
+        #[arg(long)]
+        query: String,
+        /// Directory to save into (created if missing)
+        #[arg(long, default_value = ".")]
+        out: PathBuf,
+        /// Maximum number of messages to search
+        #[arg(short = 'n', long, default_value = "100")]
+        max_emails: u32,
+    },
+}
+
+#[derive(Subcommand)]
+enum RulesAction {
+    /// List the rules in ~/.clinbox/rules/ (default)
+    List,
+    /// Show what the rules would do with the messages matching a Gmail search
+    Test {
+        /// Gmail search query (e.g. "from:ci@example.com newer_than:7d")
+        #[arg(long)]
+        query: String,
+        /// Maximum number of messages to check
+        #[arg(short = 'n', long, default_value = "20")]
+        max_emails: u32,
+    },
+}
+
+#[derive(Subcommand)]
+enum StyleAction {
+    /// Show the current style profile (default)
+    Show,
+    /// Build the profile from a sample of the Sent folder
+    Learn {
+        /// Number of sent messages to learn from
+        #[arg(short = 'n', long, default_value = "50")]
+        max_emails: u32,
+    },
+    /// Forget the profile; drafts go back to a neutral style
+    Clear,
+}
+
+#[derive(Subcommand)]
+enum CleanupAction {
+    /// Ask the AI for rules such as "auto-archive calendar@…, 97% archived
+    /// historically", from the triage journal, sender history and inbox
+    Suggest {
+        /// Maximum number of inbox messages to look at
+        #[arg(short = 'n', long, default_value = "500")]
+        max_emails: u32,
+    },
+}
+
+#[derive(Subcommand)]
+enum OutboxAction {
+    /// List queued replies (default)
+    List,
+    /// Cancel a queued reply
+    Cancel {
+        /// Reply id, or a unique prefix of it
+        id: String,
+    },
+    /// Send every reply that is due now, including ones that failed to send (e.g. from cron)
+    #[command(alias = "send")]
+    Flush,
+}
+
 #[derive(Subcommand)]
 enum AccountAction {
     /// Add a new Gmail account (starts OAuth flow)
@@ -80,12 +474,90 @@ enum AccountAction {
         /// Account identifier to set as default
         id: String,
     },
+    /// Re-run the OAuth flow, e.g. to grant Google Contacts access
+    Reauth {
+        /// Account identifier to re-authorize
+        id: String,
+    },
+    /// Bcc these addresses on every reply from an account (none to stop)
+    Bcc {
+        /// Account identifier
+        id: String,
+        /// Addresses, e.g. a CRM's dropbox address
+        addresses: Vec<String>,
+    },
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
+    let config = Config::load().ok();
+    let _log_guard = logging::init(cli.verbose, config.as_ref().is_some_and(|c| c.log_file))?;
+    let ui = config.map(|c| c.ui).unwrap_or_default();
+    i18n::init(ui.locale, ui.ascii);
+
+    let started = std::time::Instant::now();
+    let show_timings = cli.timings;
+    let result = run(cli).await;
+    if let Err(e) = &result {
+        tracing::error!("{:#}", e);
+    }
+    if show_timings {
+        print_timings(started.elapsed());
+    }
+    result
+}
+
+/// Where the run's time went, on stderr so it stays out of piped output
+fn print_timings(elapsed: std::time::Duration) {
+    let report = timings::report();
+    eprintln!("\n⏱️  Timings ({} total)", format_duration(elapsed));
+    for kind in [
+        timings::Kind::Gmail,
+        timings::Kind::Ai,
+        timings::Kind::Render,
+    ] {
+        let totals = report.get(kind);
+        if totals.calls == 0 {
+            continue;
+        }
+        eprintln!(
+            "  {:<10} {:>5} × {:>7} avg, {:>7} slowest = {:>7}",
+            kind.label(),
+            totals.calls,
+            format_duration(totals.average()),
+            format_duration(totals.slowest),
+            format_duration(totals.total)
+        );
+    }
+    let waited: std::time::Duration = report.totals.iter().map(|t| t.total).sum();
+    eprintln!(
+        "  {:<10} {:>46}",
+        "Other",
+        format_duration(elapsed.saturating_sub(waited))
+    );
+    if report.prompt_tokens + report.completion_tokens > 0 {
+        let cost = report
+            .cost
+            .map(|c| format!(", ${:.4}", c))
+            .unwrap_or_default();
+        eprintln!(
+            "  AI tokens: {} in, {} out{}",
+            report.prompt_tokens, report.completion_tokens, cost
+        );
+    }
+}
+
+/// "850ms" or "12.3s"
+fn format_duration(duration: std::time::Duration) -> String {
+    if duration.as_millis() < 1000 {
+        format!("{}ms", duration.as_millis())
+    } else {
+        format!("{:.1}s", duration.as_secs_f64())
+    }
+}
 
+async fn run(cli: Cli) -> Result<()> {
     match cli.command {
         Some(Commands::Config { key, value }) => {
             configure(&key, &value)?;
@@ -96,11 +568,200 @@ async fn main() -> Result<()> {
         Some(Commands::Tasks) => {
             show_tasks()?;
         }
+        Some(Commands::Later) => {
+            run_later().await?;
+        }
+        Some(Commands::Block { entry }) => {
+            block_sender(entry.as_deref())?;
+        }
+        Some(Commands::Unblock { entry }) => {
+            unblock_sender(&entry)?;
+        }
+        Some(Commands::Attachments { action }) => match action {
+            AttachmentsAction::Export {
+                query,
+                out,
+                max_emails,
+            } => {
+                export_attachments(cli.account.as_deref(), &query, &out, max_emails).await?;
+            }
+        },
+        Some(Commands::Read { id, headers, raw }) => {
+            read_email(cli.account.as_deref(), &id, headers, raw).await?;
+        }
+        Some(Commands::Analyze { file, summary }) => {
+            analyze_file(&file, summary).await?;
+        }
+        Some(Commands::Export {
+            query,
+            format,
+            out,
+            max_emails,
+            delete,
+        }) => {
+            let out = out.unwrap_or_else(|| format.default_out());
+            export_messages(
+                cli.account.as_deref(),
+                &query,
+                format,
+                &out,
+                max_emails,
+                delete,
+            )
+            .await?;
+        }
+        Some(Commands::Sweep {
+            category,
+            older_than,
+            query,
+            max_emails,
+            yes,
+        }) => {
+            run_sweep(
+                cli.account.as_deref(),
+                &category,
+                older_than,
+                query.as_deref(),
+                max_emails,
+                yes,
+            )
+            .await?;
+        }
+        Some(Commands::Style { action }) => match action.unwrap_or(StyleAction::Show) {
+            StyleAction::Show => show_style()?,
+            StyleAction::Learn { max_emails } => {
+                learn_style(cli.account.as_deref(), max_emails).await?;
+            }
+            StyleAction::Clear => {
+                let path = Config::style_path()?;
+                if path.exists() {
+                    std::fs::remove_file(&path)?;
+                }
+                println!("{}", t("🧽 Style profile cleared"));
+            }
+        },
+        Some(Commands::Cleanup {
+            action: CleanupAction::Suggest { max_emails },
+        }) => {
+            suggest_cleanup(cli.account.as_deref(), max_emails, cli.no_ai).await?;
+        }
+        Some(Commands::Rules { action }) => match action.unwrap_or(RulesAction::List) {
+            RulesAction::List => list_rules()?,
+            RulesAction::Test { query, max_emails } => {
+                test_rules(cli.account.as_deref(), &query, max_emails).await?;
+            }
+        },
+        Some(Commands::Dashboard { query, max_emails }) => {
+            run_dashboard(
+                cli.account.as_deref(),
+                &query,
+                max_emails,
+                DashboardMode::Full,
+                &Filters::default(),
+            )
+            .await?;
+        }
+        Some(Commands::Browse {
+            label,
+            query,
+            max_emails,
+        }) => {
+            let mut query_terms = vec![filter::label_query(&label)];
+            query_terms.extend(query);
+            run_dashboard(
+                cli.account.as_deref(),
+                &query_terms.join(" "),
+                max_emails,
+                DashboardMode::Browse,
+                &Filters::default(),
+            )
+            .await?;
+        }
+        Some(Commands::Search { query, max_emails }) => {
+            run_search(cli.account.as_deref(), &query, max_emails).await?;
+        }
+        Some(Commands::Waiting { days, max_threads }) => {
+            run_waiting(cli.account.as_deref(), days, max_threads).await?;
+        }
+        Some(Commands::Trash { max_emails }) => {
+            run_trash(cli.account.as_deref(), max_emails).await?;
+        }
+        Some(Commands::Storage {
+            larger_than,
+            max_emails,
+        }) => {
+            run_storage(cli.account.as_deref(), &larger_than, max_emails).await?;
+        }
+        Some(Commands::Spam { max_emails }) => {
+            run_spam(cli.account.as_deref(), max_emails).await?;
+        }
+        Some(Commands::Drafts { max_drafts }) => {
+            run_drafts(cli.account.as_deref(), max_drafts).await?;
+        }
+        Some(Commands::Outbox { action }) => match action.unwrap_or(OutboxAction::List) {
+            OutboxAction::List => show_outbox()?,
+            OutboxAction::Cancel { id } => {
+                let reply = Outbox::load()?.cancel(&id)?;
+                println!(
+                    "{}",
+                    tr(
+                        "🗑️  Cancelled reply to {} ({})",
+                        &[&reply.to, &reply.subject]
+                    )
+                );
+            }
+            OutboxAction::Flush => send_all_due_replies().await?,
+        },
+        Some(Commands::Contacts { query, limit }) => {
+            show_contacts(query.as_deref().unwrap_or(""), limit)?;
+        }
+        Some(Commands::Serve { port, host }) => {
+            let config = Config::load()?;
+            let account = resolve_account(&config, cli.account.as_deref())?;
+            serve::run(account, &host, port).await?;
+        }
+        Some(Commands::Expenses { month }) => {
+            show_expenses(month.as_deref())?;
+        }
+        Some(Commands::Feedback { limit }) => {
+            show_feedback(limit)?;
+        }
+        Some(Commands::Debug {
+            action: DebugAction::Bundle { out },
+        }) => {
+            let (path, contents) = debug::bundle(out.as_deref())?;
+            println!("{}", tr("📦 Wrote {}", &[&path.display()]));
+            for item in contents {
+                println!("   • {}", item);
+            }
+            println!("{}", t("Look it over before attaching it to an issue."));
+        }
         Some(Commands::Status) => {
-            show_status()?;
+            show_status().await?;
+        }
+        None if cli.list => {
+            let query = cli.filters.gmail_query(cli.all);
+            run_dashboard(
+                cli.account.as_deref(),
+                &query,
+                cli.max_emails,
+                DashboardMode::List,
+                &cli.filters,
+            )
+            .await?;
         }
         None => {
-            run_interactive(cli.max_emails, cli.all, cli.account.as_deref()).await?;
+            run_interactive(
+                cli.max_emails,
+                cli.all,
+                cli.account.as_deref(),
+                cli.resume,
+                cli.journal,
+                cli.no_ai,
+                &cli.filters,
+                &cli.limits,
+            )
+            .await?;
         }
     }
 
@@ -112,8 +773,168 @@ fn configure(key: &str, value: &str) -> Result<()> {
 
     match key {
         "ai.api_key" => config.ai.api_key = value.to_string(),
-        "ai.model" => config.ai.model_analysis = value.to_string(),
+        "ai.model" => config.ai.model_analysis = value.parse()?,
+        "ai.escalation_model" => {
+            config.ai.model_escalation = Some(value.to_string()).filter(|v| !v.is_empty())
+        }
+        "ai.history_messages" => {
+            config.ai.history_messages = value
+                .parse()
+                .map_err(|_| anyhow::anyhow!("Expected a number of messages, got '{}'", value))?
+        }
+        "ai.redact_pii" => {
+            config.ai.redact_pii = value
+                .parse()
+                .map_err(|_| anyhow::anyhow!("Expected true or false, got '{}'", value))?
+        }
+        "ai.skip_bulk" => {
+            config.ai.skip_bulk = value
+                .parse()
+                .map_err(|_| anyhow::anyhow!("Expected true or false, got '{}'", value))?
+        }
         "language" => config.language = value.to_string(),
+        "pipe_command" => config.pipe_command = Some(value.to_string()).filter(|v| !v.is_empty()),
+        "serve_token" => config.serve_token = Some(value.to_string()).filter(|v| !v.is_empty()),
+        "serve_origins" => {
+            config.serve_origins = value
+                .split(',')
+                .map(|o| o.trim().trim_end_matches('/').to_string())
+                .filter(|o| !o.is_empty())
+                .collect();
+            // Only to check them
+            serve::cors(&config.serve_origins).map(|_| ())?;
+        }
+        "network.proxy" => {
+            config.network.proxy = Some(value.to_string()).filter(|v| !v.is_empty());
+            config.network.client()?;
+        }
+        "network.ca_bundle" => {
+            config.network.ca_bundle = Some(PathBuf::from(value)).filter(|_| !value.is_empty());
+            config.network.client()?;
+        }
+        "group_similar" => {
+            config.group_similar = value
+                .parse()
+                .map_err(|_| anyhow::anyhow!("Expected true or false, got '{}'", value))?;
+        }
+        "log_file" => {
+            config.log_file = value
+                .parse()
+                .map_err(|_| anyhow::anyhow!("Expected true or false, got '{}'", value))?;
+            if config.log_file {
+                println!("{}", tr("Logging to {}", &[&Config::logs_dir()?.display()]));
+            }
+        }
+        "network.timeout" => config.network.timeout_secs = parse_seconds(value)?,
+        "network.connect_timeout" => config.network.connect_timeout_secs = parse_seconds(value)?,
+        "gmail.people_lookup" => {
+            config.gmail.people_lookup = value
+                .parse()
+                .map_err(|_| anyhow::anyhow!("Expected true or false, got '{}'", value))?;
+            if config.gmail.people_lookup {
+                println!(
+                    "{}",
+                    t(
+                        "Google Contacts needs an extra permission: run 'clinbox account reauth <id>' for each account."
+                    )
+                );
+            }
+        }
+        "gmail.permanent_delete" => {
+            config.gmail.permanent_delete = value
+                .parse()
+                .map_err(|_| anyhow::anyhow!("Expected true or false, got '{}'", value))?;
+            if config.gmail.permanent_delete {
+                println!(
+                    "{}",
+                    t(
+                        "Deleting for good needs full mail access: run 'clinbox account reauth <id>' for each account."
+                    )
+                );
+            }
+        }
+        "gmail.manage_filters" => {
+            config.gmail.manage_filters = value
+                .parse()
+                .map_err(|_| anyhow::anyhow!("Expected true or false, got '{}'", value))?;
+            if config.gmail.manage_filters {
+                println!(
+                    "{}",
+                    t(
+                        "Creating Gmail filters needs an extra permission: run 'clinbox account reauth <id>' for each account."
+                    )
+                );
+            }
+        }
+        "gmail.storage_quota" => {
+            config.gmail.storage_quota = value
+                .parse()
+                .map_err(|_| anyhow::anyhow!("Expected true or false, got '{}'", value))?;
+            if config.gmail.storage_quota {
+                println!(
+                    "{}",
+                    t(
+                        "Reading the storage quota needs an extra permission: run 'clinbox account reauth <id>' for each account."
+                    )
+                );
+            }
+        }
+        "github.status" => {
+            config.github.status = value
+                .parse()
+                .map_err(|_| anyhow::anyhow!("Expected true or false, got '{}'", value))?;
+        }
+        "github.token" => config.github.token = Some(value.to_string()).filter(|v| !v.is_empty()),
+        "gmail.calendar_conflicts" => {
+            config.gmail.calendar_conflicts = value
+                .parse()
+                .map_err(|_| anyhow::anyhow!("Expected true or false, got '{}'", value))?;
+            if config.gmail.calendar_conflicts {
+                println!(
+                    "{}",
+                    t(
+                        "Checking your calendar needs an extra permission: run 'clinbox account reauth <id>' for each account."
+                    )
+                );
+            }
+        }
+        "gmail.category_labels" => {
+            config.gmail.category_labels = value
+                .parse()
+                .map_err(|_| anyhow::anyhow!("Expected true or false, got '{}'", value))?;
+        }
+        "gmail.message_cache" => {
+            config.gmail.message_cache = value
+                .parse()
+                .map_err(|_| anyhow::anyhow!("Expected true or false, got '{}'", value))?;
+        }
+        "gmail.reply_quote" => {
+            config.gmail.reply_quote = value.parse().map_err(|e: String| anyhow::anyhow!(e))?;
+        }
+        "journal" => {
+            config.journal = match value {
+                "off" | "none" => None,
+                _ => Some(value.parse().map_err(|e: String| anyhow::anyhow!(e))?),
+            };
+        }
+        "ui.ascii" => {
+            config.ui.ascii = value
+                .parse()
+                .map_err(|_| anyhow::anyhow!("Expected true or false, got '{}'", value))?;
+        }
+        "ui.locale" => {
+            config.ui.locale = value.parse().map_err(|e: String| anyhow::anyhow!(e))?;
+        }
+        "ui.theme" => {
+            if Theme::preset(value).is_none() {
+                anyhow::bail!(
+                    "Unknown theme '{}'. Available themes: {}",
+                    value,
+                    crate::theme::PRESETS.join(", ")
+                );
+            }
+            config.ui.theme = value.to_string();
+        }
         _ => anyhow::bail!(
             "Unknown config key: {}. Use 'clinbox account add' to configure Gmail accounts.",
             key
@@ -125,6 +946,17 @@ fn configure(key: &str, value: &str) -> Result<()> {
     Ok(())
 }
 
+/// A positive number of seconds, or None (the default) for an empty value
+fn parse_seconds(value: &str) -> Result<Option<u64>> {
+    if value.is_empty() {
+        return Ok(None);
+    }
+    match value.parse() {
+        Ok(secs) if secs > 0 => Ok(Some(secs)),
+        _ => anyhow::bail!("Expected a number of seconds, got '{}'", value),
+    }
+}
+
 async fn handle_account_command(action: AccountAction) -> Result<()> {
     match action {
         AccountAction::Add {
@@ -143,6 +975,12 @@ async fn handle_account_command(action: AccountAction) -> Result<()> {
         AccountAction::Default { id } => {
             set_default_account(&id)?;
         }
+        AccountAction::Reauth { id } => {
+            reauth_account(&id).await?;
+        }
+        AccountAction::Bcc { id, addresses } => {
+            set_account_bcc(&id, addresses)?;
+        }
     }
     Ok(())
 }
@@ -180,14 +1018,15 @@ async fn add_account(id: &str, client_id: Option<&str>, client_secret: Option<&s
         email: None,
         client_id: resolved_client_id.clone(),
         client_secret: resolved_client_secret.clone(),
+        bcc: Vec::new(),
     };
 
     // Run OAuth flow to get token
     println!("Starting OAuth flow for account '{}'...", id);
-    GmailClient::oauth_flow(&account).await?;
+    GmailClient::oauth_flow(&account, &config).await?;
 
     // Create client to fetch user email
-    let client = GmailClient::new(&account).await?;
+    let client = GmailClient::new(&account, &config).await?;
     let email = client.fetch_user_email().await?;
 
     // Add account with email to config
@@ -196,6 +1035,7 @@ async fn add_account(id: &str, client_id: Option<&str>, client_secret: Option<&s
         email: Some(email.clone()),
         client_id: resolved_client_id,
         client_secret: resolved_client_secret,
+        bcc: Vec::new(),
     };
 
     config.add_account(account_with_email)?;
@@ -277,6 +1117,34 @@ struct InstalledCredentials {
     client_secret: String,
 }
 
+async fn reauth_account(id: &str) -> Result<()> {
+    let config = Config::load()?;
+    let account = config
+        .get_account(id)
+        .ok_or_else(|| anyhow::anyhow!("Account '{}' not found.", id))?;
+
+    println!("Starting OAuth flow for account '{}'...", id);
+    GmailClient::oauth_flow(account, &config).await?;
+    println!("{}", tr("✅ Account '{}' re-authorized.", &[&id]));
+    Ok(())
+}
+
+/// The account named by `--account`, or the default one
+fn resolve_account<'a>(config: &'a Config, account_id: Option<&str>) -> Result<&'a GmailAccount> {
+    if let Some(id) = account_id {
+        config.get_account(id).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Account '{}' not found. Run 'clinbox account list' to see available accounts.",
+                id
+            )
+        })
+    } else {
+        config.get_default_account().ok_or_else(|| {
+            anyhow::anyhow!("No default account set. Run 'clinbox account add' to add an account.")
+        })
+    }
+}
+
 fn list_accounts() -> Result<()> {
     let config = Config::load()?;
 
@@ -296,6 +1164,9 @@ fn list_accounts() -> Result<()> {
         let default_label = if is_default { " [default]" } else { "" };
         let email = account.email.as_deref().unwrap_or("(email not set)");
         println!("{}{} ({}){}", marker, account.id, email, default_label);
+        if !account.bcc.is_empty() {
+            println!("    Bcc: {}", account.bcc.join(", "));
+        }
     }
 
     Ok(())
@@ -315,11 +1186,34 @@ fn set_default_account(id: &str) -> Result<()> {
     Ok(())
 }
 
+fn set_account_bcc(id: &str, addresses: Vec<String>) -> Result<()> {
+    if let Some(invalid) = addresses.iter().find(|a| !a.contains('@')) {
+        anyhow::bail!("Not an email address: {}", invalid);
+    }
+    let mut config = Config::load()?;
+    config.set_account_bcc(id, addresses.clone())?;
+    if addresses.is_empty() {
+        println!("{}", tr("Replies from '{}' are no longer Bcc'd.", &[&id]));
+    } else {
+        println!(
+            "{}",
+            tr(
+                "Replies from '{}' will be Bcc'd to {}.",
+                &[&id, &addresses.join(", ")]
+            )
+        );
+    }
+    Ok(())
+}
+
 fn mask_secret(s: &str) -> String {
-    if s.len() <= 8 {
+    let chars: Vec<char> = s.chars().collect();
+    if chars.len() <= 8 {
         "****".to_string()
     } else {
-        format!("{}...{}", &s[..4], &s[s.len() - 4..])
+        let head: String = chars[..4].iter().collect();
+        let tail: String = chars[chars.len() - 4..].iter().collect();
+        format!("{}...{}", head, tail)
     }
 }
 
@@ -348,172 +1242,3655 @@ fn show_tasks() -> Result<()> {
     Ok(())
 }
 
-fn show_status() -> Result<()> {
-    let config = Config::load()?;
-    let config_dir = Config::config_dir()?;
-
-    println!("Config directory: {}", config_dir.display());
-    println!();
-
-    // Gmail accounts
-    println!("Gmail Accounts:");
-    if config.gmail.accounts.is_empty() {
-        println!("  No accounts configured");
-    } else {
-        for account in &config.gmail.accounts {
-            let is_default = config.gmail.default_account.as_deref() == Some(&account.id);
-            let marker = if is_default { "* " } else { "  " };
-            let default_label = if is_default { " [default]" } else { "" };
-            let email = account.email.as_deref().unwrap_or("(not authenticated)");
-            println!("{}{}: {}{}", marker, account.id, email, default_label);
-        }
-    }
-    println!();
+fn block_sender(entry: Option<&str>) -> Result<()> {
+    let mut blocklist = Blocklist::load()?;
 
-    // AI configuration
-    println!("AI Configuration:");
-    println!(
-        "  API Key: {}",
-        if config.ai.api_key.is_empty() {
-            "Not set"
+    let Some(entry) = entry else {
+        if blocklist.entries.is_empty() {
+            println!("{}", t("No blocked senders."));
         } else {
-            "Set"
+            println!("{}\n", t("⛔ Blocked senders:"));
+            for entry in &blocklist.entries {
+                println!("  {}", entry);
+            }
         }
-    );
-    println!("  Model: {}", config.ai.model_analysis);
-    println!();
+        return Ok(());
+    };
 
-    if !config.is_valid() {
-        println!("Configuration incomplete. Run:");
-        println!();
-        if config.gmail.accounts.is_empty() {
-            println!(
-                "  clinbox account add <id> --client-id <CLIENT_ID> --client-secret <CLIENT_SECRET>"
-            );
-        }
-        if config.ai.api_key.is_empty() {
-            println!("  clinbox config ai.api_key YOUR_OPENROUTER_KEY");
-        }
+    if blocklist.add(entry)? {
+        println!(
+            "{}",
+            tr(
+                "⛔ Blocked {}. Matching mail will be trashed on fetch.",
+                &[&entry]
+            )
+        );
     } else {
-        println!("Configuration complete. Run 'clinbox' to start.");
+        println!("{}", tr("{} is already blocked.", &[&entry]));
     }
+    Ok(())
+}
 
+fn unblock_sender(entry: &str) -> Result<()> {
+    let mut blocklist = Blocklist::load()?;
+    if blocklist.remove(entry)? {
+        println!("{}", tr("✅ Unblocked {}", &[&entry]));
+    } else {
+        println!("{}", tr("{} is not blocked.", &[&entry]));
+    }
     Ok(())
 }
 
-async fn run_interactive(
-    max_emails: u32,
-    include_all: bool,
+async fn export_attachments(
     account_id: Option<&str>,
+    query: &str,
+    out: &Path,
+    max_emails: u32,
 ) -> Result<()> {
     let config = Config::load()?;
+    let account = resolve_account(&config, account_id)?;
+    let gmail = GmailClient::new(account, &config)
+        .await
+        .context("Failed to connect to Gmail")?;
+
+    println!("{}", tr("🔍 Searching '{}'...", &[&query]));
+    let emails = gmail
+        .search(query, max_emails, |done, total| {
+            print_progress("Fetched", done, total)
+        })
+        .await?;
+    println!();
+
+    let total: usize = emails.iter().map(|e| e.attachments.len()).sum();
+    if total == 0 {
+        println!("{}", t("📭 No attachments found."));
+        return Ok(());
+    }
+
+    std::fs::create_dir_all(out).with_context(|| format!("Failed to create {}", out.display()))?;
+
+    let mut saved = 0;
+    for email in &emails {
+        for attachment in &email.attachments {
+            let risks = attachments::risks(attachment);
+            if !risks.is_empty() {
+                println!("⚠️  {}: {}", attachment.filename, risks.join(", "));
+            }
+
+            let result = async {
+                let data = gmail
+                    .fetch_attachment(&email.id, &attachment.attachment_id)
+                    .await?;
+                attachments::save_unique(out, &attachment.filename, &data)
+            }
+            .await;
+
+            match result {
+                Ok(path) => {
+                    saved += 1;
+                    println!("   {}", path.display());
+                }
+                Err(e) => eprintln!("❌ {}: {}", attachment.filename, e),
+            }
+        }
+    }
+    println!();
+
+    println!(
+        "{}",
+        tr(
+            "📎 Saved {} of {} attachments to {}",
+            &[&saved, &total, &out.display()]
+        )
+    );
+    Ok(())
+}
+
+async fn read_email(account_id: Option<&str>, id: &str, headers: bool, raw: bool) -> Result<()> {
+    let config = Config::load()?;
+    let account = resolve_account(&config, account_id)?;
+    let gmail = GmailClient::new(account, &config)
+        .await
+        .context("Failed to connect to Gmail")?;
+
+    if raw {
+        use std::io::Write;
+        let message = gmail.fetch_raw(id).await?;
+        std::io::stdout().write_all(&message)?;
+        return Ok(());
+    }
+
+    let email = eml::with_raw_body(&gmail, &gmail.fetch_email(id).await?).await?;
+    if headers {
+        println!("From: {}", email.from);
+        println!("To: {}", email.to);
+        println!("Subject: {}", email.subject);
+        println!("Date: {}", email.date.to_rfc2822());
+        println!();
+    }
+    println!("{}", email.body_text().trim_end());
+    Ok(())
+}
+
+async fn analyze_file(path: &Path, with_summary: bool) -> Result<()> {
+    let config = Config::load()?;
+    if config.ai.api_key.is_empty() {
+        anyhow::bail!("AI API key not set. Run 'clinbox config ai.api_key <key>'.");
+    }
+
+    let message = eml::load(path)?;
+    let email = &message.email;
+    let ai = AiClient::new(&config)?;
+
+    println!("📧 {}", email.subject);
+    println!("   {}", tr("From: {}", &[&email.from]));
+    println!(
+        "   {}",
+        tr(
+            "Date: {}",
+            &[&email
+                .date
+                .with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M")]
+        )
+    );
+    for attachment in &email.attachments {
+        println!(
+            "   📎 {} ({})",
+            attachment.filename,
+            attachments::format_size(attachment.size)
+        );
+    }
+    for warning in attachments::warnings(email) {
+        println!("   {}", warning);
+    }
+    if let Some(warning) = phishing::warning(email, None) {
+        println!("   {}", warning);
+    }
+    if let Some(notice) = tracking::notice(email) {
+        println!("   {}", notice);
+    }
+    if let Some(shipment) = shipping::detect(email) {
+        println!("   {}", shipment.describe());
+        if let Some(url) = shipment.tracking_url() {
+            println!("      {}", url);
+        }
+    }
+    println!();
+
+    let attachment_text = match attachments::excerpt_candidate(email) {
+        Some(attachment) => match message.data(attachment) {
+            Some(data) => attachments::extract_excerpt(attachment, data.to_vec()).await,
+            None => None,
+        },
+        None => None,
+    };
+
+    println!("{}", t("🤖 Analyzing..."));
+    let mut analysis = ai
+        .analyze_email(email, attachment_text.as_deref(), &[])
+        .await?;
+    Vip::new(&config)?.escalate(email, &mut analysis);
+
+    println!("{}\n", analysis.summary);
+    println!(
+        "{}",
+        tr(
+            "Priority: {} {}{} | Category: {}",
+            &[
+                &analysis.priority.emoji(),
+                &analysis.priority.label(),
+                &analysis.confidence_label(),
+                &analysis.category.label()
+            ]
+        )
+    );
+    if let Some(model) = &analysis.model {
+        println!("{}", tr("Model: {}", &[&model]));
+    }
+    if let Some(action) = &analysis.suggested_action {
+        println!(
+            "{}",
+            tr(
+                "Suggested action: {} (~{} min)",
+                &[&action, &analysis.estimated_time_minutes]
+            )
+        );
+    }
+
+    if with_summary {
+        println!("\n{}", t("🤖 Generating comprehensive summary..."));
+        let summary = ai.summarize_article(email, &config.language).await?;
+        let path = save_summary_markdown(email, &summary)?;
+        println!("{}", tr("📝 Saved: {}", &[&path.display()]));
+    }
+
+    Ok(())
+}
+
+async fn export_messages(
+    account_id: Option<&str>,
+    query: &str,
+    format: ExportFormat,
+    out: &Path,
+    max_emails: u32,
+    delete: bool,
+) -> Result<()> {
+    let config = Config::load()?;
+    let account = resolve_account(&config, account_id)?;
+    let gmail = GmailClient::new(account, &config)
+        .await
+        .context("Failed to connect to Gmail")?;
+
+    println!("{}", tr("🔍 Searching '{}'...", &[&query]));
+    let emails = gmail
+        .search(query, max_emails, |done, total| {
+            print_progress("Fetched", done, total)
+        })
+        .await?;
+    println!();
+
+    if emails.is_empty() {
+        println!("{}", t("📭 No messages match."));
+        return Ok(());
+    }
+
+    let mut mbox = match format {
+        ExportFormat::Mbox => Some(export::Mbox::open(out)?),
+        _ => None,
+    };
+
+    let mut saved = Vec::new();
+    for (i, email) in emails.iter().enumerate() {
+        let result = match &mut mbox {
+            Some(mbox) => mbox.append(&gmail, email).await,
+            None => export::save(&gmail, email, format, out).await.map(|_| ()),
+        };
+        match result {
+            Ok(()) => saved.push(email),
+            Err(e) => eprintln!("\n❌ {}: {}", email.subject, e),
+        }
+        print_progress("Exported", i + 1, emails.len());
+    }
+    println!();
+
+    println!(
+        "{}",
+        tr(
+            "💾 Saved {} of {} messages to {}",
+            &[&saved.len(), &emails.len(), &out.display()]
+        )
+    );
+
+    // Only messages that made it to disk are removed
+    if delete && !saved.is_empty() {
+        let ids: Vec<String> = saved.iter().map(|e| e.id.clone()).collect();
+        let result = gmail.trash_many(&ids).await;
+        println!(
+            "{}",
+            tr(
+                "🗑️  Moved {} exported messages to trash",
+                &[&result.succeeded.len()]
+            )
+        );
+        report_trash_failures(&result);
+    }
+    Ok(())
+}
+
+/// Say how many messages a batch trash left in place, and why
+fn report_trash_failures(result: &BatchResult) {
+    if let Some(error) = result.first_error() {
+        eprintln!(
+            "{}",
+            tr(
+                "⚠️  {} messages couldn't be moved to the trash: {}",
+                &[&result.failed.len(), &error]
+            )
+        );
+    }
+}
+
+async fn run_sweep(
+    account_id: Option<&str>,
+    categories: &[Category],
+    older_than: DateBound,
+    query: Option<&str>,
+    max_emails: u32,
+    yes: bool,
+) -> Result<()> {
+    if categories.is_empty() && query.is_none() {
+        anyhow::bail!("Say what to sweep with --category and/or --query");
+    }
+    let config = Config::load()?;
+    let account = resolve_account(&config, account_id)?;
+    let gmail = GmailClient::new(account, &config)
+        .await
+        .context("Failed to connect to Gmail")?;
+
+    // Category labels are the ones clinbox applies with gmail.category_labels on
+    let mut terms = vec!["in:inbox".to_string()];
+    let labels: Vec<String> = categories
+        .iter()
+        .map(|c| filter::label_query(&format!("clinbox/{}", c.name())))
+        .collect();
+    match labels.len() {
+        0 => {}
+        1 => terms.extend(labels),
+        _ => terms.push(format!("{{{}}}", labels.join(" "))),
+    }
+    terms.push(format!("before:{}", older_than.0.format("%Y/%m/%d")));
+    terms.extend(query.map(String::from));
+    let query = terms.join(" ");
+
+    println!("{}", tr("🔍 Searching '{}'...", &[&query]));
+    let emails = gmail
+        .search_metadata(&query, max_emails, |done, total| {
+            print_progress("Fetched", done, total)
+        })
+        .await?;
+    println!();
+
+    if emails.is_empty() {
+        println!("{}", t("📭 Nothing to sweep."));
+        return Ok(());
+    }
+
+    println!();
+    for email in &emails {
+        println!(
+            "   {}  {}  {}",
+            email.date.with_timezone(&chrono::Local).format("%Y-%m-%d"),
+            text::pad(&email.sender_name(), 30),
+            email.subject
+        );
+    }
+    println!();
+
+    if !yes
+        && !confirm(&format!(
+            "🧹 Archive these {} messages? [y/N] ",
+            emails.len()
+        ))?
+    {
+        println!("{}", t("Nothing archived."));
+        return Ok(());
+    }
+
+    let ids: Vec<String> = emails.into_iter().map(|e| e.id).collect();
+    let result = gmail.archive_many(&ids).await;
+    println!(
+        "{}",
+        tr("📦 Archived {} messages", &[&result.succeeded.len()])
+    );
+    if let Some(error) = result.first_error() {
+        eprintln!(
+            "{}",
+            tr(
+                "⚠️  {} could not be archived: {}",
+                &[&result.failed.len(), &error]
+            )
+        );
+    }
+    Ok(())
+}
+
+fn show_style() -> Result<()> {
+    let path = Config::style_path()?;
+    match std::fs::read_to_string(&path) {
+        Ok(style) => {
+            println!("{}\n", tr("✍️  Writing style ({}):", &[&path.display()]));
+            println!("{}", style.trim());
+        }
+        Err(_) => println!(
+            "{}",
+            t("📭 No style profile yet. Build one with 'clinbox style learn'.")
+        ),
+    }
+    Ok(())
+}
+
+async fn learn_style(account_id: Option<&str>, max_emails: u32) -> Result<()> {
+    let config = Config::load()?;
+    let account = resolve_account(&config, account_id)?;
+    let gmail = GmailClient::new(account, &config)
+        .await
+        .context("Failed to connect to Gmail")?;
+    let ai = AiClient::new(&config)?;
+
+    println!("{}", t("📤 Reading sent mail..."));
+    let sent = gmail
+        .search("in:sent", max_emails, |done, total| {
+            print_progress("Fetched", done, total)
+        })
+        .await?;
+    println!();
+    if sent.is_empty() {
+        println!("{}", t("📭 No sent mail to learn from."));
+        return Ok(());
+    }
+
+    println!("{}", tr("🤖 Studying {} messages...", &[&sent.len()]));
+    let style = ai.learn_style(&sent).await?;
+
+    let path = Config::style_path()?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(&path, format!("{}\n", style))?;
+    println!("\n{}\n", style);
+    println!(
+        "{}",
+        tr(
+            "✅ Saved to {}; reply drafts now follow it. Edit the file to adjust.",
+            &[&path.display()]
+        )
+    );
+    Ok(())
+}
+
+async fn suggest_cleanup(account_id: Option<&str>, max_emails: u32, no_ai: bool) -> Result<()> {
+    let config = Config::load()?;
+    let account = resolve_account(&config, account_id)?;
+    let gmail = GmailClient::new(account, &config)
+        .await
+        .context("Failed to connect to Gmail")?;
+    let feedback = Feedback::load()?;
+
+    println!("{}", t("🔍 Looking through the inbox..."));
+    let inbox = gmail
+        .search_metadata("in:inbox", max_emails, |done, total| {
+            print_progress("Fetched", done, total)
+        })
+        .await?;
+    println!();
+
+    // The journal reaches back further than the per-sender counts
+    let senders = feedback.senders_with(&journal::past_decisions()?);
+    let overview = cleanup::overview(&senders, &inbox);
+    let suggestions = if overview.is_empty() {
+        Vec::new()
+    } else if no_ai || config.ai.api_key.is_empty() {
+        println!(
+            "{}",
+            t("📊 Without the AI, suggesting senders treated the same way 90% of the time.")
+        );
+        cleanup::from_counts(&senders, &inbox)
+    } else {
+        println!("{}", t("🤖 Asking the AI for rules..."));
+        match AiClient::new(&config)?.suggest_cleanup(&overview).await {
+            Ok(proposals) => cleanup::from_proposals(proposals, &senders, &inbox),
+            Err(e) => {
+                eprintln!(
+                    "{}",
+                    tr(
+                        "⚠️  The AI couldn't suggest rules ({}); going by the counts instead.",
+                        &[&format!("{:#}", e)]
+                    )
+                );
+                cleanup::from_counts(&senders, &inbox)
+            }
+        }
+    };
+
+    // Senders that already have a cleanup rule were dealt with on an earlier run
+    let rules_dir = Config::rules_dir()?;
+    let suggestions: Vec<_> = suggestions
+        .into_iter()
+        .filter(|s| !rules_dir.join(s.rule_file_name()).exists())
+        .collect();
+    if suggestions.is_empty() {
+        println!(
+            "{}",
+            t("✨ Nothing stands out yet. Triage a few more sessions and ask again.")
+        );
+        return Ok(());
+    }
+
+    let (mut rules, mut filters) = (0, 0);
+    for suggestion in &suggestions {
+        println!("\n🧹 {}", suggestion.describe());
+        let answer = ask("   [r] clinbox rule  [f] Gmail filter  [q] quit  [Enter] skip: ")?;
+        match answer.as_str() {
+            "r" => {
+                std::fs::create_dir_all(&rules_dir)?;
+                let path = rules_dir.join(suggestion.rule_file_name());
+                std::fs::write(&path, suggestion.rule_script())?;
+                println!("   {}", tr("📜 Wrote {}", &[&path.display()]));
+                rules += 1;
+            }
+            "f" => {
+                let (add, remove) = suggestion.filter_labels();
+                match gmail.create_filter(&suggestion.address, add, remove).await {
+                    Ok(()) => {
+                        println!("   {}", t("✅ Gmail filter created for new mail"));
+                        filters += 1;
+                    }
+                    Err(e) => eprintln!("   ❌ {:#}", e),
+                }
+            }
+            "q" => break,
+            _ => {}
+        }
+    }
+
+    println!(
+        "\n{}",
+        tr(
+            "📜 {} rule(s) and {} Gmail filter(s) created",
+            &[&rules, &filters]
+        )
+    );
+    if rules + filters > 0 {
+        println!(
+            "{}",
+            t(
+                "Mail already in the inbox stays put; clear it with: clinbox sweep --query \"from:<address>\" --older-than 1d"
+            )
+        );
+    }
+    Ok(())
+}
+
+/// Ask a question on the terminal and return the trimmed, lowercased answer
+fn ask(question: &str) -> Result<String> {
+    use std::io::Write;
+
+    print!("{}", i18n::plain(question));
+    std::io::stdout().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(answer.trim().to_lowercase())
+}
+
+/// Ask a yes/no question on the terminal; anything but "y" or "yes" is a no
+fn confirm(question: &str) -> Result<bool> {
+    Ok(matches!(ask(question)?.as_str(), "y" | "yes"))
+}
+
+fn list_rules() -> Result<()> {
+    let rules = Rules::load()?;
+    let dir = Config::rules_dir()?;
+
+    if rules.is_empty() {
+        println!(
+            "{}",
+            tr("No rules yet. Add .rhai scripts to {}", &[&dir.display()])
+        );
+        return Ok(());
+    }
+
+    println!(
+        "{}",
+        tr("📜 Rules in {} (run in this order):", &[&dir.display()])
+    );
+    for name in rules.names() {
+        println!("   {}", name);
+    }
+    Ok(())
+}
+
+/// Dry run: analyses come from the history cache only, so nothing is sent to the AI
+async fn test_rules(account_id: Option<&str>, query: &str, max_emails: u32) -> Result<()> {
+    let config = Config::load()?;
+    let account = resolve_account(&config, account_id)?;
+    let rules = Rules::load()?;
+    if rules.is_empty() {
+        println!(
+            "{}",
+            tr(
+                "No rules yet. Add .rhai scripts to {}",
+                &[&Config::rules_dir()?.display()]
+            )
+        );
+        return Ok(());
+    }
+
+    let gmail = GmailClient::new(account, &config)
+        .await
+        .context("Failed to connect to Gmail")?;
+    let history = History::load()?;
+    let aliases = Aliases::new(&config.aliases, account.email.as_deref());
+
+    println!("{}", tr("🔍 Searching '{}'...", &[&query]));
+    let emails = gmail
+        .search(query, max_emails, |done, total| {
+            print_progress("Fetched", done, total)
+        })
+        .await?;
+    println!("\n");
+
+    for email in &emails {
+        let analysis = history.get(&email.id).and_then(|h| h.analysis.as_ref());
+        let outcome = match rules.evaluate(email, analysis, &aliases) {
+            Ok(Some(m)) => format!("{} by {}", m.action.label(), m.rule),
+            Ok(None) => "no match".to_string(),
+            Err(e) => format!("❌ {}", e),
+        };
+        println!("{:<30} {}", outcome, email.subject);
+    }
+    Ok(())
+}
+
+/// Connect to every account and run `job` on all of them at once. Each account has
+/// its own client and rate limit, so a slow or throttled account doesn't hold up the
+/// others. Results come back in the order of `accounts`.
+async fn across_accounts<'a, T, Fut>(
+    config: &Config,
+    accounts: &[&'a GmailAccount],
+    job: impl Fn(GmailClient, &'a GmailAccount) -> Fut,
+) -> Vec<Result<T>>
+where
+    Fut: std::future::Future<Output = Result<T>>,
+{
+    let jobs = accounts.iter().map(|account| async {
+        let gmail = GmailClient::new(account, config)
+            .await
+            .context("Failed to connect to Gmail")?;
+        job(gmail, account).await
+    });
+    futures::future::join_all(jobs).await
+}
+
+/// Run one query against all accounts in parallel and print the merged hits, newest first
+async fn run_search(account_id: Option<&str>, query: &str, max_emails: u32) -> Result<()> {
+    let config = Config::load()?;
+    let accounts: Vec<&GmailAccount> = match account_id {
+        Some(_) => vec![resolve_account(&config, account_id)?],
+        None => config.gmail.accounts.iter().collect(),
+    };
+    if accounts.is_empty() {
+        anyhow::bail!("No accounts configured. Run 'clinbox account add' to add an account.");
+    }
+
+    println!(
+        "{}",
+        tr(
+            "🔍 Searching '{}' in {} account(s)...",
+            &[&query, &accounts.len()]
+        )
+    );
+    let results = across_accounts(&config, &accounts, |gmail, _| async move {
+        gmail.search(query, max_emails, |_, _| {}).await
+    })
+    .await;
+
+    let mut hits: Vec<(&str, Email)> = Vec::new();
+    let mut failed = 0;
+    for (account, result) in accounts.iter().zip(results) {
+        match result {
+            Ok(emails) => hits.extend(emails.into_iter().map(|e| (account.id.as_str(), e))),
+            Err(e) => {
+                eprintln!("⚠️  {}: {:#}", account.id, e);
+                failed += 1;
+            }
+        }
+    }
+    if failed == accounts.len() {
+        anyhow::bail!("Search failed in every account");
+    }
+
+    if hits.is_empty() {
+        println!("{}", t("No messages found."));
+        return Ok(());
+    }
+    hits.sort_by_key(|(_, email)| std::cmp::Reverse(email.date));
+
+    let width = accounts.iter().map(|a| a.id.len()).max().unwrap_or(0);
+    println!();
+    for (account, email) in &hits {
+        println!(
+            "[{:<width$}] {}  {}  {}",
+            account,
+            email
+                .date
+                .with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M"),
+            text::pad(&email.from, 30),
+            email.subject,
+            width = width
+        );
+        println!("  {:width$}  id {}", "", email.id, width = width);
+    }
+    println!(
+        "\n{}",
+        tr(
+            "{} message(s). Read one with: clinbox read <id> --account <account>",
+            &[&hits.len()]
+        )
+    );
+    Ok(())
+}
+
+async fn run_waiting(account_id: Option<&str>, days: u32, max_threads: u32) -> Result<()> {
+    let config = Config::load()?;
+    let account = resolve_account(&config, account_id)?;
+    let theme = Theme::from_config(&config.ui)?;
+    let keymap = Keymap::from_config(&config.keys)?;
+
+    let gmail = GmailClient::new(account, &config)
+        .await
+        .context("Failed to connect to Gmail")?;
+    let ai = AiClient::new(&config)?;
+    let mut outbox = Outbox::load()?;
+    let contacts = ContactBook::load()?;
+
+    println!(
+        "{}",
+        tr("📤 Checking sent mail from the last {} days...", &[&days])
+    );
+    let mut threads = waiting::find(&gmail, days, max_threads, |done, total| {
+        print_progress("Checked", done, total)
+    })
+    .await?;
+    println!();
+
+    if threads.is_empty() {
+        println!("{}", t("🎉 Nobody owes you a reply."));
+        return Ok(());
+    }
+
+    let mut tui = Tui::new(theme, keymap)?;
+    tui.watch_throttle(&gmail);
+    let mut selected = 0;
+
+    while !threads.is_empty() {
+        selected = selected.min(threads.len() - 1);
+        tui.draw_waiting(&threads, selected)?;
+
+        match tui.wait_for_waiting_action().await? {
+            WaitingAction::Up => selected = selected.saturating_sub(1),
+            WaitingAction::Down => selected += 1,
+            WaitingAction::Open => {
+                let url = format!(
+                    "https://mail.google.com/mail/u/0/#sent/{}",
+                    threads[selected].thread_id
+                );
+                let _ = open::that(&url);
+                tui.toast(t("🌐 Opened in browser"), false)?;
+            }
+            WaitingAction::Quit => break,
+            WaitingAction::Nudge => {
+                let thread = &threads[selected];
+                let sent = match tui
+                    .busy(
+                        t("📥 Loading message..."),
+                        gmail.fetch_email(&thread.message_id),
+                    )
+                    .await?
+                {
+                    Ok(sent) => sent,
+                    Err(e) => {
+                        tui.toast(format!("❌ {}", e), true)?;
+                        continue;
+                    }
+                };
+
+                let Some(result) = tui
+                    .busy_cancellable(t("🤖 Drafting a follow-up..."), ai.generate_nudge(&sent))
+                    .await?
+                else {
+                    continue;
+                };
+                let draft = match result {
+                    Ok(draft) => draft,
+                    Err(e) => {
+                        tui.toast(tr("❌ Failed to generate draft: {}", &[&e]), true)?;
+                        continue;
+                    }
+                };
+
+                // The follow-up goes to whoever my message was addressed to
+                let target = Email {
+                    from: sent.to.clone(),
+                    ..sent
+                };
+                let mut copies = Copies::default();
+                match tui
+                    .review_reply(&target, &draft, &gmail, &contacts, &mut copies)
+                    .await?
+                {
+                    ReplyAction::Send => {
+                        match tui
+                            .busy(
+                                t("📤 Sending..."),
+                                gmail.send_reply(&target, &draft, &copies),
+                            )
+                            .await?
+                        {
+                            Ok(()) => {
+                                tui.toast(t("✅ Follow-up sent"), false)?;
+                                threads.remove(selected);
+                            }
+                            Err(e) => {
+                                let message = queue_failed_reply(
+                                    &gmail,
+                                    &mut outbox,
+                                    &account.id,
+                                    &target,
+                                    &draft,
+                                    &copies,
+                                    &e,
+                                )?;
+                                tui.toast(message, true)?;
+                            }
+                        }
+                    }
+                    ReplyAction::Schedule => {
+                        if schedule_reply(
+                            &mut tui,
+                            &gmail,
+                            &mut outbox,
+                            &account.id,
+                            &target,
+                            &draft,
+                            &copies,
+                        )
+                        .await?
+                        .is_some()
+                        {
+                            tui.toast(t("🕒 Follow-up scheduled"), false)?;
+                            threads.remove(selected);
+                        }
+                    }
+                    ReplyAction::Edit => {
+                        let url = format!(
+                            "https://mail.google.com/mail/u/0/#sent/{}",
+                            thread.thread_id
+                        );
+                        let _ = open::that(&url);
+                        tui.toast(t("📧 Opened in browser for editing"), false)?;
+                    }
+                    ReplyAction::Cancel | ReplyAction::Cc | ReplyAction::Bcc => {}
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_trash(account_id: Option<&str>, max_emails: u32) -> Result<()> {
+    let config = Config::load()?;
+    let account = resolve_account(&config, account_id)?;
+    let theme = Theme::from_config(&config.ui)?;
+    let keymap = Keymap::from_config(&config.keys)?;
+
+    let gmail = GmailClient::new(account, &config)
+        .await
+        .context("Failed to connect to Gmail")?;
+
+    println!("{}", t("🗑️  Loading trash..."));
+    let mut emails = gmail
+        .fetch_label("TRASH", max_emails, |done, total| {
+            print_progress("Fetched", done, total)
+        })
+        .await?;
+    println!();
+
+    if emails.is_empty() {
+        println!("{}", t("🎉 Trash is empty."));
+        return Ok(());
+    }
+
+    let mut tui = Tui::new(theme, keymap)?;
+    tui.watch_throttle(&gmail);
+    let mut selected = 0;
+
+    while !emails.is_empty() {
+        selected = selected.min(emails.len() - 1);
+        tui.draw_trash(&emails, selected)?;
+
+        match tui.wait_for_review_action().await? {
+            ReviewAction::Up => selected = selected.saturating_sub(1),
+            ReviewAction::Down => selected += 1,
+            ReviewAction::Quit => break,
+            ReviewAction::View => {
+                tui.view_full_email(&emails[selected], &gmail).await?;
+            }
+            ReviewAction::Open => {
+                let url = format!(
+                    "https://mail.google.com/mail/u/0/#trash/{}",
+                    emails[selected].id
+                );
+                let _ = open::that(&url);
+                tui.toast(t("🌐 Opened in browser"), false)?;
+            }
+            ReviewAction::Restore => {
+                match tui
+                    .busy(t("♻️  Restoring..."), gmail.untrash(&emails[selected].id))
+                    .await?
+                {
+                    Ok(()) => {
+                        emails.remove(selected);
+                        tui.toast(t("♻️  Restored"), false)?;
+                    }
+                    Err(e) => tui.toast(format!("❌ {}", e), true)?,
+                }
+            }
+            ReviewAction::Delete => {
+                tui.draw_message(
+                    &tr(
+                        "Delete \"{}\" for good? This cannot be undone. [y/n]",
+                        &[&emails[selected].subject],
+                    ),
+                    true,
+                )?;
+                if !tui.wait_for_yes_no().await? {
+                    continue;
+                }
+                match tui
+                    .busy(
+                        t("🔥 Deleting..."),
+                        gmail.delete_permanently(&emails[selected].id),
+                    )
+                    .await?
+                {
+                    Ok(()) => {
+                        emails.remove(selected);
+                        tui.toast(t("🔥 Deleted for good"), false)?;
+                    }
+                    Err(e) => tui.toast(format!("❌ {}", e), true)?,
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_storage(account_id: Option<&str>, larger_than: &str, max_emails: u32) -> Result<()> {
+    let size = larger_than.trim().to_uppercase();
+    let digits = size.trim_end_matches(['K', 'M']);
+    if digits.is_empty()
+        || !digits.chars().all(|c| c.is_ascii_digit())
+        || digits.len() + 1 < size.len()
+    {
+        anyhow::bail!(
+            "Invalid size '{}' (use bytes, or K or M, e.g. 500K or 10M)",
+            larger_than
+        );
+    }
+
+    let config = Config::load()?;
+    let account = resolve_account(&config, account_id)?;
+    let theme = Theme::from_config(&config.ui)?;
+    let keymap = Keymap::from_config(&config.keys)?;
+
+    let gmail = GmailClient::new(account, &config)
+        .await
+        .context("Failed to connect to Gmail")?;
+
+    println!(
+        "{}",
+        tr("💾 Looking for messages larger than {}...", &[&size])
+    );
+    let mut emails = gmail
+        .search(&format!("larger:{}", size), max_emails, |done, total| {
+            print_progress("Fetched", done, total)
+        })
+        .await?;
+    println!();
+
+    if emails.is_empty() {
+        println!("{}", tr("🎉 No messages larger than {}.", &[&size]));
+        return Ok(());
+    }
+    emails.sort_by_key(|e| std::cmp::Reverse(e.size));
+
+    let mut tui = Tui::new(theme, keymap)?;
+    tui.watch_throttle(&gmail);
+    let mut selected = 0;
+    let (mut trashed, mut freed) = (0, 0);
+
+    while !emails.is_empty() {
+        selected = selected.min(emails.len() - 1);
+        tui.draw_storage(&emails, selected)?;
+        let email = &emails[selected];
+
+        match tui.wait_for_storage_action().await? {
+            StorageAction::Up => selected = selected.saturating_sub(1),
+            StorageAction::Down => selected += 1,
+            StorageAction::Quit => break,
+            StorageAction::View => {
+                tui.view_full_email(email, &gmail).await?;
+            }
+            StorageAction::Open => {
+                let url = format!("https://mail.google.com/mail/u/0/#all/{}", email.id);
+                let _ = open::that(&url);
+                tui.toast(t("🌐 Opened in browser"), false)?;
+            }
+            StorageAction::SaveAttachments => {
+                if email.attachments.is_empty() {
+                    tui.toast(t("No attachments to save"), false)?;
+                    continue;
+                }
+                let dir = Config::exports_dir()?;
+                let result = tui
+                    .busy(t("💾 Saving attachments..."), async {
+                        std::fs::create_dir_all(&dir)?;
+                        for attachment in &email.attachments {
+                            let data = gmail
+                                .fetch_attachment(&email.id, &attachment.attachment_id)
+                                .await?;
+                            attachments::save_unique(&dir, &attachment.filename, &data)?;
+                        }
+                        anyhow::Ok(())
+                    })
+                    .await?;
+                match result {
+                    Ok(()) => tui.toast(
+                        tr(
+                            "💾 Saved {} attachments to {}",
+                            &[&email.attachments.len(), &dir.display()],
+                        ),
+                        false,
+                    )?,
+                    Err(e) => tui.toast(format!("❌ {}", e), true)?,
+                }
+            }
+            StorageAction::Archive => {
+                match tui
+                    .busy(t("📦 Archiving..."), gmail.archive(&email.id))
+                    .await?
+                {
+                    Ok(()) => {
+                        emails.remove(selected);
+                        tui.toast(t("📦 Archived (it still counts towards storage)"), false)?;
+                    }
+                    Err(e) => tui.toast(format!("❌ {}", e), true)?,
+                }
+            }
+            StorageAction::Delete => {
+                tui.draw_message(
+                    &tr(
+                        "Move \"{}\" ({}) to trash? [y/n]",
+                        &[&email.subject, &attachments::format_size(email.size)],
+                    ),
+                    true,
+                )?;
+                if !tui.wait_for_yes_no().await? {
+                    continue;
+                }
+                match tui
+                    .busy(t("🗑️ Deleting..."), gmail.delete(&email.id))
+                    .await?
+                {
+                    Ok(()) => {
+                        trashed += 1;
+                        freed += email.size;
+                        emails.remove(selected);
+                        tui.toast(t("🗑️ Deleted"), false)?;
+                    }
+                    Err(e) => tui.toast(format!("❌ {}", e), true)?,
+                }
+            }
+        }
+    }
+
+    drop(tui);
+    if trashed > 0 {
+        println!(
+            "{}",
+            tr(
+                "🗑️  Moved {} messages ({}) to trash; the space is freed when the trash is emptied (after 30 days, or from clinbox trash)",
+                &[&trashed, &attachments::format_size(freed)]
+            )
+        );
+    }
+    Ok(())
+}
+
+async fn run_spam(account_id: Option<&str>, max_emails: u32) -> Result<()> {
+    use futures::StreamExt;
+
+    const CONCURRENCY: usize = 4;
+
+    let config = Config::load()?;
+    if config.ai.api_key.is_empty() {
+        anyhow::bail!("AI API key not configured. Run: clinbox config ai.api_key <your-key>");
+    }
+    let account = resolve_account(&config, account_id)?;
+    let theme = Theme::from_config(&config.ui)?;
+    let keymap = Keymap::from_config(&config.keys)?;
+
+    let gmail = GmailClient::new(account, &config)
+        .await
+        .context("Failed to connect to Gmail")?;
+    let ai = AiClient::new(&config)?;
+
+    println!("{}", t("🚫 Loading spam..."));
+    let emails = gmail
+        .fetch_label("SPAM", max_emails, |done, total| {
+            print_progress("Fetched", done, total)
+        })
+        .await?;
+    println!();
+
+    if emails.is_empty() {
+        println!("{}", t("🎉 Spam folder is empty."));
+        return Ok(());
+    }
+
+    let total = emails.len();
+    let mut done = 0;
+    print_progress("Checked", done, total);
+    let mut results: Vec<(Email, Option<SpamVerdict>)> = Vec::with_capacity(total);
+    let mut checks = futures::stream::iter(emails)
+        .map(|email| async {
+            let verdict = ai.check_spam(&email).await.ok();
+            (email, verdict)
+        })
+        .buffer_unordered(CONCURRENCY);
+    while let Some(result) = checks.next().await {
+        results.push(result);
+        done += 1;
+        print_progress("Checked", done, total);
+    }
+    drop(checks);
+    println!();
+
+    // Possible false positives first, then unchecked, then confirmed spam; newest first within each
+    results.sort_by_key(|(email, verdict)| {
+        let rank = match verdict {
+            Some(v) if v.legit => 0,
+            None => 1,
+            Some(_) => 2,
+        };
+        (rank, std::cmp::Reverse(email.date))
+    });
+    let (mut emails, mut verdicts): (Vec<Email>, Vec<Option<SpamVerdict>>) =
+        results.into_iter().unzip();
+
+    let mut tui = Tui::new(theme, keymap)?;
+    tui.watch_throttle(&gmail);
+    let mut selected = 0;
+
+    while !emails.is_empty() {
+        selected = selected.min(emails.len() - 1);
+        tui.draw_spam(&emails, &verdicts, selected)?;
+
+        match tui.wait_for_review_action().await? {
+            ReviewAction::Up => selected = selected.saturating_sub(1),
+            ReviewAction::Down => selected += 1,
+            ReviewAction::Quit => break,
+            ReviewAction::View => {
+                tui.view_full_email(&emails[selected], &gmail).await?;
+            }
+            ReviewAction::Open => {
+                let url = format!(
+                    "https://mail.google.com/mail/u/0/#spam/{}",
+                    emails[selected].id
+                );
+                let _ = open::that(&url);
+                tui.toast(t("🌐 Opened in browser"), false)?;
+            }
+            ReviewAction::Restore => {
+                match tui
+                    .busy(
+                        t("📥 Moving to inbox..."),
+                        gmail.not_spam(&emails[selected].id),
+                    )
+                    .await?
+                {
+                    Ok(()) => {
+                        emails.remove(selected);
+                        verdicts.remove(selected);
+                        tui.toast(t("📥 Moved to inbox"), false)?;
+                    }
+                    Err(e) => tui.toast(format!("❌ {}", e), true)?,
+                }
+            }
+            ReviewAction::Delete => {
+                tui.draw_message(
+                    &tr(
+                        "Delete \"{}\" for good? This cannot be undone. [y/n]",
+                        &[&emails[selected].subject],
+                    ),
+                    true,
+                )?;
+                if !tui.wait_for_yes_no().await? {
+                    continue;
+                }
+                match tui
+                    .busy(
+                        t("🔥 Deleting..."),
+                        gmail.delete_permanently(&emails[selected].id),
+                    )
+                    .await?
+                {
+                    Ok(()) => {
+                        emails.remove(selected);
+                        verdicts.remove(selected);
+                        tui.toast(t("🔥 Deleted for good"), false)?;
+                    }
+                    Err(e) => tui.toast(format!("❌ {}", e), true)?,
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_drafts(account_id: Option<&str>, max_drafts: u32) -> Result<()> {
+    let config = Config::load()?;
+    let account = resolve_account(&config, account_id)?;
+    let theme = Theme::from_config(&config.ui)?;
+    let keymap = Keymap::from_config(&config.keys)?;
+
+    let gmail = GmailClient::new(account, &config)
+        .await
+        .context("Failed to connect to Gmail")?;
+
+    println!("{}", t("✏️  Loading drafts..."));
+    let mut drafts = gmail.list_drafts(max_drafts).await?;
+
+    if drafts.is_empty() {
+        println!("{}", t("🎉 No drafts."));
+        return Ok(());
+    }
+
+    let mut tui = Tui::new(theme, keymap)?;
+    tui.watch_throttle(&gmail);
+    let mut selected = 0;
+
+    while !drafts.is_empty() {
+        selected = selected.min(drafts.len() - 1);
+        tui.draw_drafts(&drafts, selected)?;
+
+        match tui.wait_for_draft_action().await? {
+            DraftAction::Up => selected = selected.saturating_sub(1),
+            DraftAction::Down => selected += 1,
+            DraftAction::Quit => break,
+            DraftAction::View => {
+                tui.view_full_email(&drafts[selected].message, &gmail)
+                    .await?;
+            }
+            DraftAction::Open => {
+                let url = format!(
+                    "https://mail.google.com/mail/u/0/#drafts?compose={}",
+                    drafts[selected].message.id
+                );
+                let _ = open::that(&url);
+                tui.toast(t("🌐 Opened in browser"), false)?;
+            }
+            DraftAction::Edit => {
+                if drafts[selected].message.body_html.is_some() {
+                    tui.draw_message(
+                        t("This draft has an HTML version, which editing here replaces with plain text (attachments are kept). Edit anyway? [y/n]"),
+                        true,
+                    )?;
+                    if !tui.wait_for_yes_no().await? {
+                        continue;
+                    }
+                }
+                let body = drafts[selected].message.body_text();
+                tui.suspend()?;
+                let edited = editor::edit(&body);
+                tui.resume()?;
+
+                let edited = match edited {
+                    Ok(edited) => edited,
+                    Err(e) => {
+                        tui.toast(format!("❌ {}", e), true)?;
+                        continue;
+                    }
+                };
+                if edited.trim_end() == body.trim_end() {
+                    tui.toast(t("✏️  No changes"), false)?;
+                    continue;
+                }
+                match tui
+                    .busy(
+                        t("💾 Saving draft..."),
+                        gmail.update_draft(&drafts[selected], edited.trim_end()),
+                    )
+                    .await?
+                {
+                    Ok(updated) => {
+                        drafts[selected] = updated;
+                        tui.toast(t("💾 Draft saved"), false)?;
+                    }
+                    Err(e) => tui.toast(format!("❌ {}", e), true)?,
+                }
+            }
+            DraftAction::Send => {
+                let draft = &drafts[selected];
+                tui.draw_message(
+                    &tr(
+                        "Send \"{}\" to {}? [y/n]",
+                        &[&draft.message.subject, &draft.message.to],
+                    ),
+                    false,
+                )?;
+                if !tui.wait_for_yes_no().await? {
+                    continue;
+                }
+                match tui
+                    .busy(t("📤 Sending..."), gmail.send_draft(&draft.id))
+                    .await?
+                {
+                    Ok(()) => {
+                        drafts.remove(selected);
+                        tui.toast(t("✅ Sent"), false)?;
+                    }
+                    Err(e) => tui.toast(format!("❌ {}", e), true)?,
+                }
+            }
+            DraftAction::Discard => {
+                tui.draw_message(
+                    &tr(
+                        "Discard \"{}\"? Drafts don't go to the trash. [y/n]",
+                        &[&drafts[selected].message.subject],
+                    ),
+                    true,
+                )?;
+                if !tui.wait_for_yes_no().await? {
+                    continue;
+                }
+                match tui
+                    .busy(
+                        t("🗑️  Discarding..."),
+                        gmail.discard_draft(&drafts[selected].id),
+                    )
+                    .await?
+                {
+                    Ok(()) => {
+                        drafts.remove(selected);
+                        tui.toast(t("🗑️  Draft discarded"), false)?;
+                    }
+                    Err(e) => tui.toast(format!("❌ {}", e), true)?,
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Browse the read-later list, newest first: read items in the terminal, open
+/// them in Gmail, or drop them once read
+async fn run_later() -> Result<()> {
+    let config = Config::load()?;
+    let theme = Theme::from_config(&config.ui)?;
+    let keymap = Keymap::from_config(&config.keys)?;
+
+    let mut list = ReadLater::load()?;
+    if list.items.is_empty() {
+        println!("{}", t("📚 Nothing saved to read later."));
+        return Ok(());
+    }
+
+    let mut tui = Tui::new(theme, keymap)?;
+    let mut selected = 0;
+
+    loop {
+        let mut items = list.items.clone();
+        items.sort_by_key(|item| std::cmp::Reverse(item.saved_at));
+        if items.is_empty() {
+            break;
+        }
+        selected = selected.min(items.len() - 1);
+        tui.draw_later(&items, selected)?;
+
+        let item = &items[selected];
+        match tui.wait_for_later_action().await? {
+            LaterAction::Up => selected = selected.saturating_sub(1),
+            LaterAction::Down => selected += 1,
+            LaterAction::Quit => break,
+            LaterAction::Read => {
+                let mut text = format!(
+                    "{} {}\n{} {}\n\n",
+                    t("From:"),
+                    item.from,
+                    t("Date:"),
+                    item.date.format("%Y-%m-%d %H:%M")
+                );
+                if let Some(summary) = &item.summary {
+                    text.push_str(&format!("{}\n\n", summary));
+                }
+                text.push_str(&item.content);
+                tui.read_text(&item.subject, &text).await?;
+            }
+            LaterAction::Open => {
+                // Gmail picks the signed-in account by address; without one, the first
+                let user = config
+                    .gmail
+                    .accounts
+                    .iter()
+                    .find(|a| a.id == item.account_id)
+                    .and_then(|a| a.email.as_deref())
+                    .unwrap_or("0");
+                let url = format!(
+                    "https://mail.google.com/mail/u/{}/#all/{}",
+                    user, item.message_id
+                );
+                let _ = open::that(&url);
+                tui.toast(t("🌐 Opened in browser"), false)?;
+            }
+            LaterAction::Done => {
+                list.remove(&item.message_id)?;
+                tui.toast(t("✅ Done, removed from the list"), false)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Resident dashboard: message list, preview, tasks and stats on one screen,
+/// or the reduced list/browse layouts depending on `mode`
+async fn run_dashboard(
+    account_id: Option<&str>,
+    query: &str,
+    max_emails: u32,
+    mode: DashboardMode,
+    filters: &Filters,
+) -> Result<()> {
+    let config = Config::load()?;
+    if !config.is_valid() {
+        eprintln!(
+            "{}",
+            t("Configuration incomplete. Run 'clinbox status' for details.")
+        );
+        std::process::exit(1);
+    }
+    let account = resolve_account(&config, account_id)?;
+    let theme = Theme::from_config(&config.ui)?;
+    let keymap = Keymap::from_config(&config.keys)?;
+
+    println!(
+        "{}",
+        tr(
+            "Connecting to Gmail ({})...",
+            &[&account.email.as_deref().unwrap_or(&account.id)]
+        )
+    );
+    let gmail = Arc::new(
+        GmailClient::new(account, &config)
+            .await
+            .context("Failed to connect to Gmail")?,
+    );
+    send_due_replies(&gmail, &account.id).await?;
+    wake_snoozed(&gmail, &account.id).await?;
+
+    let ai = Arc::new(AiClient::new(&config)?);
+    let mut task_store = TaskStore::load()?;
+    let mut history = History::load()?;
+    let mut contacts = ContactBook::load()?;
+    let mut outbox = Outbox::load()?;
+    let vip = Vip::new(&config)?;
+    let aliases = Aliases::new(&config.aliases, account.email.as_deref());
+    let hooks = Hooks::new(&config.hooks);
+    let mut feedback = Feedback::load()?;
+    let adjust = |email: &Email, analysis: &mut EmailAnalysis| {
+        aliases.apply(email, analysis);
+        vip.escalate(email, analysis);
+    };
+
+    println!("{}", tr("📥 Fetching '{}'...", &[&query]));
+    let emails = gmail
+        .search_metadata(query, max_emails, |done, total| {
+            print_progress("Fetched", done, total)
+        })
+        .await?;
+    println!();
+
+    let emails: Vec<_> = emails
+        .into_iter()
+        .filter(|e| !filters.unseen || history.get(&e.id).is_none())
+        .collect();
+    let pending_tasks = task_store.pending().into_iter().cloned().collect();
+    let mut dashboard = Dashboard::new(query, emails, pending_tasks, mode);
+    // Filters on the analysis need every message analyzed, not just those in view
+    dashboard.prefetch_all = filters.by_analysis() && !dashboard.read_only();
+    if config.gmail.storage_quota && mode == DashboardMode::Full {
+        dashboard.storage = gmail
+            .storage_quota()
+            .await
+            .inspect_err(|e| tracing::warn!("Failed to read storage quota: {:#}", e))
+            .ok();
+    }
+    let (analyzed_tx, mut analyzed) = tokio::sync::mpsc::unbounded_channel();
+    let (bodies_tx, mut bodies) = tokio::sync::mpsc::unbounded_channel();
+    queue_analyses(&mut dashboard, &history, &adjust, &ai, &gmail, &analyzed_tx);
+    // Category/priority filters hide messages as their analysis comes in
+    dashboard.retain_analyzed(|a| filters.matches(a));
+
+    let mut tui = Tui::new(theme, keymap)?;
+    tui.watch_throttle(&gmail);
+    let mut background: JoinSet<Result<()>> = JoinSet::new();
+    // Archive, delete or mark-read held back for the undo window, with the message
+    let mut pending: Option<(PendingChange, Email)> = None;
+
+    loop {
+        prefetch_bodies(&gmail, dashboard.wanted_bodies(), &bodies_tx);
+        tui.draw_dashboard(&dashboard)?;
+
+        let commit_at = pending.as_ref().map(|(change, _)| change.commit_at);
+        let action = tokio::select! {
+            action = tui.wait_for_dashboard_action() => action?,
+            _ = tokio::time::sleep_until(commit_at.unwrap_or_else(tokio::time::Instant::now)), if commit_at.is_some() => {
+                commit_held(
+                    &mut pending,
+                    &dashboard,
+                    &mut feedback,
+                    &mut history,
+                    &mut background,
+                    &gmail,
+                    &hooks,
+                    config.gmail.category_labels,
+                )?;
+                continue;
+            }
+            Some((id, result)) = bodies.recv() => {
+                match result {
+                    Ok(email) => {
+                        // Messages are analyzed once their body is in
+                        if !dashboard.analyses.contains_key(&id) && !dashboard.read_only() {
+                            analyze_in_background(&ai, &gmail, vec![email.clone()], &analyzed_tx);
+                        }
+                        dashboard.set_body(email);
+                    }
+                    // Retried on the next refresh, or fetched on demand when acted on
+                    Err(e) => tracing::warn!("Failed to prefetch message {}: {:#}", id, e),
+                }
+                continue;
+            }
+            Some((id, result)) = analyzed.recv() => {
+                match result {
+                    Ok(mut analysis) => {
+                        if let Some(email) = dashboard.emails.iter().find(|e| e.id == id) {
+                            adjust(email, &mut analysis);
+                            dashboard.analyses.insert(id, analysis);
+                            dashboard.retain_analyzed(|a| filters.matches(a));
+                        }
+                    }
+                    Err(e) => tui.toast(tr("AI analysis failed: {}", &[&e]), true)?,
+                }
+                continue;
+            }
+            Some(result) = background.join_next() => {
+                report_background_result(&mut tui, result)?;
+                continue;
+            }
+        };
+
+        // Moving around keeps the undo window open; anything else closes it
+        if !matches!(
+            action,
+            DashboardAction::Up
+                | DashboardAction::Down
+                | DashboardAction::NextPane
+                | DashboardAction::PrevPane
+                | DashboardAction::Triage(Action::Undo)
+        ) {
+            commit_held(
+                &mut pending,
+                &dashboard,
+                &mut feedback,
+                &mut history,
+                &mut background,
+                &gmail,
+                &hooks,
+                config.gmail.category_labels,
+            )?;
+        }
+
+        let action = match action {
+            DashboardAction::Up => {
+                dashboard.move_by(-1);
+                continue;
+            }
+            DashboardAction::Down => {
+                dashboard.move_by(1);
+                continue;
+            }
+            DashboardAction::NextPane => {
+                dashboard.next_pane();
+                continue;
+            }
+            DashboardAction::PrevPane => {
+                dashboard.prev_pane();
+                continue;
+            }
+            DashboardAction::Refresh => {
+                match tui
+                    .busy(
+                        t("📥 Refreshing..."),
+                        gmail.search_metadata(&dashboard.query, max_emails, |_, _| {}),
+                    )
+                    .await?
+                {
+                    Ok(emails) => {
+                        dashboard.set_emails(
+                            emails
+                                .into_iter()
+                                .filter(|e| !filters.unseen || history.get(&e.id).is_none())
+                                .collect(),
+                        );
+                        queue_analyses(
+                            &mut dashboard,
+                            &history,
+                            &adjust,
+                            &ai,
+                            &gmail,
+                            &analyzed_tx,
+                        );
+                        dashboard.retain_analyzed(|a| filters.matches(a));
+                    }
+                    Err(e) => tui.toast(format!("❌ {}", e), true)?,
+                }
+                task_store = TaskStore::load()?;
+                dashboard.set_tasks(task_store.pending().into_iter().cloned().collect());
+                continue;
+            }
+            DashboardAction::Search => {
+                let Some(query) = tui
+                    .prompt_with(
+                        &format!(" {} ", t("Search")),
+                        t("Gmail search for the message list"),
+                        &dashboard.query,
+                    )
+                    .await?
+                    .filter(|q| !q.trim().is_empty())
+                else {
+                    continue;
+                };
+                match tui
+                    .busy(
+                        t("🔍 Searching..."),
+                        gmail.search_metadata(&query, max_emails, |_, _| {}),
+                    )
+                    .await?
+                {
+                    Ok(emails) => {
+                        dashboard.query = query;
+                        dashboard.set_emails(
+                            emails
+                                .into_iter()
+                                .filter(|e| !filters.unseen || history.get(&e.id).is_none())
+                                .collect(),
+                        );
+                        queue_analyses(
+                            &mut dashboard,
+                            &history,
+                            &adjust,
+                            &ai,
+                            &gmail,
+                            &analyzed_tx,
+                        );
+                        dashboard.retain_analyzed(|a| filters.matches(a));
+                    }
+                    Err(e) => tui.toast(format!("❌ {}", e), true)?,
+                }
+                continue;
+            }
+            DashboardAction::Select if dashboard.focus == Pane::Tasks => {
+                if let Some(task) = dashboard.selected_task() {
+                    task_store.complete(&task.id.clone())?;
+                    dashboard.set_tasks(task_store.pending().into_iter().cloned().collect());
+                    tui.toast(t("✅ Task completed"), false)?;
+                }
+                continue;
+            }
+            DashboardAction::Select => Action::ViewFull,
+            DashboardAction::Triage(Action::Quit) => break,
+            DashboardAction::Triage(Action::Undo) => {
+                match pending.take() {
+                    Some((change, email)) => {
+                        dashboard.stats.undo(&change.kind);
+                        dashboard.restore(change.index, email);
+                        tui.toast(t("↩️ Undone"), false)?;
+                    }
+                    None => tui.toast(t("Nothing to undo"), false)?,
+                }
+                continue;
+            }
+            DashboardAction::Triage(Action::Delete) if dashboard.focus == Pane::Tasks => {
+                if let Some(task) = dashboard.selected_task() {
+                    task_store.delete(&task.id.clone())?;
+                    dashboard.set_tasks(task_store.pending().into_iter().cloned().collect());
+                    tui.toast(t("🗑️ Task deleted"), false)?;
+                }
+                continue;
+            }
+            DashboardAction::Triage(_) if dashboard.focus == Pane::Tasks => continue,
+            DashboardAction::Triage(action) => action,
+        };
+
+        let Some(mut email) = dashboard.selected_email().cloned() else {
+            continue;
+        };
+        if !dashboard.is_loaded(&email) {
+            match tui
+                .busy(t("📥 Loading message..."), gmail.fetch_email(&email.id))
+                .await?
+            {
+                Ok(full) => {
+                    dashboard.set_body(full.clone());
+                    email = full;
+                }
+                Err(e) => {
+                    tui.toast(format!("❌ {}", e), true)?;
+                    continue;
+                }
+            }
+        }
+        let analysis = dashboard.analysis(&email).cloned();
+
+        let changes_mail = matches!(
+            action,
+            Action::Archive
+                | Action::Delete
+                | Action::MarkRead
+                | Action::Task
+                | Action::ReadLater
+                | Action::Reply
+        );
+        if changes_mail && dashboard.read_only() {
+            tui.toast(t("👀 Browsing is read-only"), false)?;
+            continue;
+        }
+
+        match action {
+            Action::Archive | Action::Delete => {
+                let index = dashboard.selected;
+                dashboard.remove_selected();
+                let (kind, message) = if action == Action::Archive {
+                    dashboard.stats.archived += 1;
+                    (PendingKind::Archive, "{}✅ Archived — press {} to undo")
+                } else {
+                    dashboard.stats.deleted += 1;
+                    (PendingKind::Delete, "{}🗑️ Deleted — press {} to undo")
+                };
+                pending = Some((PendingChange::new(index, kind), email));
+                tui.toast_for(
+                    tr(message, &[&"", &tui.key_label(Action::Undo)]),
+                    UNDO_WINDOW,
+                )?;
+            }
+            Action::MarkRead => {
+                if !email.is_unread {
+                    continue;
+                }
+                if let Some(listed) = dashboard.emails.get_mut(dashboard.selected) {
+                    listed.is_unread = false;
+                }
+                let change = PendingChange::new(dashboard.selected, PendingKind::MarkRead);
+                pending = Some((change, email));
+                dashboard.stats.marked_read += 1;
+                tui.toast_for(
+                    tr(
+                        "{}👁️ Marked read — press {} to undo",
+                        &[&"", &tui.key_label(Action::Undo)],
+                    ),
+                    UNDO_WINDOW,
+                )?;
+            }
+            Action::Task => {
+                let title = analysis
+                    .as_ref()
+                    .and_then(|a| a.suggested_action.clone())
+                    .unwrap_or_else(|| email.subject.clone());
+                tui.draw_task_input(&title, &email.subject)?;
+                if !tui.wait_for_confirm().await? {
+                    continue;
+                }
+
+                task_store.add(
+                    title,
+                    Some(
+                        analysis
+                            .as_ref()
+                            .map(|a| a.summary.clone())
+                            .unwrap_or_default(),
+                    ),
+                    Some(email.id.clone()),
+                    Some(email.subject.clone()),
+                )?;
+                dashboard.set_tasks(task_store.pending().into_iter().cloned().collect());
+                dashboard.remove_selected();
+                archive_in_background(&mut background, &gmail, &email.id);
+                history.forget(&email.id)?;
+                hooks.fire(
+                    &mut background,
+                    HookEvent::TaskCreated,
+                    &email,
+                    analysis.as_ref(),
+                );
+                dashboard.stats.tasks_created += 1;
+                feedback.record(&Decision::new(&email, analysis.as_ref(), "task created"));
+                tui.toast(t("📝 Task created & email archived"), false)?;
+            }
+            Action::ReadLater => {
+                let index = dashboard.selected;
+                dashboard.remove_selected();
+                let item = later::Item::new(&email, analysis.as_ref(), &account.id);
+                let kind = PendingKind::ReadLater(Box::new(item));
+                pending = Some((PendingChange::new(index, kind), email));
+                dashboard.stats.read_later += 1;
+                tui.toast_for(
+                    tr(
+                        "{}📚 Saved to read later — press {} to undo",
+                        &[&"", &tui.key_label(Action::Undo)],
+                    ),
+                    UNDO_WINDOW,
+                )?;
+            }
+            Action::Reply => {
+                let Some(result) = tui
+                    .busy_cancellable(
+                        t("🤖 Generating reply draft..."),
+                        draft_reply(&ai, &gmail, &email, &[]),
+                    )
+                    .await?
+                else {
+                    continue;
+                };
+                let draft = match result {
+                    Ok(draft) => draft,
+                    Err(e) => {
+                        tui.toast(tr("❌ Failed to generate draft: {}", &[&e]), true)?;
+                        continue;
+                    }
+                };
+
+                let mut copies = Copies::default();
+                let sent = match tui
+                    .review_reply(&email, &draft, &gmail, &contacts, &mut copies)
+                    .await?
+                {
+                    ReplyAction::Send => {
+                        match tui
+                            .busy(
+                                t("📤 Sending..."),
+                                gmail.send_reply(&email, &draft, &copies),
+                            )
+                            .await?
+                        {
+                            Ok(()) => Some(t("✅ Reply sent & archived").to_string()),
+                            Err(e) => {
+                                let message = queue_failed_reply(
+                                    &gmail,
+                                    &mut outbox,
+                                    &account.id,
+                                    &email,
+                                    &draft,
+                                    &copies,
+                                    &e,
+                                )?;
+                                tui.toast(message, true)?;
+                                None
+                            }
+                        }
+                    }
+                    ReplyAction::Schedule => schedule_reply(
+                        &mut tui,
+                        &gmail,
+                        &mut outbox,
+                        &account.id,
+                        &email,
+                        &draft,
+                        &copies,
+                    )
+                    .await?
+                    .map(|at| {
+                        tr(
+                            "🕒 Reply scheduled for {} & archived",
+                            &[&at.with_timezone(&chrono::Local).format("%a %Y-%m-%d %H:%M")],
+                        )
+                    }),
+                    ReplyAction::Edit => {
+                        let url = format!("https://mail.google.com/mail/u/0/#inbox/{}", email.id);
+                        let _ = open::that(&url);
+                        tui.toast(t("📧 Opened in browser for editing"), false)?;
+                        None
+                    }
+                    ReplyAction::Cancel | ReplyAction::Cc | ReplyAction::Bcc => None,
+                };
+
+                if let Some(message) = sent {
+                    contacts.record_reply(&email)?;
+                    dashboard.remove_selected();
+                    archive_in_background(&mut background, &gmail, &email.id);
+                    history.forget(&email.id)?;
+                    hooks.fire(&mut background, HookEvent::Reply, &email, analysis.as_ref());
+                    dashboard.stats.replied += 1;
+                    feedback.record(&Decision::new(&email, analysis.as_ref(), "replied"));
+                    tui.toast(message, false)?;
+                }
+            }
+            Action::Open => {
+                let mut warnings = attachments::warnings(&email);
+                warnings.extend(phishing::warning(&email, analysis.as_ref()));
+                if !warnings.is_empty() {
+                    tui.draw_message(
+                        &tr(
+                            "{}\n\nOpen in browser anyway? [y/n]",
+                            &[&warnings.join("\n")],
+                        ),
+                        true,
+                    )?;
+                    if !tui.wait_for_yes_no().await? {
+                        continue;
+                    }
+                }
+                let url = format!("https://mail.google.com/mail/u/0/#inbox/{}", email.id);
+                let _ = open::that(&url);
+                tui.toast(t("🌐 Opened in browser"), false)?;
+            }
+            Action::ViewFull => tui.view_full_email(&email, &gmail).await?,
+            Action::Links => show_links(&mut tui, &config, &email).await?,
+            Action::CopyCode => copy_code(&mut tui, &email)?,
+            Action::Expense => {
+                record_expense(&mut tui, &ai, &gmail, &email, analysis.as_ref()).await?
+            }
+            Action::Reasoning => tui.toggle_reasoning(),
+            Action::Reanalyze => {
+                let Some(result) = tui
+                    .busy_cancellable(
+                        &tr("🧠 Re-analyzing with {}...", &[&ai.escalation_model()]),
+                        reanalyze(&ai, &gmail, &email),
+                    )
+                    .await?
+                else {
+                    continue;
+                };
+                match result {
+                    Ok(mut a) => {
+                        adjust(&email, &mut a);
+                        dashboard.analyses.insert(email.id.clone(), a);
+                        tui.toast(t("🧠 Re-analyzed"), false)?;
+                    }
+                    Err(e) => tui.toast(tr("❌ Re-analysis failed: {}", &[&e]), true)?,
+                }
+            }
+            Action::Export => {
+                tui.draw_message(t("Export as [e]ml, [h]tml or [p]df? (Esc cancels)"), false)?;
+                let format = match tui.next_key().await?.code {
+                    KeyCode::Char('e') => ExportFormat::Eml,
+                    KeyCode::Char('h') => ExportFormat::Html,
+                    KeyCode::Char('p') => ExportFormat::Pdf,
+                    _ => continue,
+                };
+
+                let dir = Config::exports_dir()?;
+                match tui
+                    .busy(
+                        t("💾 Exporting..."),
+                        export::save(&gmail, &email, format, &dir),
+                    )
+                    .await?
+                {
+                    Ok(path) => tui.toast(tr("💾 Saved {}", &[&path.display()]), false)?,
+                    Err(e) => tui.toast(tr("❌ Export failed: {}", &[&e]), true)?,
+                }
+            }
+            _ => {}
+        }
+    }
+
+    commit_held(
+        &mut pending,
+        &dashboard,
+        &mut feedback,
+        &mut history,
+        &mut background,
+        &gmail,
+        &hooks,
+        config.gmail.category_labels,
+    )?;
+    finish_background(&mut tui, &mut background).await?;
+    drop(tui);
+    feedback.save()?;
+    Ok(())
+}
+
+/// Fill in cached analyses and start analyzing the rest of the listed emails
+fn queue_analyses(
+    dashboard: &mut Dashboard,
+    history: &History,
+    adjust: &impl Fn(&Email, &mut EmailAnalysis),
+    ai: &Arc<AiClient>,
+    gmail: &Arc<GmailClient>,
+    tx: &tokio::sync::mpsc::UnboundedSender<(String, Result<EmailAnalysis>)>,
+) {
+    let mut missing = Vec::new();
+    for email in &dashboard.emails {
+        if dashboard.analyses.contains_key(&email.id) {
+            continue;
+        }
+        let cached = history
+            .get(&email.id)
+            .and_then(|h| h.analysis.clone())
+            .filter(|a| !heuristics::is_guess(a));
+        match cached {
+            Some(mut analysis) => {
+                adjust(email, &mut analysis);
+                dashboard.analyses.insert(email.id.clone(), analysis);
+            }
+            // The rest are analyzed as their bodies arrive
+            None if dashboard.is_loaded(email) => missing.push(email.clone()),
+            None => {}
+        }
+    }
+    // Browsing shows cached analyses but doesn't spend AI calls on new ones
+    if !dashboard.read_only() {
+        analyze_in_background(ai, gmail, missing, tx);
+    }
+}
+
+fn show_expenses(month: Option<&str>) -> Result<()> {
+    let entries = expenses::load()?;
+    let mut shown: Vec<&expenses::Entry> = entries
+        .iter()
+        .filter(|e| month.is_none_or(|m| e.date.format("%Y-%m").to_string() == m))
+        .collect();
+    shown.sort_by_key(|e| e.date);
+
+    if shown.is_empty() {
+        match month {
+            Some(month) => println!(
+                "{}",
+                tr(
+                    "📭 No expenses recorded for {}. Press $ on an invoice during triage to add one.",
+                    &[&month]
+                )
+            ),
+            None => println!(
+                "{}",
+                t("📭 No expenses recorded. Press $ on an invoice during triage to add one.")
+            ),
+        }
+        return Ok(());
+    }
+
+    println!(
+        "{}\n",
+        tr("🧾 Expenses ({}):", &[&Config::expenses_path()?.display()])
+    );
+    for entry in &shown {
+        println!("  {}", entry.describe());
+    }
+    println!();
+    for (currency, total) in expenses::totals(shown) {
+        println!(
+            "  {}",
+            tr("Total: {} {}", &[&format!("{:.2}", total), &currency])
+        );
+    }
+    Ok(())
+}
+
+fn show_feedback(limit: usize) -> Result<()> {
+    let feedback = Feedback::load()?;
+
+    let Some(agreement) = feedback.agreement() else {
+        println!("{}", t("📭 No triage decisions recorded yet."));
+        return Ok(());
+    };
+    println!(
+        "{}\n",
+        tr(
+            "🎯 Agreed with the AI on {}% of {} decisions ({} disagreements)",
+            &[
+                &format!("{:.0}", agreement * 100.0),
+                &feedback.judged,
+                &feedback.disagreed
+            ]
+        )
+    );
+
+    let confidences: Vec<f32> = feedback
+        .disagreements
+        .iter()
+        .filter_map(|d| d.confidence)
+        .collect();
+    if !confidences.is_empty() {
+        let average = confidences.iter().sum::<f32>() / confidences.len() as f32 * 100.0;
+        println!(
+            "{}\n",
+            tr(
+                "Average AI confidence when you disagreed: {}%",
+                &[&format!("{:.0}", average)]
+            )
+        );
+    }
+
+    for d in feedback.disagreements.iter().rev().take(limit) {
+        let confidence = d
+            .confidence
+            .map(|c| format!(" ({:.0}%)", c * 100.0))
+            .unwrap_or_default();
+        println!(
+            "{}  {} {}{} → {}  {} ({})",
+            d.at.with_timezone(&chrono::Local).format("%Y-%m-%d"),
+            d.priority.emoji(),
+            d.priority.label(),
+            confidence,
+            d.action,
+            d.subject,
+            d.from
+        );
+    }
+    Ok(())
+}
+
+fn show_outbox() -> Result<()> {
+    let outbox = Outbox::load()?;
+
+    if outbox.replies.is_empty() {
+        println!("{}", t("📭 The outbox is empty."));
+        return Ok(());
+    }
+
+    println!("{}\n", t("📮 Outbox:"));
+    for reply in &outbox.replies {
+        println!(
+            "  [{}] {} → {} ({})",
+            reply.id,
+            reply
+                .send_at
+                .with_timezone(&chrono::Local)
+                .format("%a %Y-%m-%d %H:%M"),
+            reply.to,
+            reply.account_id
+        );
+        println!("     Re: {}", reply.subject);
+        if !reply.cc.is_empty() {
+            println!("     Cc: {}", reply.cc.join(", "));
+        }
+        if !reply.bcc.is_empty() {
+            println!("     Bcc: {}", reply.bcc.join(", "));
+        }
+        if let Some(error) = &reply.last_error {
+            println!("     {}", tr("⚠️  Not sent yet: {}", &[error]));
+        }
+        if reply.held {
+            println!(
+                "     {}",
+                tr(
+                    "⏸  Held after {} failed attempts; `clinbox outbox flush` tries again",
+                    &[&reply.attempts]
+                )
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Ask when to send a reply and queue it; None if cancelled or the time was invalid
+async fn schedule_reply(
+    tui: &mut Tui,
+    gmail: &GmailClient,
+    outbox: &mut Outbox,
+    account_id: &str,
+    email: &Email,
+    draft: &str,
+    copies: &Copies,
+) -> Result<Option<chrono::DateTime<chrono::Utc>>> {
+    let Some(when) = tui
+        .prompt(
+            &format!(" {} ", t("Send Later")),
+            t("Send at (2h, 17:30, tomorrow 9:00, 2024-07-01 09:00):"),
+        )
+        .await?
+    else {
+        return Ok(None);
+    };
+
+    let scheduled = outbox::parse_send_at(&when).and_then(|at| {
+        outbox.schedule(
+            account_id,
+            email,
+            &gmail.reply_body(email, draft),
+            copies,
+            at,
+        )?;
+        Ok(at)
+    });
+    match scheduled {
+        Ok(at) => Ok(Some(at)),
+        Err(e) => {
+            tui.toast(format!("❌ {}", e), true)?;
+            Ok(None)
+        }
+    }
+}
+
+/// Queue a reply whose send failed for a passing reason (network, 429, 5xx) so
+/// the next session (or `outbox flush`) retries it, quoted as it would have been
+/// sent; a permanent error queues nothing. Returns the message to show.
+fn queue_failed_reply(
+    gmail: &GmailClient,
+    outbox: &mut Outbox,
+    account_id: &str,
+    email: &Email,
+    draft: &str,
+    copies: &Copies,
+    error: &anyhow::Error,
+) -> Result<String> {
+    if !is_transient(error) {
+        return Ok(tr("❌ Failed to send reply: {}", &[error]));
+    }
+    outbox.queue_failed(
+        account_id,
+        email,
+        &gmail.reply_body(email, draft),
+        copies,
+        &error.to_string(),
+    )?;
+    Ok(tr(
+        "📮 Couldn't send ({}); reply queued in the outbox",
+        &[error],
+    ))
+}
+
+/// Move an account's messages whose snooze is over back to the inbox, unread
+async fn wake_snoozed(gmail: &GmailClient, account_id: &str) -> Result<()> {
+    let mut snoozes = SnoozeList::load()?;
+    let due: Vec<String> = snoozes
+        .due(account_id)
+        .into_iter()
+        .map(|m| m.message_id)
+        .collect();
+    if due.is_empty() {
+        return Ok(());
+    }
+
+    let result = gmail.batch_modify(&due, &["INBOX", "UNREAD"], &[]).await;
+    if !result.succeeded.is_empty() {
+        println!(
+            "{}",
+            tr(
+                "💤 {} snoozed messages back in the inbox",
+                &[&result.succeeded.len()]
+            )
+        );
+    }
+    if let Some(error) = result.first_error() {
+        eprintln!(
+            "{}",
+            tr(
+                "⚠️  {} snoozed messages stay snoozed: {}",
+                &[&result.failed.len(), &error]
+            )
+        );
+    }
+    snoozes.remove(&result.succeeded)
+}
+
+/// Send queued replies that are due for one account, dropping each once sent
+async fn send_due_replies(gmail: &GmailClient, account_id: &str) -> Result<()> {
+    let mut outbox = Outbox::load()?;
+    let results = send_queued(gmail, outbox.due(account_id, false)).await;
+    record_sends(&mut outbox, results)
+}
+
+/// Try to send each reply, returning how each went
+async fn send_queued(
+    gmail: &GmailClient,
+    replies: Vec<QueuedReply>,
+) -> Vec<(QueuedReply, Result<()>)> {
+    let mut results = Vec::new();
+    for reply in replies {
+        let result = gmail
+            .send_reply_to(
+                &reply.to,
+                &reply.subject,
+                &reply.reply_to_id,
+                &reply.thread_id,
+                &reply.body,
+                &reply.copies(),
+            )
+            .await;
+        results.push((reply, result));
+    }
+    results
+}
+
+/// Drop sent replies from the outbox and note why the others failed; a reply
+/// that failed for good, or too many times, is held for `outbox flush`
+fn record_sends(outbox: &mut Outbox, results: Vec<(QueuedReply, Result<()>)>) -> Result<()> {
+    for (reply, result) in results {
+        match result {
+            Ok(()) => {
+                outbox.remove(&reply.id)?;
+                println!(
+                    "{}",
+                    tr(
+                        "📤 Sent queued reply to {} ({})",
+                        &[&reply.to, &reply.subject]
+                    )
+                );
+            }
+            Err(e) => {
+                if outbox.mark_failed(&reply.id, &e.to_string(), is_transient(&e))? {
+                    eprintln!(
+                        "{}",
+                        tr(
+                            "❌ Failed to send queued reply to {}: {} (held in the outbox; retry with `clinbox outbox flush`)",
+                            &[&reply.to, &e]
+                        )
+                    );
+                } else {
+                    eprintln!(
+                        "{}",
+                        tr(
+                            "❌ Failed to send queued reply to {}: {} (kept in the outbox)",
+                            &[&reply.to, &e]
+                        )
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Send what's due in every account at once
+async fn send_all_due_replies() -> Result<()> {
+    let config = Config::load()?;
+    let mut outbox = Outbox::load()?;
+
+    let mut account_ids: Vec<String> = outbox
+        .replies
+        .iter()
+        .filter(|r| r.send_at <= chrono::Utc::now())
+        .map(|r| r.account_id.clone())
+        .collect();
+    account_ids.sort();
+    account_ids.dedup();
+
+    if account_ids.is_empty() {
+        println!("{}", t("Nothing due to send."));
+        return Ok(());
+    }
+
+    let mut accounts = Vec::new();
+    for account_id in &account_ids {
+        match config.get_account(account_id) {
+            Some(account) => accounts.push(account),
+            None => eprintln!(
+                "{}",
+                tr(
+                    "⚠️  Account '{}' no longer exists; its replies stay queued",
+                    &[&account_id]
+                )
+            ),
+        }
+    }
+
+    let outbox_ref = &outbox;
+    let results = across_accounts(&config, &accounts, |gmail, account| async move {
+        let due = outbox_ref.due(&account.id, true);
+        Ok(send_queued(&gmail, due).await)
+    })
+    .await;
+    for (account, result) in accounts.iter().zip(results) {
+        match result {
+            Ok(sends) => record_sends(&mut outbox, sends)?,
+            Err(e) => eprintln!("⚠️  {}: {:#}", account.id, e),
+        }
+    }
+
+    Ok(())
+}
+
+fn show_contacts(query: &str, limit: usize) -> Result<()> {
+    let book = ContactBook::load()?;
+    let matches = book.suggest(query, limit);
+
+    if matches.is_empty() {
+        println!(
+            "{}",
+            t("No matching contacts. Contacts are collected from mail seen during triage.")
+        );
+        return Ok(());
+    }
+
+    println!("{}\n", t("👤 Contacts:"));
+    for contact in matches {
+        println!("  {}", contact.display());
+        println!("     {}", contact.describe());
+    }
+
+    Ok(())
+}
+
+async fn show_status() -> Result<()> {
+    let config = Config::load()?;
+    let config_dir = Config::config_dir()?;
+
+    println!("Config directory: {}", config_dir.display());
+    println!();
+
+    // Storage use, for accounts that have signed in
+    let accounts: Vec<&GmailAccount> = config
+        .gmail
+        .accounts
+        .iter()
+        .filter(|a| config.gmail.storage_quota && a.email.is_some())
+        .collect();
+    let quotas = across_accounts(&config, &accounts, |gmail, _| async move {
+        gmail.storage_quota().await
+    })
+    .await;
+    let quotas: HashMap<&str, Result<StorageQuota>> =
+        accounts.iter().map(|a| a.id.as_str()).zip(quotas).collect();
+
+    // Gmail accounts
+    println!("Gmail Accounts:");
+    if config.gmail.accounts.is_empty() {
+        println!("  No accounts configured");
+    } else {
+        for account in &config.gmail.accounts {
+            let is_default = config.gmail.default_account.as_deref() == Some(&account.id);
+            let marker = if is_default { "* " } else { "  " };
+            let default_label = if is_default { " [default]" } else { "" };
+            let email = account.email.as_deref().unwrap_or("(not authenticated)");
+            println!("{}{}: {}{}", marker, account.id, email, default_label);
+            match quotas.get(account.id.as_str()) {
+                Some(Ok(quota)) => {
+                    println!("    {}", tr("Storage: {}", &[&tui::format_quota(quota)]));
+                    if quota.is_nearly_full() {
+                        println!(
+                            "    {}",
+                            t(
+                                "⚠️  Nearly full: new mail bounces once it is. Free space with 'clinbox storage'"
+                            )
+                        );
+                    }
+                }
+                Some(Err(e)) => println!(
+                    "    {}",
+                    tr("Storage: unavailable ({})", &[&format!("{:#}", e)])
+                ),
+                None => {}
+            }
+        }
+    }
+    println!();
+
+    // AI configuration
+    println!("AI Configuration:");
+    println!(
+        "  API Key: {}",
+        if config.ai.api_key.is_empty() {
+            "Not set"
+        } else {
+            "Set"
+        }
+    );
+    println!("  Model: {}", config.ai.model_analysis);
+    println!();
 
     if !config.is_valid() {
-        eprintln!("Configuration incomplete. Run 'clinbox status' for details.");
-        std::process::exit(1);
+        println!("Configuration incomplete. Run:");
+        println!();
+        if config.gmail.accounts.is_empty() {
+            println!(
+                "  clinbox account add <id> --client-id <CLIENT_ID> --client-secret <CLIENT_SECRET>"
+            );
+        }
+        if config.ai.api_key.is_empty() {
+            println!("  clinbox config ai.api_key YOUR_OPENROUTER_KEY");
+        }
+    } else {
+        println!("Configuration complete. Run 'clinbox' to start.");
     }
 
-    // Get the account to use
-    let account = if let Some(id) = account_id {
-        config.get_account(id).ok_or_else(|| {
-            anyhow::anyhow!(
-                "Account '{}' not found. Run 'clinbox account list' to see available accounts.",
-                id
-            )
-        })?
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn run_interactive(
+    max_emails: u32,
+    include_all: bool,
+    account_id: Option<&str>,
+    resume: bool,
+    journal: Option<JournalFormat>,
+    no_ai: bool,
+    filters: &Filters,
+    limits: &Limits,
+) -> Result<()> {
+    let config = Config::load()?;
+    let journal = journal.or(config.journal);
+
+    // Without the AI, Gmail is all it takes
+    let ready = if no_ai {
+        !config.gmail.accounts.is_empty()
     } else {
-        config.get_default_account().ok_or_else(|| {
-            anyhow::anyhow!("No default account set. Run 'clinbox account add' to add an account.")
-        })?
+        config.is_valid()
     };
+    if !ready {
+        eprintln!(
+            "{}",
+            t("Configuration incomplete. Run 'clinbox status' for details.")
+        );
+        std::process::exit(1);
+    }
+
+    let saved = Session::load()?;
+    if resume && saved.is_none() {
+        anyhow::bail!("No unfinished session to resume.");
+    }
+    let saved = saved.filter(|_| resume);
+
+    // A resumed session stays on the account it was started with
+    let account_id = saved.as_ref().map(|s| s.account_id.as_str()).or(account_id);
+
+    let account = resolve_account(&config, account_id)?;
+
+    let theme = Theme::from_config(&config.ui)?;
+    let keymap = Keymap::from_config(&config.keys)?;
 
     let account_label = account.email.as_deref().unwrap_or(&account.id);
 
     // Initialize clients
-    println!("Connecting to Gmail ({})...", account_label);
-    let gmail = GmailClient::new(account)
+    println!("{}", tr("Connecting to Gmail ({})...", &[&account_label]));
+    let gmail = GmailClient::new(account, &config)
         .await
         .context("Failed to connect to Gmail")?;
 
-    let ai = AiClient::new(&config);
+    send_due_replies(&gmail, &account.id).await?;
+    wake_snoozed(&gmail, &account.id).await?;
+
+    let ai = AiClient::new(&config)?;
     let mut task_store = TaskStore::load()?;
 
-    // Fetch emails
-    let emails = if include_all {
-        println!("📥 Fetching latest {} emails...", max_emails);
-        gmail.fetch_latest(max_emails).await?
-    } else {
-        println!("📥 Fetching unread emails...");
-        gmail.fetch_unread(max_emails).await?
+    let mut history = History::load()?;
+    let mut recent_labels = RecentLabels::load()?;
+    let mut snoozes = SnoozeList::load()?;
+    let mut contacts = ContactBook::load()?;
+    let mut blocklist = Blocklist::load()?;
+    let mut outbox = Outbox::load()?;
+    let vip = Vip::new(&config)?;
+    let aliases = Aliases::new(&config.aliases, account.email.as_deref());
+    let hooks = Hooks::new(&config.hooks);
+    let rules = Rules::load()?;
+    // Sender history for the heuristics, with --no-ai or once the AI is down
+    let feedback = Feedback::load()?;
+
+    // Config rules on top of the AI: alias priorities first, then VIP escalation
+    let adjust = |email: &Email, analysis: &mut EmailAnalysis| {
+        aliases.apply(email, analysis);
+        vip.escalate(email, analysis);
     };
 
-    if emails.is_empty() {
-        println!("📭 No unread emails. Inbox zero! 🎉");
-        return Ok(());
-    }
+    let resumed = saved.is_some();
+    let mut session = match saved {
+        Some(session) => {
+            println!(
+                "{}",
+                tr(
+                    "▶️  Resuming session from {} ({} of {} emails left)\n",
+                    &[
+                        &session
+                            .saved_at
+                            .with_timezone(&chrono::Local)
+                            .format("%Y-%m-%d %H:%M"),
+                        &session.remaining(),
+                        &session.emails.len()
+                    ]
+                )
+            );
+            session
+        }
+        None => {
+            if let Some(previous) = Session::load()? {
+                println!(
+                    "{}",
+                    tr(
+                        "💡 Discarding unfinished session from {} (use --resume to continue it)",
+                        &[&previous
+                            .saved_at
+                            .with_timezone(&chrono::Local)
+                            .format("%Y-%m-%d %H:%M")]
+                    )
+                );
+            }
 
-    println!(
-        "📧 Found {} unread emails. Starting triage...\n",
-        emails.len()
-    );
+            // Fetch emails
+            let query = filters.gmail_query(include_all);
+            let scope = filters
+                .label
+                .as_ref()
+                .map(|l| format!(" in '{}'", l))
+                .unwrap_or_default();
+            if include_all {
+                println!(
+                    "{}",
+                    tr("📥 Fetching latest {} emails{}...", &[&max_emails, &scope])
+                );
+            } else {
+                println!("{}", tr("📥 Fetching unread emails{}...", &[&scope]));
+            }
+            let emails = gmail
+                .search(&query, max_emails, |done, total| {
+                    print_progress("Fetched", done, total)
+                })
+                .await?;
+            println!();
+
+            // Mail from blocked senders goes straight to the trash
+            let (blocked, emails): (Vec<_>, Vec<_>) =
+                emails.into_iter().partition(|e| blocklist.matches(e));
+            if !blocked.is_empty() {
+                let ids: Vec<String> = blocked.iter().map(|e| e.id.clone()).collect();
+                let result = gmail.trash_many(&ids).await;
+                println!(
+                    "{}",
+                    tr(
+                        "⛔ Trashed {} emails from blocked senders",
+                        &[&result.succeeded.len()]
+                    )
+                );
+                report_trash_failures(&result);
+            }
+
+            if emails.is_empty() {
+                println!("{}", t("📭 No unread emails. Inbox zero! 🎉"));
+                Session::clear()?;
+                return Ok(());
+            }
+
+            contacts.record_emails(&emails, account.email.as_deref())?;
+
+            let new: Vec<_> = emails
+                .iter()
+                .filter(|e| history.get(&e.id).is_none())
+                .collect();
+
+            for email in new.iter().filter(|e| vip.is_vip(e)) {
+                println!(
+                    "{}",
+                    tr("⭐ VIP: {} — {}", &[&email.sender_name(), &email.subject])
+                );
+                if let Err(e) = vip.notify(email).await {
+                    eprintln!("⚠️  {}", e);
+                }
+            }
+
+            // Mail seen in earlier sessions can be hidden, and reuses its cached analysis
+            let emails: Vec<_> = if filters.unseen {
+                emails
+                    .into_iter()
+                    .filter(|e| history.get(&e.id).is_none())
+                    .collect()
+            } else {
+                emails
+            };
+            let mut analyses: Vec<_> = emails
+                .iter()
+                .map(|e| {
+                    let mut analysis = history
+                        .get(&e.id)
+                        .and_then(|h| h.analysis.clone())
+                        // A guess from an earlier --no-ai session doesn't stand in for the AI
+                        .filter(|a| no_ai || !heuristics::is_guess(a))?;
+                    adjust(e, &mut analysis);
+                    Some(analysis)
+                })
+                .collect();
+
+            // Category/priority filters need the analysis up front
+            let emails = if filters.needs_analysis() {
+                if no_ai {
+                    for (email, analysis) in emails.iter().zip(analyses.iter_mut()) {
+                        analysis.get_or_insert_with(|| {
+                            let mut guess = heuristics::classify(email, &feedback);
+                            adjust(email, &mut guess);
+                            guess
+                        });
+                    }
+                } else {
+                    let missing = analyses.iter().filter(|a| a.is_none()).count();
+                    println!(
+                        "{}",
+                        tr("🤖 Analyzing {} emails to apply filters...", &[&missing])
+                    );
+                    analyze_all(&ai, &gmail, &adjust, &emails, &mut analyses).await;
+                    println!();
+
+                    // Emails the AI failed on are guessed rather than let through unfiltered
+                    let mut guessed = 0;
+                    for (email, analysis) in emails.iter().zip(analyses.iter_mut()) {
+                        if analysis.is_none() {
+                            let mut guess = heuristics::classify(email, &feedback);
+                            adjust(email, &mut guess);
+                            *analysis = Some(guess);
+                            guessed += 1;
+                        }
+                    }
+                    if guessed > 0 {
+                        eprintln!(
+                            "{}",
+                            tr(
+                                "⚠️  AI analysis failed for {} emails; guessed with heuristics",
+                                &[&guessed]
+                            )
+                        );
+                    }
+                }
+
+                let (kept, dropped): (Vec<_>, Vec<_>) = emails
+                    .into_iter()
+                    .zip(analyses)
+                    .partition(|(_, a)| a.as_ref().is_none_or(|a| filters.matches(a)));
+
+                // In focus mode the rest stays unread in the inbox, labeled by category if enabled
+                if filters.focus && config.gmail.category_labels {
+                    // One batch per category label
+                    let mut by_label: HashMap<String, Vec<String>> = HashMap::new();
+                    for (email, analysis) in &dropped {
+                        if let Some(analysis) = analysis {
+                            by_label
+                                .entry(format!("clinbox/{}", analysis.category.name()))
+                                .or_default()
+                                .push(email.id.clone());
+                        }
+                    }
+                    let mut labeled = 0;
+                    for (name, ids) in by_label {
+                        let result = match gmail.find_or_create_label(&name).await {
+                            Ok(label) => gmail.add_label_many(&ids, &label.id).await,
+                            Err(e) => BatchResult {
+                                failed: ids
+                                    .into_iter()
+                                    .map(|id| (id, format!("{:#}", e)))
+                                    .collect(),
+                                ..Default::default()
+                            },
+                        };
+                        labeled += result.succeeded.len();
+                        if let Some(error) = result.first_error() {
+                            eprintln!(
+                                "{}",
+                                tr(
+                                    "⚠️  Failed to label {} emails as {}: {}",
+                                    &[&result.failed.len(), &name, &error]
+                                )
+                            );
+                        }
+                    }
+                    println!(
+                        "{}",
+                        tr("🏷️  Labeled {} emails left for later", &[&labeled])
+                    );
+                }
+
+                let (emails, kept): (Vec<_>, Vec<_>) = kept.into_iter().unzip();
+                analyses = kept;
+                emails
+            } else {
+                emails
+            };
+
+            if emails.is_empty() {
+                if filters.focus {
+                    println!("{}", t("🎉 Nothing urgent or waiting on you."));
+                } else {
+                    println!("{}", t("📭 No emails match the filters."));
+                }
+                Session::clear()?;
+                return Ok(());
+            }
+
+            // VIP mail always comes first
+            let mut queue: Vec<_> = emails.into_iter().zip(analyses).collect();
+            queue.sort_by_key(|(e, _)| !vip.is_vip(e));
+            let (emails, analyses): (Vec<_>, Vec<_>) = queue.into_iter().unzip();
+
+            println!(
+                "{}",
+                tr(
+                    "📧 Found {} unread emails. Starting triage...\n",
+                    &[&emails.len()]
+                )
+            );
+            Session::new(&account.id, emails, analyses)
+        }
+    };
 
     // Initialize TUI
-    let mut tui = Tui::new()?;
-    let mut stats = Stats::default();
+    let gmail = Arc::new(gmail);
+    let mut tui = Tui::new(theme, keymap)?;
+    tui.watch_throttle(&gmail);
+    let mut background: JoinSet<Result<()>> = JoinSet::new();
+
+    // Heuristics stand in for the AI with --no-ai, or after it fails
+    // AI_FAILURES_BEFORE_HEURISTICS times in a row
+    let mut heuristic = no_ai;
+    let mut ai_failures = 0;
+    tui.set_heuristic_mode(heuristic);
+
+    // --budget and --target end the session on their own; the clock starts now
+    let mut progress = Progress::start(limits, session.stats.total());
+
+    let category_labels = config.gmail.category_labels;
+    let mut people_lookup = config.gmail.people_lookup;
+    let mut calendar_conflicts = config.gmail.calendar_conflicts;
+    let github = config
+        .github
+        .status
+        .then(|| GitHub::new(&config))
+        .transpose()?;
+    let mut github_status = github.is_some();
+    let mut people: HashMap<String, Option<Person>> = HashMap::new();
+    let mut pending: Option<PendingChange> = None;
+    let mut idx = session.position;
+
+    // What was done in this run, for the journal
+    let started_at = chrono::Utc::now();
+    let mut decisions: Vec<Decision> = Vec::new();
+    let mut urgent_notified: HashSet<String> = HashSet::new();
+    // Rules act on an email at most once, so undoing a rule's action sticks
+    let mut ruled: HashSet<String> = HashSet::new();
+    // Queued emails already archived by "archive everything from this sender"
+    let mut swept_ids: HashSet<String> = HashSet::new();
+
+    if !resumed && config.group_similar {
+        decisions = triage_groups(
+            &mut tui,
+            &mut session,
+            &gmail,
+            &mut history,
+            &hooks,
+            &mut background,
+            category_labels,
+        )
+        .await?;
+    }
+
+    while idx < session.emails.len() || pending.is_some() {
+        // The last email's change keeps its undo window before the summary
+        if idx >= session.emails.len() {
+            match offer_undo(&mut tui, &mut pending, &mut session.stats, &mut decisions).await? {
+                Some(index) => {
+                    idx = index;
+                    continue;
+                }
+                None => break,
+            }
+        }
+        if swept_ids.remove(&session.emails[idx].id) {
+            session.emails.remove(idx);
+            session.analyses.remove(idx);
+            continue;
+        }
+        if let Some(p) = &mut progress {
+            p.set_done(session.stats.total());
+            tui.set_progress(Some(*p));
+            if p.target_reached() {
+                if let Some(index) =
+                    offer_undo(&mut tui, &mut pending, &mut session.stats, &mut decisions).await?
+                {
+                    idx = index;
+                    continue;
+                }
+                tui.toast(t("🎯 Target reached"), false)?;
+                break;
+            }
+        }
+        let deadline = progress
+            .and_then(|p| p.deadline())
+            .map(tokio::time::Instant::from_std);
+        let email = &session.emails[idx];
+        let current = idx + 1;
+        let total = session.emails.len();
+
+        session.save(idx, pending.as_ref())?;
+
+        let sender = parse_addresses(&email.from)
+            .into_iter()
+            .next()
+            .map(|(_, address)| address.to_lowercase());
+        let person = sender
+            .as_ref()
+            .and_then(|a| people.get(a))
+            .cloned()
+            .flatten();
+        tui.set_notes(email_notes(
+            email,
+            &vip,
+            &aliases,
+            &contacts,
+            &history,
+            person.as_ref(),
+        ));
+
+        // Show email right away; the analysis fills in when it arrives
+        tui.draw_email(email, session.analyses[idx].as_ref(), current, total)?;
+        notify_urgent(
+            &hooks,
+            &mut background,
+            &mut urgent_notified,
+            email,
+            session.analyses[idx].as_ref(),
+        );
+
+        let guess_only = heuristic;
+        let analysis_fut = async {
+            if guess_only {
+                Ok(heuristics::classify(email, &feedback))
+            } else {
+                analyze(&ai, &gmail, email).await
+            }
+        };
+        tokio::pin!(analysis_fut);
+        let mut analyzing = session.analyses[idx].is_none();
+
+        // Google Contacts entry for the sender, looked up once per address
+        let lookup_fut = async {
+            match &sender {
+                Some(address) => gmail.lookup_contact(address).await,
+                None => Ok(None),
+            }
+        };
+        tokio::pin!(lookup_fut);
+        let mut looking_up =
+            people_lookup && sender.as_ref().is_some_and(|a| !people.contains_key(a));
+
+        // Calendar events clashing with the times the email proposes, checked
+        // once the analysis has found them
+        let (slots_tx, slots_rx) = tokio::sync::oneshot::channel::<Vec<conflicts::Slot>>();
+        let mut slots_tx = Some(slots_tx);
+        let conflicts_fut = async {
+            match slots_rx.await {
+                Ok(slots) => conflicts::find(&gmail, &slots).await,
+                Err(_) => Ok(Vec::new()),
+            }
+        };
+        tokio::pin!(conflicts_fut);
+        let mut checking_calendar = calendar_conflicts;
+        let mut conflicts: Vec<String> = Vec::new();
+        tui.set_conflicts(Vec::new());
+        if !analyzing && let Some(tx) = slots_tx.take() {
+            let _ = tx.send(conflicts::slots(email, session.analyses[idx].as_ref()));
+        }
+
+        // Whether the issue or pull request behind a GitHub notification is
+        // still open, looked up while the email is shown
+        let reference = github
+            .as_ref()
+            .filter(|_| github_status)
+            .and_then(|_| github::reference(email));
+        let github_fut = async {
+            match (&github, &reference) {
+                (Some(github), Some(reference)) => github.status(reference).await.map(Some),
+                _ => Ok(None),
+            }
+        };
+        tokio::pin!(github_fut);
+        let mut checking_github = reference.is_some();
+        tui.set_github(None);
+
+        // With the analysis already cached, rules can act right away
+        let mut rule_match = if analyzing {
+            None
+        } else {
+            apply_rules(
+                &rules,
+                &mut tui,
+                &mut ruled,
+                email,
+                session.analyses[idx].as_ref(),
+                &aliases,
+            )?
+        };
+
+        // Wait for user action; the loop yields the index of the next email to show
+        idx = loop {
+            let commit_at = pending
+                .as_ref()
+                .map_or_else(tokio::time::Instant::now, |p| p.commit_at);
+
+            // A matching rule acts as if its key was pressed
+            let rule = rule_match.take();
+            let action = match &rule {
+                Some(m) => match m.action {
+                    RuleAction::Archive => Action::Archive,
+                    RuleAction::Delete => Action::Delete,
+                    RuleAction::MarkRead => Action::MarkRead,
+                    RuleAction::Skip => Action::Skip,
+                },
+                None => tokio::select! {
+                    result = &mut analysis_fut, if analyzing => {
+                        analyzing = false;
+                        let result = match result {
+                            Ok(a) => {
+                                ai_failures = 0;
+                                Some(a)
+                            }
+                            Err(e) => {
+                                ai_failures += 1;
+                                if ai_failures < AI_FAILURES_BEFORE_HEURISTICS {
+                                    tui.toast(tr("AI analysis failed: {}", &[&e]), true)?;
+                                    None
+                                } else {
+                                    if !heuristic {
+                                        heuristic = true;
+                                        tui.set_heuristic_mode(true);
+                                        tui.toast(
+                                            tr("⚠️  The AI keeps failing ({}); switching to heuristic mode", &[&e]),
+                                            true,
+                                        )?;
+                                    }
+                                    Some(heuristics::classify(email, &feedback))
+                                }
+                            }
+                        };
+                        if let Some(mut a) = result {
+                            adjust(email, &mut a);
+                            session.analyses[idx] = Some(a);
+                            notify_urgent(
+                                &hooks,
+                                &mut background,
+                                &mut urgent_notified,
+                                email,
+                                session.analyses[idx].as_ref(),
+                            );
+                            session.save(idx, pending.as_ref())?;
+                            tui.draw_email(email, session.analyses[idx].as_ref(), current, total)?;
+                        }
+                        if let Some(tx) = slots_tx.take() {
+                            let _ = tx.send(conflicts::slots(email, session.analyses[idx].as_ref()));
+                        }
+                        rule_match = apply_rules(
+                            &rules,
+                            &mut tui,
+                            &mut ruled,
+                            email,
+                            session.analyses[idx].as_ref(),
+                            &aliases,
+                        )?;
+                        continue;
+                    }
+                    result = &mut lookup_fut, if looking_up => {
+                        looking_up = false;
+                        match result {
+                            Ok(person) => {
+                                tui.set_notes(email_notes(email, &vip, &aliases, &contacts, &history, person.as_ref()));
+                                if let Some(address) = &sender {
+                                    people.insert(address.clone(), person);
+                                }
+                                tui.draw_email(email, session.analyses[idx].as_ref(), current, total)?;
+                            }
+                            Err(e) => {
+                                // Don't retry (and re-toast) for every email
+                                people_lookup = false;
+                                tui.toast(tr("Contact lookup failed: {}", &[&e]), true)?;
+                            }
+                        }
+                        continue;
+                    }
+                    result = &mut conflicts_fut, if checking_calendar => {
+                        checking_calendar = false;
+                        match result {
+                            Ok(events) => {
+                                conflicts = events.iter().map(conflicts::describe).collect();
+                                if !conflicts.is_empty() {
+                                    tui.set_conflicts(conflicts.clone());
+                                    tui.draw_email(email, session.analyses[idx].as_ref(), current, total)?;
+                                }
+                            }
+                            Err(e) => {
+                                // Don't retry (and re-toast) for every email
+                                calendar_conflicts = false;
+                                tui.toast(tr("Calendar check failed: {}", &[&e]), true)?;
+                            }
+                        }
+                        continue;
+                    }
+                    result = &mut github_fut, if checking_github => {
+                        checking_github = false;
+                        match result {
+                            Ok(Some(status)) => {
+                                tui.set_github(Some(status.describe()));
+                                tui.draw_email(email, session.analyses[idx].as_ref(), current, total)?;
+                            }
+                            Ok(None) => {}
+                            Err(e) => {
+                                // Don't retry (and re-toast) for every email
+                                github_status = false;
+                                tui.toast(tr("GitHub lookup failed: {}", &[&e]), true)?;
+                            }
+                        }
+                        continue;
+                    }
+                    _ = tokio::time::sleep_until(commit_at), if pending.is_some() => {
+                        commit_pending(&mut pending, &session, &mut history, &mut background, &gmail, &hooks, category_labels)?;
+                        continue;
+                    }
+                    Some(result) = background.join_next(), if !background.is_empty() => {
+                        report_background_result(&mut tui, result)?;
+                        continue;
+                    }
+                    _ = tokio::time::sleep_until(deadline.unwrap_or(commit_at)), if deadline.is_some() => {
+                        tui.toast(t("⏱ Time's up"), false)?;
+                        Action::Quit
+                    }
+                    action = tui.wait_for_action() => action?,
+                },
+            };
+            let via = rule.map(|m| format!("📜 {}: ", m.rule)).unwrap_or_default();
+
+            if no_ai
+                && matches!(
+                    action,
+                    Action::Chat
+                        | Action::Command
+                        | Action::Summary
+                        | Action::Reanalyze
+                        | Action::Expense
+                )
+            {
+                tui.toast(t("🚫 AI is off for this session (--no-ai)"), true)?;
+                continue;
+            }
+
+            // Acting on the next email makes the previous change final
+            if action != Action::Undo {
+                commit_pending(
+                    &mut pending,
+                    &session,
+                    &mut history,
+                    &mut background,
+                    &gmail,
+                    &hooks,
+                    category_labels,
+                )?;
+            }
+
+            // Remember mail left in place so later sessions can flag (or hide) it
+            match action {
+                Action::Skip => history.record(
+                    &email.id,
+                    SeenAction::Skipped,
+                    session.analyses[idx].as_ref(),
+                )?,
+                Action::ViewFull | Action::Open | Action::Quit => history.record(
+                    &email.id,
+                    SeenAction::Viewed,
+                    session.analyses[idx].as_ref(),
+                )?,
+                _ => {}
+            }
+
+            match action {
+                Action::Archive => {
+                    pending = Some(PendingChange::new(idx, PendingKind::Archive));
+                    tui.toast_for(
+                        tr(
+                            "{}✅ Archived — press {} to undo",
+                            &[&via, &tui.key_label(Action::Undo)],
+                        ),
+                        UNDO_WINDOW,
+                    )?;
+                    session.stats.archived += 1;
+                    decisions.push(Decision::new(
+                        email,
+                        session.analyses[idx].as_ref(),
+                        "archived",
+                    ));
+                    break idx + 1;
+                }
+                Action::Delete => {
+                    pending = Some(PendingChange::new(idx, PendingKind::Delete));
+                    tui.toast_for(
+                        tr(
+                            "{}🗑️ Deleted — press {} to undo",
+                            &[&via, &tui.key_label(Action::Undo)],
+                        ),
+                        UNDO_WINDOW,
+                    )?;
+                    session.stats.deleted += 1;
+                    decisions.push(Decision::new(
+                        email,
+                        session.analyses[idx].as_ref(),
+                        "deleted",
+                    ));
+                    break idx + 1;
+                }
+                Action::MarkRead => {
+                    pending = Some(PendingChange::new(idx, PendingKind::MarkRead));
+                    tui.toast_for(
+                        tr(
+                            "{}👁️ Marked read — press {} to undo",
+                            &[&via, &tui.key_label(Action::Undo)],
+                        ),
+                        UNDO_WINDOW,
+                    )?;
+                    session.stats.marked_read += 1;
+                    decisions.push(Decision::new(
+                        email,
+                        session.analyses[idx].as_ref(),
+                        "marked read",
+                    ));
+                    break idx + 1;
+                }
+                Action::Snooze => {
+                    // The delivery day or the analysis's date, when there is one, is
+                    // a keystroke away
+                    let initial = snooze::suggestion(email, session.analyses[idx].as_ref())
+                        .unwrap_or_default();
+                    let Some(when) = tui
+                        .prompt_with(
+                            &format!(" {} ", t("Snooze")),
+                            t("Snooze until (2h, tomorrow 9:00, 2024-07-01):"),
+                            &initial,
+                        )
+                        .await?
+                    else {
+                        tui.draw_email(email, session.analyses[idx].as_ref(), current, total)?;
+                        continue;
+                    };
+
+                    let snoozed = outbox::parse_send_at(&when).and_then(|until| {
+                        snoozes.add(&session.account_id, email, until)?;
+                        Ok(until)
+                    });
+                    match snoozed {
+                        Ok(until) => {
+                            history.forget(&email.id)?;
+                            let gmail = Arc::clone(&gmail);
+                            let id = email.id.clone();
+                            background.spawn(async move { gmail.archive(&id).await });
+                            tui.toast(
+                                tr(
+                                    "💤 Snoozed until {}",
+                                    &[&until
+                                        .with_timezone(&chrono::Local)
+                                        .format("%a %Y-%m-%d %H:%M")],
+                                ),
+                                false,
+                            )?;
+                            session.stats.archived += 1;
+                            decisions.push(Decision::new(
+                                email,
+                                session.analyses[idx].as_ref(),
+                                "snoozed",
+                            ));
+                            break idx + 1;
+                        }
+                        Err(e) => {
+                            tui.draw_email(email, session.analyses[idx].as_ref(), current, total)?;
+                            tui.toast(format!("❌ {}", e), true)?;
+                        }
+                    }
+                }
+                Action::MoveToLabel => {
+                    let recent = recent_labels.get(&session.account_id).to_vec();
+                    let Some(name) = tui.pick_label(&email.subject, &recent).await? else {
+                        tui.draw_email(email, session.analyses[idx].as_ref(), current, total)?;
+                        continue;
+                    };
+
+                    match tui
+                        .busy(
+                            t("🏷️ Looking up label..."),
+                            gmail.find_or_create_label(&name),
+                        )
+                        .await?
+                    {
+                        Ok(label) => {
+                            recent_labels.record(&session.account_id, &label.name)?;
+                            pending = Some(PendingChange::new(
+                                idx,
+                                PendingKind::Move { label_id: label.id },
+                            ));
+                            tui.toast_for(
+                                tr(
+                                    "🏷️ Moved to {} — press {} to undo",
+                                    &[&label.name, &tui.key_label(Action::Undo)],
+                                ),
+                                UNDO_WINDOW,
+                            )?;
+                            session.stats.moved += 1;
+                            decisions.push(Decision::new(
+                                email,
+                                session.analyses[idx].as_ref(),
+                                format!("moved to {}", label.name),
+                            ));
+                            break idx + 1;
+                        }
+                        Err(e) => {
+                            tui.draw_email(email, session.analyses[idx].as_ref(), current, total)?;
+                            tui.toast(format!("❌ {}", e), true)?;
+                        }
+                    }
+                }
+                Action::Block => {
+                    let Some(address) = sender.clone() else {
+                        tui.toast(t("No sender address to block"), true)?;
+                        continue;
+                    };
+
+                    tui.draw_message(
+                        &tr("Block {} and move this email to trash?\n\nFuture mail from this sender is trashed on fetch. [y/n]", &[&address]),
+                        false,
+                    )?;
+                    if tui.wait_for_yes_no().await? {
+                        blocklist.add(&address)?;
+                        history.forget(&email.id)?;
+                        let gmail = Arc::clone(&gmail);
+                        let id = email.id.clone();
+                        background.spawn(async move { gmail.delete(&id).await });
+                        tui.toast(tr("⛔ Blocked {} & deleted", &[&address]), false)?;
+                        session.stats.deleted += 1;
+                        decisions.push(Decision::new(
+                            email,
+                            session.analyses[idx].as_ref(),
+                            "blocked",
+                        ));
+                        hooks.fire(
+                            &mut background,
+                            HookEvent::Block,
+                            email,
+                            session.analyses[idx].as_ref(),
+                        );
+                        break idx + 1;
+                    }
+                    tui.draw_email(email, session.analyses[idx].as_ref(), current, total)?;
+                }
+                Action::SweepSender => {
+                    let Some(address) = sender.clone() else {
+                        tui.toast(t("No sender address to sweep"), true)?;
+                        continue;
+                    };
+
+                    let query = format!("from:{} in:inbox", address);
+                    let ids = match tui
+                        .busy(
+                            &tr("🔍 Looking for mail from {}...", &[&address]),
+                            gmail.search_ids(&query, MAX_SWEEP),
+                        )
+                        .await?
+                    {
+                        Ok(ids) if !ids.is_empty() => ids,
+                        Ok(_) => {
+                            tui.draw_email(email, session.analyses[idx].as_ref(), current, total)?;
+                            tui.toast(tr("No inbox mail from {}", &[&address]), false)?;
+                            continue;
+                        }
+                        Err(e) => {
+                            tui.draw_email(email, session.analyses[idx].as_ref(), current, total)?;
+                            tui.toast(format!("❌ {}", e), true)?;
+                            continue;
+                        }
+                    };
+
+                    tui.draw_message(
+                        &tr(
+                            "Archive all {} inbox emails from {}? [y/n]",
+                            &[&ids.len(), &address],
+                        ),
+                        false,
+                    )?;
+                    if !tui.wait_for_yes_no().await? {
+                        tui.draw_email(email, session.analyses[idx].as_ref(), current, total)?;
+                        continue;
+                    }
+
+                    let result = tui
+                        .busy(
+                            &tr("📦 Archiving {} emails...", &[&ids.len()]),
+                            gmail.archive_many(&ids),
+                        )
+                        .await?;
+                    let swept: HashSet<&String> = result.succeeded.iter().collect();
+
+                    let current_swept = swept.contains(&email.id);
+                    if current_swept {
+                        history.forget(&email.id)?;
+                        hooks.fire(
+                            &mut background,
+                            HookEvent::Archive,
+                            email,
+                            session.analyses[idx].as_ref(),
+                        );
+                        if category_labels {
+                            label_in_background(
+                                &mut background,
+                                &gmail,
+                                &email.id,
+                                session.analyses[idx].as_ref(),
+                            );
+                        }
+                        decisions.push(Decision::new(
+                            email,
+                            session.analyses[idx].as_ref(),
+                            "archived",
+                        ));
+                        session.stats.archived += 1;
+                    }
+
+                    // Later emails from the sender leave the queue when reached;
+                    // earlier ones are behind us
+                    for (later, analysis) in session
+                        .emails
+                        .iter()
+                        .zip(&session.analyses)
+                        .skip(idx + 1)
+                        .filter(|(e, _)| swept.contains(&e.id))
+                    {
+                        history.forget(&later.id)?;
+                        hooks.fire(
+                            &mut background,
+                            HookEvent::Archive,
+                            later,
+                            analysis.as_ref(),
+                        );
+                        if category_labels {
+                            label_in_background(
+                                &mut background,
+                                &gmail,
+                                &later.id,
+                                analysis.as_ref(),
+                            );
+                        }
+                        decisions.push(Decision::new(later, analysis.as_ref(), "archived"));
+                        session.stats.archived += 1;
+                        swept_ids.insert(later.id.clone());
+                    }
+
+                    match result.first_error() {
+                        Some(error) => tui.toast(
+                            tr(
+                                "⚠️  Archived {}; {} failed and stay in the queue: {}",
+                                &[&result.succeeded.len(), &result.failed.len(), &error],
+                            ),
+                            true,
+                        )?,
+                        None => tui.toast(
+                            tr("✅ Archived {} emails", &[&result.succeeded.len()]),
+                            false,
+                        )?,
+                    }
+                    break if current_swept { idx + 1 } else { idx };
+                }
+                Action::Chat => {
+                    let mut turns: Vec<(String, String)> = Vec::new();
+                    while let Some(question) = tui.chat_input(&email.subject, &turns).await? {
+                        match tui
+                            .busy_cancellable(
+                                t("🤖 Thinking..."),
+                                ai.ask_about(email, &turns, &question),
+                            )
+                            .await?
+                        {
+                            Some(Ok(answer)) => turns.push((question, answer)),
+                            Some(Err(e)) => tui.toast(format!("❌ {}", e), true)?,
+                            None => {}
+                        }
+                    }
+                    tui.draw_email(email, session.analyses[idx].as_ref(), current, total)?;
+                }
+                Action::Command => {
+                    let Some(command) = tui
+                        .prompt(
+                            &format!(" {} ", t("Command")),
+                            t("What to do (e.g. archive all from amazon, label this receipts and skip):"),
+                        )
+                        .await?
+                    else {
+                        tui.draw_email(email, session.analyses[idx].as_ref(), current, total)?;
+                        continue;
+                    };
 
-    for (idx, email) in emails.iter().enumerate() {
-        let current = idx + 1;
-        let total = emails.len();
+                    // Searches run before confirming, so the plan can say how much it touches
+                    let planned = tui
+                        .busy(t("🤖 Planning..."), async {
+                            let steps = ai.plan_command(&command, email).await?;
+                            let mut matches = Vec::new();
+                            for step in &steps {
+                                matches.push(match step.query() {
+                                    Some(query) => {
+                                        gmail.search_ids(query, command::MAX_MATCHES).await?
+                                    }
+                                    None => Vec::new(),
+                                });
+                            }
+                            anyhow::Ok((steps, matches))
+                        })
+                        .await?;
+                    let (steps, matches) = match planned {
+                        Ok((steps, _)) if steps.is_empty() => {
+                            tui.draw_email(email, session.analyses[idx].as_ref(), current, total)?;
+                            tui.toast(t("🤷 Couldn't turn that into actions"), true)?;
+                            continue;
+                        }
+                        Ok(plan) => plan,
+                        Err(e) => {
+                            tui.draw_email(email, session.analyses[idx].as_ref(), current, total)?;
+                            tui.toast(format!("❌ {}", e), true)?;
+                            continue;
+                        }
+                    };
 
-        // Show email without analysis first
-        tui.draw_email(email, None, current, total)?;
+                    let plan: Vec<String> = steps
+                        .iter()
+                        .zip(&matches)
+                        .enumerate()
+                        .map(|(i, (step, ids))| match step.query() {
+                            Some(_) => format!(
+                                "{}. {} ({})",
+                                i + 1,
+                                step.describe(),
+                                tr("{} emails", &[&ids.len()])
+                            ),
+                            None => format!("{}. {}", i + 1, step.describe()),
+                        })
+                        .collect();
+                    tui.draw_message(
+                        &tr("Run this plan?\n\n{}\n\n[y/n]", &[&plan.join("\n")]),
+                        false,
+                    )?;
+                    if !tui.wait_for_yes_no().await? {
+                        tui.draw_email(email, session.analyses[idx].as_ref(), current, total)?;
+                        continue;
+                    }
 
-        // Get AI analysis
-        let analysis = match ai.analyze_email(email).await {
-            Ok(a) => Some(a),
-            Err(e) => {
-                // Show error briefly but continue
-                tui.draw_message(&format!("AI analysis failed: {}", e), true)?;
-                std::thread::sleep(std::time::Duration::from_secs(1));
-                None
-            }
-        };
+                    let outcome = tui
+                        .busy(
+                            t("⚙️ Running..."),
+                            command::run(&gmail, email, &steps, &matches),
+                        )
+                        .await?;
 
-        // Show email with analysis
-        tui.draw_email(email, analysis.as_ref(), current, total)?;
+                    // Later queued emails a search archived leave the queue when reached
+                    for (later, analysis) in session
+                        .emails
+                        .iter()
+                        .zip(&session.analyses)
+                        .skip(idx + 1)
+                        .filter(|(e, _)| outcome.archived.contains(&e.id))
+                    {
+                        history.forget(&later.id)?;
+                        hooks.fire(
+                            &mut background,
+                            HookEvent::Archive,
+                            later,
+                            analysis.as_ref(),
+                        );
+                        decisions.push(Decision::new(later, analysis.as_ref(), "archived"));
+                        session.stats.archived += 1;
+                        swept_ids.insert(later.id.clone());
+                    }
 
-        // Wait for user action
-        loop {
-            let action = tui.wait_for_action()?;
+                    if let Some(done) = outcome.current {
+                        match done {
+                            command::Done::Skipped => {
+                                history.record(
+                                    &email.id,
+                                    SeenAction::Skipped,
+                                    session.analyses[idx].as_ref(),
+                                )?;
+                                session.stats.skipped += 1;
+                            }
+                            command::Done::Deleted => {
+                                history.forget(&email.id)?;
+                                session.stats.deleted += 1;
+                            }
+                            command::Done::MarkedRead => {
+                                history.forget(&email.id)?;
+                                session.stats.marked_read += 1;
+                            }
+                            command::Done::Archived => {
+                                history.forget(&email.id)?;
+                                session.stats.archived += 1;
+                            }
+                        }
+                        hooks.fire(
+                            &mut background,
+                            done.hook(),
+                            email,
+                            session.analyses[idx].as_ref(),
+                        );
+                        decisions.push(Decision::new(
+                            email,
+                            session.analyses[idx].as_ref(),
+                            done.name(),
+                        ));
+                    } else {
+                        tui.draw_email(email, session.analyses[idx].as_ref(), current, total)?;
+                    }
 
-            match action {
-                Action::Archive => {
-                    gmail.archive(&email.id).await?;
-                    tui.draw_message("✅ Archived", false)?;
-                    std::thread::sleep(std::time::Duration::from_millis(300));
-                    stats.archived += 1;
-                    break;
-                }
-                Action::Delete => {
-                    gmail.delete(&email.id).await?;
-                    tui.draw_message("🗑️ Deleted", false)?;
-                    std::thread::sleep(std::time::Duration::from_millis(300));
-                    stats.deleted += 1;
-                    break;
+                    match &outcome.error {
+                        Some(error) => tui.toast(tr("⚠️  Stopped at {}", &[error]), true)?,
+                        None => tui.toast(tr("✅ Done: {}", &[&outcome.done.join(", ")]), false)?,
+                    }
+                    if outcome.current.is_some() {
+                        break idx + 1;
+                    }
                 }
+                Action::Undo => match pending.take() {
+                    Some(change) => {
+                        session.stats.undo(&change.kind);
+                        decisions.pop();
+                        tui.toast(t("↩️ Undone"), false)?;
+                        break change.index;
+                    }
+                    None => tui.toast(t("Nothing to undo"), false)?,
+                },
                 Action::Task => {
-                    let title = analysis
+                    let title = session.analyses[idx]
                         .as_ref()
                         .and_then(|a| a.suggested_action.clone())
                         .unwrap_or_else(|| email.subject.clone());
 
                     tui.draw_task_input(&title, &email.subject)?;
 
-                    if tui.wait_for_confirm()? {
+                    if tui.wait_for_confirm().await? {
                         task_store.add(
                             title,
                             Some(
-                                analysis
+                                session.analyses[idx]
                                     .as_ref()
                                     .map(|a| a.summary.clone())
                                     .unwrap_or_default(),
@@ -521,41 +4898,165 @@ async fn run_interactive(
                             Some(email.id.clone()),
                             Some(email.subject.clone()),
                         )?;
-                        gmail.archive(&email.id).await?;
-                        tui.draw_message("📝 Task created & email archived", false)?;
-                        std::thread::sleep(std::time::Duration::from_millis(500));
-                        stats.tasks_created += 1;
+                        archive_in_background(&mut background, &gmail, &email.id);
+                        history.forget(&email.id)?;
+                        tui.toast(t("📝 Task created & email archived"), false)?;
+                        session.stats.tasks_created += 1;
+                        decisions.push(Decision::new(
+                            email,
+                            session.analyses[idx].as_ref(),
+                            "task created",
+                        ));
+                        hooks.fire(
+                            &mut background,
+                            HookEvent::TaskCreated,
+                            email,
+                            session.analyses[idx].as_ref(),
+                        );
+                        break idx + 1;
                     }
-                    break;
+                    tui.draw_email(email, session.analyses[idx].as_ref(), current, total)?;
+                }
+                Action::ReadLater => {
+                    let item = later::Item::new(
+                        email,
+                        session.analyses[idx].as_ref(),
+                        &session.account_id,
+                    );
+                    let kind = PendingKind::ReadLater(Box::new(item));
+                    pending = Some(PendingChange::new(idx, kind));
+                    tui.toast_for(
+                        tr(
+                            "{}📚 Saved to read later — press {} to undo",
+                            &[&via, &tui.key_label(Action::Undo)],
+                        ),
+                        UNDO_WINDOW,
+                    )?;
+                    session.stats.read_later += 1;
+                    decisions.push(Decision::new(
+                        email,
+                        session.analyses[idx].as_ref(),
+                        "read later",
+                    ));
+                    break idx + 1;
                 }
                 Action::Reply => {
-                    // Generate AI draft
-                    tui.draw_message("🤖 Generating reply draft...", false)?;
+                    // Generate AI draft (Esc cancels)
+                    let Some(result) = tui
+                        .busy_cancellable(t("🤖 Generating reply draft..."), async {
+                            // Without the AI the reply starts blank, ready to edit
+                            if no_ai {
+                                Ok(String::new())
+                            } else {
+                                draft_reply(&ai, &gmail, email, &conflicts).await
+                            }
+                        })
+                        .await?
+                    else {
+                        continue;
+                    };
 
-                    match ai.generate_reply(email).await {
+                    match result {
                         Ok(draft) => {
-                            tui.draw_reply_draft(email, &draft)?;
-
-                            match tui.wait_for_reply_action()? {
+                            let mut copies = Copies::default();
+                            match tui
+                                .review_reply(email, &draft, &gmail, &contacts, &mut copies)
+                                .await?
+                            {
                                 ReplyAction::Send => {
-                                    tui.draw_message("📤 Sending...", false)?;
-                                    match gmail.send_reply(email, &draft).await {
-                                        Ok(()) => {
-                                            gmail.archive(&email.id).await?;
-                                            tui.draw_message("✅ Reply sent & archived", false)?;
-                                            std::thread::sleep(std::time::Duration::from_millis(
-                                                500,
-                                            ));
-                                            stats.replied += 1;
-                                            break;
-                                        }
-                                        Err(e) => {
-                                            tui.draw_message(
-                                                &format!("❌ Failed to send: {}", e),
-                                                true,
+                                    if let Err(e) = tui
+                                        .busy(
+                                            t("📤 Sending..."),
+                                            gmail.send_reply(email, &draft, &copies),
+                                        )
+                                        .await?
+                                    {
+                                        // Nothing was sent, so the email stays put
+                                        let message = queue_failed_reply(
+                                            &gmail,
+                                            &mut outbox,
+                                            &session.account_id,
+                                            email,
+                                            &draft,
+                                            &copies,
+                                            &e,
+                                        )?;
+                                        tui.draw_email(
+                                            email,
+                                            session.analyses[idx].as_ref(),
+                                            current,
+                                            total,
+                                        )?;
+                                        tui.toast(message, true)?;
+                                        continue;
+                                    }
+                                    contacts.record_reply(email)?;
+                                    archive_in_background(&mut background, &gmail, &email.id);
+                                    history.forget(&email.id)?;
+                                    tui.toast(t("✅ Reply sent & archived"), false)?;
+                                    session.stats.replied += 1;
+                                    decisions.push(Decision::new(
+                                        email,
+                                        session.analyses[idx].as_ref(),
+                                        "replied",
+                                    ));
+                                    hooks.fire(
+                                        &mut background,
+                                        HookEvent::Reply,
+                                        email,
+                                        session.analyses[idx].as_ref(),
+                                    );
+                                    break idx + 1;
+                                }
+                                ReplyAction::Schedule => {
+                                    match schedule_reply(
+                                        &mut tui,
+                                        &gmail,
+                                        &mut outbox,
+                                        &session.account_id,
+                                        email,
+                                        &draft,
+                                        &copies,
+                                    )
+                                    .await?
+                                    {
+                                        Some(at) => {
+                                            contacts.record_reply(email)?;
+                                            archive_in_background(
+                                                &mut background,
+                                                &gmail,
+                                                &email.id,
+                                            );
+                                            history.forget(&email.id)?;
+                                            tui.toast(
+                                                tr(
+                                                    "🕒 Reply scheduled for {} & archived",
+                                                    &[&at
+                                                        .with_timezone(&chrono::Local)
+                                                        .format("%a %Y-%m-%d %H:%M")],
+                                                ),
+                                                false,
                                             )?;
-                                            std::thread::sleep(std::time::Duration::from_secs(2));
+                                            session.stats.replied += 1;
+                                            decisions.push(Decision::new(
+                                                email,
+                                                session.analyses[idx].as_ref(),
+                                                "reply scheduled",
+                                            ));
+                                            hooks.fire(
+                                                &mut background,
+                                                HookEvent::Reply,
+                                                email,
+                                                session.analyses[idx].as_ref(),
+                                            );
+                                            break idx + 1;
                                         }
+                                        None => tui.draw_email(
+                                            email,
+                                            session.analyses[idx].as_ref(),
+                                            current,
+                                            total,
+                                        )?,
                                     }
                                 }
                                 ReplyAction::Edit => {
@@ -565,130 +5066,988 @@ async fn run_interactive(
                                         email.id
                                     );
                                     let _ = open::that(&url);
-                                    tui.draw_message("📧 Opened in browser for editing", false)?;
-                                    std::thread::sleep(std::time::Duration::from_millis(500));
-                                    break;
+                                    tui.toast(t("📧 Opened in browser for editing"), false)?;
+                                    break idx + 1;
                                 }
-                                ReplyAction::Cancel => {
+                                ReplyAction::Cancel | ReplyAction::Cc | ReplyAction::Bcc => {
                                     // Re-draw email and continue
-                                    tui.draw_email(email, analysis.as_ref(), current, total)?;
+                                    tui.draw_email(
+                                        email,
+                                        session.analyses[idx].as_ref(),
+                                        current,
+                                        total,
+                                    )?;
                                 }
                             }
                         }
                         Err(e) => {
-                            tui.draw_message(&format!("❌ Failed to generate draft: {}", e), true)?;
-                            std::thread::sleep(std::time::Duration::from_secs(2));
-                            tui.draw_email(email, analysis.as_ref(), current, total)?;
+                            tui.toast(tr("❌ Failed to generate draft: {}", &[&e]), true)?;
                         }
                     }
                 }
                 Action::Summary => {
-                    // Generate summary
-                    tui.draw_message("🤖 Generating comprehensive summary...", false)?;
+                    // Generate summary (Esc cancels)
+                    let Some(result) = tui
+                        .busy_cancellable(
+                            t("🤖 Generating comprehensive summary..."),
+                            ai.summarize_article(email, &config.language),
+                        )
+                        .await?
+                    else {
+                        continue;
+                    };
 
-                    match ai.summarize_article(email, &config.language).await {
+                    match result {
                         Ok(summary) => {
                             // Show preview
                             tui.draw_summary_preview(email, &summary)?;
 
                             // Wait for confirmation
-                            if tui.wait_for_confirm()? {
+                            if tui.wait_for_confirm().await? {
                                 // Save to markdown file
                                 match save_summary_markdown(email, &summary) {
                                     Ok(path) => {
                                         // Show saved message with path
                                         tui.draw_message(
-                                            &format!("📝 Saved: {}\n\nOpen file? [y/n]", path.display()),
+                                            &tr(
+                                                "📝 Saved: {}\n\nOpen file? [y/n]",
+                                                &[&path.display()],
+                                            ),
                                             false,
                                         )?;
 
                                         // Ask if open file
-                                        if tui.wait_for_yes_no()? {
+                                        if tui.wait_for_yes_no().await? {
                                             let _ = open::that(&path);
                                         }
 
                                         // Ask if archive
-                                        tui.draw_message("Archive email? [y/n]", false)?;
-                                        if tui.wait_for_yes_no()? {
-                                            gmail.archive(&email.id).await?;
-                                            stats.archived += 1;
+                                        tui.draw_message(t("Archive email? [y/n]"), false)?;
+                                        let mut decision = "summarized";
+                                        if tui.wait_for_yes_no().await? {
+                                            archive_in_background(
+                                                &mut background,
+                                                &gmail,
+                                                &email.id,
+                                            );
+                                            session.stats.archived += 1;
+                                            decision = "summarized & archived";
+                                            hooks.fire(
+                                                &mut background,
+                                                HookEvent::Archive,
+                                                email,
+                                                session.analyses[idx].as_ref(),
+                                            );
                                         }
-                                        stats.summaries_saved += 1;
-                                        break;
+                                        session.stats.summaries_saved += 1;
+                                        decisions.push(Decision::new(
+                                            email,
+                                            session.analyses[idx].as_ref(),
+                                            decision,
+                                        ));
+                                        break idx + 1;
                                     }
                                     Err(e) => {
-                                        tui.draw_message(
-                                            &format!("❌ Failed to save: {}", e),
-                                            true,
+                                        tui.draw_email(
+                                            email,
+                                            session.analyses[idx].as_ref(),
+                                            current,
+                                            total,
                                         )?;
-                                        std::thread::sleep(std::time::Duration::from_secs(2));
-                                        tui.draw_email(email, analysis.as_ref(), current, total)?;
+                                        tui.toast(tr("❌ Failed to save: {}", &[&e]), true)?;
                                     }
                                 }
                             } else {
                                 // User cancelled
-                                tui.draw_email(email, analysis.as_ref(), current, total)?;
+                                tui.draw_email(
+                                    email,
+                                    session.analyses[idx].as_ref(),
+                                    current,
+                                    total,
+                                )?;
                             }
                         }
                         Err(e) => {
-                            tui.draw_message(&format!("❌ Failed to generate summary: {}", e), true)?;
-                            std::thread::sleep(std::time::Duration::from_secs(2));
-                            tui.draw_email(email, analysis.as_ref(), current, total)?;
+                            tui.toast(tr("❌ Failed to generate summary: {}", &[&e]), true)?;
                         }
                     }
                 }
                 Action::Open => {
+                    // Risky attachments and likely phishing get a second look
+                    // before they're a click away
+                    let mut warnings = attachments::warnings(email);
+                    warnings.extend(phishing::warning(email, session.analyses[idx].as_ref()));
+                    if !warnings.is_empty() {
+                        tui.draw_message(
+                            &tr(
+                                "{}\n\nOpen in browser anyway? [y/n]",
+                                &[&warnings.join("\n")],
+                            ),
+                            true,
+                        )?;
+                        let confirmed = tui.wait_for_yes_no().await?;
+                        tui.draw_email(email, session.analyses[idx].as_ref(), current, total)?;
+                        if !confirmed {
+                            continue;
+                        }
+                    }
+
                     let url = format!("https://mail.google.com/mail/u/0/#inbox/{}", email.id);
                     let _ = open::that(&url);
-                    tui.draw_message("🌐 Opened in browser", false)?;
-                    std::thread::sleep(std::time::Duration::from_millis(300));
+                    tui.toast(t("🌐 Opened in browser"), false)?;
+                    // Don't break - let user continue with other actions
+                }
+                Action::Export => {
+                    tui.draw_message(t("Export as [e]ml, [h]tml or [p]df? (Esc cancels)"), false)?;
+                    let format = match tui.next_key().await?.code {
+                        KeyCode::Char('e') => Some(ExportFormat::Eml),
+                        KeyCode::Char('h') => Some(ExportFormat::Html),
+                        KeyCode::Char('p') => Some(ExportFormat::Pdf),
+                        _ => None,
+                    };
+                    tui.draw_email(email, session.analyses[idx].as_ref(), current, total)?;
+                    let Some(format) = format else {
+                        continue;
+                    };
+
+                    let dir = Config::exports_dir()?;
+                    match tui
+                        .busy(
+                            t("💾 Exporting..."),
+                            export::save(&gmail, email, format, &dir),
+                        )
+                        .await?
+                    {
+                        Ok(path) => tui.toast(tr("💾 Saved {}", &[&path.display()]), false)?,
+                        Err(e) => tui.toast(tr("❌ Export failed: {}", &[&e]), true)?,
+                    }
+                    // Don't break - let user continue with other actions
+                }
+                Action::Pipe => {
+                    let Some(command) = tui
+                        .prompt_with(
+                            &format!(" {} ", t("Pipe to Command")),
+                            &tr(
+                                "The body goes to stdin; {} are replaced with the email's fields",
+                                &[&pipe::PLACEHOLDERS.join(" ")],
+                            ),
+                            config.pipe_command.as_deref().unwrap_or_default(),
+                        )
+                        .await?
+                    else {
+                        tui.draw_email(email, session.analyses[idx].as_ref(), current, total)?;
+                        continue;
+                    };
+
+                    tui.suspend()?;
+                    println!("$ {}\n", command);
+                    let result = pipe::run(&command, email);
+                    println!("{}", t("\nPress Enter to return to clinbox..."));
+                    let _ = std::io::stdin().read_line(&mut String::new());
+                    tui.resume()?;
+
+                    tui.draw_email(email, session.analyses[idx].as_ref(), current, total)?;
+                    match result {
+                        Ok(()) => tui.toast(t("📤 Piped"), false)?,
+                        Err(e) => tui.toast(format!("❌ {}", e), true)?,
+                    }
                     // Don't break - let user continue with other actions
                 }
                 Action::ViewFull => {
-                    tui.draw_full_email(email)?;
-                    tui.wait_for_key()?;
-                    tui.draw_email(email, analysis.as_ref(), current, total)?;
+                    tui.view_full_email(email, &gmail).await?;
+                    tui.draw_email(email, session.analyses[idx].as_ref(), current, total)?;
+                    // Don't break - let user continue with other actions
+                }
+                Action::Links => {
+                    show_links(&mut tui, &config, email).await?;
+                    tui.draw_email(email, session.analyses[idx].as_ref(), current, total)?;
+                    // Don't break - let user continue with other actions
+                }
+                Action::CopyCode => copy_code(&mut tui, email)?,
+                Action::Expense => {
+                    record_expense(&mut tui, &ai, &gmail, email, session.analyses[idx].as_ref())
+                        .await?;
+                    tui.draw_email(email, session.analyses[idx].as_ref(), current, total)?;
+                    // Don't break - let user continue with other actions
+                }
+                Action::Reasoning => {
+                    tui.toggle_reasoning();
+                    tui.draw_email(email, session.analyses[idx].as_ref(), current, total)?;
+                    // Don't break - let user continue with other actions
+                }
+                Action::Reanalyze => {
+                    if analyzing {
+                        tui.toast(t("🔄 Still analyzing"), false)?;
+                        continue;
+                    }
+                    let Some(result) = tui
+                        .busy_cancellable(
+                            &tr("🧠 Re-analyzing with {}...", &[&ai.escalation_model()]),
+                            reanalyze(&ai, &gmail, email),
+                        )
+                        .await?
+                    else {
+                        continue;
+                    };
+                    match result {
+                        Ok(mut a) => {
+                            adjust(email, &mut a);
+                            session.analyses[idx] = Some(a);
+                            session.save(idx, pending.as_ref())?;
+                            // The AI answered again, so the next emails go back to it
+                            if heuristic && !no_ai {
+                                heuristic = false;
+                                ai_failures = 0;
+                                tui.set_heuristic_mode(false);
+                            }
+                            tui.draw_email(email, session.analyses[idx].as_ref(), current, total)?;
+                            tui.toast(t("🧠 Re-analyzed"), false)?;
+                        }
+                        Err(e) => tui.toast(tr("❌ Re-analysis failed: {}", &[&e]), true)?,
+                    }
                     // Don't break - let user continue with other actions
                 }
                 Action::Skip => {
-                    stats.skipped += 1;
-                    break;
+                    if category_labels {
+                        label_in_background(
+                            &mut background,
+                            &gmail,
+                            &email.id,
+                            session.analyses[idx].as_ref(),
+                        );
+                    }
+                    session.stats.skipped += 1;
+                    decisions.push(Decision::new(
+                        email,
+                        session.analyses[idx].as_ref(),
+                        "skipped",
+                    ));
+                    hooks.fire(
+                        &mut background,
+                        HookEvent::Skip,
+                        email,
+                        session.analyses[idx].as_ref(),
+                    );
+                    if !via.is_empty() {
+                        tui.toast(tr("{}⏭️ Skipped", &[&via]), false)?;
+                    }
+                    break idx + 1;
                 }
                 Action::Quit => {
-                    tui.draw_summary(
-                        stats.total(),
-                        stats.archived,
-                        stats.deleted,
-                        stats.tasks_created,
-                        stats.skipped,
-                        stats.replied,
-                        stats.summaries_saved,
+                    commit_pending(
+                        &mut pending,
+                        &session,
+                        &mut history,
+                        &mut background,
+                        &gmail,
+                        &hooks,
+                        category_labels,
                     )?;
-                    tui.wait_for_key()?;
+                    finish_background(&mut tui, &mut background).await?;
+                    session.save(idx, None)?;
+                    tui.draw_summary(&session.stats)?;
+                    tui.wait_for_key().await?;
+                    drop(tui);
+                    save_journal(journal, &session.account_id, started_at, &decisions)?;
+                    record_feedback(&decisions)?;
                     return Ok(());
                 }
             }
-        }
+        };
     }
 
     // Show final summary
-    tui.draw_summary(
-        stats.total(),
-        stats.archived,
-        stats.deleted,
-        stats.tasks_created,
-        stats.skipped,
-        stats.replied,
-        stats.summaries_saved,
+    commit_pending(
+        &mut pending,
+        &session,
+        &mut history,
+        &mut background,
+        &gmail,
+        &hooks,
+        category_labels,
     )?;
-    tui.wait_for_key()?;
+    finish_background(&mut tui, &mut background).await?;
+    // A reached target leaves the rest of the queue for --resume
+    if idx < session.emails.len() {
+        session.save(idx, None)?;
+    } else {
+        Session::clear()?;
+    }
+    tui.draw_summary(&session.stats)?;
+    tui.wait_for_key().await?;
+    drop(tui);
+    save_journal(journal, &session.account_id, started_at, &decisions)?;
+    record_feedback(&decisions)?;
+
+    Ok(())
+}
+
+/// Add the run's decisions to the AI agreement stats and sender records
+fn record_feedback(decisions: &[Decision]) -> Result<()> {
+    if decisions.is_empty() {
+        return Ok(());
+    }
+    let mut feedback = Feedback::load()?;
+    for decision in decisions {
+        feedback.record(decision);
+    }
+    feedback.save()
+}
+
+/// Append the run's decisions to the session journal, when enabled
+fn save_journal(
+    format: Option<JournalFormat>,
+    account_id: &str,
+    started_at: chrono::DateTime<chrono::Utc>,
+    decisions: &[Decision],
+) -> Result<()> {
+    let Some(format) = format else {
+        return Ok(());
+    };
+    if decisions.is_empty() {
+        return Ok(());
+    }
+
+    let path = JournalEntry::new(account_id, started_at, decisions).append(format)?;
+    println!("{}", tr("📓 Session logged to {}", &[&path.display()]));
+    Ok(())
+}
+
+/// Extra metadata lines for the email screen: who the sender is and whether it was seen before
+fn email_notes(
+    email: &Email,
+    vip: &Vip,
+    aliases: &Aliases,
+    contacts: &ContactBook,
+    history: &History,
+    person: Option<&Person>,
+) -> Vec<String> {
+    let mut notes = Vec::new();
+    if vip.is_vip(email) {
+        notes.push("⭐ VIP sender".to_string());
+    }
+    if let Some(alias) = aliases.matched(email) {
+        notes.push(format!("📨 Sent to alias: {}", alias.address));
+    }
+    if let Some(auth) = email.authentication() {
+        notes.push(format!("🔐 {}", auth.describe()));
+    }
+    notes.extend(tracking::notice(email));
+    notes.extend(shipping::detect(email).map(|shipment| shipment.describe()));
+    if let Some(person) = person {
+        notes.push(format!("📇 {}", person.describe()));
+    }
+    if let Some((_, address)) = parse_addresses(&email.from).first()
+        && let Some(contact) = contacts.get(address)
+    {
+        notes.push(format!("👤 Contact: {}", contact.describe()));
+    }
+    if let Some(h) = history.get(&email.id) {
+        notes.push(format!("👀 Seen before: {}", h.describe()));
+    }
+    notes
+}
+
+/// List the email's links, expanding shortened ones first
+async fn show_links(tui: &mut Tui, config: &Config, email: &Email) -> Result<()> {
+    let found = links::extract(email);
+    if found.is_empty() {
+        return tui.toast(t("🔗 No links in this email"), false);
+    }
+    let client = links::client(&config.network)?;
+    let checked = tui
+        .busy(
+            t("🔗 Expanding links..."),
+            links::expand_all(&client, found),
+        )
+        .await?;
+    tui.view_links(&checked).await
+}
+
+/// Read an invoice or receipt with the AI and, once I've checked what it read,
+/// add it to the expense ledger
+async fn record_expense(
+    tui: &mut Tui,
+    ai: &AiClient,
+    gmail: &GmailClient,
+    email: &Email,
+    analysis: Option<&EmailAnalysis>,
+) -> Result<()> {
+    if analysis.is_some_and(|a| a.category != Category::Billing) {
+        tui.draw_message(
+            t("🧾 This email isn't filed as billing. Read it as an invoice anyway? [y/n]"),
+            false,
+        )?;
+        if !tui.wait_for_yes_no().await? {
+            return Ok(());
+        }
+    }
+
+    let read = tui
+        .busy(t("🧾 Reading the invoice..."), async {
+            let excerpt = attachments::text_excerpt(gmail, email).await;
+            ai.extract_expense(email, excerpt.as_deref()).await
+        })
+        .await?;
+    let mut entry = match read.and_then(|expense| expenses::Entry::from_expense(email, expense)) {
+        Ok(entry) => entry,
+        Err(e) => {
+            return tui.toast(
+                tr("❌ Couldn't read the invoice: {}", &[&format!("{:#}", e)]),
+                true,
+            );
+        }
+    };
+
+    let Some(line) = tui
+        .prompt_with(
+            &format!(" {} ", t("Expense")),
+            t("Vendor; amount; currency; date:"),
+            &entry.editable(),
+        )
+        .await?
+    else {
+        return Ok(());
+    };
+    if let Err(e) = entry.apply_edit(&line) {
+        return tui.toast(format!("❌ {}", e), true);
+    }
+
+    let line = entry.describe();
+    let message = if expenses::record(entry)? {
+        "🧾 Updated in the ledger: {}"
+    } else {
+        "🧾 Added to the ledger: {}"
+    };
+    tui.toast(tr(message, &[&line]), false)
+}
+
+/// Copy the email's one-time code to the clipboard, digits only
+fn copy_code(tui: &mut Tui, email: &Email) -> Result<()> {
+    let Some(code) = otp::find(email) else {
+        return tui.toast(t("🔑 No verification code found"), false);
+    };
+    match clipboard::copy(&otp::digits(&code)) {
+        Ok(clipboard::Copied::Clipboard) => tui.toast(tr("📋 Copied {}", &[&code]), false),
+        // OSC 52 has no reply, so whether it worked is the terminal's secret
+        Ok(clipboard::Copied::Terminal) => tui.toast(
+            tr(
+                "📋 Asked the terminal to copy {} (needs OSC 52 support; paste to check)",
+                &[&code],
+            ),
+            false,
+        ),
+        Err(e) => tui.toast(tr("❌ Couldn't copy: {}", &[&e]), true),
+    }
+}
+
+/// Redraw a single progress line, e.g. while messages download
+fn print_progress(verb: &str, done: usize, total: usize) {
+    use std::io::Write;
+
+    const WIDTH: usize = 30;
+    let filled = (done * WIDTH).checked_div(total).unwrap_or(WIDTH);
+    print!(
+        "\r   [{}{}] {} {}/{}…",
+        "█".repeat(filled),
+        "░".repeat(WIDTH - filled),
+        verb,
+        done,
+        total
+    );
+    let _ = std::io::stdout().flush();
+}
+
+/// Analyze an email, reading its attachment when the body only points to it
+/// and the earlier messages with the sender. With `ai.skip_bulk`, bulk mail is
+/// classified from its headers instead.
+async fn analyze(ai: &AiClient, gmail: &GmailClient, email: &Email) -> Result<EmailAnalysis> {
+    if ai.skip_bulk() && email.bulk_signal().is_some() {
+        return Ok(heuristics::classify(email, &Feedback::default()));
+    }
+    // A body Gmail's payload tree missed is worth a second parse before
+    // the model sees nothing but the subject
+    let repaired;
+    let email = if eml::body_missing(email) {
+        repaired = eml::with_raw_body(gmail, email).await.unwrap_or_else(|e| {
+            tracing::debug!("Failed to reparse message {}: {:#}", email.id, e);
+            email.clone()
+        });
+        &repaired
+    } else {
+        email
+    };
+    let (excerpt, earlier) = tokio::join!(
+        attachments::text_excerpt(gmail, email),
+        related::earlier_messages(gmail, email, ai.history_messages())
+    );
+    ai.analyze_email(email, excerpt.as_deref(), &earlier).await
+}
+
+/// Like `analyze`, with the escalation model
+async fn reanalyze(ai: &AiClient, gmail: &GmailClient, email: &Email) -> Result<EmailAnalysis> {
+    let (excerpt, earlier) = tokio::join!(
+        attachments::text_excerpt(gmail, email),
+        related::earlier_messages(gmail, email, ai.history_messages())
+    );
+    ai.reanalyze_email(email, excerpt.as_deref(), &earlier)
+        .await
+}
+
+/// Draft a reply knowing the earlier messages with the sender; when the times
+/// it proposes clash with my calendar, the draft offers others
+async fn draft_reply(
+    ai: &AiClient,
+    gmail: &GmailClient,
+    email: &Email,
+    conflicts: &[String],
+) -> Result<String> {
+    let earlier = related::earlier_messages(gmail, email, ai.history_messages()).await;
+    if conflicts.is_empty() {
+        ai.generate_reply(email, &earlier).await
+    } else {
+        ai.generate_reply_around(email, conflicts, &earlier).await
+    }
+}
+
+/// Fill in missing analyses a few emails at a time
+async fn analyze_all(
+    ai: &AiClient,
+    gmail: &GmailClient,
+    adjust: &impl Fn(&Email, &mut EmailAnalysis),
+    emails: &[Email],
+    analyses: &mut [Option<EmailAnalysis>],
+) {
+    use futures::StreamExt;
+
+    const CONCURRENCY: usize = 4;
+
+    let missing: Vec<usize> = (0..emails.len())
+        .filter(|&i| analyses[i].is_none())
+        .collect();
+    let total = missing.len();
+    let mut done = 0;
+    print_progress("Analyzed", done, total);
+
+    let mut results = futures::stream::iter(missing)
+        .map(|i| async move { (i, analyze(ai, gmail, &emails[i]).await) })
+        .buffer_unordered(CONCURRENCY);
+
+    while let Some((i, result)) = results.next().await {
+        analyses[i] = result.ok().map(|mut a| {
+            adjust(&emails[i], &mut a);
+            a
+        });
+        done += 1;
+        print_progress("Analyzed", done, total);
+    }
+}
+
+/// Analyze emails a few at a time on a background task, sending each result as it lands
+fn analyze_in_background(
+    ai: &Arc<AiClient>,
+    gmail: &Arc<GmailClient>,
+    emails: Vec<Email>,
+    tx: &tokio::sync::mpsc::UnboundedSender<(String, Result<EmailAnalysis>)>,
+) {
+    use futures::StreamExt;
+
+    const CONCURRENCY: usize = 4;
+
+    if emails.is_empty() {
+        return;
+    }
+    let (ai, gmail, tx) = (Arc::clone(ai), Arc::clone(gmail), tx.clone());
+    tokio::spawn(async move {
+        let (ai, gmail) = (&ai, &gmail);
+        let mut results = futures::stream::iter(emails)
+            .map(|email| async move {
+                let result = analyze(ai, gmail, &email).await;
+                (email.id, result)
+            })
+            .buffer_unordered(CONCURRENCY);
+        while let Some(result) = results.next().await {
+            if tx.send(result).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+/// Fetch full messages for the dashboard a few at a time, sending each back by id
+fn prefetch_bodies(
+    gmail: &Arc<GmailClient>,
+    ids: Vec<String>,
+    tx: &tokio::sync::mpsc::UnboundedSender<(String, Result<Email>)>,
+) {
+    use futures::StreamExt;
+
+    const CONCURRENCY: usize = 4;
+
+    if ids.is_empty() {
+        return;
+    }
+    let (gmail, tx) = (Arc::clone(gmail), tx.clone());
+    tokio::spawn(async move {
+        let gmail = &gmail;
+        let mut results = futures::stream::iter(ids)
+            .map(|id| async move {
+                let result = gmail.fetch_email(&id).await;
+                (id, result)
+            })
+            .buffer_unordered(CONCURRENCY);
+        while let Some(result) = results.next().await {
+            if tx.send(result).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+/// Give a change that ends the session its undo window: Undo brings the email
+/// back, returning its index, while any other key or the window running out
+/// lets the change stand
+async fn offer_undo(
+    tui: &mut Tui,
+    pending: &mut Option<PendingChange>,
+    stats: &mut Stats,
+    decisions: &mut Vec<Decision>,
+) -> Result<Option<usize>> {
+    let Some(commit_at) = pending.as_ref().map(|p| p.commit_at) else {
+        return Ok(None);
+    };
+    let action = tokio::select! {
+        _ = tokio::time::sleep_until(commit_at) => return Ok(None),
+        action = tui.wait_for_action() => action?,
+    };
+    if action != Action::Undo {
+        return Ok(None);
+    }
+    let Some(change) = pending.take() else {
+        return Ok(None);
+    };
+    stats.undo(&change.kind);
+    decisions.pop();
+    tui.toast(t("↩️ Undone"), false)?;
+    Ok(Some(change.index))
+}
+
+/// Send the held-back change (if any) to Gmail
+fn commit_pending(
+    pending: &mut Option<PendingChange>,
+    session: &Session,
+    history: &mut History,
+    background: &mut JoinSet<Result<()>>,
+    gmail: &Arc<GmailClient>,
+    hooks: &Hooks,
+    category_labels: bool,
+) -> Result<()> {
+    let Some(change) = pending.take() else {
+        return Ok(());
+    };
+    commit_change(
+        change.kind,
+        &session.emails[change.index],
+        session.analyses[change.index].as_ref(),
+        history,
+        background,
+        gmail,
+        hooks,
+        category_labels,
+    )
+}
+
+/// Send the dashboard's held-back change (if any) to Gmail, and learn from it
+#[allow(clippy::too_many_arguments)]
+fn commit_held(
+    pending: &mut Option<(PendingChange, Email)>,
+    dashboard: &Dashboard,
+    feedback: &mut Feedback,
+    history: &mut History,
+    background: &mut JoinSet<Result<()>>,
+    gmail: &Arc<GmailClient>,
+    hooks: &Hooks,
+    category_labels: bool,
+) -> Result<()> {
+    let Some((change, email)) = pending.take() else {
+        return Ok(());
+    };
+    let analysis = dashboard.analysis(&email);
+    let decision = match change.kind {
+        PendingKind::Archive => "archived",
+        PendingKind::Delete => "deleted",
+        PendingKind::MarkRead => "marked read",
+        PendingKind::Move { .. } => "moved",
+        PendingKind::ReadLater(_) => "read later",
+    };
+    feedback.record(&Decision::new(&email, analysis, decision));
+    commit_change(
+        change.kind,
+        &email,
+        analysis,
+        history,
+        background,
+        gmail,
+        hooks,
+        category_labels,
+    )
+}
+
+/// Make a change final: drop the email from the seen history (only now, so an
+/// undone change keeps it), fire its hook and send it to Gmail
+#[allow(clippy::too_many_arguments)]
+fn commit_change(
+    kind: PendingKind,
+    email: &Email,
+    analysis: Option<&EmailAnalysis>,
+    history: &mut History,
+    background: &mut JoinSet<Result<()>>,
+    gmail: &Arc<GmailClient>,
+    hooks: &Hooks,
+    category_labels: bool,
+) -> Result<()> {
+    let id = &email.id;
+    history.forget(id)?;
+
+    let event = match kind {
+        PendingKind::Archive | PendingKind::ReadLater(_) => HookEvent::Archive,
+        PendingKind::Delete => HookEvent::Delete,
+        PendingKind::MarkRead => HookEvent::MarkRead,
+        PendingKind::Move { .. } => HookEvent::Move,
+    };
+    hooks.fire(background, event, email, analysis);
+
+    match kind {
+        PendingKind::Archive => {
+            archive_in_background(background, gmail, id);
+            if category_labels {
+                label_in_background(background, gmail, id, analysis);
+            }
+        }
+        PendingKind::Delete => {
+            let gmail = Arc::clone(gmail);
+            let id = id.clone();
+            background.spawn(async move { gmail.delete(&id).await });
+        }
+        PendingKind::MarkRead => {
+            let gmail = Arc::clone(gmail);
+            let id = id.clone();
+            background.spawn(async move { gmail.mark_read(&id).await });
+        }
+        PendingKind::Move { label_id } => {
+            let gmail = Arc::clone(gmail);
+            let id = id.clone();
+            background.spawn(async move { gmail.move_to_label(&id, &label_id).await });
+        }
+        PendingKind::ReadLater(item) => {
+            ReadLater::load()?.add(*item)?;
+            archive_in_background(background, gmail, id);
+            if category_labels {
+                label_in_background(background, gmail, id, analysis);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Offer each group of similar emails in the queue as one card. Archived groups
+/// leave the queue; the rest stay for one-at-a-time triage.
+async fn triage_groups(
+    tui: &mut Tui,
+    session: &mut Session,
+    gmail: &Arc<GmailClient>,
+    history: &mut History,
+    hooks: &Hooks,
+    background: &mut JoinSet<Result<()>>,
+    category_labels: bool,
+) -> Result<Vec<Decision>> {
+    let groups = clusters::find(&session.emails);
+    let mut decisions = Vec::new();
+
+    for (position, cluster) in groups.iter().enumerate() {
+        let mut selected = 0;
+        loop {
+            let emails: Vec<&Email> = session
+                .emails
+                .iter()
+                .filter(|e| cluster.ids.contains(&e.id))
+                .collect();
+            tui.draw_cluster(cluster, &emails, position + 1, groups.len(), selected)?;
+
+            match tui.wait_for_cluster_action().await? {
+                ClusterAction::Up => selected = selected.saturating_sub(1),
+                ClusterAction::Down => selected = (selected + 1).min(emails.len() - 1),
+                ClusterAction::View => {
+                    tui.view_full_email(emails[selected], gmail).await?;
+                }
+                ClusterAction::OpenList => {
+                    let url = format!(
+                        "https://mail.google.com/mail/u/0/#search/from%3A{}+in%3Ainbox",
+                        cluster.address.replace('+', "%2B")
+                    );
+                    let _ = open::that(&url);
+                    tui.toast(t("🌐 Opened in browser"), false)?;
+                }
+                ClusterAction::Individually => break,
+                ClusterAction::Stop => return Ok(decisions),
+                ClusterAction::ArchiveAll => {
+                    let result = tui
+                        .busy(
+                            &tr("📦 Archiving {} emails...", &[&cluster.ids.len()]),
+                            gmail.archive_many(&cluster.ids),
+                        )
+                        .await?;
+
+                    let mut labels: HashMap<String, Vec<String>> = HashMap::new();
+                    for id in &result.succeeded {
+                        let Some(index) = session.emails.iter().position(|e| &e.id == id) else {
+                            continue;
+                        };
+                        let email = session.emails.remove(index);
+                        let analysis = session.analyses.remove(index);
+                        history.forget(id)?;
+                        hooks.fire(background, HookEvent::Archive, &email, analysis.as_ref());
+                        if let Some(analysis) = &analysis {
+                            labels
+                                .entry(format!("clinbox/{}", analysis.category.name()))
+                                .or_default()
+                                .push(id.clone());
+                        }
+                        decisions.push(Decision::new(&email, analysis.as_ref(), "archived"));
+                        session.stats.archived += 1;
+                    }
+                    if category_labels {
+                        for (name, ids) in labels {
+                            let gmail = Arc::clone(gmail);
+                            background.spawn(async move {
+                                let label = gmail.find_or_create_label(&name).await?;
+                                let result = gmail.add_label_many(&ids, &label.id).await;
+                                match result.first_error() {
+                                    Some(error) => anyhow::bail!(
+                                        "Failed to label {} emails as {}: {}",
+                                        result.failed.len(),
+                                        name,
+                                        error
+                                    ),
+                                    None => Ok(()),
+                                }
+                            });
+                        }
+                    }
+                    session.save(0, None)?;
+
+                    match result.first_error() {
+                        Some(error) => tui.toast(
+                            tr(
+                                "⚠️  Archived {}; {} failed and stay in the queue: {}",
+                                &[&result.succeeded.len(), &result.failed.len(), &error],
+                            ),
+                            true,
+                        )?,
+                        None => tui.toast(
+                            tr("✅ Archived {} emails", &[&result.succeeded.len()]),
+                            false,
+                        )?,
+                    }
+                    break;
+                }
+            }
+        }
+    }
+
+    Ok(decisions)
+}
+
+/// Run the rules the first time an email is ready for them. A match is returned
+/// as the action to take; a broken rule is reported and otherwise ignored.
+fn apply_rules(
+    rules: &Rules,
+    tui: &mut Tui,
+    ruled: &mut HashSet<String>,
+    email: &Email,
+    analysis: Option<&EmailAnalysis>,
+    aliases: &Aliases,
+) -> Result<Option<rules::RuleMatch>> {
+    if rules.is_empty() || !ruled.insert(email.id.clone()) {
+        return Ok(None);
+    }
+
+    match rules.evaluate(email, analysis, aliases) {
+        Ok(rule_match) => Ok(rule_match),
+        Err(e) => {
+            tui.toast(format!("❌ {}", e), true)?;
+            Ok(None)
+        }
+    }
+}
+
+/// Run the on_urgent hook the first time an email shows up with an urgent analysis
+fn notify_urgent(
+    hooks: &Hooks,
+    background: &mut JoinSet<Result<()>>,
+    notified: &mut HashSet<String>,
+    email: &Email,
+    analysis: Option<&EmailAnalysis>,
+) {
+    if analysis.is_some_and(|a| a.priority == Priority::Urgent) && notified.insert(email.id.clone())
+    {
+        hooks.fire(background, HookEvent::Urgent, email, analysis);
+    }
+}
 
+/// Queue an archive so the next email can be shown without waiting on Gmail
+fn archive_in_background(background: &mut JoinSet<Result<()>>, gmail: &Arc<GmailClient>, id: &str) {
+    let gmail = Arc::clone(gmail);
+    let id = id.to_string();
+    background.spawn(async move { gmail.archive(&id).await });
+}
+
+/// Queue applying the AI category as a `clinbox/<category>` Gmail label
+fn label_in_background(
+    background: &mut JoinSet<Result<()>>,
+    gmail: &Arc<GmailClient>,
+    id: &str,
+    analysis: Option<&EmailAnalysis>,
+) {
+    let Some(analysis) = analysis else {
+        return;
+    };
+    let gmail = Arc::clone(gmail);
+    let id = id.to_string();
+    let name = format!("clinbox/{}", analysis.category.name());
+    background.spawn(async move {
+        let label = gmail.find_or_create_label(&name).await?;
+        gmail.add_label(&id, &label.id).await
+    });
+}
+
+/// Surface a failed background Gmail operation as an error toast
+fn report_background_result(
+    tui: &mut Tui,
+    result: std::result::Result<Result<()>, tokio::task::JoinError>,
+) -> Result<()> {
+    match result {
+        Ok(Ok(())) => Ok(()),
+        Ok(Err(e)) => tui.toast(format!("❌ {}", e), true),
+        Err(e) => tui.toast(tr("❌ Background task failed: {}", &[&e]), true),
+    }
+}
+
+/// Wait for queued Gmail operations to finish before leaving the session
+async fn finish_background(tui: &mut Tui, background: &mut JoinSet<Result<()>>) -> Result<()> {
+    while let Some(result) = tui
+        .busy(t("Finishing pending changes..."), background.join_next())
+        .await?
+    {
+        report_background_result(tui, result)?;
+    }
     Ok(())
 }
 
 fn save_summary_markdown(
-    email: &crate::email::Email,
-    summary: &crate::ai::ArticleSummary,
+    email: &Email,
+    summary: &clinbox_core::ai::ArticleSummary,
 ) -> Result<std::path::PathBuf> {
     use std::fs;
 
@@ -719,44 +6078,33 @@ fn save_summary_markdown(
     let content = format!(
         r#"# {}
 
-**From:** {}
-**Date:** {}
+**{}** {}
+**{}** {}
 
-## Resumen
+## {}
 
 {}
 
-## Puntos Clave
+## {}
 
 {}
 
 ---
-*Generated by Clinbox*
+*{}*
 "#,
         email.subject,
+        t("From:"),
         email.from,
+        t("Date:"),
         email.date.format("%Y-%m-%d %H:%M"),
+        t("Summary"),
         summary.summary,
-        takeaways
+        t("Key Takeaways"),
+        takeaways,
+        t("Generated by Clinbox")
     );
 
     fs::write(&file_path, content)?;
 
     Ok(file_path)
 }
-
-#[derive(Default)]
-struct Stats {
-    archived: usize,
-    deleted: usize,
-    tasks_created: usize,
-    skipped: usize,
-    replied: usize,
-    summaries_saved: usize,
-}
-
-impl Stats {
-    fn total(&self) -> usize {
-        self.archived + self.deleted + self.tasks_created + self.skipped + self.replied + self.summaries_saved
-    }
-}
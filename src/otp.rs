@@ -0,0 +1,145 @@
+use clinbox_core::email::Email;
+
+/// Words that come with one-time codes
+const KEYWORDS: &[&str] = &[
+    "code",
+    "otp",
+    "one-time",
+    "passcode",
+    "verification",
+    "verify",
+    "pin",
+    "2fa",
+    "two-factor",
+    "código",
+    "clave",
+    "verificación",
+];
+
+/// Words before "code" or "pin" that mean something else
+const NOT_BEFORE: &[&str] = &[
+    "promo", "discount", "coupon", "zip", "postal", "source", "qr",
+];
+
+/// Digits in a code
+const MIN_DIGITS: usize = 4;
+const MAX_DIGITS: usize = 8;
+
+/// Farthest a code may be from its keyword, in bytes
+const WINDOW: usize = 80;
+
+/// The one-time code in an email, as written (e.g. "493-221"): a run of 4 to 8
+/// digits next to a word like "code" or "verification", the subject first
+pub fn find(email: &Email) -> Option<String> {
+    find_in(&email.subject).or_else(|| find_in(&email.body_text()))
+}
+
+/// The code without separators, as sites expect it typed or pasted
+pub fn digits(code: &str) -> String {
+    code.chars().filter(char::is_ascii_digit).collect()
+}
+
+/// The candidate nearest a keyword, if one is close enough
+fn find_in(text: &str) -> Option<String> {
+    let lower = text.to_ascii_lowercase();
+    let keywords = keyword_spans(&lower);
+    if keywords.is_empty() {
+        return None;
+    }
+
+    candidates(text)
+        .into_iter()
+        .filter_map(|(start, end)| {
+            let distance = keywords
+                .iter()
+                .map(|&(k_start, k_end)| {
+                    if k_end <= start {
+                        start - k_end
+                    } else {
+                        k_start.saturating_sub(end)
+                    }
+                })
+                .min()?;
+            (distance <= WINDOW).then_some((distance, start, end))
+        })
+        .min()
+        .map(|(_, start, end)| text[start..end].to_string())
+}
+
+/// Where the keywords are, as whole words
+fn keyword_spans(lower: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    for keyword in KEYWORDS {
+        for (at, _) in lower.match_indices(keyword) {
+            let end = at + keyword.len();
+            let before = lower[..at].chars().next_back();
+            let after = lower[end..].chars().next();
+            if before.is_some_and(char::is_alphanumeric) || after.is_some_and(char::is_alphanumeric)
+            {
+                continue;
+            }
+            let previous_word = lower[..at]
+                .split_whitespace()
+                .next_back()
+                .unwrap_or_default();
+            if NOT_BEFORE.contains(&previous_word) {
+                continue;
+            }
+            spans.push((at, end));
+        }
+    }
+    spans
+}
+
+/// Runs of 4 to 8 digits that stand alone, optionally split in two equal
+/// groups ("493-221", "1234 5678"); prices, dates, times and longer numbers
+/// are left out
+fn candidates(text: &str) -> Vec<(usize, usize)> {
+    let bytes = text.as_bytes();
+    let digit_run = |from: usize| {
+        bytes[from..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit())
+            .count()
+    };
+    let mut spans = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if !bytes[i].is_ascii_digit() {
+            i += 1;
+            continue;
+        }
+        let first = digit_run(i);
+        let start = i;
+        let mut end = i + first;
+        i = end;
+
+        let glued = text[..start]
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_alphanumeric() || "$€£.,:/#+".contains(c));
+        if glued {
+            continue;
+        }
+
+        // A second group of the same length: "493-221", "1234 5678"
+        if (3..=4).contains(&first)
+            && matches!(bytes.get(end), Some(b'-' | b' '))
+            && digit_run(end + 1) == first
+        {
+            end += 1 + first;
+            i = end;
+        }
+
+        let count = digits(&text[start..end]).len();
+        let next = bytes.get(end).copied();
+        let continues = next.is_some_and(|b| b.is_ascii_alphanumeric() || b == b'%')
+            || (next.is_some_and(|b| b".,:/-".contains(&b))
+                && bytes.get(end + 1).is_some_and(u8::is_ascii_digit));
+        if (MIN_DIGITS..=MAX_DIGITS).contains(&count) && !continues {
+            spans.push((start, end));
+        }
+    }
+    spans
+}
@@ -0,0 +1,358 @@
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::time::{Duration as StdDuration, Instant};
+
+use clinbox_core::config::Config;
+use clinbox_core::email::Email;
+use clinbox_core::gmail::Copies;
+
+/// A reply waiting for its send time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedReply {
+    pub id: String,
+    pub account_id: String,
+    pub send_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub to: String,
+    pub subject: String,
+    pub thread_id: String,
+    /// Gmail id of the message being replied to
+    pub reply_to_id: String,
+    pub body: String,
+    /// Cc addresses added on the draft screen
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cc: Vec<String>,
+    /// Bcc addresses added on the draft screen; the account's automatic Bcc is
+    /// added when it's sent
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bcc: Vec<String>,
+    /// Why the last send attempt failed; set for replies queued after a failed send
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    /// Failed send attempts so far
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub attempts: u32,
+    /// No longer retried automatically, after a permanent error or
+    /// MAX_SEND_ATTEMPTS failures; `outbox flush` still tries it
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub held: bool,
+}
+
+/// Failed sends before a queued reply is held for `outbox flush`
+pub const MAX_SEND_ATTEMPTS: u32 = 5;
+
+fn is_zero(n: &u32) -> bool {
+    *n == 0
+}
+
+impl QueuedReply {
+    /// The Cc and Bcc addresses added on the draft screen
+    pub fn copies(&self) -> Copies {
+        Copies {
+            cc: self.cc.clone(),
+            bcc: self.bcc.clone(),
+        }
+    }
+}
+
+/// Replies scheduled with "send later", plus replies that failed to send (e.g.
+/// offline). Gmail's API has no scheduled send, so they're kept locally and sent
+/// the next time Clinbox runs after they're due.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Outbox {
+    pub replies: Vec<QueuedReply>,
+}
+
+impl Outbox {
+    /// Load the outbox from file
+    pub fn load() -> Result<Self> {
+        let path = Config::outbox_path()?;
+
+        if path.exists() {
+            let content = fs::read_to_string(&path).context("Failed to read outbox file")?;
+            let outbox: Outbox =
+                serde_json::from_str(&content).context("Failed to parse outbox file")?;
+            Ok(outbox)
+        } else {
+            Ok(Outbox::default())
+        }
+    }
+
+    /// Save the outbox to file
+    pub fn save(&self) -> Result<()> {
+        let path = Config::outbox_path()?;
+        fs::create_dir_all(path.parent().unwrap())?;
+
+        let content = serde_json::to_string_pretty(self).context("Failed to serialize outbox")?;
+        fs::write(&path, content).context("Failed to write outbox file")?;
+
+        Ok(())
+    }
+
+    /// Reload the outbox under its lock, apply `change` and save, so changes
+    /// another Clinbox process (e.g. a cron `outbox send`) made since this
+    /// copy was loaded aren't overwritten
+    fn update<T>(&mut self, change: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        let _lock = Lock::acquire()?;
+        *self = Self::load()?;
+        let result = change(self)?;
+        self.save()?;
+        Ok(result)
+    }
+
+    /// Queue a reply to `original` and save
+    pub fn schedule(
+        &mut self,
+        account_id: &str,
+        original: &Email,
+        body: &str,
+        copies: &Copies,
+        send_at: DateTime<Utc>,
+    ) -> Result<&QueuedReply> {
+        let reply = QueuedReply {
+            id: format!("{:x}", Utc::now().timestamp_millis()),
+            account_id: account_id.to_string(),
+            send_at,
+            created_at: Utc::now(),
+            to: original.from.clone(),
+            subject: original.subject.clone(),
+            thread_id: original.thread_id.clone(),
+            reply_to_id: original.id.clone(),
+            body: body.to_string(),
+            cc: copies.cc.clone(),
+            bcc: copies.bcc.clone(),
+            last_error: None,
+            attempts: 0,
+            held: false,
+        };
+        self.update(|outbox| {
+            outbox.replies.push(reply);
+            Ok(())
+        })?;
+        Ok(self.replies.last().unwrap())
+    }
+
+    /// Queue a reply that failed to send with a transient error so the next
+    /// session retries it, and save
+    pub fn queue_failed(
+        &mut self,
+        account_id: &str,
+        original: &Email,
+        body: &str,
+        copies: &Copies,
+        error: &str,
+    ) -> Result<()> {
+        let id = self
+            .schedule(account_id, original, body, copies, Utc::now())?
+            .id
+            .clone();
+        self.mark_failed(&id, error, true).map(|_| ())
+    }
+
+    /// Remember why sending a reply failed, keeping it queued. It's held back from
+    /// automatic retries after a permanent error or MAX_SEND_ATTEMPTS failures;
+    /// returns whether it is now held.
+    pub fn mark_failed(&mut self, id: &str, error: &str, transient: bool) -> Result<bool> {
+        self.update(|outbox| {
+            let Some(reply) = outbox.replies.iter_mut().find(|r| r.id == id) else {
+                return Ok(false);
+            };
+            reply.last_error = Some(error.to_string());
+            reply.attempts += 1;
+            reply.held = !transient || reply.attempts >= MAX_SEND_ATTEMPTS;
+            Ok(reply.held)
+        })
+    }
+
+    /// Remove a queued reply by id (or unique id prefix)
+    pub fn cancel(&mut self, id: &str) -> Result<QueuedReply> {
+        self.update(|outbox| {
+            let matches: Vec<usize> = outbox
+                .replies
+                .iter()
+                .enumerate()
+                .filter(|(_, r)| r.id.starts_with(id))
+                .map(|(i, _)| i)
+                .collect();
+
+            match matches.as_slice() {
+                [index] => Ok(outbox.replies.remove(*index)),
+                [] => bail!("No queued reply with id '{}'", id),
+                _ => bail!("Id '{}' matches several queued replies", id),
+            }
+        })
+    }
+
+    /// Replies for an account whose send time has passed; held ones only with
+    /// `include_held`
+    pub fn due(&self, account_id: &str, include_held: bool) -> Vec<QueuedReply> {
+        let now = Utc::now();
+        self.replies
+            .iter()
+            .filter(|r| r.account_id == account_id && r.send_at <= now)
+            .filter(|r| include_held || !r.held)
+            .cloned()
+            .collect()
+    }
+
+    /// Drop a reply once it has been sent
+    pub fn remove(&mut self, id: &str) -> Result<()> {
+        self.update(|outbox| {
+            outbox.replies.retain(|r| r.id != id);
+            Ok(())
+        })
+    }
+}
+
+/// How long to wait for another process to release the outbox
+const LOCK_WAIT: StdDuration = StdDuration::from_secs(10);
+
+/// A lock file older than this was left by a process that died holding it
+const LOCK_STALE: StdDuration = StdDuration::from_secs(60);
+
+/// A lock file next to the outbox, held while it is read, changed and written
+/// back; removed on drop
+struct Lock(PathBuf);
+
+impl Lock {
+    fn acquire() -> Result<Self> {
+        let path = Config::outbox_path()?.with_extension("json.lock");
+        fs::create_dir_all(path.parent().unwrap())?;
+        let started = Instant::now();
+        loop {
+            match fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)
+            {
+                Ok(_) => return Ok(Lock(path)),
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                    let stale = fs::metadata(&path)
+                        .and_then(|m| m.modified())
+                        .is_ok_and(|at| at.elapsed().is_ok_and(|age| age > LOCK_STALE));
+                    if stale {
+                        let _ = fs::remove_file(&path);
+                    } else if started.elapsed() > LOCK_WAIT {
+                        bail!(
+                            "The outbox is locked by another Clinbox process (remove {} if none is running)",
+                            path.display()
+                        );
+                    } else {
+                        std::thread::sleep(StdDuration::from_millis(50));
+                    }
+                }
+                Err(e) => return Err(e).context("Failed to lock the outbox"),
+            }
+        }
+    }
+}
+
+impl Drop for Lock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+/// Parse a send time: "30m", "2h", "1d", "17:30" (today, or tomorrow once past),
+/// "tomorrow 9:00" or "2024-07-01 09:00"
+pub fn parse_send_at(input: &str) -> Result<DateTime<Utc>> {
+    let input = input.trim().to_lowercase();
+    let now = Local::now();
+
+    if let Some(last) = input.chars().last()
+        && let Ok(amount) = input[..input.len() - last.len_utf8()].trim().parse::<i64>()
+    {
+        if amount <= 0 {
+            bail!("The delay must be positive, e.g. 30m or 2h");
+        }
+        let offset = match last {
+            'm' => Duration::try_minutes(amount),
+            'h' => Duration::try_hours(amount),
+            'd' => Duration::try_days(amount),
+            _ => bail!("Unknown unit '{}' (use m, h or d)", last),
+        };
+        return offset
+            .and_then(|offset| now.checked_add_signed(offset))
+            .map(|at| at.with_timezone(&Utc))
+            .context("That's too far in the future");
+    }
+
+    let at = if let Some(time) = input.strip_prefix("tomorrow") {
+        let date = now.date_naive() + Duration::days(1);
+        date.and_time(parse_time(time.trim()).unwrap_or(NaiveTime::MIN + Duration::hours(9)))
+    } else if let Some(time) = parse_time(&input) {
+        let today = now.date_naive().and_time(time);
+        if today > now.naive_local() {
+            today
+        } else {
+            today + Duration::days(1)
+        }
+    } else if let Ok(at) = NaiveDateTime::parse_from_str(&input, "%Y-%m-%d %H:%M") {
+        at
+    } else if let Ok(date) = NaiveDate::parse_from_str(&input, "%Y-%m-%d") {
+        date.and_time(NaiveTime::MIN + Duration::hours(9))
+    } else {
+        bail!(
+            "Couldn't understand '{}'. Try 2h, 17:30, tomorrow 9:00 or 2024-07-01 09:00",
+            input
+        );
+    };
+
+    let at = Local
+        .from_local_datetime(&at)
+        .earliest()
+        .context("That time doesn't exist in the local timezone")?;
+    if at <= now {
+        bail!("That time is in the past");
+    }
+    Ok(at.with_timezone(&Utc))
+}
+
+fn parse_time(s: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(s, "%H:%M").ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn relative_delays() {
+        let before = Utc::now();
+        let at = parse_send_at("2h").unwrap();
+        assert!(at >= before + Duration::hours(2));
+        assert!(at <= Utc::now() + Duration::hours(2));
+        assert!(parse_send_at(" 30M ").unwrap() > before);
+        assert!(parse_send_at("1d").unwrap() > before + Duration::hours(23));
+    }
+
+    #[test]
+    fn rejects_empty_negative_and_huge_delays() {
+        assert!(parse_send_at("0m").is_err());
+        assert!(parse_send_at("-5h").is_err());
+        assert!(parse_send_at("9223372036854775807d").is_err());
+        assert!(parse_send_at("999999999999m").is_err());
+        assert!(parse_send_at("5w").is_err());
+    }
+
+    #[test]
+    fn absolute_times() {
+        let now = Local::now();
+        let at = parse_send_at("tomorrow 9:00")
+            .unwrap()
+            .with_timezone(&Local);
+        assert_eq!(at.date_naive(), now.date_naive() + Duration::days(1));
+        assert_eq!(at.time(), NaiveTime::from_hms_opt(9, 0, 0).unwrap());
+
+        let at = parse_send_at("17:30").unwrap().with_timezone(&Local);
+        assert!(at > now && at <= now + Duration::days(1));
+
+        assert!(parse_send_at("2000-01-01 09:00").is_err());
+        assert!(parse_send_at("2999-07-01").is_ok());
+        assert!(parse_send_at("someday").is_err());
+    }
+}
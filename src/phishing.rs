@@ -0,0 +1,63 @@
+use clinbox_core::email::{Category, Email, EmailAnalysis};
+
+use crate::contacts::parse_addresses;
+
+/// Sender names and domains phishers like to pose as
+const TRUSTED_NAMES: &[&str] = &[
+    "bank",
+    "banco",
+    "paypal",
+    "stripe",
+    "visa",
+    "mastercard",
+    "amex",
+    "revolut",
+    "wise",
+    "apple",
+    "google",
+    "microsoft",
+    "amazon",
+];
+
+/// A warning for mail that fails sender authentication; stronger when it looks
+/// like something phishers fake (billing or security mail, a bank's name)
+pub fn warning(email: &Email, analysis: Option<&EmailAnalysis>) -> Option<String> {
+    let auth = email.authentication()?;
+    if !auth.failed() {
+        return None;
+    }
+
+    let address = parse_addresses(&email.from)
+        .into_iter()
+        .next()
+        .map(|(_, address)| address.to_lowercase())
+        .unwrap_or_default();
+    let domain = auth
+        .from_domain
+        .clone()
+        .or_else(|| address.rsplit_once('@').map(|(_, d)| d.to_string()))
+        .unwrap_or_else(|| email.from.clone());
+
+    if looks_sensitive(email, analysis) {
+        Some(format!(
+            "🎣 Possible phishing: claims to be from {} but fails authentication ({})",
+            domain,
+            auth.describe()
+        ))
+    } else {
+        Some(format!(
+            "⚠️  {} failed sender authentication ({})",
+            domain,
+            auth.describe()
+        ))
+    }
+}
+
+/// Whether the email is about money or accounts, or its sender poses as a
+/// well-known company
+pub fn looks_sensitive(email: &Email, analysis: Option<&EmailAnalysis>) -> bool {
+    let sensitive_category =
+        analysis.is_some_and(|a| matches!(a.category, Category::Billing | Category::Security));
+    let from = email.from.to_lowercase();
+    sensitive_category || TRUSTED_NAMES.iter().any(|name| from.contains(name))
+}
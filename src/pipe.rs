@@ -0,0 +1,177 @@
+use anyhow::{Context, Result, bail};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use clinbox_core::email::Email;
+
+/// Placeholders accepted in pipe commands, with the email field they expand to
+pub const PLACEHOLDERS: &[&str] = &["{id}", "{subject}", "{from}", "{to}", "{date}"];
+
+/// Run a shell command with the email body on stdin. Placeholders such as
+/// `{subject}` are replaced with shell-quoted values, which are also exported
+/// as CLINBOX_* environment variables. A placeholder inside quotes is refused,
+/// since the value's own quoting doesn't hold there.
+pub fn run(command: &str, email: &Email) -> Result<()> {
+    let fields = fields(email);
+
+    let expanded = expand(command, &fields)?;
+    let mut child = shell(&expanded)
+        .envs(fields.iter().map(|(placeholder, value)| {
+            let name = placeholder.trim_matches(['{', '}']).to_uppercase();
+            (format!("CLINBOX_{}", name), value.clone())
+        }))
+        .stdin(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to run '{}'", command))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // A command that doesn't read its input closes the pipe early; that's fine
+        let _ = stdin.write_all(email.body_text().as_bytes());
+    }
+
+    let status = child.wait()?;
+    if !status.success() {
+        bail!("'{}' exited with {}", command, status);
+    }
+    Ok(())
+}
+
+/// Replace placeholders in one pass over the template; inserted values are
+/// never scanned again, so a subject of "{from}" stays text
+fn expand(command: &str, fields: &[(&str, String)]) -> Result<String> {
+    let mut expanded = String::with_capacity(command.len());
+    let mut quote: Option<char> = None;
+    let mut rest = command;
+    while let Some(c) = rest.chars().next() {
+        if c == '{'
+            && let Some((placeholder, value)) = fields
+                .iter()
+                .find(|(placeholder, _)| rest.starts_with(placeholder))
+        {
+            if quote.is_some() {
+                let name = placeholder.trim_matches(['{', '}']).to_uppercase();
+                bail!(
+                    "{} is inside quotes in '{}'; leave it unquoted (its value is quoted already) or use $CLINBOX_{}",
+                    placeholder,
+                    command,
+                    name
+                );
+            }
+            expanded.push_str(&shell_quote(value));
+            rest = &rest[placeholder.len()..];
+            continue;
+        }
+
+        quote = match (quote, c) {
+            (None, '"') => Some('"'),
+            (None, '\'') if cfg!(unix) => Some('\''),
+            (Some(open), c) if c == open => None,
+            (quote, _) => quote,
+        };
+        // A backslash outside single quotes keeps the next character literal
+        let escaped = c == '\\' && cfg!(unix) && quote != Some('\'');
+        let len = if escaped {
+            c.len_utf8() + rest[1..].chars().next().map_or(0, char::len_utf8)
+        } else {
+            c.len_utf8()
+        };
+        expanded.push_str(&rest[..len]);
+        rest = &rest[len..];
+    }
+    Ok(expanded)
+}
+
+fn fields(email: &Email) -> Vec<(&'static str, String)> {
+    PLACEHOLDERS
+        .iter()
+        .map(|&placeholder| {
+            let value = match placeholder {
+                "{id}" => email.id.clone(),
+                "{subject}" => email.subject.clone(),
+                "{from}" => email.from.clone(),
+                "{to}" => email.to.clone(),
+                _ => email.date.to_rfc3339(),
+            };
+            (placeholder, value)
+        })
+        .collect()
+}
+
+/// A command that runs `command` through the platform shell
+#[cfg(unix)]
+pub fn shell(command: &str) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.args(["-c", command]);
+    cmd
+}
+
+/// A command that runs `command` through the platform shell
+#[cfg(windows)]
+pub fn shell(command: &str) -> Command {
+    let mut cmd = Command::new("cmd");
+    cmd.args(["/C", command]);
+    cmd
+}
+
+/// Quote a value so the shell passes it through as a single argument
+pub fn shell_quote(value: &str) -> String {
+    if cfg!(windows) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        format!("'{}'", value.replace('\'', r"'\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_are_not_expanded_again() {
+        let fields = vec![
+            ("{subject}", "{from}".to_string()),
+            ("{from}", "a; echo INJECTED; #".to_string()),
+        ];
+        let expanded = expand("echo {subject}", &fields).unwrap();
+        if cfg!(windows) {
+            assert_eq!(expanded, "echo \"{from}\"");
+        } else {
+            assert_eq!(expanded, "echo '{from}'");
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn quoted_values_reach_the_command_intact() {
+        let fields = vec![
+            ("{subject}", "{from}".to_string()),
+            ("{from}", "a'; echo INJECTED; #".to_string()),
+        ];
+        let output = shell(&expand("echo {subject} {from} {unknown}", &fields).unwrap())
+            .output()
+            .unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "{from} a'; echo INJECTED; # {unknown}\n"
+        );
+    }
+
+    #[test]
+    fn placeholders_inside_quotes_are_refused() {
+        let fields = vec![("{subject}", "$(reboot)".to_string())];
+        assert!(expand("notify-send \"{subject}\"", &fields).is_err());
+        assert!(expand("notify-send \"New: {subject}\"", &fields).is_err());
+        assert!(expand("notify-send \"New:\" {subject}", &fields).is_ok());
+        assert!(expand("notify-send \"$CLINBOX_SUBJECT\"", &fields).is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn shell_quoting_rules() {
+        let fields = vec![("{subject}", "x".to_string())];
+        assert!(expand("echo '{subject}'", &fields).is_err());
+        assert!(expand("echo \\\"{subject}", &fields).is_ok());
+        assert!(expand("awk '{print}' mail/{subject}", &fields).is_ok());
+        assert!(expand("echo \"it's\" {subject}", &fields).is_ok());
+    }
+}
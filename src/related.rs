@@ -0,0 +1,40 @@
+use clinbox_core::email::Email;
+use clinbox_core::gmail::GmailClient;
+
+use crate::contacts::parse_addresses;
+
+/// Up to `count` messages exchanged with the sender before `email`, oldest
+/// first, for the AI to see the ongoing conversation. Fetched messages come
+/// from the message cache when they're in it. Best effort: a failed search
+/// gives none.
+pub async fn earlier_messages(gmail: &GmailClient, email: &Email, count: usize) -> Vec<Email> {
+    if count == 0 {
+        return Vec::new();
+    }
+    let Some((_, address)) = parse_addresses(&email.from).into_iter().next() else {
+        return Vec::new();
+    };
+
+    // A few extra, since the email itself and anything newer are among the results
+    let query = format!("{{from:{} to:{}}}", address, address);
+    let found = match gmail.search(&query, count as u32 + 2, |_, _| {}).await {
+        Ok(found) => found,
+        Err(e) => {
+            tracing::warn!(
+                "Failed to search earlier messages with {}: {:#}",
+                address,
+                e
+            );
+            return Vec::new();
+        }
+    };
+
+    let mut earlier: Vec<Email> = found
+        .into_iter()
+        .filter(|e| e.id != email.id && e.date < email.date)
+        .collect();
+    earlier.sort_by_key(|e| std::cmp::Reverse(e.date));
+    earlier.truncate(count);
+    earlier.reverse();
+    earlier
+}
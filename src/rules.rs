@@ -0,0 +1,226 @@
+use anyhow::{Context, Result, anyhow, bail};
+use rhai::{AST, Array, Dynamic, Engine, Map, Scope};
+use std::fs;
+
+use clinbox_core::config::Config;
+use clinbox_core::email::{Email, EmailAnalysis};
+
+use crate::aliases::Aliases;
+use crate::contacts::parse_addresses;
+
+/// Scripts are stopped after this many operations, so a stray loop can't hang triage
+const MAX_OPERATIONS: u64 = 100_000;
+
+/// What a rule does with a matching email
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleAction {
+    Archive,
+    Delete,
+    MarkRead,
+    Skip,
+}
+
+impl RuleAction {
+    /// The action a name such as "mark_read" stands for
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "archive" => Some(RuleAction::Archive),
+            "delete" => Some(RuleAction::Delete),
+            "mark_read" => Some(RuleAction::MarkRead),
+            "skip" => Some(RuleAction::Skip),
+            _ => None,
+        }
+    }
+
+    /// What a script returns for this action
+    pub fn name(&self) -> &'static str {
+        match self {
+            RuleAction::Archive => "archive",
+            RuleAction::Delete => "delete",
+            RuleAction::MarkRead => "mark_read",
+            RuleAction::Skip => "skip",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            RuleAction::Archive => "archived",
+            RuleAction::Delete => "deleted",
+            RuleAction::MarkRead => "marked read",
+            RuleAction::Skip => "skipped",
+        }
+    }
+}
+
+/// A rule that fired
+pub struct RuleMatch {
+    /// File name of the rule, without the .rhai extension
+    pub rule: String,
+    pub action: RuleAction,
+}
+
+/// Rhai scripts in ~/.clinbox/rules/, run in file name order once an email's
+/// analysis is in. A script returns "archive", "delete", "mark_read" or "skip"
+/// to act on the email, or nothing to leave it alone.
+pub struct Rules {
+    engine: Engine,
+    scripts: Vec<(String, AST)>,
+}
+
+impl Rules {
+    /// Load and compile every rule script; a syntax error names the file
+    pub fn load() -> Result<Self> {
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        // Output from print/debug would scribble over the TUI
+        engine.on_print(|_| {});
+        engine.on_debug(|_, _, _| {});
+
+        let dir = Config::rules_dir()?;
+        let mut paths: Vec<_> = match fs::read_dir(&dir) {
+            Ok(entries) => entries
+                .filter_map(|e| e.ok().map(|e| e.path()))
+                .filter(|p| p.extension().is_some_and(|ext| ext == "rhai"))
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+        paths.sort();
+
+        let mut scripts = Vec::new();
+        for path in paths {
+            let source = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read rule {}", path.display()))?;
+            let ast = engine
+                .compile(&source)
+                .map_err(|e| anyhow!("Rule {}: {}", path.display(), e))?;
+            let name = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            scripts.push((name, ast));
+        }
+
+        Ok(Self { engine, scripts })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scripts.is_empty()
+    }
+
+    /// Names of the loaded rules, in evaluation order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.scripts.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Run the rules against an email; the first one that returns an action wins
+    pub fn evaluate(
+        &self,
+        email: &Email,
+        analysis: Option<&EmailAnalysis>,
+        aliases: &Aliases,
+    ) -> Result<Option<RuleMatch>> {
+        if self.scripts.is_empty() {
+            return Ok(None);
+        }
+
+        let email_map = email_map(email, aliases);
+        let analysis_map = analysis.map_or(Dynamic::UNIT, |a| analysis_map(a).into());
+
+        for (name, ast) in &self.scripts {
+            let mut scope = Scope::new();
+            scope.push("email", email_map.clone());
+            scope.push("analysis", analysis_map.clone());
+
+            let result: Dynamic = self
+                .engine
+                .eval_ast_with_scope(&mut scope, ast)
+                .map_err(|e| anyhow!("Rule '{}' failed: {}", name, e))?;
+
+            if result.is_unit() {
+                continue;
+            }
+            let Some(action) = result
+                .into_immutable_string()
+                .ok()
+                .and_then(|s| RuleAction::parse(&s))
+            else {
+                bail!(
+                    "Rule '{}' must return \"archive\", \"delete\", \"mark_read\", \"skip\" or nothing",
+                    name
+                );
+            };
+            return Ok(Some(RuleMatch {
+                rule: name.clone(),
+                action,
+            }));
+        }
+
+        Ok(None)
+    }
+}
+
+/// Email fields visible to scripts as `email.<field>`
+fn email_map(email: &Email, aliases: &Aliases) -> Map {
+    let sender = parse_addresses(&email.from)
+        .into_iter()
+        .next()
+        .map(|(_, address)| address.to_lowercase())
+        .unwrap_or_default();
+    let domain = sender
+        .rsplit_once('@')
+        .map(|(_, d)| d.to_string())
+        .unwrap_or_default();
+
+    let mut map = Map::new();
+    map.insert("id".into(), email.id.clone().into());
+    map.insert("from".into(), email.from.clone().into());
+    map.insert("sender".into(), sender.into());
+    map.insert("domain".into(), domain.into());
+    map.insert("to".into(), email.to.clone().into());
+    map.insert("subject".into(), email.subject.clone().into());
+    map.insert("body".into(), email.body_text().into());
+    map.insert("date".into(), email.date.to_rfc3339().into());
+    map.insert("unread".into(), email.is_unread.into());
+    map.insert(
+        "labels".into(),
+        Dynamic::from_array(email.labels.iter().map(|l| l.clone().into()).collect()),
+    );
+    map.insert(
+        "attachments".into(),
+        Dynamic::from_array(
+            email
+                .attachments
+                .iter()
+                .map(|a| a.filename.clone().into())
+                .collect::<Array>(),
+        ),
+    );
+    map.insert(
+        "alias".into(),
+        aliases
+            .matched(email)
+            .map_or(Dynamic::UNIT, |m| m.address.to_lowercase().into()),
+    );
+    map
+}
+
+/// Analysis fields visible to scripts as `analysis.<field>`
+fn analysis_map(analysis: &EmailAnalysis) -> Map {
+    let priority = serde_json::to_value(analysis.priority)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default();
+
+    let mut map = Map::new();
+    map.insert("priority".into(), priority.into());
+    map.insert("category".into(), analysis.category.name().into());
+    map.insert("summary".into(), analysis.summary.clone().into());
+    map.insert(
+        "suggested_action".into(),
+        analysis
+            .suggested_action
+            .clone()
+            .map_or(Dynamic::UNIT, Into::into),
+    );
+    map
+}
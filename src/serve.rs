@@ -0,0 +1,331 @@
+use anyhow::{Context, Result};
+use axum::extract::{Path, Query, Request, State};
+use axum::http::{HeaderValue, Method, StatusCode, header};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
+use serde::Deserialize;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+use clinbox_core::ai::AiClient;
+use clinbox_core::config::{Config, GmailAccount};
+use clinbox_core::email::{Email, EmailAnalysis};
+use clinbox_core::gmail::{GmailClient, StatusError};
+use clinbox_core::tasks::{Task, TaskStore};
+
+use crate::attachments;
+use crate::i18n::{t, tr};
+use crate::related;
+
+/// Most emails a single list request may fetch
+const MAX_LIMIT: u32 = 100;
+
+struct AppState {
+    gmail: GmailClient,
+    ai: AiClient,
+    token: String,
+    /// Serializes read-modify-write cycles on the tasks file
+    tasks: Mutex<()>,
+}
+
+/// An error returned to the client as `{"error": "..."}`, with Gmail's status
+/// when Gmail answered 401, 403, 404 or 429 and a 500 otherwise
+struct ApiError(anyhow::Error);
+
+impl<E: Into<anyhow::Error>> From<E> for ApiError {
+    fn from(err: E) -> Self {
+        Self(err.into())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = self
+            .0
+            .chain()
+            .find_map(|cause| cause.downcast_ref::<StatusError>())
+            .map(|e| e.status)
+            .filter(|status| {
+                matches!(
+                    *status,
+                    StatusCode::UNAUTHORIZED
+                        | StatusCode::FORBIDDEN
+                        | StatusCode::NOT_FOUND
+                        | StatusCode::TOO_MANY_REQUESTS
+                )
+            })
+            .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        let body = Json(serde_json::json!({ "error": format!("{:#}", self.0) }));
+        (status, body).into_response()
+    }
+}
+
+type ApiResult<T> = std::result::Result<T, ApiError>;
+
+/// Cross-origin access for the browser frontends in `serve_origins`; with none
+/// configured, browsers keep pages on other origins out
+pub fn cors(origins: &[String]) -> Result<CorsLayer> {
+    let layer = CorsLayer::new()
+        .allow_methods([Method::GET, Method::POST, Method::DELETE])
+        .allow_headers([header::AUTHORIZATION, header::CONTENT_TYPE]);
+    if origins.iter().any(|o| o == "*") {
+        return Ok(layer.allow_origin(AllowOrigin::any()));
+    }
+    let origins = origins
+        .iter()
+        .map(|o| {
+            HeaderValue::from_str(o)
+                .ok()
+                .filter(|_| o.starts_with("http://") || o.starts_with("https://"))
+                .with_context(|| format!("Not an origin: '{}' (e.g. http://localhost:5173)", o))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(layer.allow_origin(origins))
+}
+
+/// Serve the REST API on `host:port` until interrupted
+pub async fn run(account: &GmailAccount, host: &str, port: u16) -> Result<()> {
+    let mut config = Config::load()?;
+    if config.ai.api_key.is_empty() {
+        anyhow::bail!("AI API key not configured. Run: clinbox config ai.api_key <your-key>");
+    }
+
+    let token = match &config.serve_token {
+        Some(token) => token.clone(),
+        None => {
+            let token = generate_token()?;
+            config.serve_token = Some(token.clone());
+            config.save()?;
+            token
+        }
+    };
+
+    let gmail = GmailClient::new(account, &config)
+        .await
+        .context("Failed to connect to Gmail")?;
+
+    let state = Arc::new(AppState {
+        gmail,
+        ai: AiClient::new(&config)?,
+        token: token.clone(),
+        tasks: Mutex::new(()),
+    });
+
+    let app = Router::new()
+        .route("/api/emails", get(list_emails))
+        .route("/api/emails/archive", post(archive_emails))
+        .route("/api/emails/{id}", get(get_email))
+        .route("/api/emails/{id}/analyze", post(analyze_email))
+        .route("/api/emails/{id}/archive", post(archive_email))
+        .route("/api/tasks", get(list_tasks).post(add_task))
+        .route("/api/tasks/{id}/complete", post(complete_task))
+        .route("/api/tasks/{id}", axum::routing::delete(delete_task))
+        .layer(middleware::from_fn_with_state(state.clone(), require_token))
+        // Outside the token check, so browsers' preflight requests get an answer
+        .layer(cors(&config.serve_origins)?)
+        .with_state(state);
+
+    let listener = tokio::net::TcpListener::bind((host, port))
+        .await
+        .with_context(|| format!("Failed to listen on {}:{}", host, port))?;
+
+    println!(
+        "{}",
+        tr(
+            "🌐 Serving {} on {}",
+            &[&account.id, &format!("http://{}:{}/api", host, port)]
+        )
+    );
+    println!("{}", tr("🔑 Token: {}", &[&token]));
+    println!(
+        "   {}",
+        t("Send it as 'Authorization: Bearer <token>'. Ctrl+C to stop.")
+    );
+    if host != "127.0.0.1" && host != "localhost" {
+        println!(
+            "{}",
+            t("⚠️  Plain HTTP: put a TLS proxy in front before exposing this beyond your network.")
+        );
+    }
+
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await?;
+    Ok(())
+}
+
+/// A random URL-safe token
+fn generate_token() -> Result<String> {
+    let mut bytes = [0u8; 24];
+    getrandom::fill(&mut bytes).map_err(|e| anyhow::anyhow!("Failed to generate token: {}", e))?;
+    Ok(URL_SAFE_NO_PAD.encode(bytes))
+}
+
+/// Reject requests without `Authorization: Bearer <serve_token>`
+async fn require_token(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let authorized = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .is_some_and(|token| constant_time_eq(token.as_bytes(), state.token.as_bytes()));
+
+    if !authorized {
+        let body = Json(serde_json::json!({ "error": "missing or invalid token" }));
+        return (StatusCode::UNAUTHORIZED, body).into_response();
+    }
+    next.run(request).await
+}
+
+/// Compare without stopping at the first difference, so timing doesn't leak the token
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[derive(Deserialize)]
+struct ListParams {
+    /// Gmail search query
+    #[serde(default = "default_query")]
+    q: String,
+    #[serde(default = "default_limit")]
+    limit: u32,
+}
+
+fn default_query() -> String {
+    "in:inbox is:unread".to_string()
+}
+
+fn default_limit() -> u32 {
+    20
+}
+
+/// `GET /api/emails?q=<gmail search>&limit=<n>`
+async fn list_emails(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<ListParams>,
+) -> ApiResult<Json<Vec<Email>>> {
+    let emails = state
+        .gmail
+        .search(&params.q, params.limit.min(MAX_LIMIT), |_, _| {})
+        .await?;
+    Ok(Json(emails))
+}
+
+/// `GET /api/emails/{id}`
+async fn get_email(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<Email>> {
+    Ok(Json(state.gmail.fetch_email(&id).await?))
+}
+
+/// `POST /api/emails/{id}/analyze`
+async fn analyze_email(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<EmailAnalysis>> {
+    let email = state.gmail.fetch_email(&id).await?;
+    let (excerpt, earlier) = tokio::join!(
+        attachments::text_excerpt(&state.gmail, &email),
+        related::earlier_messages(&state.gmail, &email, state.ai.history_messages())
+    );
+    Ok(Json(
+        state
+            .ai
+            .analyze_email(&email, excerpt.as_deref(), &earlier)
+            .await?,
+    ))
+}
+
+/// `POST /api/emails/{id}/archive`
+async fn archive_email(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> ApiResult<StatusCode> {
+    state.gmail.archive(&id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Deserialize)]
+struct Ids {
+    ids: Vec<String>,
+}
+
+/// `POST /api/emails/archive` with `{"ids": [...]}`: archives in batches and reports
+/// `{"archived": [...], "failed": [{"id": ..., "error": ...}]}`
+async fn archive_emails(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<Ids>,
+) -> ApiResult<Json<serde_json::Value>> {
+    let result = state.gmail.archive_many(&request.ids).await;
+    let failed: Vec<_> = result
+        .failed
+        .iter()
+        .map(|(id, error)| serde_json::json!({ "id": id, "error": error }))
+        .collect();
+    Ok(Json(serde_json::json!({
+        "archived": result.succeeded,
+        "failed": failed,
+    })))
+}
+
+/// `GET /api/tasks`: pending tasks
+async fn list_tasks(State(state): State<Arc<AppState>>) -> ApiResult<Json<Vec<Task>>> {
+    let _guard = state.tasks.lock().await;
+    let store = TaskStore::load()?;
+    Ok(Json(store.pending().into_iter().cloned().collect()))
+}
+
+#[derive(Deserialize)]
+struct NewTask {
+    title: String,
+    description: Option<String>,
+    /// Gmail id of the email the task comes from
+    email_id: Option<String>,
+}
+
+/// `POST /api/tasks` with `{"title": ..., "description": ..., "email_id": ...}`
+async fn add_task(
+    State(state): State<Arc<AppState>>,
+    Json(new): Json<NewTask>,
+) -> ApiResult<(StatusCode, Json<Task>)> {
+    let subject = match &new.email_id {
+        Some(id) => Some(state.gmail.fetch_email(id).await?.subject),
+        None => None,
+    };
+
+    let _guard = state.tasks.lock().await;
+    let mut store = TaskStore::load()?;
+    let task = store.add(new.title, new.description, new.email_id, subject)?;
+    Ok((StatusCode::CREATED, Json(task)))
+}
+
+/// `POST /api/tasks/{id}/complete`
+async fn complete_task(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> ApiResult<StatusCode> {
+    let _guard = state.tasks.lock().await;
+    TaskStore::load()?.complete(&id)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// `DELETE /api/tasks/{id}`
+async fn delete_task(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> ApiResult<StatusCode> {
+    let _guard = state.tasks.lock().await;
+    TaskStore::load()?.delete(&id)?;
+    Ok(StatusCode::NO_CONTENT)
+}
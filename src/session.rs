@@ -0,0 +1,177 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+use clinbox_core::config::Config;
+use clinbox_core::email::{Email, EmailAnalysis};
+
+use crate::later::Item;
+
+/// How long an archive/delete/mark-read/move/read-later can be undone before it is sent to Gmail
+pub const UNDO_WINDOW: std::time::Duration = std::time::Duration::from_secs(3);
+
+/// Counters for the actions taken during a triage session
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Stats {
+    pub archived: usize,
+    pub deleted: usize,
+    pub tasks_created: usize,
+    pub skipped: usize,
+    pub replied: usize,
+    pub summaries_saved: usize,
+    #[serde(default)]
+    pub marked_read: usize,
+    #[serde(default)]
+    pub moved: usize,
+    #[serde(default)]
+    pub read_later: usize,
+}
+
+impl Stats {
+    pub fn total(&self) -> usize {
+        self.archived
+            + self.deleted
+            + self.tasks_created
+            + self.skipped
+            + self.replied
+            + self.summaries_saved
+            + self.marked_read
+            + self.moved
+            + self.read_later
+    }
+
+    /// Roll back the counter for an undone change
+    pub fn undo(&mut self, kind: &PendingKind) {
+        match kind {
+            PendingKind::Archive => self.archived -= 1,
+            PendingKind::Delete => self.deleted -= 1,
+            PendingKind::MarkRead => self.marked_read -= 1,
+            PendingKind::Move { .. } => self.moved -= 1,
+            PendingKind::ReadLater(_) => self.read_later -= 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum PendingKind {
+    Archive,
+    Delete,
+    MarkRead,
+    /// Archive and apply the label with this id
+    Move {
+        label_id: String,
+    },
+    /// Add this to the read-later list and archive
+    ReadLater(Box<Item>),
+}
+
+/// An archive, delete, mark-read, move or read-later held back locally until the undo window closes
+#[derive(Debug)]
+pub struct PendingChange {
+    pub index: usize,
+    pub kind: PendingKind,
+    pub commit_at: tokio::time::Instant,
+}
+
+impl PendingChange {
+    pub fn new(index: usize, kind: PendingKind) -> Self {
+        Self {
+            index,
+            kind,
+            commit_at: tokio::time::Instant::now() + UNDO_WINDOW,
+        }
+    }
+}
+
+/// An in-progress triage queue, persisted so `clinbox --resume` can pick it up
+#[derive(Debug, Deserialize)]
+pub struct Session {
+    pub account_id: String,
+    pub saved_at: DateTime<Utc>,
+    /// Index of the next email to show
+    pub position: usize,
+    pub emails: Vec<Email>,
+    pub analyses: Vec<Option<EmailAnalysis>>,
+    pub stats: Stats,
+}
+
+/// Borrowed view of a session used for writing it to disk
+#[derive(Serialize)]
+struct SessionSnapshot<'a> {
+    account_id: &'a str,
+    saved_at: DateTime<Utc>,
+    position: usize,
+    emails: &'a [Email],
+    analyses: &'a [Option<EmailAnalysis>],
+    stats: &'a Stats,
+}
+
+impl Session {
+    pub fn new(account_id: &str, emails: Vec<Email>, analyses: Vec<Option<EmailAnalysis>>) -> Self {
+        Self {
+            account_id: account_id.to_string(),
+            saved_at: Utc::now(),
+            position: 0,
+            emails,
+            analyses,
+            stats: Stats::default(),
+        }
+    }
+
+    /// Load the saved session, if any
+    pub fn load() -> Result<Option<Self>> {
+        let path = Config::session_path()?;
+
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(&path).context("Failed to read session file")?;
+        let session: Session =
+            serde_json::from_str(&content).context("Failed to parse session file")?;
+        Ok(Some(session))
+    }
+
+    /// Persist the queue. A change still inside its undo window has not reached
+    /// Gmail yet, so the snapshot rewinds to that email and its counter.
+    pub fn save(&self, position: usize, pending: Option<&PendingChange>) -> Result<()> {
+        let mut stats = self.stats.clone();
+        let position = match pending {
+            Some(change) => {
+                stats.undo(&change.kind);
+                change.index
+            }
+            None => position,
+        };
+
+        let snapshot = SessionSnapshot {
+            account_id: &self.account_id,
+            saved_at: Utc::now(),
+            position,
+            emails: &self.emails,
+            analyses: &self.analyses,
+            stats: &stats,
+        };
+
+        let path = Config::session_path()?;
+        fs::create_dir_all(path.parent().unwrap())?;
+        let content = serde_json::to_string(&snapshot).context("Failed to serialize session")?;
+        fs::write(&path, content).context("Failed to write session file")?;
+
+        Ok(())
+    }
+
+    /// Remove the saved session once the queue is finished
+    pub fn clear() -> Result<()> {
+        let path = Config::session_path()?;
+        if path.exists() {
+            fs::remove_file(&path).context("Failed to remove session file")?;
+        }
+        Ok(())
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.emails.len().saturating_sub(self.position)
+    }
+}
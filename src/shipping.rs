@@ -0,0 +1,432 @@
+use chrono::{Datelike, Duration, Local, NaiveDate, Weekday};
+use clinbox_core::email::Email;
+
+/// Wording of shipping notifications, checked in order so the latest state wins
+const STATUS_WORDS: &[(Status, &[&str])] = &[
+    (Status::Delivered, &["delivered", "entregado"]),
+    (
+        Status::OutForDelivery,
+        &[
+            "out for delivery",
+            "en reparto",
+            "arriving today",
+            "llega hoy",
+        ],
+    ),
+    (
+        Status::Delayed,
+        &["delivery exception", "delayed", "retrasado", "incidencia"],
+    ),
+    (
+        Status::InTransit,
+        &["in transit", "en tránsito", "en camino"],
+    ),
+    (
+        Status::Shipped,
+        &[
+            "has shipped",
+            "shipped",
+            "on its way",
+            "on the way",
+            "dispatched",
+            "tracking number",
+            "enviado",
+            "número de seguimiento",
+        ],
+    ),
+];
+
+/// Words that introduce the expected delivery day
+const DELIVERY_WORDS: &[&str] = &[
+    "arriv",
+    "deliver",
+    "expected",
+    "estimated",
+    "llega",
+    "entrega",
+    "prevista",
+];
+
+/// Words that introduce a tracking number the patterns below don't know
+const NUMBER_WORDS: &[&str] = &[
+    "tracking number",
+    "tracking no",
+    "tracking #",
+    "tracking id",
+    "número de seguimiento",
+    "seguimiento:",
+];
+
+/// Farthest the delivery day may be from its word, in bytes
+const WINDOW: usize = 60;
+
+const MONTHS: &[&[&str]] = &[
+    &["january", "jan", "enero", "ene"],
+    &["february", "feb", "febrero"],
+    &["march", "mar", "marzo"],
+    &["april", "apr", "abril", "abr"],
+    &["may", "mayo"],
+    &["june", "jun", "junio"],
+    &["july", "jul", "julio"],
+    &["august", "aug", "agosto", "ago"],
+    &["september", "sep", "sept", "septiembre"],
+    &["october", "oct", "octubre"],
+    &["november", "nov", "noviembre"],
+    &["december", "dec", "diciembre", "dic"],
+];
+
+const WEEKDAYS: &[(Weekday, &[&str])] = &[
+    (Weekday::Mon, &["monday", "lunes"]),
+    (Weekday::Tue, &["tuesday", "martes"]),
+    (Weekday::Wed, &["wednesday", "miércoles", "miercoles"]),
+    (Weekday::Thu, &["thursday", "jueves"]),
+    (Weekday::Fri, &["friday", "viernes"]),
+    (Weekday::Sat, &["saturday", "sábado", "sabado"]),
+    (Weekday::Sun, &["sunday", "domingo"]),
+];
+
+/// Where a package stands, as the notification tells it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Shipped,
+    InTransit,
+    OutForDelivery,
+    Delayed,
+    Delivered,
+}
+
+impl Status {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Status::Shipped => "shipped",
+            Status::InTransit => "in transit",
+            Status::OutForDelivery => "out for delivery",
+            Status::Delayed => "delayed",
+            Status::Delivered => "delivered",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Carrier {
+    Ups,
+    Usps,
+    FedEx,
+    Dhl,
+    Amazon,
+    RoyalMail,
+    Correos,
+    /// A postal service's S10 number ("RR123456785CH") from another country
+    Post,
+}
+
+impl Carrier {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Carrier::Ups => "UPS",
+            Carrier::Usps => "USPS",
+            Carrier::FedEx => "FedEx",
+            Carrier::Dhl => "DHL",
+            Carrier::Amazon => "Amazon",
+            Carrier::RoyalMail => "Royal Mail",
+            Carrier::Correos => "Correos",
+            Carrier::Post => "Post",
+        }
+    }
+
+    /// The carrier's public tracking page for a number
+    pub fn tracking_url(&self, number: &str) -> Option<String> {
+        let url = match self {
+            Carrier::Ups => format!("https://www.ups.com/track?tracknum={}", number),
+            Carrier::Usps => format!(
+                "https://tools.usps.com/go/TrackConfirmAction?tLabels={}",
+                number
+            ),
+            Carrier::FedEx => format!("https://www.fedex.com/fedextrack/?trknbr={}", number),
+            Carrier::Dhl => format!(
+                "https://www.dhl.com/global-en/home/tracking.html?tracking-id={}",
+                number
+            ),
+            Carrier::RoyalMail => format!(
+                "https://www.royalmail.com/track-your-item#/tracking-results/{}",
+                number
+            ),
+            Carrier::Correos => format!(
+                "https://www.correos.es/es/es/herramientas/localizador/envios/detalle?tracking-number={}",
+                number
+            ),
+            Carrier::Amazon | Carrier::Post => return None,
+        };
+        Some(url)
+    }
+
+    /// The carrier the sender's domain belongs to
+    fn from_sender(from: &str) -> Option<Self> {
+        let from = from.to_lowercase();
+        [
+            ("ups.com", Carrier::Ups),
+            ("usps.com", Carrier::Usps),
+            ("fedex.com", Carrier::FedEx),
+            ("dhl.", Carrier::Dhl),
+            ("amazon.", Carrier::Amazon),
+            ("royalmail.", Carrier::RoyalMail),
+            ("correos.es", Carrier::Correos),
+        ]
+        .into_iter()
+        .find(|(domain, _)| from.contains(domain))
+        .map(|(_, carrier)| carrier)
+    }
+}
+
+/// A package a shipping notification is about
+#[derive(Debug, Clone)]
+pub struct Shipment {
+    pub carrier: Option<Carrier>,
+    pub number: Option<String>,
+    pub status: Status,
+    /// Expected delivery day, when the email gives one
+    pub delivery: Option<NaiveDate>,
+}
+
+impl Shipment {
+    /// "📦 UPS 1Z999AA10123456784 · out for delivery · arriving Tue 2 Jul"
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        let package = match (&self.carrier, &self.number) {
+            (Some(carrier), Some(number)) => format!("{} {}", carrier.name(), number),
+            (Some(carrier), None) => carrier.name().to_string(),
+            (None, Some(number)) => number.clone(),
+            (None, None) => "Package".to_string(),
+        };
+        parts.push(package);
+        parts.push(self.status.label().to_string());
+        if let Some(day) = self.delivery
+            && self.status != Status::Delivered
+        {
+            parts.push(format!("arriving {}", day.format("%a %-d %b")));
+        }
+        format!("📦 {}", parts.join(" · "))
+    }
+
+    /// The carrier's tracking page, when both carrier and number are known
+    pub fn tracking_url(&self) -> Option<String> {
+        self.carrier?.tracking_url(self.number.as_deref()?)
+    }
+}
+
+/// The shipment a notification is about, if the email is one: it has to use
+/// shipping wording, and its tracking number, carrier and delivery day are
+/// picked up when present
+pub fn detect(email: &Email) -> Option<Shipment> {
+    let body = email.body_text();
+    let text = format!("{}\n{}", email.subject, body);
+    let lower = text.to_lowercase();
+
+    let status = STATUS_WORDS
+        .iter()
+        .find(|(_, words)| {
+            words
+                .iter()
+                .any(|w| email.subject.to_lowercase().contains(w))
+        })
+        .or_else(|| {
+            STATUS_WORDS
+                .iter()
+                .find(|(_, words)| words.iter().any(|w| lower.contains(w)))
+        })
+        .map(|(status, _)| *status)?;
+
+    let found = tracking_number(&text, &lower, &email.from.to_lowercase());
+    let carrier = found
+        .as_ref()
+        .and_then(|(_, carrier)| *carrier)
+        .or_else(|| Carrier::from_sender(&email.from));
+    let number = found.map(|(number, _)| number);
+    // Without a number, only trust a carrier's own mail
+    if number.is_none() && carrier.is_none() {
+        return None;
+    }
+
+    Some(Shipment {
+        carrier,
+        number,
+        status,
+        delivery: delivery_day(&lower, Local::now().date_naive()),
+    })
+}
+
+/// A tracking number and the carrier its format gives away. FedEx and DHL
+/// numbers are plain digits, so those need the carrier named in the email or
+/// its sender.
+fn tracking_number(text: &str, lower: &str, sender: &str) -> Option<(String, Option<Carrier>)> {
+    let mentions = |name: &str| lower.contains(name) || sender.contains(name);
+    let tokens: Vec<&str> = text
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|t| t.len() >= 8)
+        .collect();
+    let all_digits = |t: &str| t.chars().all(|c| c.is_ascii_digit());
+
+    for token in &tokens {
+        let upper = token.to_ascii_uppercase();
+        let carrier = if upper.starts_with("1Z") && upper.len() == 18 {
+            Some(Carrier::Ups)
+        } else if upper.starts_with("TBA") && upper.len() == 15 && all_digits(&upper[3..]) {
+            Some(Carrier::Amazon)
+        } else if upper.starts_with("PQ") && upper.len() == 23 {
+            Some(Carrier::Correos)
+        } else if is_s10(&upper) {
+            Some(match &upper[11..] {
+                "US" => Carrier::Usps,
+                "GB" => Carrier::RoyalMail,
+                "ES" => Carrier::Correos,
+                _ => Carrier::Post,
+            })
+        } else if all_digits(token) && (20..=22).contains(&token.len()) && token.starts_with('9') {
+            Some(Carrier::Usps)
+        } else if all_digits(token) && matches!(token.len(), 12 | 15) && mentions("fedex") {
+            Some(Carrier::FedEx)
+        } else if (all_digits(token) && token.len() == 10 || upper.starts_with("JJD"))
+            && mentions("dhl")
+        {
+            Some(Carrier::Dhl)
+        } else {
+            None
+        };
+        if carrier.is_some() {
+            return Some((upper, carrier));
+        }
+    }
+
+    // "Tracking number: XY12345678" in a format not listed above. Searched in
+    // an ASCII-lowercased copy, whose offsets still line up with `text`.
+    let ascii = text.to_ascii_lowercase();
+    NUMBER_WORDS.iter().find_map(|word| {
+        let at = ascii.find(word)? + word.len();
+        text[at..]
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|t| !t.is_empty())
+            .take(3)
+            .find(|t| t.len() >= 8 && t.chars().any(|c| c.is_ascii_digit()))
+            .map(|t| (t.to_ascii_uppercase(), None))
+    })
+}
+
+/// The UPU S10 format of postal services: "RR123456785US"
+fn is_s10(token: &str) -> bool {
+    let bytes = token.as_bytes();
+    bytes.len() == 13
+        && bytes[..2].iter().all(u8::is_ascii_uppercase)
+        && bytes[2..11].iter().all(u8::is_ascii_digit)
+        && bytes[11..].iter().all(u8::is_ascii_uppercase)
+}
+
+/// The first day after a delivery word: "today", "tomorrow", "2024-07-02",
+/// "July 2", "2 de julio" or a weekday. Days already past are ignored.
+fn delivery_day(lower: &str, today: NaiveDate) -> Option<NaiveDate> {
+    DELIVERY_WORDS
+        .iter()
+        .flat_map(|word| lower.match_indices(word).map(|(at, _)| at))
+        .filter_map(|at| {
+            let mut end = (at + WINDOW).min(lower.len());
+            while !lower.is_char_boundary(end) {
+                end -= 1;
+            }
+            day_in(&lower[at..end], today)
+        })
+        .filter(|day| *day >= today)
+        .min()
+}
+
+/// The first date named in a short piece of text
+fn day_in(text: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let words: Vec<&str> = text
+        .split(|c: char| c.is_whitespace() || c == ',' || c == '.')
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    for (i, word) in words.iter().enumerate() {
+        match *word {
+            "today" | "hoy" => return Some(today),
+            "tomorrow" | "mañana" => return Some(today + Duration::days(1)),
+            _ => {}
+        }
+        if let Ok(date) = NaiveDate::parse_from_str(word, "%Y-%m-%d") {
+            return Some(date);
+        }
+        if let Some(month) = MONTHS.iter().position(|names| names.contains(word)) {
+            // "July 2" or "2 July" / "2 de julio"
+            let day = words
+                .get(i + 1)
+                .and_then(|w| day_number(w))
+                .or_else(|| i.checked_sub(1).and_then(|j| day_number(words[j])))
+                .or_else(|| {
+                    (i >= 2 && words[i - 1] == "de")
+                        .then(|| day_number(words[i - 2]))
+                        .flatten()
+                })?;
+            return dated(today, month as u32 + 1, day);
+        }
+        if let Some((weekday, _)) = WEEKDAYS.iter().find(|(_, names)| names.contains(word)) {
+            let ahead =
+                (weekday.num_days_from_monday() + 7 - today.weekday().num_days_from_monday()) % 7;
+            return Some(today + Duration::days(ahead as i64));
+        }
+    }
+    None
+}
+
+/// "2", "2nd" or "02" as a day of the month
+fn day_number(word: &str) -> Option<u32> {
+    let digits: String = word.chars().take_while(char::is_ascii_digit).collect();
+    let rest = &word[digits.len()..];
+    if !matches!(rest, "" | "st" | "nd" | "rd" | "th") {
+        return None;
+    }
+    digits.parse().ok().filter(|d| (1..=31).contains(d))
+}
+
+/// The next occurrence of a month and day: this year, or next year once it's
+/// well past
+fn dated(today: NaiveDate, month: u32, day: u32) -> Option<NaiveDate> {
+    let date = NaiveDate::from_ymd_opt(today.year(), month, day)?;
+    if date < today - Duration::days(60) {
+        NaiveDate::from_ymd_opt(today.year() + 1, month, day)
+    } else {
+        Some(date)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn number(text: &str) -> Option<String> {
+        tracking_number(text, &text.to_lowercase(), "").map(|(number, _)| number)
+    }
+
+    #[test]
+    fn known_formats() {
+        assert_eq!(
+            number("Your parcel 1Z999AA10123456784 is on its way").as_deref(),
+            Some("1Z999AA10123456784")
+        );
+        assert_eq!(
+            number("Envío RR123456785ES en camino").as_deref(),
+            Some("RR123456785ES")
+        );
+    }
+
+    #[test]
+    fn number_after_tracking_words() {
+        assert_eq!(
+            number("Tracking number: xy12345678").as_deref(),
+            Some("XY12345678")
+        );
+        // Lowercasing "İ" takes more bytes, which must not shift the offset
+        assert_eq!(
+            number("SİPARİŞİNİZ shipped\nTracking no AB1234567").as_deref(),
+            Some("AB1234567")
+        );
+        assert_eq!(number("SİPARİŞİNİZ shipped\nTracking no"), None);
+    }
+}
@@ -0,0 +1,94 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+use clinbox_core::config::Config;
+use clinbox_core::email::{Email, EmailAnalysis};
+
+use crate::shipping::{self, Status};
+
+/// An archived message to bring back to the inbox later
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snoozed {
+    pub account_id: String,
+    pub message_id: String,
+    pub subject: String,
+    pub until: DateTime<Utc>,
+}
+
+/// Snoozed messages. Gmail's API can't snooze, so they're archived and listed
+/// here, and go back to the inbox the next time Clinbox runs after they're due.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SnoozeList {
+    pub messages: Vec<Snoozed>,
+}
+
+impl SnoozeList {
+    /// Load the list from file
+    pub fn load() -> Result<Self> {
+        let path = Config::snoozed_path()?;
+
+        if path.exists() {
+            let content = fs::read_to_string(&path).context("Failed to read snoozed file")?;
+            let list: SnoozeList =
+                serde_json::from_str(&content).context("Failed to parse snoozed file")?;
+            Ok(list)
+        } else {
+            Ok(SnoozeList::default())
+        }
+    }
+
+    /// Save the list to file
+    pub fn save(&self) -> Result<()> {
+        let path = Config::snoozed_path()?;
+        fs::create_dir_all(path.parent().unwrap())?;
+
+        let content =
+            serde_json::to_string_pretty(self).context("Failed to serialize snoozed list")?;
+        fs::write(&path, content).context("Failed to write snoozed file")?;
+
+        Ok(())
+    }
+
+    /// Snooze a message until `until` and save, replacing an earlier snooze of it
+    pub fn add(&mut self, account_id: &str, email: &Email, until: DateTime<Utc>) -> Result<()> {
+        self.messages.retain(|m| m.message_id != email.id);
+        self.messages.push(Snoozed {
+            account_id: account_id.to_string(),
+            message_id: email.id.clone(),
+            subject: email.subject.clone(),
+            until,
+        });
+        self.save()
+    }
+
+    /// Messages for an account whose snooze is over
+    pub fn due(&self, account_id: &str) -> Vec<Snoozed> {
+        let now = Utc::now();
+        self.messages
+            .iter()
+            .filter(|m| m.account_id == account_id && m.until <= now)
+            .cloned()
+            .collect()
+    }
+
+    /// Drop messages once they're back in the inbox
+    pub fn remove(&mut self, ids: &[String]) -> Result<()> {
+        self.messages.retain(|m| !ids.contains(&m.message_id));
+        self.save()
+    }
+}
+
+/// A snooze date still ahead, ready to edit in the snooze prompt: a package's
+/// delivery day, or else the analysis's snooze date
+pub fn suggestion(email: &Email, analysis: Option<&EmailAnalysis>) -> Option<String> {
+    let delivery = shipping::detect(email)
+        .filter(|shipment| shipment.status != Status::Delivered)
+        .and_then(|shipment| shipment.delivery);
+    let date = delivery.or_else(|| {
+        let date = analysis?.snooze_until.as_deref()?;
+        NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d").ok()
+    })?;
+    (date > Local::now().date_naive()).then(|| date.format("%Y-%m-%d").to_string())
+}
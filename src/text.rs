@@ -0,0 +1,43 @@
+//! Fitting text into terminal columns
+//!
+//! Emoji and CJK characters take two columns and accents combine with the
+//! character before them, so lengths here are display widths and cuts fall
+//! between grapheme clusters.
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Columns `s` takes in a terminal
+pub fn width(s: &str) -> usize {
+    s.width()
+}
+
+/// `s` cut to at most `max_width` columns, ending in "…" when shortened
+pub fn truncate(s: &str, max_width: usize) -> String {
+    if s.width() <= max_width {
+        return s.to_string();
+    }
+    let budget = max_width.saturating_sub(1);
+    let mut out = String::new();
+    let mut used = 0;
+    for grapheme in s.graphemes(true) {
+        let w = grapheme.width();
+        if used + w > budget {
+            break;
+        }
+        out.push_str(grapheme);
+        used += w;
+    }
+    if max_width > 0 {
+        out.push('…');
+    }
+    out
+}
+
+/// `s` truncated or padded with spaces to exactly `columns` wide, for aligned lists
+pub fn pad(s: &str, columns: usize) -> String {
+    let mut out = truncate(s, columns);
+    let filled = out.width();
+    out.extend(std::iter::repeat_n(' ', columns.saturating_sub(filled)));
+    out
+}
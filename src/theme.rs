@@ -0,0 +1,175 @@
+use anyhow::{Result, bail};
+use ratatui::style::{Color, Modifier, Style};
+use std::str::FromStr;
+
+use clinbox_core::config::UiConfig;
+use clinbox_core::email::Priority;
+
+/// Names of the built-in theme presets
+pub const PRESETS: &[&str] = &["default", "solarized", "monochrome", "high-contrast"];
+
+/// Central color palette used by every TUI screen
+#[derive(Debug, Clone)]
+pub struct Theme {
+    /// Screen headers and informational popups
+    pub title: Color,
+    /// Regular text (metadata, full email view)
+    pub text: Color,
+    /// De-emphasized text (body preview)
+    pub muted: Color,
+    /// Secondary borders
+    pub border: Color,
+    /// Keybinding footers
+    pub keys: Color,
+    /// Confirmation messages
+    pub success: Color,
+    /// Error messages
+    pub error: Color,
+    /// Loading indicators and key takeaways
+    pub highlight: Color,
+    /// AI-generated content (drafts, summaries)
+    pub ai_content: Color,
+    pub urgent: Color,
+    pub action_required: Color,
+    pub informative: Color,
+    pub low: Color,
+    pub spam: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            title: Color::Cyan,
+            text: Color::White,
+            muted: Color::Gray,
+            border: Color::DarkGray,
+            keys: Color::Green,
+            success: Color::Green,
+            error: Color::Red,
+            highlight: Color::Yellow,
+            ai_content: Color::Green,
+            urgent: Color::Red,
+            action_required: Color::Yellow,
+            informative: Color::Blue,
+            low: Color::Gray,
+            spam: Color::DarkGray,
+        }
+    }
+}
+
+impl Theme {
+    /// Get a built-in preset by name
+    pub fn preset(name: &str) -> Option<Self> {
+        match name {
+            "default" => Some(Self::default()),
+            "solarized" => Some(Self {
+                title: Color::Rgb(0x2a, 0xa1, 0x98),
+                text: Color::Rgb(0x93, 0xa1, 0xa1),
+                muted: Color::Rgb(0x83, 0x94, 0x96),
+                border: Color::Rgb(0x58, 0x6e, 0x75),
+                keys: Color::Rgb(0x85, 0x99, 0x00),
+                success: Color::Rgb(0x85, 0x99, 0x00),
+                error: Color::Rgb(0xdc, 0x32, 0x2f),
+                highlight: Color::Rgb(0xb5, 0x89, 0x00),
+                ai_content: Color::Rgb(0x6c, 0x71, 0xc4),
+                urgent: Color::Rgb(0xdc, 0x32, 0x2f),
+                action_required: Color::Rgb(0xcb, 0x4b, 0x16),
+                informative: Color::Rgb(0x26, 0x8b, 0xd2),
+                low: Color::Rgb(0x83, 0x94, 0x96),
+                spam: Color::Rgb(0x58, 0x6e, 0x75),
+            }),
+            "monochrome" => Some(Self {
+                title: Color::White,
+                text: Color::White,
+                muted: Color::Gray,
+                border: Color::Gray,
+                keys: Color::White,
+                success: Color::White,
+                error: Color::White,
+                highlight: Color::White,
+                ai_content: Color::White,
+                urgent: Color::White,
+                action_required: Color::White,
+                informative: Color::Gray,
+                low: Color::Gray,
+                spam: Color::DarkGray,
+            }),
+            "high-contrast" => Some(Self {
+                title: Color::White,
+                text: Color::White,
+                muted: Color::White,
+                border: Color::White,
+                keys: Color::LightYellow,
+                success: Color::LightGreen,
+                error: Color::LightRed,
+                highlight: Color::LightYellow,
+                ai_content: Color::LightCyan,
+                urgent: Color::LightRed,
+                action_required: Color::LightYellow,
+                informative: Color::LightCyan,
+                low: Color::White,
+                spam: Color::Gray,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Build the theme from config: start from the preset, then apply color overrides
+    pub fn from_config(ui: &UiConfig) -> Result<Self> {
+        let mut theme = match Self::preset(&ui.theme) {
+            Some(theme) => theme,
+            None => bail!(
+                "Unknown theme '{}'. Available themes: {}",
+                ui.theme,
+                PRESETS.join(", ")
+            ),
+        };
+
+        for (key, value) in &ui.colors {
+            let color = Color::from_str(value)
+                .map_err(|_| anyhow::anyhow!("Invalid color for ui.colors.{}: {}", key, value))?;
+
+            let slot = match key.as_str() {
+                "title" => &mut theme.title,
+                "text" => &mut theme.text,
+                "muted" => &mut theme.muted,
+                "border" => &mut theme.border,
+                "keys" => &mut theme.keys,
+                "success" => &mut theme.success,
+                "error" => &mut theme.error,
+                "highlight" => &mut theme.highlight,
+                "ai_content" => &mut theme.ai_content,
+                "urgent" => &mut theme.urgent,
+                "action_required" => &mut theme.action_required,
+                "informative" => &mut theme.informative,
+                "low" => &mut theme.low,
+                "spam" => &mut theme.spam,
+                _ => bail!("Unknown theme color: ui.colors.{}", key),
+            };
+            *slot = color;
+        }
+
+        Ok(theme)
+    }
+
+    /// Color plus weight for a priority, so urgent mail stands out even without color
+    pub fn priority_style(&self, priority: Priority) -> Style {
+        let style = Style::default().fg(self.priority(priority));
+        match priority {
+            Priority::Urgent | Priority::ActionRequired => style.add_modifier(Modifier::BOLD),
+            Priority::Informative => style,
+            Priority::Low | Priority::Spam => style.add_modifier(Modifier::DIM),
+        }
+    }
+
+    /// Color associated with a priority level
+    pub fn priority(&self, priority: Priority) -> Color {
+        match priority {
+            Priority::Urgent => self.urgent,
+            Priority::ActionRequired => self.action_required,
+            Priority::Informative => self.informative,
+            Priority::Low => self.low,
+            Priority::Spam => self.spam,
+        }
+    }
+}
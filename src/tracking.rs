@@ -0,0 +1,9 @@
+use clinbox_core::email::Email;
+
+/// "👁️ Contains read tracking (…)" when the HTML body has tracking images.
+/// Clinbox never loads remote images, so reading the email here doesn't set
+/// them off; opening it in Gmail or a browser does.
+pub fn notice(email: &Email) -> Option<String> {
+    let trackers = email.trackers();
+    (!trackers.is_empty()).then(|| format!("👁️  Contains read tracking ({})", trackers.join(", ")))
+}
@@ -1,47 +1,266 @@
-use anyhow::Result;
+use anyhow::{Result, bail};
+use chrono::{DateTime, Utc};
 use crossterm::{
-    event::{self, Event, KeyCode, KeyEventKind},
+    event::{Event, EventStream, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
+use futures::StreamExt;
 use ratatui::{
     prelude::*,
-    widgets::{Block, Borders, Paragraph, Wrap},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
 };
+use std::future::Future;
 use std::io::{self, stdout};
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
 
-use crate::ai::ArticleSummary;
-use crate::email::{Email, EmailAnalysis};
+use clinbox_core::ai::{ArticleSummary, SpamVerdict};
+use clinbox_core::config::Config;
+use clinbox_core::email::{Attachment, Email, EmailAnalysis, Priority};
+use clinbox_core::gmail::{Copies, Draft, GmailClient, StorageQuota};
+use clinbox_core::timings;
 
+use crate::attachments;
+use crate::budget::Progress;
+use crate::calendar;
+use crate::clusters::Cluster;
+use crate::contacts::{ContactBook, parse_addresses};
+use crate::dashboard::{Dashboard, Pane};
+use crate::history::format_age;
+use crate::i18n::{self, plain, t, tr};
+use crate::keymap::Keymap;
+use crate::later;
+use crate::links::Link;
+use crate::otp;
+use crate::phishing;
+use crate::session::Stats;
+use crate::shipping;
+use crate::text::{self, truncate};
+use crate::theme::Theme;
+use crate::tracking;
+use crate::viewer::{self, Preview};
+use crate::waiting::WaitingThread;
+
+/// How often spinners animate and toasts are checked for expiry
+const TICK_INTERVAL: Duration = Duration::from_millis(100);
+const TOAST_DURATION: Duration = Duration::from_millis(1500);
+const ERROR_TOAST_DURATION: Duration = Duration::from_secs(3);
+/// Below this size screens are replaced by a "terminal too small" notice
+const MIN_WIDTH: u16 = 40;
+const MIN_HEIGHT: u16 = 12;
+/// Below this height the email screen switches to a compact layout
+const COMPACT_HEIGHT: u16 = 24;
+const SPINNER_FRAMES: &[&str] = &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+const ASCII_SPINNER_FRAMES: &[&str] = &["|", "/", "-", "\\"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Action {
     Archive,
     Delete,
+    MarkRead,
+    MoveToLabel,
+    /// Archive until a date, then bring back to the inbox
+    Snooze,
+    Block,
+    /// Archive every inbox email from the sender
+    SweepSender,
     Task,
     Reply,
     Summary,
     Open,
     Skip,
     ViewFull,
+    /// List the links, expanding shortened ones
+    Links,
+    /// Copy the email's one-time code to the clipboard
+    CopyCode,
+    /// Add the invoice or receipt to the expense ledger
+    Expense,
+    /// Keep the email in the read-later list and archive it
+    ReadLater,
+    /// Expand or collapse the AI's reasoning under the summary
+    Reasoning,
+    /// Analyze again with the escalation model
+    Reanalyze,
+    Export,
+    Pipe,
+    /// Ask the AI questions about the email
+    Chat,
+    /// Type a command in plain words for the AI to carry out
+    Command,
+    Undo,
     Quit,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ReplyAction {
     Send,
+    Schedule,
     Edit,
+    /// Add or change Cc recipients
+    Cc,
+    /// Add or change Bcc recipients
+    Bcc,
     Cancel,
 }
 
+/// Keys on the awaiting-response list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaitingAction {
+    Up,
+    Down,
+    Nudge,
+    Open,
+    Quit,
+}
+
+/// Keys on the read-later list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LaterAction {
+    Up,
+    Down,
+    Read,
+    /// Drop the item from the list
+    Done,
+    Open,
+    Quit,
+}
+
+/// Keys on the trash and spam review lists
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReviewAction {
+    Up,
+    Down,
+    /// Untrash, or "not spam"
+    Restore,
+    /// Delete for good
+    Delete,
+    View,
+    Open,
+    Quit,
+}
+
+/// Keys on a group-of-similar-emails card
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClusterAction {
+    Up,
+    Down,
+    ArchiveAll,
+    /// Leave the group in the queue to go through one at a time
+    Individually,
+    View,
+    /// The group as a Gmail search in the browser
+    OpenList,
+    /// Skip the remaining groups
+    Stop,
+}
+
+/// Keys on the large-message list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageAction {
+    Up,
+    Down,
+    Archive,
+    Delete,
+    /// Save the attachments to the exports folder
+    SaveAttachments,
+    View,
+    Open,
+    Quit,
+}
+
+/// Keys on the drafts list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DraftAction {
+    Up,
+    Down,
+    Edit,
+    Send,
+    Discard,
+    View,
+    Open,
+    Quit,
+}
+
+/// Keys on the dashboard; triage keys come from the keymap
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DashboardAction {
+    Up,
+    Down,
+    NextPane,
+    PrevPane,
+    /// Enter: view the message, or complete the task
+    Select,
+    Refresh,
+    /// Replace the Gmail search behind the message list
+    Search,
+    Triage(Action),
+}
+
+/// A screen, kept around so it can be redrawn under toasts and spinners
+type View = Box<dyn Fn(&mut Frame, &Theme, &Keymap)>;
+
+/// Transient message drawn on top of the current screen
+struct Toast {
+    message: String,
+    is_error: bool,
+    expires_at: Instant,
+}
+
 pub struct Tui {
     terminal: Terminal<CrosstermBackend<io::Stdout>>,
+    theme: Theme,
+    keymap: Keymap,
+    events: EventStream,
+    view: Option<View>,
+    toast: Option<Toast>,
+    busy: Option<String>,
+    spinner_frame: usize,
+    notes: Vec<String>,
+    /// Calendar events the email's proposed times clash with, e.g. "Standup 10:00"
+    conflicts: Vec<String>,
+    /// Live state of a GitHub notification's issue or pull request
+    github: Option<String>,
+    /// Analyses come from local heuristics instead of the AI; flagged in the header
+    heuristic_mode: bool,
+    /// The session's time budget and target, counted down in the header
+    progress: Option<Progress>,
+    /// Seconds left when the countdown was last drawn
+    countdown: Option<u64>,
+    /// Show the AI's reasoning under its summary; sticks across emails
+    show_reasoning: bool,
+    /// Gmail's next retry time while it is throttling us
+    throttle: Option<watch::Receiver<Option<DateTime<Utc>>>>,
+    /// Ctrl+C was pressed; screens wind down and the session quits
+    interrupted: bool,
 }
 
 impl Tui {
-    pub fn new() -> Result<Self> {
+    pub fn new(theme: Theme, keymap: Keymap) -> Result<Self> {
         enable_raw_mode()?;
         execute!(stdout(), EnterAlternateScreen)?;
         let backend = CrosstermBackend::new(stdout());
         let terminal = Terminal::new(backend)?;
-        Ok(Self { terminal })
+        Ok(Self {
+            terminal,
+            theme,
+            keymap,
+            events: EventStream::new(),
+            view: None,
+            toast: None,
+            busy: None,
+            spinner_frame: 0,
+            notes: Vec::new(),
+            conflicts: Vec::new(),
+            github: None,
+            heuristic_mode: false,
+            progress: None,
+            countdown: None,
+            show_reasoning: false,
+            throttle: None,
+            interrupted: false,
+        })
     }
 
     pub fn restore(&mut self) -> Result<()> {
@@ -50,6 +269,276 @@ impl Tui {
         Ok(())
     }
 
+    /// Hand the terminal back to the shell, e.g. to run an external command
+    pub fn suspend(&mut self) -> Result<()> {
+        self.restore()
+    }
+
+    /// Take the terminal back after `suspend` and redraw the current screen
+    pub fn resume(&mut self) -> Result<()> {
+        enable_raw_mode()?;
+        execute!(self.terminal.backend_mut(), EnterAlternateScreen)?;
+        self.terminal.clear()?;
+        // Start a fresh reader so nothing typed while suspended leaks in
+        self.events = EventStream::new();
+        self.render()
+    }
+
+    /// Show a countdown toast whenever `gmail` backs off because Gmail is throttling
+    pub fn watch_throttle(&mut self, gmail: &GmailClient) {
+        self.throttle = Some(gmail.throttled());
+    }
+
+    /// Flip whether the AI's reasoning is shown; redraw the screen afterwards
+    pub fn toggle_reasoning(&mut self) {
+        self.show_reasoning = !self.show_reasoning;
+    }
+
+    /// Replace the current screen and draw it
+    fn show(&mut self, view: impl Fn(&mut Frame, &Theme, &Keymap) + 'static) -> Result<()> {
+        self.view = Some(Box::new(view));
+        self.render()
+    }
+
+    /// Draw the current screen plus any toast or spinner overlay
+    fn render(&mut self) -> Result<()> {
+        let started = std::time::Instant::now();
+        let theme = &self.theme;
+        let keymap = &self.keymap;
+        let view = &self.view;
+        let toast = &self.toast;
+        let busy = self.busy.as_ref().map(|message| {
+            let frames = if i18n::ascii() {
+                ASCII_SPINNER_FRAMES
+            } else {
+                SPINNER_FRAMES
+            };
+            let frame = frames[self.spinner_frame % frames.len()];
+            format!("{} {}", frame, plain(message))
+        });
+
+        self.terminal.draw(|frame| {
+            let area = frame.area();
+
+            if area.width < MIN_WIDTH || area.height < MIN_HEIGHT {
+                let notice = Paragraph::new(tr(
+                    "Terminal too small ({}x{}). Resize to at least {}x{}.",
+                    &[&area.width, &area.height, &MIN_WIDTH, &MIN_HEIGHT],
+                ))
+                .style(Style::default().fg(theme.highlight))
+                .wrap(Wrap { trim: true });
+                frame.render_widget(notice, area);
+                return;
+            }
+
+            if let Some(view) = view {
+                view(frame, theme, keymap);
+            }
+
+            if let Some(busy) = &busy {
+                let widget = Paragraph::new(busy.as_str())
+                    .style(Style::default().fg(theme.highlight))
+                    .alignment(Alignment::Center)
+                    .block(Block::default().borders(Borders::ALL));
+                let popup = bottom_rect(60, 3, area);
+                frame.render_widget(Clear, popup);
+                frame.render_widget(widget, popup);
+            }
+
+            if let Some(toast) = toast {
+                let color = if toast.is_error {
+                    theme.error
+                } else {
+                    theme.success
+                };
+                let widget = Paragraph::new(plain(&toast.message))
+                    .style(Style::default().fg(color))
+                    .alignment(Alignment::Center)
+                    .wrap(Wrap { trim: true })
+                    .block(Block::default().borders(Borders::ALL));
+                let popup = top_rect(60, 3, area);
+                frame.render_widget(Clear, popup);
+                frame.render_widget(widget, popup);
+            }
+        })?;
+        timings::record(timings::Kind::Render, started.elapsed());
+        Ok(())
+    }
+
+    /// Show a short-lived message over the current screen without blocking input
+    pub fn toast(&mut self, message: impl Into<String>, is_error: bool) -> Result<()> {
+        let duration = if is_error {
+            ERROR_TOAST_DURATION
+        } else {
+            TOAST_DURATION
+        };
+        self.toast = Some(Toast {
+            message: message.into(),
+            is_error,
+            expires_at: Instant::now() + duration,
+        });
+        self.render()
+    }
+
+    /// Show a non-error toast for a custom duration (e.g. an undo window)
+    pub fn toast_for(&mut self, message: impl Into<String>, duration: Duration) -> Result<()> {
+        self.toast = Some(Toast {
+            message: message.into(),
+            is_error: false,
+            expires_at: Instant::now() + duration,
+        });
+        self.render()
+    }
+
+    /// Display name of the first key bound to an action, for hints like "press u to undo"
+    pub fn key_label(&self, action: Action) -> String {
+        self.keymap.key_label(action)
+    }
+
+    /// Run a future while showing a spinner, keeping the screen responsive
+    pub async fn busy<F: Future>(&mut self, message: &str, fut: F) -> Result<F::Output> {
+        self.busy = Some(message.to_string());
+        self.render()?;
+
+        tokio::pin!(fut);
+        let output = loop {
+            tokio::select! {
+                output = &mut fut => break output,
+                key = self.next_key() => {
+                    key?;
+                }
+            }
+        };
+
+        self.busy = None;
+        self.render()?;
+        Ok(output)
+    }
+
+    /// Like `busy`, but Esc abandons the future and returns `None`
+    pub async fn busy_cancellable<F: Future>(
+        &mut self,
+        message: &str,
+        fut: F,
+    ) -> Result<Option<F::Output>> {
+        self.busy = Some(tr("{} (Esc to cancel)", &[&message]));
+        self.render()?;
+
+        tokio::pin!(fut);
+        let output = loop {
+            tokio::select! {
+                output = &mut fut => break Some(output),
+                key = self.next_key() => {
+                    if key?.code == KeyCode::Esc {
+                        break None;
+                    }
+                }
+            }
+        };
+
+        self.busy = None;
+        self.render()?;
+        Ok(output)
+    }
+
+    /// Wait for the next key press, animating spinners and expiring toasts meanwhile.
+    /// Ctrl+C comes back as Esc, so whatever is open gets cancelled, and marks the
+    /// session as interrupted. Cancel-safe, so it can be used as a `tokio::select!` branch.
+    pub async fn next_key(&mut self) -> Result<KeyEvent> {
+        let mut ticker = tokio::time::interval(TICK_INTERVAL);
+
+        loop {
+            tokio::select! {
+                event = self.events.next() => match event {
+                    Some(Ok(Event::Key(key)))
+                        if key.kind == KeyEventKind::Press
+                            && key.code == KeyCode::Char('c')
+                            && key.modifiers.contains(KeyModifiers::CONTROL) =>
+                    {
+                        self.interrupted = true;
+                        if self.busy.is_some() {
+                            self.toast(t("⏹️ Interrupted, finishing up…"), false)?;
+                        }
+                        return Ok(KeyEvent::from(KeyCode::Esc));
+                    }
+                    Some(Ok(Event::Key(key))) if key.kind == KeyEventKind::Press => return Ok(key),
+                    Some(Ok(Event::Resize(_, _))) => {
+                        self.terminal.autoresize()?;
+                        self.render()?;
+                    }
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => return Err(e.into()),
+                    None => bail!("Terminal event stream closed"),
+                },
+                _ = ticker.tick() => self.tick()?,
+            }
+        }
+    }
+
+    fn tick(&mut self) -> Result<()> {
+        let mut dirty = false;
+
+        if let Some(retry_at) = self.throttle.as_ref().and_then(|rx| *rx.borrow()) {
+            let secs = (retry_at - Utc::now()).num_seconds().max(0);
+            self.toast = Some(Toast {
+                message: tr("⏳ Gmail is throttling, retrying in {}s…", &[&secs]),
+                is_error: false,
+                expires_at: Instant::now() + TICK_INTERVAL * 2,
+            });
+            dirty = true;
+        }
+
+        if self
+            .toast
+            .as_ref()
+            .is_some_and(|t| t.expires_at <= Instant::now())
+        {
+            self.toast = None;
+            dirty = true;
+        }
+
+        if self.busy.is_some() {
+            self.spinner_frame = (self.spinner_frame + 1) % SPINNER_FRAMES.len();
+            dirty = true;
+        }
+
+        let countdown = self.progress.and_then(|p| p.seconds_left());
+        if countdown != self.countdown {
+            self.countdown = countdown;
+            dirty = true;
+        }
+
+        if dirty {
+            self.render()?;
+        }
+        Ok(())
+    }
+
+    /// Set extra lines shown under the email metadata (e.g. "seen before") until replaced
+    pub fn set_notes(&mut self, notes: Vec<String>) {
+        self.notes = notes;
+    }
+
+    /// Set the calendar conflicts shown in the analysis pane until replaced
+    pub fn set_conflicts(&mut self, conflicts: Vec<String>) {
+        self.conflicts = conflicts;
+    }
+
+    /// Set the GitHub issue or pull request state shown in the analysis pane until replaced
+    pub fn set_github(&mut self, status: Option<String>) {
+        self.github = status;
+    }
+
+    /// Flag the header while analyses come from local heuristics
+    pub fn set_heuristic_mode(&mut self, on: bool) {
+        self.heuristic_mode = on;
+    }
+
+    /// Show the session's countdown and target progress in the header
+    pub fn set_progress(&mut self, progress: Option<Progress>) {
+        self.progress = progress;
+    }
+
     pub fn draw_email(
         &mut self,
         email: &Email,
@@ -57,354 +546,1201 @@ impl Tui {
         current: usize,
         total: usize,
     ) -> Result<()> {
-        self.terminal.draw(|frame| {
+        let email = email.clone();
+        let analysis = analysis.cloned();
+        let notes = self.notes.clone();
+        let mode = if self.heuristic_mode {
+            format!("  {}", t("🧮 heuristic mode"))
+        } else {
+            String::new()
+        };
+        let conflicts = (!self.conflicts.is_empty())
+            .then(|| tr("⚠️ Conflicts with {}", &[&self.conflicts.join(", ")]));
+        let github = self.github.clone();
+        let progress = self.progress;
+        let mut warnings = attachments::warnings(&email);
+        warnings.extend(phishing::warning(&email, analysis.as_ref()));
+        let code = otp::find(&email);
+        let event = email.calendar.as_deref().and_then(calendar::parse);
+        let event_height = event.as_ref().map_or(0, |e| e.lines().len() as u16 + 2);
+        // Convert the body once rather than on every redraw
+        let body_preview = truncate(&email.body_text(), 500);
+        let reasoning = analysis
+            .as_ref()
+            .and_then(|a| a.reasoning.clone())
+            .filter(|_| self.show_reasoning);
+        self.show(move |frame, theme, keymap| {
             let area = frame.area();
 
+            // Short terminals drop the header and body preview to keep the analysis visible
+            let compact = area.height < COMPACT_HEIGHT;
+
             // Main layout: header, content, footer
             let chunks = Layout::default()
                 .direction(Direction::Vertical)
                 .constraints([
-                    Constraint::Length(3), // Header
-                    Constraint::Length(5), // Email metadata
-                    Constraint::Min(10),   // AI analysis + body
-                    Constraint::Length(3), // Actions
+                    Constraint::Length(if compact { 0 } else { 3 }), // Header
+                    Constraint::Length(metadata_height(&email, &notes, &warnings, code.is_some())), // Email metadata
+                    Constraint::Length(event_height), // Calendar invitation
+                    Constraint::Min(if compact { 4 } else { 10 }), // AI analysis + body
+                    Constraint::Length(footer_height(keymap.footer(), area.width)), // Actions
                 ])
                 .split(area);
 
             // Header
+            let limits = progress
+                .map(|p| format!("  {}", plain(&p.describe())))
+                .unwrap_or_default();
             let header = Paragraph::new(format!(
-                " 📧 Clinbox                                          [{}/{}]",
-                current, total
+                "{}{}{}                                          [{}/{}]",
+                plain(" 📧 Clinbox"),
+                mode,
+                limits,
+                current,
+                total
             ))
             .style(
                 Style::default()
-                    .fg(Color::Cyan)
+                    .fg(theme.title)
                     .add_modifier(Modifier::BOLD),
             )
             .block(Block::default().borders(Borders::ALL));
-            frame.render_widget(header, chunks[0]);
+            if !compact {
+                frame.render_widget(header, chunks[0]);
+            }
 
             // Email metadata
             let date_str = email.date.format("%Y-%m-%d %H:%M").to_string();
-            let metadata = format!(
-                " From: {}\n Subject: {}\n Date: {}",
-                email.sender_name(),
-                truncate(&email.subject, 60),
-                date_str
-            );
+            let mut metadata = vec![
+                Line::from(format!(" {} {}", t("From:"), email.sender_name())),
+                Line::from(format!(
+                    " {} {}",
+                    t("Subject:"),
+                    truncate(&email.subject, 60)
+                )),
+                Line::from(format!(" {} {}", t("Date:"), date_str)),
+            ];
+            if !email.attachments.is_empty() {
+                metadata.push(Line::from(
+                    plain(&format!(" 📎 {}", attachment_list(&email.attachments))).into_owned(),
+                ));
+            }
+            if let Some(code) = &code {
+                let hint = tr(
+                    "🔑 Code {} — [{}] to copy",
+                    &[code, &keymap.key_label(Action::CopyCode)],
+                );
+                metadata.push(
+                    Line::from(plain(&format!(" {}", hint)).into_owned()).style(
+                        Style::default()
+                            .fg(theme.highlight)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                );
+            }
+            for warning in &warnings {
+                metadata.push(
+                    Line::from(plain(&format!(" {}", warning)).into_owned()).style(
+                        Style::default()
+                            .fg(theme.error)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                );
+            }
+            for note in &notes {
+                metadata.push(Line::from(plain(&format!(" {}", note)).into_owned()));
+            }
             let metadata_widget = Paragraph::new(metadata)
-                .style(Style::default().fg(Color::White))
+                .style(Style::default().fg(theme.text))
                 .block(Block::default().borders(Borders::LEFT | Borders::RIGHT));
             frame.render_widget(metadata_widget, chunks[1]);
 
+            // Calendar invitation
+            if let Some(event) = &event {
+                let lines: Vec<Line> = event
+                    .lines()
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, line)| {
+                        let line = Line::from(plain(&format!(" {}", line)).into_owned());
+                        if i == 0 {
+                            line.style(Style::default().add_modifier(Modifier::BOLD))
+                        } else {
+                            line
+                        }
+                    })
+                    .collect();
+                let event_widget = Paragraph::new(lines)
+                    .style(Style::default().fg(theme.text))
+                    .block(
+                        Block::default()
+                            .title(plain(event.title()).into_owned())
+                            .borders(Borders::ALL)
+                            .border_style(Style::default().fg(theme.highlight)),
+                    );
+                frame.render_widget(event_widget, chunks[2]);
+            }
+
             // AI analysis + body preview
             let content_chunks = Layout::default()
                 .direction(Direction::Vertical)
-                .constraints([
-                    Constraint::Length(6), // AI summary
-                    Constraint::Min(4),    // Body preview
-                ])
-                .split(chunks[2]);
+                .constraints(if compact {
+                    [Constraint::Min(4), Constraint::Length(0)]
+                } else {
+                    [
+                        Constraint::Length(
+                            6 + reasoning_height(reasoning.as_deref(), area.width)
+                                + u16::from(conflicts.is_some())
+                                + u16::from(github.is_some()),
+                        ), // AI summary
+                        Constraint::Min(4), // Body preview
+                    ]
+                })
+                .split(chunks[3]);
 
-            if let Some(analysis) = analysis {
-                let priority_style = match analysis.priority {
-                    crate::email::Priority::Urgent => Style::default().fg(Color::Red),
-                    crate::email::Priority::ActionRequired => Style::default().fg(Color::Yellow),
-                    crate::email::Priority::Informative => Style::default().fg(Color::Blue),
-                    crate::email::Priority::Low => Style::default().fg(Color::Gray),
-                    crate::email::Priority::Spam => Style::default().fg(Color::DarkGray),
-                };
+            if let Some(analysis) = &analysis {
+                let priority_style = theme.priority_style(analysis.priority);
 
                 let ai_text = format!(
-                    " 🤖 AI Analysis:\n {}\n\n {} {} | {} | ~{} min{}",
+                    " {}{}:\n {}{}\n\n {} {}{} | {} | ~{} min{}{}{}",
+                    t("🤖 AI Analysis"),
+                    analysis
+                        .model
+                        .as_ref()
+                        .map(|m| format!(" ({})", m))
+                        .unwrap_or_default(),
                     analysis.summary,
+                    reasoning
+                        .as_ref()
+                        .map(|r| format!("\n 💭 {}", r))
+                        .unwrap_or_default(),
                     analysis.priority.emoji(),
-                    analysis.priority.label(),
-                    analysis.category.label(),
+                    t(analysis.priority.label()),
+                    analysis.confidence_label(),
+                    t(analysis.category.label()),
                     analysis.estimated_time_minutes,
                     analysis
                         .suggested_action
                         .as_ref()
                         .map(|a| format!("\n ➡️  {}", a))
+                        .unwrap_or_default(),
+                    conflicts
+                        .as_ref()
+                        .map(|c| format!("\n {}", c))
+                        .unwrap_or_default(),
+                    github
+                        .as_ref()
+                        .map(|g| format!("\n {}", g))
                         .unwrap_or_default()
                 );
 
-                let ai_widget = Paragraph::new(ai_text).style(priority_style).block(
-                    Block::default()
-                        .borders(Borders::LEFT | Borders::RIGHT)
-                        .border_style(Style::default().fg(Color::DarkGray)),
-                );
+                let ai_widget = Paragraph::new(plain(&ai_text).into_owned())
+                    .style(priority_style)
+                    .wrap(Wrap { trim: false })
+                    .block(
+                        Block::default()
+                            .borders(Borders::LEFT | Borders::RIGHT)
+                            .border_style(Style::default().fg(theme.border)),
+                    );
                 frame.render_widget(ai_widget, content_chunks[0]);
             } else {
-                let loading = Paragraph::new(" 🔄 Analyzing email...")
-                    .style(Style::default().fg(Color::Yellow))
+                let loading = Paragraph::new(format!(" {}", t("🔄 Analyzing email...")))
+                    .style(Style::default().fg(theme.highlight))
                     .block(Block::default().borders(Borders::LEFT | Borders::RIGHT));
                 frame.render_widget(loading, content_chunks[0]);
             }
 
             // Body preview
-            let body_preview = truncate(&email.body_text(), 500);
             let body_widget = Paragraph::new(format!(" {}", body_preview.replace('\n', "\n ")))
-                .style(Style::default().fg(Color::Gray))
+                .style(Style::default().fg(theme.muted))
                 .wrap(Wrap { trim: true })
                 .block(
                     Block::default()
-                        .title(" Preview ")
+                        .title(format!(" {} ", t("Preview")))
                         .borders(Borders::ALL)
-                        .border_style(Style::default().fg(Color::DarkGray)),
+                        .border_style(Style::default().fg(theme.border)),
                 );
-            frame.render_widget(body_widget, content_chunks[1]);
+            if !compact {
+                frame.render_widget(body_widget, content_chunks[1]);
+            }
 
             // Actions footer
-            let actions = " [a]rchive [d]elete [t]ask [r]eply [n]ote [o]pen [v]iew [s]kip [q]uit ";
+            let actions = keymap.footer();
             let actions_widget = Paragraph::new(actions)
-                .style(Style::default().fg(Color::Green))
+                .style(Style::default().fg(theme.keys))
                 .alignment(Alignment::Center)
+                .wrap(Wrap { trim: true })
                 .block(Block::default().borders(Borders::ALL));
-            frame.render_widget(actions_widget, chunks[3]);
-        })?;
-
-        Ok(())
+            frame.render_widget(actions_widget, chunks[4]);
+        })
     }
 
     pub fn draw_message(&mut self, message: &str, is_error: bool) -> Result<()> {
-        self.terminal.draw(|frame| {
+        let message = plain(message).into_owned();
+        self.show(move |frame, theme, _keymap| {
             let area = frame.area();
             let style = if is_error {
-                Style::default().fg(Color::Red)
+                Style::default().fg(theme.error)
             } else {
-                Style::default().fg(Color::Green)
+                Style::default().fg(theme.success)
             };
 
-            let widget = Paragraph::new(message)
+            let widget = Paragraph::new(message.as_str())
                 .style(style)
                 .alignment(Alignment::Center)
                 .block(Block::default().borders(Borders::ALL));
 
             let centered = centered_rect(60, 20, area);
             frame.render_widget(widget, centered);
-        })?;
-        Ok(())
+        })
+    }
+
+    pub fn draw_task_input(&mut self, title: &str, email_subject: &str) -> Result<()> {
+        let title = title.to_string();
+        let email_subject = email_subject.to_string();
+        self.show(move |frame, theme, _keymap| {
+            let area = frame.area();
+
+            let text = tr(
+                "Creating task from email:\n\nSubject: {}\n\nTask title: {}\n\nPress [Enter] to confirm, [Esc] to cancel",
+                &[&email_subject, &title],
+            );
+
+            let widget = Paragraph::new(text)
+                .style(Style::default().fg(theme.title))
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .title(format!(" {} ", t("New Task")))
+                        .borders(Borders::ALL),
+                );
+
+            let centered = centered_rect(70, 40, area);
+            frame.render_widget(widget, centered);
+        })
+    }
+
+    /// A one-line input box, with `suggestions` listed below the input
+    fn draw_prompt(
+        &mut self,
+        title: &str,
+        question: &str,
+        input: &str,
+        suggestions: &[String],
+    ) -> Result<()> {
+        let title = plain(title).into_owned();
+        let question = plain(question).into_owned();
+        let input = input.to_string();
+        let suggestions = suggestions.to_vec();
+        self.show(move |frame, theme, _keymap| {
+            let area = frame.area();
+
+            let mut lines = vec![
+                Line::from(question.as_str()),
+                Line::from(""),
+                Line::from(format!("> {}▏", input)).style(Style::default().fg(theme.text)),
+            ];
+            for suggestion in &suggestions {
+                lines.push(
+                    Line::from(plain(suggestion).into_owned())
+                        .style(Style::default().fg(theme.muted)),
+                );
+            }
+            lines.push(Line::from(""));
+            lines.push(
+                Line::from(if suggestions.is_empty() {
+                    t("[Enter] confirm, [Esc] cancel")
+                } else {
+                    t("[Tab] complete, [Enter] confirm, [Esc] cancel")
+                })
+                .style(Style::default().fg(theme.keys)),
+            );
+
+            let widget = Paragraph::new(lines)
+                .style(Style::default().fg(theme.title))
+                .alignment(Alignment::Center)
+                .wrap(Wrap { trim: false })
+                .block(Block::default().title(title.as_str()).borders(Borders::ALL));
+
+            let centered = centered_rect(70, 40, area);
+            frame.render_widget(widget, centered);
+        })
+    }
+
+    /// Ask for a line of text; returns None when cancelled
+    pub async fn prompt(&mut self, title: &str, question: &str) -> Result<Option<String>> {
+        self.prompt_with(title, question, "").await
+    }
+
+    /// Like `prompt`, with the input pre-filled so it can be edited or accepted as is
+    pub async fn prompt_with(
+        &mut self,
+        title: &str,
+        question: &str,
+        initial: &str,
+    ) -> Result<Option<String>> {
+        let mut input = initial.to_string();
+
+        loop {
+            self.draw_prompt(title, question, &input, &[])?;
+
+            match self.next_key().await?.code {
+                KeyCode::Esc => return Ok(None),
+                KeyCode::Enter if !input.trim().is_empty() => {
+                    return Ok(Some(input.trim().to_string()));
+                }
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(c) => input.push(c),
+                _ => {}
+            }
+        }
+    }
+
+    /// Like `prompt_with`, for comma-separated addresses: contacts matching the
+    /// one being typed are listed, and Tab takes the first
+    pub async fn prompt_addresses(
+        &mut self,
+        title: &str,
+        question: &str,
+        initial: &str,
+        contacts: &ContactBook,
+    ) -> Result<Option<String>> {
+        let mut input = initial.to_string();
+
+        loop {
+            let start = input.rfind(',').map_or(0, |i| i + 1);
+            let typed = input[start..].trim();
+            let suggestions: Vec<String> = if typed.is_empty() {
+                Vec::new()
+            } else {
+                contacts
+                    .suggest(typed, 5)
+                    .iter()
+                    // A comma in the name would split the list
+                    .map(|c| match &c.name {
+                        Some(name) if name.contains(',') => c.address.clone(),
+                        _ => c.display(),
+                    })
+                    .collect()
+            };
+            self.draw_prompt(title, question, &input, &suggestions)?;
+
+            match self.next_key().await?.code {
+                KeyCode::Esc => return Ok(None),
+                KeyCode::Enter if !input.trim().is_empty() => {
+                    return Ok(Some(input.trim().trim_end_matches(',').to_string()));
+                }
+                KeyCode::Tab => {
+                    if let Some(first) = suggestions.first() {
+                        let done = input[..start].trim_end();
+                        input = if done.is_empty() {
+                            format!("{}, ", first)
+                        } else {
+                            format!("{} {}, ", done, first)
+                        };
+                    }
+                }
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(c) => input.push(c),
+                _ => {}
+            }
+        }
+    }
+
+    fn draw_chat(&mut self, subject: &str, turns: &[(String, String)], input: &str) -> Result<()> {
+        let title = plain(&format!(" 💬 {} ", truncate(subject, 60))).into_owned();
+        let turns = turns.to_vec();
+        let input = input.to_string();
+        self.show(move |frame, theme, _keymap| {
+            let area = frame.area();
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(3), Constraint::Length(3)])
+                .split(area);
+
+            let mut lines: Vec<Line> = Vec::new();
+            if turns.is_empty() {
+                lines.push(
+                    Line::from(t(
+                        "Ask anything about this email, e.g. what exactly they're asking for",
+                    ))
+                    .style(Style::default().fg(theme.muted)),
+                );
+            }
+            for (question, answer) in &turns {
+                lines.push(
+                    Line::from(plain(&format!("> {}", question)).into_owned()).style(
+                        Style::default()
+                            .fg(theme.highlight)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                );
+                for line in answer.lines() {
+                    lines.push(Line::from(plain(line).into_owned()));
+                }
+                lines.push(Line::from(""));
+            }
+
+            // Keep the latest answer in view
+            let width = chunks[0].width.saturating_sub(2).max(1) as usize;
+            let rows: usize = lines.iter().map(|l| l.width().div_ceil(width).max(1)).sum();
+            let visible = chunks[0].height.saturating_sub(2) as usize;
+            let scroll = rows.saturating_sub(visible) as u16;
+
+            let conversation = Paragraph::new(lines)
+                .style(Style::default().fg(theme.text))
+                .wrap(Wrap { trim: false })
+                .scroll((scroll, 0))
+                .block(Block::default().title(title.as_str()).borders(Borders::ALL));
+            frame.render_widget(conversation, chunks[0]);
+
+            let prompt = Paragraph::new(format!("> {}▏", input))
+                .style(Style::default().fg(theme.text))
+                .block(
+                    Block::default()
+                        .title(format!(" {} ", t("[Enter] ask, [Esc] back")))
+                        .borders(Borders::ALL)
+                        .border_style(Style::default().fg(theme.keys)),
+                );
+            frame.render_widget(prompt, chunks[1]);
+        })
+    }
+
+    /// Show the conversation about an email and read the next question;
+    /// returns None when the user leaves
+    pub async fn chat_input(
+        &mut self,
+        subject: &str,
+        turns: &[(String, String)],
+    ) -> Result<Option<String>> {
+        let mut input = String::new();
+
+        loop {
+            self.draw_chat(subject, turns, &input)?;
+
+            match self.next_key().await?.code {
+                KeyCode::Esc => return Ok(None),
+                KeyCode::Enter if !input.trim().is_empty() => {
+                    return Ok(Some(input.trim().to_string()));
+                }
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(c) => input.push(c),
+                _ => {}
+            }
+        }
+    }
+
+    fn draw_label_picker(
+        &mut self,
+        email_subject: &str,
+        recent: &[String],
+        input: &str,
+        selected: Option<usize>,
+    ) -> Result<()> {
+        let email_subject = email_subject.to_string();
+        let recent = recent.to_vec();
+        let input = input.to_string();
+        self.show(move |frame, theme, _keymap| {
+            let area = frame.area();
+
+            let mut lines = vec![
+                Line::from(format!("{} {}", t("Subject:"), email_subject)),
+                Line::from(""),
+                Line::from(tr("Label: {}▏", &[&input])).style(Style::default().fg(theme.text)),
+                Line::from(""),
+            ];
+
+            if recent.is_empty() {
+                lines.push(
+                    Line::from(t("No recent labels yet")).style(Style::default().fg(theme.muted)),
+                );
+            } else {
+                lines.push(Line::from(t("Recent:")).style(Style::default().fg(theme.muted)));
+                for (i, label) in recent.iter().enumerate() {
+                    let style = if selected == Some(i) {
+                        Style::default()
+                            .fg(theme.highlight)
+                            .add_modifier(Modifier::BOLD)
+                    } else {
+                        Style::default().fg(theme.text)
+                    };
+                    lines.push(Line::from(format!("[{}] {}", i + 1, label)).style(style));
+                }
+            }
+
+            lines.push(Line::from(""));
+            lines.push(
+                Line::from(t(
+                    "Type a label or pick a recent one — [Enter] move, [Esc] cancel",
+                ))
+                .style(Style::default().fg(theme.keys)),
+            );
+
+            let widget = Paragraph::new(lines)
+                .style(Style::default().fg(theme.title))
+                .alignment(Alignment::Center)
+                .wrap(Wrap { trim: false })
+                .block(
+                    Block::default()
+                        .title(format!(" {} ", t("Move to Label")))
+                        .borders(Borders::ALL),
+                );
+
+            let centered = centered_rect(70, 60, area);
+            frame.render_widget(widget, centered);
+        })
+    }
+
+    /// Ask for a label name; digits pick a recent label while the input is empty,
+    /// Up/Down cycle through them. Returns None when cancelled.
+    pub async fn pick_label(
+        &mut self,
+        email_subject: &str,
+        recent: &[String],
+    ) -> Result<Option<String>> {
+        let mut input = String::new();
+        let mut selected: Option<usize> = None;
+        let numbered = |c: char| {
+            let n = c.to_digit(10)? as usize;
+            recent.get(n.checked_sub(1)?)
+        };
+
+        loop {
+            self.draw_label_picker(email_subject, recent, &input, selected)?;
+
+            match self.next_key().await?.code {
+                KeyCode::Esc => return Ok(None),
+                KeyCode::Enter => {
+                    let label = input.trim();
+                    if !label.is_empty() {
+                        return Ok(Some(label.to_string()));
+                    }
+                }
+                // A digit picks a recent label; with none at that number it's
+                // typed, so labels like "2024" can still be entered
+                KeyCode::Char(c) if input.is_empty() && numbered(c).is_some() => {
+                    return Ok(numbered(c).cloned());
+                }
+                KeyCode::Down if !recent.is_empty() => {
+                    let next = selected.map_or(0, |i| (i + 1) % recent.len());
+                    selected = Some(next);
+                    input = recent[next].clone();
+                }
+                KeyCode::Up if !recent.is_empty() => {
+                    let prev = selected
+                        .map_or(recent.len() - 1, |i| (i + recent.len() - 1) % recent.len());
+                    selected = Some(prev);
+                    input = recent[prev].clone();
+                }
+                KeyCode::Backspace => {
+                    input.pop();
+                    selected = None;
+                }
+                KeyCode::Char(c) => {
+                    input.push(c);
+                    selected = None;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// The whole email until a key other than `h` or `a` is pressed; `h` toggles
+    /// the raw headers, fetched the first time they're asked for, and `a` shows
+    /// the attachments
+    pub async fn view_full_email(&mut self, email: &Email, gmail: &GmailClient) -> Result<()> {
+        let mut headers: Option<Vec<(String, String)>> = None;
+        let mut show_headers = false;
+        loop {
+            self.draw_full_email(email, headers.as_deref().filter(|_| show_headers))?;
+            match self.next_key().await?.code {
+                KeyCode::Char('h') => {}
+                KeyCode::Char('a') if !email.attachments.is_empty() => {
+                    self.view_attachments(email, gmail).await?;
+                    continue;
+                }
+                _ => return Ok(()),
+            }
+            if headers.is_none() {
+                match self
+                    .busy(t("📥 Fetching headers..."), gmail.fetch_headers(&email.id))
+                    .await?
+                {
+                    Ok(fetched) => headers = Some(fetched),
+                    Err(e) => {
+                        self.toast(tr("❌ Failed to fetch headers: {}", &[&e]), true)?;
+                        continue;
+                    }
+                }
+            }
+            show_headers = !show_headers;
+        }
+    }
+
+    /// The whole email; with `headers`, all of them instead of the usual four
+    fn draw_full_email(
+        &mut self,
+        email: &Email,
+        headers: Option<&[(String, String)]>,
+    ) -> Result<()> {
+        let email = email.clone();
+        let body = email.body_text();
+        let headers = headers.map(|h| h.to_vec());
+        self.show(move |frame, theme, _keymap| {
+            let area = frame.area();
+
+            let mut content = match &headers {
+                Some(headers) => headers
+                    .iter()
+                    .map(|(name, value)| format!("{}: {}\n", name, value))
+                    .collect(),
+                None => format!(
+                    "{} {}\n{} {}\n{} {}\n{} {}\n",
+                    t("From:"),
+                    email.from,
+                    t("To:"),
+                    email.to,
+                    t("Date:"),
+                    email.date.format("%Y-%m-%d %H:%M:%S"),
+                    t("Subject:"),
+                    email.subject,
+                ),
+            };
+            if !email.attachments.is_empty() {
+                content.push_str(&format!(
+                    "{} {}\n",
+                    t("Attachments:"),
+                    attachment_list(&email.attachments)
+                ));
+            }
+            for warning in attachments::warnings(&email) {
+                content.push_str(&format!("{}\n", warning));
+            }
+            if let Some(auth) = email.authentication() {
+                content.push_str(&format!("🔐 {}\n", auth.describe()));
+            }
+            if let Some(warning) = phishing::warning(&email, None) {
+                content.push_str(&format!("{}\n", warning));
+            }
+            if let Some(notice) = tracking::notice(&email) {
+                content.push_str(&format!("{}\n", notice));
+            }
+            if let Some(shipment) = shipping::detect(&email) {
+                content.push_str(&format!("{}\n", shipment.describe()));
+                if let Some(url) = shipment.tracking_url() {
+                    content.push_str(&format!("   {}\n", url));
+                }
+            }
+            if let Some(event) = email.calendar.as_deref().and_then(calendar::parse) {
+                content.push_str(&format!("\n{}\n", event.title().trim()));
+                for line in event.lines() {
+                    content.push_str(&format!("  {}\n", line));
+                }
+            }
+            content.push_str(&format!("\n{}", body));
+
+            let widget = Paragraph::new(plain(&content).into_owned())
+                .style(Style::default().fg(theme.text))
+                .wrap(Wrap { trim: false })
+                .block(
+                    Block::default()
+                        .title(format!(
+                            " {} ",
+                            if email.attachments.is_empty() {
+                                t("Full Email - [h] headers, any other key to go back")
+                            } else {
+                                t("Full Email - [h] headers, [a] attachments, any other key to go back")
+                            }
+                        ))
+                        .borders(Borders::ALL),
+                );
+
+            frame.render_widget(widget, area);
+        })
+    }
+
+    /// Pick one of the email's attachments (straight away when there's only one)
+    /// and show it, or save it and open it with the system's viewer when it
+    /// can't be shown here
+    async fn view_attachments(&mut self, email: &Email, gmail: &GmailClient) -> Result<()> {
+        let attachment = if email.attachments.len() == 1 {
+            &email.attachments[0]
+        } else {
+            let list: Vec<String> = email
+                .attachments
+                .iter()
+                .enumerate()
+                .map(|(i, a)| {
+                    format!(
+                        " [{}] {} ({})",
+                        i + 1,
+                        a.filename,
+                        attachments::format_size(a.size)
+                    )
+                })
+                .collect();
+            self.show(move |frame, theme, _keymap| {
+                let area = frame.area();
+                let widget = Paragraph::new(plain(&list.join("\n")).into_owned())
+                    .style(Style::default().fg(theme.text))
+                    .block(
+                        Block::default()
+                            .title(format!(
+                                " {} ",
+                                t("Attachments - number to view, any other key to go back")
+                            ))
+                            .borders(Borders::ALL),
+                    );
+                frame.render_widget(widget, area);
+            })?;
+            let picked = match self.next_key().await?.code {
+                KeyCode::Char(c) => c.to_digit(10).and_then(|n| n.checked_sub(1)),
+                _ => None,
+            };
+            match picked.and_then(|i| email.attachments.get(i as usize)) {
+                Some(attachment) => attachment,
+                None => return Ok(()),
+            }
+        };
+
+        let data = match self
+            .busy(
+                &tr("📥 Downloading {}...", &[&attachment.filename]),
+                gmail.fetch_attachment(&email.id, &attachment.attachment_id),
+            )
+            .await?
+        {
+            Ok(data) => data,
+            Err(e) => return self.toast(format!("❌ {}", e), true),
+        };
+
+        let preview = (attachment.size <= viewer::MAX_VIEW_BYTES)
+            .then(|| viewer::preview(attachment, &data))
+            .flatten();
+        match preview {
+            Some(preview) => self.view_attachment(attachment, preview).await,
+            None => self.save_and_open(attachment, &data),
+        }
+    }
+
+    /// Save an attachment to the exports folder and open it with the system's
+    /// viewer; risky ones (executables, macros) are only saved
+    fn save_and_open(&mut self, attachment: &Attachment, data: &[u8]) -> Result<()> {
+        let saved = Config::exports_dir().and_then(|dir| {
+            std::fs::create_dir_all(&dir)?;
+            attachments::save_unique(&dir, &attachment.filename, data)
+        });
+        let path = match saved {
+            Ok(path) => path,
+            Err(e) => return self.toast(format!("❌ {}", e), true),
+        };
+        if !attachments::risks(attachment).is_empty() {
+            return self.toast(tr("⚠️  Saved to {}, not opened", &[&path.display()]), true);
+        }
+        let _ = open::that(&path);
+        self.toast(tr("📂 Saved to {} and opened", &[&path.display()]), false)
     }
 
-    pub fn draw_task_input(&mut self, title: &str, email_subject: &str) -> Result<()> {
-        self.terminal.draw(|frame| {
-            let area = frame.area();
+    /// An attachment shown in the terminal until Esc or q; arrows and
+    /// PgUp/PgDn scroll text and tables
+    async fn view_attachment(&mut self, attachment: &Attachment, preview: Preview) -> Result<()> {
+        let title = format!(" {} ", tr("{} - [q] back", &[&attachment.filename]));
 
-            let text = format!(
-                "Creating task from email:\n\n\
-                 Subject: {}\n\n\
-                 Task title: {}\n\n\
-                 Press [Enter] to confirm, [Esc] to cancel",
-                email_subject, title
+        if let Preview::Image(protocol, data) = preview {
+            self.show(move |frame, theme, _keymap| {
+                let block = Block::default()
+                    .title(plain(&title).into_owned())
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.border));
+                frame.render_widget(block, frame.area());
+            })?;
+            // The terminal draws the image over the empty frame
+            let size = self.terminal.size()?;
+            let sequence = viewer::image_sequence(
+                protocol,
+                &data,
+                size.width.saturating_sub(2),
+                size.height.saturating_sub(2),
             );
+            execute!(stdout(), crossterm::cursor::MoveTo(1, 1))?;
+            io::Write::write_all(&mut stdout(), sequence.as_bytes())?;
+            io::Write::flush(&mut stdout())?;
+            self.next_key().await?;
+            io::Write::write_all(&mut stdout(), viewer::clear_sequence(protocol).as_bytes())?;
+            self.terminal.clear()?;
+            return Ok(());
+        }
 
-            let widget = Paragraph::new(text)
-                .style(Style::default().fg(Color::Cyan))
-                .alignment(Alignment::Center)
-                .block(Block::default().title(" New Task ").borders(Borders::ALL));
+        let (lines, diff) = match &preview {
+            Preview::Table(rows) => (viewer::table_lines(rows), false),
+            Preview::Text(text) => (viewer::lines(text), false),
+            Preview::Diff(text) => (viewer::lines(text), true),
+            Preview::Image(..) => unreachable!("images are drawn above"),
+        };
+        let wrap = matches!(preview, Preview::Text(_));
+        self.scroll_lines(title, lines, diff, wrap).await
+    }
 
-            let centered = centered_rect(70, 40, area);
-            frame.render_widget(widget, centered);
-        })?;
-        Ok(())
+    /// Text shown in the terminal, wrapped, until Esc or q; arrows and
+    /// PgUp/PgDn scroll
+    pub async fn read_text(&mut self, title: &str, text: &str) -> Result<()> {
+        let title = format!(" {} ", tr("{} - [q] back", &[&title]));
+        self.scroll_lines(title, viewer::lines(text), false, true)
+            .await
     }
 
-    pub fn draw_full_email(&mut self, email: &Email) -> Result<()> {
-        self.terminal.draw(|frame| {
+    /// Scrollable lines, colored as a diff with `diff`, cut at the edge and
+    /// scrolled sideways unless they `wrap`
+    async fn scroll_lines(
+        &mut self,
+        title: String,
+        lines: Vec<String>,
+        diff: bool,
+        wrap: bool,
+    ) -> Result<()> {
+        let last = (lines.len() as u16).saturating_sub(1);
+        let mut top: u16 = 0;
+        let mut left: u16 = 0;
+        loop {
+            let lines = lines.clone();
+            let title = title.clone();
+            self.show(move |frame, theme, _keymap| {
+                let styled: Vec<Line> = lines
+                    .iter()
+                    .map(|line| {
+                        let color = match line.chars().next() {
+                            _ if !diff => theme.text,
+                            Some('+') if !line.starts_with("+++") => theme.success,
+                            Some('-') if !line.starts_with("---") => theme.error,
+                            Some('@') => theme.highlight,
+                            _ => theme.text,
+                        };
+                        Line::from(format!(" {}", line)).style(Style::default().fg(color))
+                    })
+                    .collect();
+                let mut widget = Paragraph::new(styled).scroll((top, left)).block(
+                    Block::default()
+                        .title(plain(&title).into_owned())
+                        .borders(Borders::ALL)
+                        .border_style(Style::default().fg(theme.border)),
+                );
+                if wrap {
+                    widget = widget.wrap(Wrap { trim: false });
+                }
+                frame.render_widget(widget, frame.area());
+            })?;
+
+            let page = self.terminal.size()?.height.saturating_sub(3);
+            match self.next_key().await?.code {
+                KeyCode::Down | KeyCode::Char('j') => top = (top + 1).min(last),
+                KeyCode::Up | KeyCode::Char('k') => top = top.saturating_sub(1),
+                KeyCode::PageDown | KeyCode::Char(' ') => top = (top + page).min(last),
+                KeyCode::PageUp => top = top.saturating_sub(page),
+                KeyCode::Home | KeyCode::Char('g') => top = 0,
+                KeyCode::End | KeyCode::Char('G') => top = last,
+                KeyCode::Right | KeyCode::Char('l') if !wrap => left += 8,
+                KeyCode::Left | KeyCode::Char('h') if !wrap => left = left.saturating_sub(8),
+                KeyCode::Esc | KeyCode::Char('q') => return Ok(()),
+                _ => {}
+            }
+        }
+    }
+
+    /// The email's links with where they really lead, suspicious ones first,
+    /// until a key is pressed
+    pub async fn view_links(&mut self, links: &[Link]) -> Result<()> {
+        let mut links = links.to_vec();
+        links.sort_by_key(|link| link.mismatch().is_none());
+        self.show(move |frame, theme, _keymap| {
             let area = frame.area();
 
-            let body = email.body_text();
-            let content = format!(
-                "From: {}\nTo: {}\nDate: {}\nSubject: {}\n\n{}",
-                email.from,
-                email.to,
-                email.date.format("%Y-%m-%d %H:%M:%S"),
-                email.subject,
-                body
-            );
+            let mut lines: Vec<Line> = Vec::new();
+            for link in &links {
+                let title = link.text.as_deref().unwrap_or(&link.url);
+                lines.push(Line::from(plain(&format!(" 🔗 {}", title)).into_owned()));
+                if let Some(expanded) = &link.expanded {
+                    lines.push(
+                        Line::from(format!("    {}", link.url))
+                            .style(Style::default().fg(theme.muted)),
+                    );
+                    lines.push(Line::from(format!("    → {}", expanded)));
+                } else if link.text.is_some() {
+                    lines.push(Line::from(format!("    → {}", link.url)));
+                }
+                if let Some(error) = &link.error {
+                    lines.push(
+                        Line::from(tr("    Couldn't expand: {}", &[error]))
+                            .style(Style::default().fg(theme.muted)),
+                    );
+                }
+                if let Some((shown, actual)) = link.mismatch() {
+                    lines.push(
+                        Line::from(
+                            plain(&format!(
+                                "    {}",
+                                tr("⚠️  Shows {} but goes to {}", &[&shown, &actual])
+                            ))
+                            .into_owned(),
+                        )
+                        .style(
+                            Style::default()
+                                .fg(theme.error)
+                                .add_modifier(Modifier::BOLD),
+                        ),
+                    );
+                }
+            }
 
-            let widget = Paragraph::new(content)
-                .style(Style::default().fg(Color::White))
+            let widget = Paragraph::new(lines)
+                .style(Style::default().fg(theme.text))
                 .wrap(Wrap { trim: false })
                 .block(
                     Block::default()
-                        .title(" Full Email - Press any key to go back ")
+                        .title(format!(" {} ", t("Links - any key to go back")))
                         .borders(Borders::ALL),
                 );
-
             frame.render_widget(widget, area);
         })?;
+        self.next_key().await?;
         Ok(())
     }
 
-    pub fn draw_summary(
-        &mut self,
-        total: usize,
-        archived: usize,
-        deleted: usize,
-        tasks_created: usize,
-        skipped: usize,
-        replied: usize,
-        summaries_saved: usize,
-    ) -> Result<()> {
-        self.terminal.draw(|frame| {
+    pub fn draw_summary(&mut self, stats: &Stats) -> Result<()> {
+        let stats = stats.clone();
+        self.show(move |frame, theme, _keymap| {
             let area = frame.area();
 
             let mut text = format!(
-                "📊 Session Summary\n\n\
-                 Total emails processed: {}\n\
-                 ✅ Archived: {}\n\
-                 🗑️  Deleted: {}\n\
-                 📝 Tasks created: {}\n\
-                 💬 Replied: {}",
-                total, archived, deleted, tasks_created, replied
+                "{}\n\n{}\n{}\n{}\n{}\n{}",
+                t("📊 Session Summary"),
+                tr("Total emails processed: {}", &[&stats.total()]),
+                tr("✅ Archived: {}", &[&stats.archived]),
+                tr("🗑️  Deleted: {}", &[&stats.deleted]),
+                tr("📝 Tasks created: {}", &[&stats.tasks_created]),
+                tr("💬 Replied: {}", &[&stats.replied]),
             );
 
-            if summaries_saved > 0 {
-                text.push_str(&format!("\n 📓 Summaries saved: {}", summaries_saved));
+            if stats.moved > 0 {
+                text.push_str(&format!(
+                    "\n {}",
+                    tr("🏷️  Moved to label: {}", &[&stats.moved])
+                ));
+            }
+
+            if stats.marked_read > 0 {
+                text.push_str(&format!(
+                    "\n {}",
+                    tr("👁️  Marked read: {}", &[&stats.marked_read])
+                ));
+            }
+
+            if stats.summaries_saved > 0 {
+                text.push_str(&format!(
+                    "\n {}",
+                    tr("📓 Summaries saved: {}", &[&stats.summaries_saved])
+                ));
             }
 
-            text.push_str(&format!("\n ⏭️  Skipped: {}\n\n Press any key to exit", skipped));
+            if stats.read_later > 0 {
+                text.push_str(&format!(
+                    "\n {}",
+                    tr("📚 Saved to read later: {}", &[&stats.read_later])
+                ));
+            }
+
+            text.push_str(&format!(
+                "\n {}\n\n {}",
+                tr("⏭️  Skipped: {}", &[&stats.skipped]),
+                t("Press any key to exit")
+            ));
 
             let widget = Paragraph::new(text)
-                .style(Style::default().fg(Color::Cyan))
+                .style(Style::default().fg(theme.title))
                 .alignment(Alignment::Center)
                 .block(Block::default().title(" Clinbox ").borders(Borders::ALL));
 
             let centered = centered_rect(50, 40, area);
             frame.render_widget(widget, centered);
-        })?;
-        Ok(())
+        })
     }
 
-    pub fn wait_for_action(&self) -> Result<Action> {
+    /// The next triage action; always `Quit` once Ctrl+C was pressed
+    pub async fn wait_for_action(&mut self) -> Result<Action> {
         loop {
-            if let Event::Key(key) = event::read()? {
-                if key.kind != KeyEventKind::Press {
-                    continue;
-                }
-
-                match key.code {
-                    KeyCode::Char('a') => return Ok(Action::Archive),
-                    KeyCode::Char('d') => return Ok(Action::Delete),
-                    KeyCode::Char('t') => return Ok(Action::Task),
-                    KeyCode::Char('r') => return Ok(Action::Reply),
-                    KeyCode::Char('n') => return Ok(Action::Summary),
-                    KeyCode::Char('o') => return Ok(Action::Open),
-                    KeyCode::Char('v') => return Ok(Action::ViewFull),
-                    KeyCode::Char('s') => return Ok(Action::Skip),
-                    KeyCode::Char('q') | KeyCode::Esc => return Ok(Action::Quit),
-                    _ => {}
-                }
+            if self.interrupted {
+                return Ok(Action::Quit);
+            }
+            let key = self.next_key().await?;
+            if let Some(action) = self.keymap.action(key.code) {
+                return Ok(action);
             }
         }
     }
 
-    pub fn wait_for_key(&self) -> Result<()> {
+    pub async fn wait_for_key(&mut self) -> Result<()> {
+        self.next_key().await?;
+        Ok(())
+    }
+
+    pub async fn wait_for_confirm(&mut self) -> Result<bool> {
         loop {
-            if let Event::Key(key) = event::read()?
-                && key.kind == KeyEventKind::Press
-            {
-                return Ok(());
+            match self.next_key().await?.code {
+                KeyCode::Enter => return Ok(true),
+                KeyCode::Esc => return Ok(false),
+                _ => {}
             }
         }
     }
 
-    pub fn wait_for_confirm(&self) -> Result<bool> {
+    /// Show a reply draft until something other than Cc or Bcc is chosen (so
+    /// those are never returned); they ask for addresses, suggested from
+    /// `contacts` and kept in `copies`, and show the draft again
+    pub async fn review_reply(
+        &mut self,
+        email: &Email,
+        draft: &str,
+        gmail: &GmailClient,
+        contacts: &ContactBook,
+        copies: &mut Copies,
+    ) -> Result<ReplyAction> {
+        let quote = gmail.reply_quote(email);
         loop {
-            if let Event::Key(key) = event::read()? {
-                if key.kind != KeyEventKind::Press {
-                    continue;
-                }
+            let mut bcc = copies.bcc.clone();
+            bcc.extend(gmail.auto_bcc().iter().cloned());
+            self.draw_reply_draft(email, draft, quote.as_deref(), &copies.cc, &bcc)?;
 
-                match key.code {
-                    KeyCode::Enter => return Ok(true),
-                    KeyCode::Esc => return Ok(false),
-                    _ => {}
-                }
+            let action = self.wait_for_reply_action().await?;
+            let (title, list) = match action {
+                ReplyAction::Cc => ("Cc", &mut copies.cc),
+                ReplyAction::Bcc => ("Bcc", &mut copies.bcc),
+                _ => return Ok(action),
+            };
+            if let Some(input) = self
+                .prompt_addresses(
+                    &format!(" {} ", t(title)),
+                    t("Addresses, separated by commas:"),
+                    &list.join(", "),
+                    contacts,
+                )
+                .await?
+            {
+                *list = parse_addresses(&input)
+                    .into_iter()
+                    .map(|(_, address)| address)
+                    .collect();
             }
         }
     }
 
-    pub fn draw_reply_draft(&mut self, email: &Email, draft: &str) -> Result<()> {
-        self.terminal.draw(|frame| {
+    /// A reply draft as it will be sent: the recipients, then the draft with
+    /// the quoted original (if replies quote it) dimmed below
+    fn draw_reply_draft(
+        &mut self,
+        email: &Email,
+        draft: &str,
+        quote: Option<&str>,
+        cc: &[String],
+        bcc: &[String],
+    ) -> Result<()> {
+        let email = email.clone();
+        let draft = draft.to_string();
+        let quote = quote.map(str::to_string);
+        let mut recipients = vec![format!(" {} {}", t("To:"), email.from)];
+        if !cc.is_empty() {
+            recipients.push(format!(" Cc: {}", cc.join(", ")));
+        }
+        if !bcc.is_empty() {
+            recipients.push(format!(" Bcc: {}", bcc.join(", ")));
+        }
+        self.show(move |frame, theme, keymap| {
             let area = frame.area();
 
             let chunks = Layout::default()
                 .direction(Direction::Vertical)
                 .constraints([
-                    Constraint::Length(3), // Header
-                    Constraint::Length(4), // To/Subject
-                    Constraint::Min(10),   // Draft content
-                    Constraint::Length(3), // Actions
+                    Constraint::Length(3),                           // Header
+                    Constraint::Length(recipients.len() as u16 + 3), // Recipients/Subject
+                    Constraint::Min(10),                             // Draft content
+                    Constraint::Length(3),                           // Actions
                 ])
                 .split(area);
 
             // Header
-            let header = Paragraph::new(" 📝 Reply Draft (AI Generated)")
+            let header = Paragraph::new(format!(" {}", t("📝 Reply Draft (AI Generated)")))
                 .style(
                     Style::default()
-                        .fg(Color::Cyan)
+                        .fg(theme.title)
                         .add_modifier(Modifier::BOLD),
                 )
                 .block(Block::default().borders(Borders::ALL));
             frame.render_widget(header, chunks[0]);
 
-            // To/Subject
+            // Recipients/Subject
             let subject = if email.subject.starts_with("Re:") || email.subject.starts_with("RE:") {
                 email.subject.clone()
             } else {
                 format!("Re: {}", email.subject)
             };
-            let metadata = format!(" To: {}\n Subject: {}", email.from, subject);
+            let metadata = format!("{}\n {} {}", recipients.join("\n"), t("Subject:"), subject);
             let metadata_widget = Paragraph::new(metadata)
-                .style(Style::default().fg(Color::White))
+                .style(Style::default().fg(theme.text))
                 .block(Block::default().borders(Borders::LEFT | Borders::RIGHT));
             frame.render_widget(metadata_widget, chunks[1]);
 
-            // Draft content
-            let draft_widget = Paragraph::new(format!(" {}", draft.replace('\n', "\n ")))
-                .style(Style::default().fg(Color::Green))
+            // Draft content, then the quoted original
+            let mut lines: Vec<Line> = draft
+                .lines()
+                .map(|line| Line::from(format!(" {}", line)))
+                .collect();
+            if let Some(quote) = &quote {
+                lines.push(Line::from(""));
+                lines.extend(quote.lines().map(|line| {
+                    Line::from(Span::styled(
+                        format!(" {}", line),
+                        Style::default().fg(theme.muted),
+                    ))
+                }));
+            }
+            let draft_widget = Paragraph::new(lines)
+                .style(Style::default().fg(theme.ai_content))
                 .wrap(Wrap { trim: false })
                 .block(
                     Block::default()
-                        .title(" Draft ")
+                        .title(format!(" {} ", t("Draft")))
                         .borders(Borders::ALL)
-                        .border_style(Style::default().fg(Color::Green)),
+                        .border_style(Style::default().fg(theme.ai_content)),
                 );
             frame.render_widget(draft_widget, chunks[2]);
 
             // Actions
-            let actions = " [s]end  [e]dit in browser  [c]ancel ";
+            let actions = keymap.reply_footer();
             let actions_widget = Paragraph::new(actions)
-                .style(Style::default().fg(Color::Yellow))
+                .style(Style::default().fg(theme.keys))
                 .alignment(Alignment::Center)
                 .block(Block::default().borders(Borders::ALL));
             frame.render_widget(actions_widget, chunks[3]);
-        })?;
-        Ok(())
+        })
     }
 
-    pub fn wait_for_reply_action(&self) -> Result<ReplyAction> {
+    pub async fn wait_for_reply_action(&mut self) -> Result<ReplyAction> {
         loop {
-            if let Event::Key(key) = event::read()? {
-                if key.kind != KeyEventKind::Press {
-                    continue;
-                }
-
-                match key.code {
-                    KeyCode::Char('s') => return Ok(ReplyAction::Send),
-                    KeyCode::Char('e') => return Ok(ReplyAction::Edit),
-                    KeyCode::Char('c') | KeyCode::Esc => return Ok(ReplyAction::Cancel),
-                    _ => {}
-                }
+            let key = self.next_key().await?;
+            if let Some(action) = self.keymap.reply_action(key.code) {
+                return Ok(action);
             }
         }
     }
 
     pub fn draw_summary_preview(&mut self, email: &Email, summary: &ArticleSummary) -> Result<()> {
-        self.terminal.draw(|frame| {
+        let email = email.clone();
+        let summary = summary.clone();
+        self.show(move |frame, theme, _keymap| {
             let area = frame.area();
 
             let chunks = Layout::default()
@@ -418,10 +1754,10 @@ impl Tui {
                 .split(area);
 
             // Header
-            let header = Paragraph::new(" 📝 Article Summary (AI Generated)")
+            let header = Paragraph::new(format!(" {}", t("📝 Article Summary (AI Generated)")))
                 .style(
                     Style::default()
-                        .fg(Color::Cyan)
+                        .fg(theme.title)
                         .add_modifier(Modifier::BOLD),
                 )
                 .block(Block::default().borders(Borders::ALL));
@@ -429,12 +1765,14 @@ impl Tui {
 
             // Email info
             let info = format!(
-                " From: {}\n Subject: {}",
+                " {} {}\n {} {}",
+                t("From:"),
                 email.sender_name(),
+                t("Subject:"),
                 truncate(&email.subject, 60)
             );
             let info_widget = Paragraph::new(info)
-                .style(Style::default().fg(Color::White))
+                .style(Style::default().fg(theme.text))
                 .block(Block::default().borders(Borders::LEFT | Borders::RIGHT));
             frame.render_widget(info_widget, chunks[1]);
 
@@ -448,15 +1786,16 @@ impl Tui {
                 .split(chunks[2]);
 
             // Summary
-            let summary_widget = Paragraph::new(format!(" {}", summary.summary.replace('\n', "\n ")))
-                .style(Style::default().fg(Color::Green))
-                .wrap(Wrap { trim: false })
-                .block(
-                    Block::default()
-                        .title(" Resumen ")
-                        .borders(Borders::ALL)
-                        .border_style(Style::default().fg(Color::Green)),
-                );
+            let summary_widget =
+                Paragraph::new(format!(" {}", summary.summary.replace('\n', "\n ")))
+                    .style(Style::default().fg(theme.ai_content))
+                    .wrap(Wrap { trim: false })
+                    .block(
+                        Block::default()
+                            .title(format!(" {} ", t("Summary")))
+                            .borders(Borders::ALL)
+                            .border_style(Style::default().fg(theme.ai_content)),
+                    );
             frame.render_widget(summary_widget, content_chunks[0]);
 
             // Key takeaways
@@ -467,39 +1806,773 @@ impl Tui {
                 .collect::<Vec<_>>()
                 .join("\n");
             let takeaways_widget = Paragraph::new(takeaways_text)
-                .style(Style::default().fg(Color::Yellow))
+                .style(Style::default().fg(theme.highlight))
                 .wrap(Wrap { trim: false })
                 .block(
                     Block::default()
-                        .title(" Puntos Clave ")
+                        .title(format!(" {} ", t("Key Takeaways")))
                         .borders(Borders::ALL)
-                        .border_style(Style::default().fg(Color::Yellow)),
+                        .border_style(Style::default().fg(theme.highlight)),
                 );
             frame.render_widget(takeaways_widget, content_chunks[1]);
 
             // Actions
-            let actions = " [Enter] Save to Notion  [Esc] Cancel ";
+            let actions = format!(" {} ", t("[Enter] Save to Notion  [Esc] Cancel"));
             let actions_widget = Paragraph::new(actions)
-                .style(Style::default().fg(Color::Magenta))
+                .style(Style::default().fg(theme.keys))
                 .alignment(Alignment::Center)
                 .block(Block::default().borders(Borders::ALL));
             frame.render_widget(actions_widget, chunks[3]);
-        })?;
-        Ok(())
+        })
+    }
+
+    pub fn draw_waiting(&mut self, threads: &[WaitingThread], selected: usize) -> Result<()> {
+        let threads = threads.to_vec();
+        self.show(move |frame, theme, _keymap| {
+            let area = frame.area();
+
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Length(3), // Header
+                    Constraint::Min(3),    // Threads
+                    Constraint::Length(3), // Actions
+                ])
+                .split(area);
+
+            let header = Paragraph::new(format!(
+                " {}",
+                tr("⏳ Waiting for a reply ({} threads)", &[&threads.len()])
+            ))
+            .style(
+                Style::default()
+                    .fg(theme.title)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .block(Block::default().borders(Borders::ALL));
+            frame.render_widget(header, chunks[0]);
+
+            // Keep the selection on screen in long lists
+            let visible = chunks[1].height.saturating_sub(2) as usize;
+            let first = selected.saturating_sub(visible.saturating_sub(1));
+            let lines: Vec<Line> = threads
+                .iter()
+                .enumerate()
+                .skip(first)
+                .take(visible)
+                .map(|(i, t)| {
+                    let text = format!(
+                        " {:>14}  {}  {}",
+                        format_age(t.sent_at),
+                        text::pad(&t.to, 30),
+                        t.subject
+                    );
+                    if i == selected {
+                        Line::from(text).style(
+                            Style::default()
+                                .fg(theme.highlight)
+                                .add_modifier(Modifier::REVERSED),
+                        )
+                    } else {
+                        Line::from(text).style(Style::default().fg(theme.text))
+                    }
+                })
+                .collect();
+            let list = Paragraph::new(lines).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.border)),
+            );
+            frame.render_widget(list, chunks[1]);
+
+            let actions = format!(" {} ", t("[↑/↓] select  [n]udge  [o]pen  [q]uit"));
+            let actions_widget = Paragraph::new(actions)
+                .style(Style::default().fg(theme.keys))
+                .alignment(Alignment::Center)
+                .block(Block::default().borders(Borders::ALL));
+            frame.render_widget(actions_widget, chunks[2]);
+        })
     }
 
-    pub fn wait_for_yes_no(&self) -> Result<bool> {
+    pub async fn wait_for_waiting_action(&mut self) -> Result<WaitingAction> {
         loop {
-            if let Event::Key(key) = event::read()? {
-                if key.kind != KeyEventKind::Press {
-                    continue;
+            match self.next_key().await?.code {
+                KeyCode::Up | KeyCode::Char('k') => return Ok(WaitingAction::Up),
+                KeyCode::Down | KeyCode::Char('j') => return Ok(WaitingAction::Down),
+                KeyCode::Char('n') | KeyCode::Enter => return Ok(WaitingAction::Nudge),
+                KeyCode::Char('o') => return Ok(WaitingAction::Open),
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(WaitingAction::Quit),
+                _ => {}
+            }
+        }
+    }
+
+    pub fn draw_trash(&mut self, emails: &[Email], selected: usize) -> Result<()> {
+        let rows = emails.iter().map(review_row).collect();
+        self.draw_review(
+            format!(" {}", tr("🗑️  Trash ({} messages)", &[&emails.len()])),
+            rows,
+            selected,
+            None,
+            t("[↑/↓] select  [r]estore  [D]elete forever  [v]iew  [o]pen  [q]uit"),
+        )
+    }
+
+    /// Spam folder with the AI's verdict on each message; `verdicts` lines up with `emails`
+    pub fn draw_spam(
+        &mut self,
+        emails: &[Email],
+        verdicts: &[Option<SpamVerdict>],
+        selected: usize,
+    ) -> Result<()> {
+        let rows = emails
+            .iter()
+            .zip(verdicts)
+            .map(|(e, v)| {
+                let tag = match v {
+                    Some(v) if v.legit => "🤔",
+                    Some(_) => "🚫",
+                    None => "❔",
+                };
+                plain(&format!(" {}{}", tag, review_row(e))).into_owned()
+            })
+            .collect();
+        let detail = match verdicts.get(selected) {
+            Some(Some(v)) if v.legit => format!(" {}", tr("🤔 Looks legit: {}", &[&v.reason])),
+            Some(Some(v)) => format!(" {}", tr("🚫 Spam: {}", &[&v.reason])),
+            _ => format!(" {}", t("❔ Not checked")),
+        };
+        self.draw_review(
+            format!(" {}", tr("🚫 Spam ({} messages)", &[&emails.len()])),
+            rows,
+            selected,
+            Some(detail),
+            t("[↑/↓] select  [n]ot spam  [D]elete forever  [v]iew  [o]pen  [q]uit"),
+        )
+    }
+
+    pub fn draw_drafts(&mut self, drafts: &[Draft], selected: usize) -> Result<()> {
+        let rows = drafts
+            .iter()
+            .map(|d| {
+                let subject = if d.message.subject.is_empty() {
+                    t("(no subject)")
+                } else {
+                    &d.message.subject
+                };
+                format!(
+                    " {:>14}  {}  {}",
+                    format_age(d.message.date),
+                    text::pad(&d.message.to, 30),
+                    subject
+                )
+            })
+            .collect();
+        let detail = drafts.get(selected).map(|d| {
+            let body = d.message.body_text();
+            let first = body
+                .lines()
+                .find(|l| !l.trim().is_empty())
+                .unwrap_or(t("(empty)"));
+            plain(&format!(" ✏️  {}", first.trim())).into_owned()
+        });
+        self.draw_review(
+            format!(" {}", tr("✏️  Drafts ({})", &[&drafts.len()])),
+            rows,
+            selected,
+            detail,
+            t("[↑/↓] select  [e]dit  [s]end  [D]iscard  [v]iew  [o]pen  [q]uit"),
+        )
+    }
+
+    pub async fn wait_for_draft_action(&mut self) -> Result<DraftAction> {
+        loop {
+            match self.next_key().await?.code {
+                KeyCode::Up | KeyCode::Char('k') => return Ok(DraftAction::Up),
+                KeyCode::Down | KeyCode::Char('j') => return Ok(DraftAction::Down),
+                KeyCode::Char('e') | KeyCode::Enter => return Ok(DraftAction::Edit),
+                KeyCode::Char('s') => return Ok(DraftAction::Send),
+                KeyCode::Char('D') | KeyCode::Delete => return Ok(DraftAction::Discard),
+                KeyCode::Char('v') => return Ok(DraftAction::View),
+                KeyCode::Char('o') => return Ok(DraftAction::Open),
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(DraftAction::Quit),
+                _ => {}
+            }
+        }
+    }
+
+    pub fn draw_later(&mut self, items: &[later::Item], selected: usize) -> Result<()> {
+        let rows = items
+            .iter()
+            .map(|item| {
+                let subject = if item.subject.is_empty() {
+                    t("(no subject)")
+                } else {
+                    &item.subject
+                };
+                format!(
+                    " {:>14}  {}  {}  ({})",
+                    format_age(item.saved_at),
+                    text::pad(&item.from, 24),
+                    subject,
+                    tr("{} min", &[&item.minutes()])
+                )
+            })
+            .collect();
+        let detail = items.get(selected).map(|item| {
+            let first = item.summary.clone().unwrap_or_else(|| {
+                item.content
+                    .lines()
+                    .find(|l| !l.trim().is_empty())
+                    .unwrap_or(t("(empty)"))
+                    .trim()
+                    .to_string()
+            });
+            plain(&format!(" 📚 {}", first)).into_owned()
+        });
+        self.draw_review(
+            format!(" {}", tr("📚 Read later ({})", &[&items.len()])),
+            rows,
+            selected,
+            detail,
+            t("[↑/↓] select  [r]ead  [d]one  [o]pen in Gmail  [q]uit"),
+        )
+    }
+
+    pub async fn wait_for_later_action(&mut self) -> Result<LaterAction> {
+        loop {
+            match self.next_key().await?.code {
+                KeyCode::Up | KeyCode::Char('k') => return Ok(LaterAction::Up),
+                KeyCode::Down | KeyCode::Char('j') => return Ok(LaterAction::Down),
+                KeyCode::Char('r') | KeyCode::Enter => return Ok(LaterAction::Read),
+                KeyCode::Char('d') | KeyCode::Delete => return Ok(LaterAction::Done),
+                KeyCode::Char('o') => return Ok(LaterAction::Open),
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(LaterAction::Quit),
+                _ => {}
+            }
+        }
+    }
+
+    /// A selectable message list with an optional line about the selected message
+    fn draw_review(
+        &mut self,
+        title: String,
+        rows: Vec<String>,
+        selected: usize,
+        detail: Option<String>,
+        actions: &'static str,
+    ) -> Result<()> {
+        self.show(move |frame, theme, _keymap| {
+            let area = frame.area();
+
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Length(3),                                    // Header
+                    Constraint::Min(3),                                       // Messages
+                    Constraint::Length(if detail.is_some() { 3 } else { 0 }), // Detail
+                    Constraint::Length(3),                                    // Actions
+                ])
+                .split(area);
+
+            let header = Paragraph::new(title.as_str())
+                .style(
+                    Style::default()
+                        .fg(theme.title)
+                        .add_modifier(Modifier::BOLD),
+                )
+                .block(Block::default().borders(Borders::ALL));
+            frame.render_widget(header, chunks[0]);
+
+            // Keep the selection on screen in long lists
+            let visible = chunks[1].height.saturating_sub(2) as usize;
+            let first = selected.saturating_sub(visible.saturating_sub(1));
+            let lines: Vec<Line> = rows
+                .iter()
+                .enumerate()
+                .skip(first)
+                .take(visible)
+                .map(|(i, row)| {
+                    if i == selected {
+                        Line::from(row.as_str()).style(
+                            Style::default()
+                                .fg(theme.highlight)
+                                .add_modifier(Modifier::REVERSED),
+                        )
+                    } else {
+                        Line::from(row.as_str()).style(Style::default().fg(theme.text))
+                    }
+                })
+                .collect();
+            let list = Paragraph::new(lines).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.border)),
+            );
+            frame.render_widget(list, chunks[1]);
+
+            if let Some(detail) = &detail {
+                let detail_widget = Paragraph::new(detail.as_str())
+                    .style(Style::default().fg(theme.ai_content))
+                    .block(Block::default().borders(Borders::ALL));
+                frame.render_widget(detail_widget, chunks[2]);
+            }
+
+            let actions_widget = Paragraph::new(format!(" {} ", actions))
+                .style(Style::default().fg(theme.keys))
+                .alignment(Alignment::Center)
+                .block(Block::default().borders(Borders::ALL));
+            frame.render_widget(actions_widget, chunks[3]);
+        })
+    }
+
+    /// One group of similar emails, listed, with what can be done to all of them
+    pub fn draw_cluster(
+        &mut self,
+        cluster: &Cluster,
+        emails: &[&Email],
+        position: usize,
+        groups: usize,
+        selected: usize,
+    ) -> Result<()> {
+        let rows = emails.iter().map(|e| review_row(e)).collect();
+        self.draw_review(
+            format!(
+                " {}",
+                tr(
+                    "📚 Group {} of {}: {} similar emails from {} — {}",
+                    &[
+                        &position,
+                        &groups,
+                        &cluster.ids.len(),
+                        &cluster.sender,
+                        &cluster.template
+                    ]
+                )
+            ),
+            rows,
+            selected,
+            None,
+            t("[a]rchive all  [t]riage individually  [↑/↓] select  [v]iew  [o]pen list  [q] no more groups"),
+        )
+    }
+
+    pub async fn wait_for_cluster_action(&mut self) -> Result<ClusterAction> {
+        loop {
+            match self.next_key().await?.code {
+                KeyCode::Up | KeyCode::Char('k') => return Ok(ClusterAction::Up),
+                KeyCode::Down | KeyCode::Char('j') => return Ok(ClusterAction::Down),
+                KeyCode::Char('a') => return Ok(ClusterAction::ArchiveAll),
+                KeyCode::Char('t') | KeyCode::Char('s') => {
+                    return Ok(ClusterAction::Individually);
+                }
+                KeyCode::Char('v') | KeyCode::Enter => return Ok(ClusterAction::View),
+                KeyCode::Char('o') => return Ok(ClusterAction::OpenList),
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(ClusterAction::Stop),
+                _ => {}
+            }
+        }
+    }
+
+    /// The biggest messages, largest first, with the selected one's attachments below
+    pub fn draw_storage(&mut self, emails: &[Email], selected: usize) -> Result<()> {
+        let total: u64 = emails.iter().map(|e| e.size).sum();
+        let rows = emails
+            .iter()
+            .map(|e| format!(" {:>9} {}", attachments::format_size(e.size), review_row(e)))
+            .collect();
+        let detail = emails.get(selected).map(|e| {
+            if e.attachments.is_empty() {
+                format!(" {}", t("No attachments; the size is in the body"))
+            } else {
+                plain(&format!(" 📎 {}", attachment_list(&e.attachments))).into_owned()
+            }
+        });
+        self.draw_review(
+            format!(
+                " {}",
+                tr(
+                    "💾 Large messages ({}, {} in all)",
+                    &[&emails.len(), &attachments::format_size(total)]
+                )
+            ),
+            rows,
+            selected,
+            detail,
+            t("[↑/↓] select  [x] save attachments  [a]rchive  [d]elete  [v]iew  [o]pen  [q]uit"),
+        )
+    }
+
+    pub async fn wait_for_storage_action(&mut self) -> Result<StorageAction> {
+        loop {
+            match self.next_key().await?.code {
+                KeyCode::Up | KeyCode::Char('k') => return Ok(StorageAction::Up),
+                KeyCode::Down | KeyCode::Char('j') => return Ok(StorageAction::Down),
+                KeyCode::Char('a') => return Ok(StorageAction::Archive),
+                KeyCode::Char('d') | KeyCode::Delete => return Ok(StorageAction::Delete),
+                KeyCode::Char('x') => return Ok(StorageAction::SaveAttachments),
+                KeyCode::Char('v') | KeyCode::Enter => return Ok(StorageAction::View),
+                KeyCode::Char('o') => return Ok(StorageAction::Open),
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(StorageAction::Quit),
+                _ => {}
+            }
+        }
+    }
+
+    pub async fn wait_for_review_action(&mut self) -> Result<ReviewAction> {
+        loop {
+            match self.next_key().await?.code {
+                KeyCode::Up | KeyCode::Char('k') => return Ok(ReviewAction::Up),
+                KeyCode::Down | KeyCode::Char('j') => return Ok(ReviewAction::Down),
+                KeyCode::Char('r') | KeyCode::Char('u') | KeyCode::Char('n') => {
+                    return Ok(ReviewAction::Restore);
+                }
+                KeyCode::Char('D') | KeyCode::Delete => return Ok(ReviewAction::Delete),
+                KeyCode::Char('v') | KeyCode::Enter => return Ok(ReviewAction::View),
+                KeyCode::Char('o') => return Ok(ReviewAction::Open),
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(ReviewAction::Quit),
+                _ => {}
+            }
+        }
+    }
+
+    pub fn draw_dashboard(&mut self, dashboard: &Dashboard) -> Result<()> {
+        let dashboard = dashboard.clone();
+        // Convert the body once rather than on every redraw
+        let body = dashboard
+            .selected_email()
+            .map(|e| e.body_text())
+            .unwrap_or_default();
+        let show_reasoning = self.show_reasoning;
+        self.show(move |frame, theme, keymap| {
+            let area = frame.area();
+
+            let rows = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(8), Constraint::Length(3)])
+                .split(area);
+            let columns = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
+                .split(rows[0]);
+            // The split layout gives the list and preview the full height
+            let side_panes = if dashboard.split() { 0 } else { 1 };
+            let left = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(4), Constraint::Length(8 * side_panes)])
+                .split(columns[0]);
+            let right = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Min(4),
+                    Constraint::Length((6 + u16::from(dashboard.storage.is_some())) * side_panes),
+                ])
+                .split(columns[1]);
+
+            let pane_block = |title: String, pane: Option<Pane>| {
+                let color = if pane == Some(dashboard.focus) {
+                    theme.highlight
+                } else {
+                    theme.border
+                };
+                Block::default()
+                    .title(title)
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(color))
+            };
+            let selected_style = |focused: bool| {
+                let style = Style::default().fg(theme.highlight);
+                if focused {
+                    style.add_modifier(Modifier::REVERSED)
+                } else {
+                    style.add_modifier(Modifier::BOLD)
                 }
+            };
+
+            // Messages
+            let visible = left[0].height.saturating_sub(2) as usize;
+            let first = dashboard
+                .selected
+                .saturating_sub(visible.saturating_sub(1));
+            let width = left[0].width.saturating_sub(6) as usize;
+            let lines: Vec<Line> = dashboard
+                .emails
+                .iter()
+                .enumerate()
+                .skip(first)
+                .take(visible)
+                .map(|(i, email)| {
+                    let analysis = dashboard.analysis(email);
+                    let marker = analysis.map_or("    ".to_string(), |a| priority_marker(a.priority));
+                    let clip = match (
+                        email.has_attachments || !email.attachments.is_empty(),
+                        i18n::ascii(),
+                    ) {
+                        (false, _) => "   ",
+                        (true, false) => "📎 ",
+                        (true, true) => "@  ",
+                    };
+                    let text = format!(
+                        "{} {} {}{}",
+                        marker,
+                        text::pad(&email.sender_name(), 18),
+                        clip,
+                        email.subject
+                    );
+                    let line = Line::from(truncate(&text, width));
+                    if i == dashboard.selected {
+                        line.style(selected_style(dashboard.focus == Pane::Messages))
+                    } else if email.is_unread {
+                        line.style(
+                            Style::default()
+                                .fg(theme.text)
+                                .add_modifier(Modifier::BOLD),
+                        )
+                    } else {
+                        line.style(Style::default().fg(theme.muted))
+                    }
+                })
+                .collect();
+            let messages = Paragraph::new(lines).block(pane_block(
+                plain(&format!(" 📧 {} ({}) ", dashboard.query, dashboard.emails.len())).into_owned(),
+                Some(Pane::Messages),
+            ));
+            frame.render_widget(messages, left[0]);
+
+            // Tasks
+            let visible = left[1].height.saturating_sub(2) as usize;
+            let first = dashboard
+                .selected_task
+                .saturating_sub(visible.saturating_sub(1));
+            let lines: Vec<Line> = dashboard
+                .tasks
+                .iter()
+                .enumerate()
+                .skip(first)
+                .take(visible)
+                .map(|(i, task)| {
+                    let line = Line::from(plain(&format!(" ☐ {}", task.title)).into_owned());
+                    if i == dashboard.selected_task {
+                        line.style(selected_style(dashboard.focus == Pane::Tasks))
+                    } else {
+                        line.style(Style::default().fg(theme.text))
+                    }
+                })
+                .collect();
+            let tasks = Paragraph::new(lines).block(pane_block(
+                format!(" {} ", tr("📝 Tasks ({})", &[&dashboard.tasks.len()])),
+                Some(Pane::Tasks),
+            ));
+            if !dashboard.split() {
+                frame.render_widget(tasks, left[1]);
+            }
 
-                match key.code {
-                    KeyCode::Char('y') | KeyCode::Char('Y') => return Ok(true),
-                    KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => return Ok(false),
-                    _ => {}
+            // Preview
+            let preview = match dashboard.selected_email() {
+                Some(email) => {
+                    let mut lines = vec![
+                        Line::from(format!(" {} {}", t("From:"), email.from)),
+                        Line::from(format!(" {} {}", t("Subject:"), email.subject)),
+                        Line::from(format!(
+                            " {} {}",
+                            t("Date:"),
+                            email
+                                .date
+                                .with_timezone(&chrono::Local)
+                                .format("%Y-%m-%d %H:%M")
+                        )),
+                    ];
+                    if !email.attachments.is_empty() {
+                        lines.push(Line::from(
+                            plain(&format!(" 📎 {}", attachment_list(&email.attachments)))
+                                .into_owned(),
+                        ));
+                    }
+                    lines.push(Line::from(""));
+                    match dashboard.analysis(email) {
+                        Some(analysis) => {
+                            let style = theme.priority_style(analysis.priority);
+                            let badge = plain(&format!(
+                                "{} {}",
+                                analysis.priority.emoji(),
+                                t(analysis.priority.label())
+                            ))
+                            .into_owned();
+                            lines.push(
+                                Line::from(format!(
+                                    " {}{} | {} | {}",
+                                    badge,
+                                    analysis.confidence_label(),
+                                    t(analysis.category.label()),
+                                    analysis.summary
+                                ))
+                                .style(style),
+                            );
+                            if let Some(action) = &analysis.suggested_action {
+                                lines.push(
+                                    Line::from(plain(&format!(" ➡️  {}", action)).into_owned())
+                                        .style(style),
+                                );
+                            }
+                            if let Some(reasoning) =
+                                analysis.reasoning.as_ref().filter(|_| show_reasoning)
+                            {
+                                lines.push(
+                                    Line::from(plain(&format!(" 💭 {}", reasoning)).into_owned())
+                                        .style(style),
+                                );
+                            }
+                        }
+                        None if dashboard.read_only() => {}
+                        None => lines.push(
+                            Line::from(format!(" {}", t("🔄 Analyzing...")))
+                                .style(Style::default().fg(theme.highlight)),
+                        ),
+                    }
+                    lines.push(Line::from(""));
+                    if !dashboard.is_loaded(email) {
+                        lines.push(
+                            Line::from(format!(" {}", t("📥 Loading message...")))
+                                .style(Style::default().fg(theme.highlight)),
+                        );
+                    }
+                    lines.extend(
+                        body.lines()
+                            .map(|l| Line::from(format!(" {}", l)).style(Style::default().fg(theme.muted))),
+                    );
+                    lines
                 }
+                None => vec![
+                    Line::from(format!(" {}", t("📭 Nothing here")))
+                        .style(Style::default().fg(theme.muted)),
+                ],
+            };
+            let preview = Paragraph::new(preview)
+                .style(Style::default().fg(theme.text))
+                .wrap(Wrap { trim: false })
+                .scroll((dashboard.scroll, 0))
+                .block(pane_block(format!(" {} ", t("Preview")), Some(Pane::Preview)));
+            frame.render_widget(preview, right[0]);
+
+            // Stats
+            let analyzed = dashboard
+                .emails
+                .iter()
+                .filter(|e| dashboard.analysis(e).is_some())
+                .count();
+            let priorities = dashboard
+                .priority_counts()
+                .into_iter()
+                .map(|(priority, count)| format!("{} {}", priority_marker(priority), count))
+                .collect::<Vec<_>>()
+                .join("  ");
+            let stats = &dashboard.stats;
+            let text = format!(
+                " {}\n {}\n {}\n {}",
+                tr(
+                    "📬 {} messages, {} unread, {} analyzed",
+                    &[&dashboard.emails.len(), &dashboard.unread(), &analyzed]
+                ),
+                priorities,
+                tr(
+                    "✅ {} archived  🗑️  {} deleted  👁️  {} read",
+                    &[&stats.archived, &stats.deleted, &stats.marked_read]
+                ),
+                tr(
+                    "📝 {} tasks  💬 {} replied",
+                    &[&stats.tasks_created, &stats.replied]
+                ),
+            );
+            let mut text = text;
+            if let Some(quota) = &dashboard.storage {
+                let warning = if quota.is_nearly_full() {
+                    format!("  {}", t("⚠️ nearly full"))
+                } else {
+                    String::new()
+                };
+                text.push_str(&format!("\n 💾 {}{}", format_quota(quota), warning));
+            }
+            let stats_widget = Paragraph::new(text)
+                .style(Style::default().fg(theme.text))
+                .wrap(Wrap { trim: false })
+                .block(pane_block(format!(" {} ", t("📊 Stats")), None));
+            if !dashboard.split() {
+                frame.render_widget(stats_widget, right[1]);
+            }
+
+            // Footer
+            let footer = match dashboard.focus {
+                Pane::Messages | Pane::Preview if dashboard.read_only() => tr(
+                    "[Tab] pane  [↑/↓] select  [Enter] view  [/] search  [{}] export  [{}] open  [R] refresh  [{}] quit",
+                    &[
+                        &keymap.key_label(Action::Export),
+                        &keymap.key_label(Action::Open),
+                        &keymap.key_label(Action::Quit),
+                    ],
+                ),
+                Pane::Messages => tr(
+                    "[Tab] pane  [↑/↓] select  [Enter] view  [{}] archive  [{}] delete  [{}] mark read  [{}] task  [{}] reply  [{}] open  [R] refresh  [{}] quit",
+                    &[
+                        &keymap.key_label(Action::Archive),
+                        &keymap.key_label(Action::Delete),
+                        &keymap.key_label(Action::MarkRead),
+                        &keymap.key_label(Action::Task),
+                        &keymap.key_label(Action::Reply),
+                        &keymap.key_label(Action::Open),
+                        &keymap.key_label(Action::Quit),
+                    ],
+                ),
+                Pane::Preview => tr(
+                    "[Tab] pane  [↑/↓] scroll  [{}] archive  [{}] reply  [{}] open  [{}] quit",
+                    &[
+                        &keymap.key_label(Action::Archive),
+                        &keymap.key_label(Action::Reply),
+                        &keymap.key_label(Action::Open),
+                        &keymap.key_label(Action::Quit),
+                    ],
+                ),
+                Pane::Tasks => tr(
+                    "[Tab] pane  [↑/↓] select  [Enter] complete  [{}] delete  [R] refresh  [{}] quit",
+                    &[&keymap.key_label(Action::Delete), &keymap.key_label(Action::Quit)],
+                ),
+            };
+            let footer = format!(" {} ", footer);
+            let footer = Paragraph::new(footer)
+                .style(Style::default().fg(theme.keys))
+                .alignment(Alignment::Center)
+                .block(Block::default().borders(Borders::ALL));
+            frame.render_widget(footer, rows[1]);
+        })
+    }
+
+    pub async fn wait_for_dashboard_action(&mut self) -> Result<DashboardAction> {
+        loop {
+            if self.interrupted {
+                return Ok(DashboardAction::Triage(Action::Quit));
+            }
+            let key = self.next_key().await?;
+            if let Some(action) = self.keymap.action(key.code) {
+                return Ok(DashboardAction::Triage(action));
+            }
+            match key.code {
+                KeyCode::Up | KeyCode::Char('k') => return Ok(DashboardAction::Up),
+                KeyCode::Down | KeyCode::Char('j') => return Ok(DashboardAction::Down),
+                KeyCode::Tab => return Ok(DashboardAction::NextPane),
+                KeyCode::BackTab => return Ok(DashboardAction::PrevPane),
+                KeyCode::Enter => return Ok(DashboardAction::Select),
+                KeyCode::Char('R') | KeyCode::F(5) => return Ok(DashboardAction::Refresh),
+                KeyCode::Char('/') => return Ok(DashboardAction::Search),
+                _ => {}
+            }
+        }
+    }
+
+    pub async fn wait_for_yes_no(&mut self) -> Result<bool> {
+        loop {
+            match self.next_key().await?.code {
+                KeyCode::Char('y') | KeyCode::Char('Y') => return Ok(true),
+                KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => return Ok(false),
+                _ => {}
             }
         }
     }
@@ -511,14 +2584,74 @@ impl Drop for Tui {
     }
 }
 
-fn truncate(s: &str, max_len: usize) -> String {
-    if s.chars().count() <= max_len {
-        s.to_string()
+/// Dot plus symbol ("🔴!!"), or a "[URGENT]" badge in ASCII mode, so priorities
+/// read the same without color
+fn priority_marker(priority: Priority) -> String {
+    if i18n::ascii() {
+        format!("[{}]", t(priority.label()))
     } else {
-        format!("{}...", s.chars().take(max_len).collect::<String>())
+        format!("{}{:<2}", priority.emoji(), priority.symbol())
+    }
+}
+
+/// Storage use such as "12.3 GB of 15.0 GB (82%)"
+pub fn format_quota(quota: &StorageQuota) -> String {
+    let usage = attachments::format_size(quota.usage);
+    match (quota.limit, quota.fraction()) {
+        (Some(limit), Some(fraction)) => tr(
+            "{} of {} ({}%)",
+            &[
+                &usage,
+                &attachments::format_size(limit),
+                &format!("{:.0}", fraction * 100.0),
+            ],
+        ),
+        _ => tr("{} (no limit)", &[&usage]),
     }
 }
 
+/// Age, sender and subject of a message on a review list
+fn review_row(email: &Email) -> String {
+    format!(
+        " {:>14}  {}  {}",
+        format_age(email.date),
+        text::pad(&email.from, 30),
+        email.subject
+    )
+}
+
+/// Lines the expanded reasoning adds to the AI summary, wrapped to `width`
+fn reasoning_height(reasoning: Option<&str>, width: u16) -> u16 {
+    let Some(reasoning) = reasoning else {
+        return 0;
+    };
+    let width = width.saturating_sub(4).max(1) as usize;
+    (text::width(reasoning) + 3).div_ceil(width) as u16
+}
+
+/// Rows used by the metadata block: from/subject/date, attachments, a one-time
+/// code, warnings and notes
+fn metadata_height(email: &Email, notes: &[String], warnings: &[String], code: bool) -> u16 {
+    let attachments = u16::from(!email.attachments.is_empty());
+    4 + attachments + u16::from(code) + warnings.len() as u16 + notes.len() as u16
+}
+
+/// "report.pdf (1.2 MB), photo.png (300.0 KB)"
+fn attachment_list(attachments: &[Attachment]) -> String {
+    attachments
+        .iter()
+        .map(|a| format!("{} ({})", a.filename, attachments::format_size(a.size)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Height of a bordered footer once its text wraps to the given width
+fn footer_height(text: &str, width: u16) -> u16 {
+    let inner = width.saturating_sub(2).max(1);
+    let lines = (text::width(text) as u16).div_ceil(inner);
+    lines.clamp(1, 3) + 2
+}
+
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
@@ -538,3 +2671,26 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
         ])
         .split(popup_layout[1])[1]
 }
+
+/// A full-width-percentage strip of fixed height at the top of the screen
+fn top_rect(percent_x: u16, height: u16, r: Rect) -> Rect {
+    let width = r.width * percent_x / 100;
+    Rect {
+        x: r.x + (r.width - width) / 2,
+        y: r.y + 1.min(r.height),
+        width,
+        height: height.min(r.height.saturating_sub(1)),
+    }
+}
+
+/// A full-width-percentage strip of fixed height at the bottom of the screen
+fn bottom_rect(percent_x: u16, height: u16, r: Rect) -> Rect {
+    let width = r.width * percent_x / 100;
+    let height = height.min(r.height);
+    Rect {
+        x: r.x + (r.width - width) / 2,
+        y: r.y + r.height.saturating_sub(height + 3),
+        width,
+        height,
+    }
+}
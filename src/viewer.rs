@@ -0,0 +1,236 @@
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+
+use clinbox_core::email::Attachment;
+
+/// Larger attachments go straight to "save and open"
+pub const MAX_VIEW_BYTES: u64 = 5 * 1024 * 1024;
+/// Most lines of text or rows of a table shown
+const MAX_LINES: usize = 5000;
+/// Widest a table column gets before its cells are cut
+const MAX_COLUMN_WIDTH: usize = 40;
+
+/// Extensions shown as plain text, besides text/* MIME types
+const TEXT_EXTENSIONS: &[&str] = &[
+    "txt", "log", "md", "rst", "ini", "toml", "yaml", "yml", "xml", "conf", "cfg", "env", "sql",
+    "rs", "py", "go", "c", "h", "java", "ts", "css",
+];
+
+/// How an attachment is shown in the terminal
+#[derive(Debug, Clone)]
+pub enum Preview {
+    /// Rows of a CSV or TSV file, header first
+    Table(Vec<Vec<String>>),
+    /// Plain text, or JSON pretty-printed
+    Text(String),
+    /// A diff or patch, colored by line
+    Diff(String),
+    /// An image, drawn by the terminal itself
+    Image(Graphics, Vec<u8>),
+}
+
+/// Terminal image protocols
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Graphics {
+    /// Kitty's graphics protocol (kitty, Ghostty); PNG only
+    Kitty,
+    /// iTerm2's inline images (iTerm2, WezTerm, Konsole's newer versions); any format
+    Iterm,
+}
+
+/// The image protocol this terminal speaks, from its environment
+pub fn graphics() -> Option<Graphics> {
+    let var = |name: &str| std::env::var(name).unwrap_or_default();
+    let term = var("TERM");
+    let program = var("TERM_PROGRAM");
+    if std::env::var_os("KITTY_WINDOW_ID").is_some()
+        || term == "xterm-kitty"
+        || term == "xterm-ghostty"
+        || program == "ghostty"
+    {
+        Some(Graphics::Kitty)
+    } else if matches!(program.as_str(), "iTerm.app" | "WezTerm") {
+        Some(Graphics::Iterm)
+    } else {
+        None
+    }
+}
+
+/// A built-in view of the attachment, or None when it has to be saved and
+/// opened with another program
+pub fn preview(attachment: &Attachment, data: &[u8]) -> Option<Preview> {
+    let mime = attachment.mime_type.to_lowercase();
+    let name = attachment.filename.to_lowercase();
+    let ext = name
+        .rsplit_once('.')
+        .map(|(_, ext)| ext)
+        .unwrap_or_default();
+
+    if mime.starts_with("image/") || matches!(ext, "png" | "jpg" | "jpeg" | "gif") {
+        let png = mime == "image/png" || ext == "png";
+        return match graphics()? {
+            Graphics::Kitty if !png => None,
+            protocol => Some(Preview::Image(protocol, data.to_vec())),
+        };
+    }
+
+    let text = std::str::from_utf8(data).ok()?;
+    if mime == "text/csv" || matches!(ext, "csv" | "tsv") {
+        let delimiter = if ext == "tsv" || mime == "text/tab-separated-values" {
+            b'\t'
+        } else {
+            sniff_delimiter(text)
+        };
+        return table(text, delimiter).map(Preview::Table);
+    }
+    if mime == "application/json" || mime.ends_with("+json") || ext == "json" {
+        let pretty = serde_json::from_str::<serde_json::Value>(text)
+            .ok()
+            .and_then(|value| serde_json::to_string_pretty(&value).ok())
+            .unwrap_or_else(|| text.to_string());
+        return Some(Preview::Text(pretty));
+    }
+    if matches!(mime.as_str(), "text/x-diff" | "text/x-patch") || matches!(ext, "diff" | "patch") {
+        return Some(Preview::Diff(text.to_string()));
+    }
+    if mime.starts_with("text/") && mime != "text/html" || TEXT_EXTENSIONS.contains(&ext) {
+        return Some(Preview::Text(text.to_string()));
+    }
+    None
+}
+
+/// Lines of a preview, capped at MAX_LINES
+pub fn lines(text: &str) -> Vec<String> {
+    text.lines().take(MAX_LINES).map(str::to_string).collect()
+}
+
+/// A table as lines of padded columns, the header underlined
+pub fn table_lines(rows: &[Vec<String>]) -> Vec<String> {
+    use unicode_width::UnicodeWidthStr;
+
+    let cell = |value: &str| crate::text::truncate(value, MAX_COLUMN_WIDTH);
+    let mut widths: Vec<usize> = Vec::new();
+    for row in rows {
+        for (i, value) in row.iter().enumerate() {
+            let width = cell(value).width();
+            match widths.get_mut(i) {
+                Some(w) => *w = (*w).max(width),
+                None => widths.push(width),
+            }
+        }
+    }
+
+    let mut lines = Vec::new();
+    for (n, row) in rows.iter().enumerate() {
+        let line = row
+            .iter()
+            .enumerate()
+            .map(|(i, value)| {
+                let value = cell(value);
+                format!("{}{}", value, " ".repeat(widths[i] - value.width()))
+            })
+            .collect::<Vec<_>>()
+            .join(" │ ");
+        lines.push(line.trim_end().to_string());
+        if n == 0 {
+            lines.push(
+                widths
+                    .iter()
+                    .map(|w| "─".repeat(*w))
+                    .collect::<Vec<_>>()
+                    .join("─┼─"),
+            );
+        }
+    }
+    lines
+}
+
+/// The escape sequence that draws an image over `cols` x `rows` cells at the cursor
+pub fn image_sequence(protocol: Graphics, data: &[u8], cols: u16, rows: u16) -> String {
+    let encoded = STANDARD.encode(data);
+    match protocol {
+        Graphics::Kitty => {
+            // Kitty stretches the image over the cells it's given, so keep its
+            // shape: terminal cells are about twice as tall as they are wide
+            let (cols, rows) = match png_size(data) {
+                Some((width, height)) if width > 0 && height > 0 => {
+                    let fit_rows = (cols as f64 * height as f64 / width as f64 / 2.0).ceil();
+                    if fit_rows <= rows as f64 {
+                        (cols, fit_rows.max(1.0) as u16)
+                    } else {
+                        let fit_cols = (rows as f64 * width as f64 / height as f64 * 2.0).ceil();
+                        (fit_cols.max(1.0) as u16, rows)
+                    }
+                }
+                _ => (cols, rows),
+            };
+            // Sent in chunks of at most 4096 bytes, m=1 on all but the last
+            let chunks: Vec<&[u8]> = encoded.as_bytes().chunks(4096).collect();
+            let mut sequence = String::new();
+            for (i, chunk) in chunks.iter().enumerate() {
+                let more = u8::from(i + 1 < chunks.len());
+                let control = if i == 0 {
+                    format!("a=T,f=100,c={},r={},q=2,m={}", cols, rows, more)
+                } else {
+                    format!("m={}", more)
+                };
+                sequence.push_str(&format!(
+                    "\x1b_G{};{}\x1b\\",
+                    control,
+                    String::from_utf8_lossy(chunk)
+                ));
+            }
+            sequence
+        }
+        Graphics::Iterm => format!(
+            "\x1b]1337;File=inline=1;size={};width={};height={};preserveAspectRatio=1:{}\x07",
+            data.len(),
+            cols,
+            rows,
+            encoded
+        ),
+    }
+}
+
+/// The sequence that removes images drawn with `protocol`, if it needs one
+pub fn clear_sequence(protocol: Graphics) -> &'static str {
+    match protocol {
+        Graphics::Kitty => "\x1b_Ga=d,q=2\x1b\\",
+        // Inline images go with the text they were drawn over
+        Graphics::Iterm => "",
+    }
+}
+
+/// Width and height of a PNG, from its IHDR chunk
+fn png_size(data: &[u8]) -> Option<(u32, u32)> {
+    if data.get(..8)? != b"\x89PNG\r\n\x1a\n" || data.get(12..16)? != b"IHDR" {
+        return None;
+    }
+    let width = u32::from_be_bytes(data.get(16..20)?.try_into().ok()?);
+    let height = u32::from_be_bytes(data.get(20..24)?.try_into().ok()?);
+    Some((width, height))
+}
+
+/// Semicolons in the first line mean a European-style CSV
+fn sniff_delimiter(text: &str) -> u8 {
+    let first = text.lines().next().unwrap_or_default();
+    if first.matches(';').count() > first.matches(',').count() {
+        b';'
+    } else {
+        b','
+    }
+}
+
+fn table(text: &str, delimiter: u8) -> Option<Vec<Vec<String>>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(text.as_bytes());
+    let rows: Vec<Vec<String>> = reader
+        .records()
+        .take(MAX_LINES)
+        .filter_map(Result::ok)
+        .map(|record| record.iter().map(|v| v.replace('\n', " ")).collect())
+        .collect();
+    (!rows.is_empty()).then_some(rows)
+}
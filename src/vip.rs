@@ -0,0 +1,89 @@
+use anyhow::{Result, bail};
+use reqwest::Client;
+
+use clinbox_core::config::Config;
+use clinbox_core::email::{Email, EmailAnalysis, Priority};
+
+use crate::contacts::{normalize_sender, sender_matches};
+
+/// Senders that always come first and are never downgraded by the AI
+pub struct Vip {
+    senders: Vec<String>,
+    desktop_notify: bool,
+    webhook: Option<String>,
+    http: Client,
+}
+
+impl Vip {
+    /// The `vip` settings, with webhooks sent through the `network` settings
+    pub fn new(config: &Config) -> Result<Self> {
+        Ok(Self {
+            senders: config
+                .vip
+                .senders
+                .iter()
+                .map(|s| normalize_sender(s))
+                .collect(),
+            desktop_notify: config.vip.desktop_notify,
+            webhook: config.vip.webhook.clone(),
+            http: config.network.client()?,
+        })
+    }
+
+    pub fn is_vip(&self, email: &Email) -> bool {
+        sender_matches(email, &self.senders)
+    }
+
+    /// Force VIP mail to urgent, whatever the model decided
+    pub fn escalate(&self, email: &Email, analysis: &mut EmailAnalysis) {
+        if self.is_vip(email) && analysis.priority != Priority::Urgent {
+            analysis.priority = Priority::Urgent;
+            analysis.confidence = None;
+        }
+    }
+
+    /// Send the configured desktop and webhook notifications for a VIP email
+    pub async fn notify(&self, email: &Email) -> Result<()> {
+        if self.desktop_notify {
+            desktop_notification(&format!("⭐ {}", email.sender_name()), &email.subject)?;
+        }
+
+        if let Some(url) = &self.webhook {
+            let payload = serde_json::json!({
+                "id": email.id,
+                "from": email.from,
+                "subject": email.subject,
+                "date": email.date,
+                "snippet": email.snippet,
+            });
+            let response = self.http.post(url).json(&payload).send().await?;
+            if !response.status().is_success() {
+                bail!("VIP webhook failed: {}", response.status());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Show a desktop notification with the platform's own tool
+fn desktop_notification(title: &str, body: &str) -> Result<()> {
+    use std::process::Command;
+
+    let status = if cfg!(target_os = "macos") {
+        let script = format!(
+            "display notification {:?} with title {:?}",
+            body.replace('"', "'"),
+            title.replace('"', "'")
+        );
+        Command::new("osascript").args(["-e", &script]).status()
+    } else {
+        Command::new("notify-send").args([title, body]).status()
+    };
+
+    match status {
+        Ok(s) if s.success() => Ok(()),
+        Ok(s) => bail!("Desktop notification failed: {}", s),
+        Err(e) => bail!("Desktop notification failed: {}", e),
+    }
+}
@@ -0,0 +1,58 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+
+use clinbox_core::gmail::GmailClient;
+
+/// A thread whose last message is mine, still waiting on an answer
+#[derive(Debug, Clone)]
+pub struct WaitingThread {
+    pub thread_id: String,
+    /// My last message in the thread
+    pub message_id: String,
+    pub to: String,
+    pub subject: String,
+    pub sent_at: DateTime<Utc>,
+}
+
+/// Threads I wrote to in the last `days` days that nobody has answered, oldest first
+pub async fn find(
+    gmail: &GmailClient,
+    days: u32,
+    max_threads: u32,
+    mut on_progress: impl FnMut(usize, usize),
+) -> Result<Vec<WaitingThread>> {
+    const CONCURRENCY: usize = 4;
+
+    let query = format!("in:sent newer_than:{}d", days);
+    let ids = gmail.search_threads(&query, max_threads).await?;
+    let total = ids.len();
+    on_progress(0, total);
+
+    let mut threads = futures::stream::iter(ids)
+        .map(|id| async move { (id.clone(), gmail.fetch_thread(&id).await) })
+        .buffer_unordered(CONCURRENCY);
+
+    let mut waiting = Vec::new();
+    let mut done = 0;
+    while let Some((thread_id, messages)) = threads.next().await {
+        done += 1;
+        on_progress(done, total);
+
+        let Some(last) = messages.ok().and_then(|m| m.into_iter().last()) else {
+            continue;
+        };
+        if last.labels.iter().any(|l| l == "SENT") {
+            waiting.push(WaitingThread {
+                thread_id,
+                message_id: last.id,
+                to: last.to,
+                subject: last.subject,
+                sent_at: last.date,
+            });
+        }
+    }
+
+    waiting.sort_by_key(|t| t.sent_at);
+    Ok(waiting)
+}